@@ -0,0 +1,75 @@
+//! Keeping a million parsed tickers around, either copied into one arena or each owning its
+//! strings, reporting throughput and peak RSS.
+//!
+//! Run with `cargo bench --bench arena_bench --features arena`. The peak is reset between the
+//! two through `/proc/self/clear_refs`, so it's only reported on Linux.
+
+use std::{fs, hint::black_box, time::Instant};
+
+use binance_json_parsing::{
+    BookTickerOwned, arena::TickArena, detect_config, parse_book_ticker_dynamic,
+};
+
+const TICKS: usize = 1_000_000;
+
+const PAYLOADS: &str = include_str!("../tests/fixtures/book_ticker.jsonl");
+
+/// Peak resident set size in KiB, from `VmHWM`.
+fn peak_rss_kib() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// Lowers the peak back to the current RSS, see proc(5).
+fn reset_peak_rss() {
+    _ = fs::write("/proc/self/clear_refs", "5");
+}
+
+fn bench(name: &str, run: impl FnOnce()) {
+    reset_peak_rss();
+    let before = peak_rss_kib();
+    let start = Instant::now();
+    run();
+    let elapsed = start.elapsed();
+
+    let ns = elapsed.as_nanos() as f64 / TICKS as f64;
+    let rss = match (before, peak_rss_kib()) {
+        (Some(before), Some(after)) => format!("{:>8} KiB peak RSS", after.saturating_sub(before)),
+        _ => "peak RSS unknown".to_owned(),
+    };
+    println!("{name:<8} {ns:>8.1} ns/tick {rss}");
+}
+
+fn main() {
+    // Cargo passes `--bench`, and a filter may follow, just like for the default harness.
+    let filter = std::env::args().skip(1).find(|arg| !arg.starts_with('-'));
+    let enabled = |name: &str| filter.as_deref().is_none_or(|filter| name.contains(filter));
+
+    let payloads: Vec<_> = PAYLOADS
+        .lines()
+        .map(|json| (json, detect_config(json).unwrap()))
+        .collect();
+    let ticks = || payloads.iter().cycle().take(TICKS);
+
+    if enabled("owned") {
+        bench("owned", || {
+            let tickers: Vec<BookTickerOwned> = ticks()
+                .map(|&(json, config)| {
+                    BookTickerOwned::try_from(parse_book_ticker_dynamic(json, config).unwrap())
+                        .unwrap()
+                })
+                .collect();
+            black_box(&tickers);
+        });
+    }
+    if enabled("arena") {
+        bench("arena", || {
+            let arena = TickArena::new();
+            let tickers: Vec<_> = ticks()
+                .map(|&(json, config)| arena.alloc_ticker(json, config).unwrap())
+                .collect();
+            black_box(&tickers);
+        });
+    }
+}
@@ -0,0 +1,87 @@
+//! Parsing the fixture's book tickers as received with and without `permessage-deflate`, i.e.
+//! inflating each first, reporting the bytes over the wire and the time it costs.
+//!
+//! Run with `cargo bench --bench deflate_bench`.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use binance_json_parsing::{
+    ParsingConfig, deflate::Inflater, detect_config, parse_book_ticker_dynamic,
+};
+
+const FIXTURE: &str = include_str!("../tests/fixtures/book_ticker.jsonl");
+/// The fixture compressed as the server would, see `tests/deflate.rs`.
+const COMPRESSED: &[u8] = include_bytes!("../tests/fixtures/book_ticker.deflate");
+
+/// How long each runs for, after warming up for a tenth of it.
+const DURATION: Duration = Duration::from_secs(2);
+
+fn messages() -> Vec<&'static [u8]> {
+    let mut messages = Vec::new();
+    let mut rest = COMPRESSED;
+    while let Some((len, tail)) = rest.split_first_chunk::<4>() {
+        let (message, tail) = tail.split_at(u32::from_le_bytes(*len) as usize);
+        messages.push(message);
+        rest = tail;
+    }
+    messages
+}
+
+/// Runs `round`, going through every message once, reporting against their `wire_bytes`.
+fn bench(name: &str, wire_bytes: usize, mut round: impl FnMut()) {
+    let mut run = |duration| {
+        let start = Instant::now();
+        let mut rounds = 0u64;
+        while start.elapsed() < duration {
+            round();
+            rounds += 1;
+        }
+        (start.elapsed(), rounds)
+    };
+    run(DURATION / 10);
+    let (elapsed, rounds) = run(DURATION);
+
+    let messages = rounds * FIXTURE.lines().count() as u64;
+    let ns = elapsed.as_nanos() as f64 / messages as f64;
+    let wire_kb = wire_bytes as f64 / 1e3;
+    println!("{name:<12} {ns:>8.1} ns/msg {wire_kb:>8.1} kB over the wire");
+}
+
+fn main() {
+    let filter = std::env::args().skip(1).find(|arg| !arg.starts_with('-'));
+    let enabled = |name: &str| filter.as_deref().is_none_or(|filter| name.contains(filter));
+
+    // Detected upfront, as the client does on the first message of each symbol.
+    let payloads: Vec<(&str, ParsingConfig)> = FIXTURE
+        .lines()
+        .map(|json| (json, detect_config(json).unwrap()))
+        .collect();
+    let messages = messages();
+    assert_eq!(messages.len(), payloads.len());
+
+    if enabled("plain") {
+        bench("plain", FIXTURE.len(), || {
+            for &(json, config) in &payloads {
+                black_box(parse_book_ticker_dynamic(black_box(json), config).unwrap());
+            }
+        });
+    }
+    if enabled("deflate") {
+        let mut json = Vec::new();
+        bench("deflate", COMPRESSED.len(), || {
+            // Each round starts over from the first message, which refers back to nothing.
+            let mut inflater = Inflater::new();
+            for (message, &(_, config)) in messages.iter().zip(&payloads) {
+                json.clear();
+                inflater
+                    .inflate_message(black_box(message), &mut json, 1 << 20)
+                    .unwrap();
+                let json = std::str::from_utf8(&json).unwrap();
+                black_box(parse_book_ticker_dynamic(json, config).unwrap());
+            }
+        });
+    }
+}
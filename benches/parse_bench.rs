@@ -0,0 +1,117 @@
+//! Parsing the same book ticker payloads with each parser, reporting latency per message and
+//! throughput.
+//!
+//! Run with `cargo bench --bench parse_bench`, adding `--features sonic` for sonic-rs.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use binance_json_parsing::{
+    BookTicker, BookTickerOwned, DECIMAL_CAPACITY, ParsingConfig, detect_config,
+    parse_book_ticker_dynamic, small_string::SmallString,
+};
+
+/// Different symbols, price precisions and volumes, integer ones included.
+const PAYLOADS: [&str; 10] = [
+    r#"{"e":"bookTicker","u":7283946601071,"s":"BTCUSDT","b":"83604.80","B":"10.746","a":"83604.90","A":"9.514","T":1744760290967,"E":1744760290968}"#,
+    r#"{"e":"bookTicker","u":7283946603312,"s":"ETHUSDT","b":"1589.37","B":"112.294","a":"1589.38","A":"27.310","T":1744760291012,"E":1744760291014}"#,
+    r#"{"e":"bookTicker","u":7283946605501,"s":"SOLUSDT","b":"131.1500","B":"43","a":"131.1600","A":"388","T":1744760291201,"E":1744760291202}"#,
+    r#"{"e":"bookTicker","u":7283946604877,"s":"DOGEUSDT","b":"0.156210","B":"51230","a":"0.156220","A":"187442","T":1744760291133,"E":1744760291135}"#,
+    r#"{"e":"bookTicker","u":7283946604120,"s":"1000PEPEUSDT","b":"0.0074136","B":"1843782","a":"0.0074137","A":"2641239","T":1744760291050,"E":1744760291051}"#,
+    r#"{"e":"bookTicker","u":7283946605790,"s":"XRPUSDT","b":"2.1043","B":"2712.4","a":"2.1044","A":"96.1","T":1744760291240,"E":1744760291241}"#,
+    r#"{"e":"bookTicker","u":7283946606013,"s":"BNBUSDT","b":"585.710","B":"3.46","a":"585.720","A":"41.07","T":1744760291266,"E":1744760291268}"#,
+    r#"{"e":"bookTicker","u":7283946606352,"s":"LTCUSDT","b":"76.27","B":"87.318","a":"76.28","A":"5.904","T":1744760291302,"E":1744760291302}"#,
+    r#"{"e":"bookTicker","u":7283946606780,"s":"1000SHIBUSDT","b":"0.012188","B":"208811","a":"0.012189","A":"39508","T":1744760291347,"E":1744760291349}"#,
+    r#"{"e":"bookTicker","u":7283946607101,"s":"AVAXUSDT","b":"19.6310","B":"70","a":"19.6320","A":"1458","T":1744760291390,"E":1744760291391}"#,
+];
+
+// The four prices and volumes of an owned ticker are kept inline and copied around as one.
+const _: () = {
+    const fn assert_copy<T: Copy>() {}
+    assert_copy::<SmallString<DECIMAL_CAPACITY>>();
+    assert!(4 * size_of::<SmallString<DECIMAL_CAPACITY>>() <= 64);
+};
+
+/// How long each parser runs for, after warming up for a tenth of it.
+const DURATION: Duration = Duration::from_secs(2);
+
+fn bench(name: &str, mut parse: impl FnMut(usize, &str)) {
+    let bytes: usize = PAYLOADS.iter().map(|json| json.len()).sum();
+    let mut run = |duration| {
+        let start = Instant::now();
+        let mut rounds = 0u64;
+        while start.elapsed() < duration {
+            for (i, json) in PAYLOADS.iter().enumerate() {
+                parse(i, black_box(json));
+            }
+            rounds += 1;
+        }
+        (start.elapsed(), rounds)
+    };
+    run(DURATION / 10);
+    let (elapsed, rounds) = run(DURATION);
+
+    let messages = rounds * PAYLOADS.len() as u64;
+    let ns = elapsed.as_nanos() as f64 / messages as f64;
+    let mb_per_s = (rounds as usize * bytes) as f64 / elapsed.as_secs_f64() / 1e6;
+    println!("{name:<16} {ns:>8.1} ns/msg {mb_per_s:>10.1} MB/s");
+}
+
+fn main() {
+    // Cargo passes `--bench`, and a filter may follow, just like for the default harness.
+    let filter = std::env::args().skip(1).find(|arg| !arg.starts_with('-'));
+    let enabled = |name: &str| filter.as_deref().is_none_or(|filter| name.contains(filter));
+
+    if enabled("serde_json") {
+        bench("serde_json", |_, json| {
+            black_box(serde_json::from_str::<BookTicker>(json).unwrap());
+        });
+    }
+    #[cfg(feature = "sonic")]
+    if enabled("sonic_rs") {
+        bench("sonic_rs", |_, json| {
+            black_box(sonic_rs::from_str::<BookTicker>(json).unwrap());
+        });
+    }
+    // Parsers working in place, like simd-json, need a copy of the frame to modify first, which
+    // costs this much on top of the parsing itself.
+    if enabled("frame_copy") {
+        bench("frame_copy", |_, json| {
+            black_box(json.to_owned());
+        });
+    }
+    // The whole of `simd_json::from_str::<BookTickerOwned>(&mut frame)`: the copy, then a full
+    // deserialization out of it, to compare with `owned` below. simd-json itself can't be
+    // fetched, so the parsers at hand stand in for it, sonic-rs being SIMD-based too.
+    if enabled("copy_serde_json") {
+        bench("copy_serde_json", |_, json| {
+            let frame = black_box(json.to_owned());
+            black_box(serde_json::from_str::<BookTickerOwned>(&frame).unwrap());
+        });
+    }
+    #[cfg(feature = "sonic")]
+    if enabled("copy_sonic_rs") {
+        bench("copy_sonic_rs", |_, json| {
+            let frame = black_box(json.to_owned());
+            black_box(sonic_rs::from_str::<BookTickerOwned>(&frame).unwrap());
+        });
+    }
+    // Detected upfront, as the client does on the first message of each symbol.
+    let configs: Vec<ParsingConfig> = PAYLOADS
+        .iter()
+        .map(|json| detect_config(json).unwrap())
+        .collect();
+    if enabled("custom") {
+        bench("custom", |i, json| {
+            black_box(parse_book_ticker_dynamic(json, black_box(configs[i])).unwrap());
+        });
+    }
+    if enabled("owned") {
+        bench("owned", |i, json| {
+            let ticker = parse_book_ticker_dynamic(json, black_box(configs[i])).unwrap();
+            black_box(BookTickerOwned::try_from(ticker).unwrap());
+        });
+    }
+}
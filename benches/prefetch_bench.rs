@@ -0,0 +1,170 @@
+//! Parsing a batch of buffered messages, scattered over the heap like frames received one by one,
+//! with and without prefetching the next one first, reporting throughput and L1 data cache misses.
+//!
+//! Run with `cargo bench --bench prefetch_bench`. The misses are counted through
+//! `perf_event_open(2)`, so they're only reported on Linux when `perf_event_paranoid` allows it.
+//! The counter is read once per run through the kernel rather than with RDPMC, which would need
+//! its page mapped, since the cost of that one read doesn't show over a whole batch.
+
+use std::{hint::black_box, time::Instant};
+
+use binance_json_parsing::{
+    ParsingConfig, detect_config, parse_book_ticker_dynamic, prefetch::prefetch_bytes,
+};
+use rand::seq::SliceRandom;
+
+/// Enough for the batch not to fit in the last level cache.
+const MESSAGES: usize = 400_000;
+const ROUNDS: usize = 5;
+
+const PAYLOADS: &str = include_str!("../tests/fixtures/book_ticker.jsonl");
+
+/// L1 data cache read misses of this thread, in user space.
+#[cfg(target_os = "linux")]
+struct L1dMisses(libc::c_int);
+
+#[cfg(target_os = "linux")]
+impl L1dMisses {
+    const PERF_TYPE_HW_CACHE: u32 = 3;
+    /// `PERF_COUNT_HW_CACHE_L1D | PERF_COUNT_HW_CACHE_OP_READ << 8 |
+    /// PERF_COUNT_HW_CACHE_RESULT_MISS << 16`.
+    const L1D_READ_MISS: u64 = 1 << 16;
+    /// `disabled`, `exclude_kernel` and `exclude_hv`.
+    const FLAGS: u64 = 1 | 1 << 5 | 1 << 6;
+
+    const IOC_ENABLE: libc::c_ulong = 0x2400;
+    const IOC_DISABLE: libc::c_ulong = 0x2401;
+    const IOC_RESET: libc::c_ulong = 0x2403;
+
+    fn open() -> Option<Self> {
+        // The first version of `perf_event_attr`, the rest of the fields being zero anyway.
+        #[repr(C)]
+        struct Attr {
+            kind: u32,
+            size: u32,
+            config: u64,
+            sample_period: u64,
+            sample_type: u64,
+            read_format: u64,
+            flags: u64,
+            wakeup_events: u32,
+            bp_type: u32,
+            config1: u64,
+        }
+
+        let attr = Attr {
+            kind: Self::PERF_TYPE_HW_CACHE,
+            size: size_of::<Attr>() as u32,
+            config: Self::L1D_READ_MISS,
+            sample_period: 0,
+            sample_type: 0,
+            read_format: 0,
+            flags: Self::FLAGS,
+            wakeup_events: 0,
+            bp_type: 0,
+            config1: 0,
+        };
+        // This thread on any CPU, with no group.
+        let fd = unsafe { libc::syscall(libc::SYS_perf_event_open, &attr, 0, -1, -1, 0) };
+        (fd >= 0).then(|| Self(fd as libc::c_int))
+    }
+
+    fn start(&self) {
+        unsafe {
+            libc::ioctl(self.0, Self::IOC_RESET, 0);
+            libc::ioctl(self.0, Self::IOC_ENABLE, 0);
+        }
+    }
+
+    fn stop(&self) -> Option<u64> {
+        let mut count = 0u64;
+        let read = unsafe {
+            libc::ioctl(self.0, Self::IOC_DISABLE, 0);
+            libc::read(self.0, (&raw mut count).cast(), size_of::<u64>())
+        };
+        (read == size_of::<u64>() as isize).then_some(count)
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Drop for L1dMisses {
+    fn drop(&mut self) {
+        unsafe { libc::close(self.0) };
+    }
+}
+
+/// Never available.
+#[cfg(not(target_os = "linux"))]
+struct L1dMisses;
+
+#[cfg(not(target_os = "linux"))]
+impl L1dMisses {
+    fn open() -> Option<Self> {
+        None
+    }
+
+    fn start(&self) {}
+
+    fn stop(&self) -> Option<u64> {
+        None
+    }
+}
+
+fn bench(name: &str, counter: Option<&L1dMisses>, run: impl Fn()) {
+    run();
+    if let Some(counter) = counter {
+        counter.start();
+    }
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        run();
+    }
+    let elapsed = start.elapsed();
+    let misses = counter.and_then(L1dMisses::stop);
+
+    let messages = (ROUNDS * MESSAGES) as f64;
+    let ns = elapsed.as_nanos() as f64 / messages;
+    let misses = match misses {
+        Some(misses) => format!("{:>6.2} L1d misses/msg", misses as f64 / messages),
+        None => "L1d misses unknown".to_owned(),
+    };
+    println!("{name:<12} {ns:>6.1} ns/msg {misses}");
+}
+
+fn parse(message: &str, config: ParsingConfig) {
+    black_box(parse_book_ticker_dynamic(black_box(message), config).unwrap());
+}
+
+fn main() {
+    // Cargo passes `--bench`, and a filter may follow, just like for the default harness.
+    let filter = std::env::args().skip(1).find(|arg| !arg.starts_with('-'));
+    let enabled = |name: &str| filter.as_deref().is_none_or(|filter| name.contains(filter));
+
+    // Each allocated on its own, then shuffled so that the next one isn't the next in memory.
+    let mut messages: Vec<(String, ParsingConfig)> = PAYLOADS
+        .lines()
+        .cycle()
+        .take(MESSAGES)
+        .map(|json| (json.to_owned(), detect_config(json).unwrap()))
+        .collect();
+    messages.shuffle(&mut rand::rng());
+
+    let counter = L1dMisses::open();
+    if enabled("sequential") {
+        bench("sequential", counter.as_ref(), || {
+            for (message, config) in &messages {
+                parse(message, *config);
+            }
+        });
+    }
+    if enabled("prefetch") {
+        bench("prefetch", counter.as_ref(), || {
+            for (i, (message, config)) in messages.iter().enumerate() {
+                if let Some((next, _)) = messages.get(i + 1) {
+                    prefetch_bytes(next.as_bytes());
+                }
+                parse(message, *config);
+            }
+        });
+    }
+}
@@ -0,0 +1,60 @@
+//! Handing values from one thread to another through `SpscQueue`, which is expected to go well
+//! over 50M per second.
+//!
+//! Run with `cargo bench --bench queue_bench`.
+
+use std::{
+    hint::black_box,
+    thread,
+    time::{Duration, Instant},
+};
+
+use binance_json_parsing::queue::SpscQueue;
+
+const COUNT: u64 = 20_000_000;
+
+fn bench<const N: usize>(name: &str) {
+    let (mut producer, mut consumer) = SpscQueue::<u64, N>::new().split();
+    let start = Instant::now();
+    let pushing = thread::spawn(move || {
+        for mut value in 0..COUNT {
+            while let Err(rejected) = producer.try_push(value) {
+                value = rejected;
+                thread::yield_now();
+            }
+        }
+    });
+
+    let mut popped = 0;
+    while popped < COUNT {
+        match consumer.try_pop() {
+            Some(value) => {
+                black_box(value);
+                popped += 1;
+            }
+            None => thread::yield_now(),
+        }
+    }
+    pushing.join().unwrap();
+    report(name, start.elapsed());
+}
+
+fn report(name: &str, elapsed: Duration) {
+    let ops = COUNT as f64 / elapsed.as_secs_f64() / 1e6;
+    println!("{name:<12} {ops:>7.1}M ops/s");
+}
+
+fn main() {
+    let filter = std::env::args().skip(1).find(|arg| !arg.starts_with('-'));
+    let enabled = |name: &str| filter.as_deref().is_none_or(|filter| name.contains(filter));
+
+    if enabled("slots_64") {
+        bench::<64>("slots_64");
+    }
+    if enabled("slots_1024") {
+        bench::<1024>("slots_1024");
+    }
+    if enabled("slots_65536") {
+        bench::<65536>("slots_65536");
+    }
+}
@@ -0,0 +1,56 @@
+//! Parsing 13-digit timestamps eight digits at a time against `str::parse`.
+//!
+//! Run with `cargo bench --bench timestamp_bench`.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use binance_json_parsing::swar::{all_digits, parse_u64_fixed_digits};
+
+const DURATION: Duration = Duration::from_secs(1);
+
+fn bench(name: &str, timestamps: &[String], parse: impl Fn(&str) -> u64) {
+    let run = |duration| {
+        let start = Instant::now();
+        let mut rounds = 0u64;
+        while start.elapsed() < duration {
+            for timestamp in timestamps {
+                black_box(parse(black_box(timestamp)));
+            }
+            rounds += 1;
+        }
+        (start.elapsed(), rounds)
+    };
+    run(DURATION / 10);
+    let (elapsed, rounds) = run(DURATION);
+
+    let ns = elapsed.as_nanos() as f64 / (rounds * timestamps.len() as u64) as f64;
+    println!("{name:<12} {ns:>6.2} ns/timestamp");
+}
+
+fn main() {
+    let filter = std::env::args().skip(1).find(|arg| !arg.starts_with('-'));
+    let enabled = |name: &str| filter.as_deref().is_none_or(|filter| name.contains(filter));
+
+    let timestamps: Vec<_> = (0..1000u64)
+        .map(|i| (1_744_760_290_967 + i * 7_919).to_string())
+        .collect();
+
+    if enabled("str_parse") {
+        bench("str_parse", &timestamps, |s| s.parse().unwrap());
+    }
+    if enabled("swar") {
+        bench("swar", &timestamps, |s| {
+            parse_u64_fixed_digits(s.as_bytes(), 13)
+        });
+    }
+    // As the parser does it, checking the digits first.
+    if enabled("swar_checked") {
+        bench("swar_checked", &timestamps, |s| {
+            assert!(all_digits(s.as_bytes(), 13));
+            parse_u64_fixed_digits(s.as_bytes(), 13)
+        });
+    }
+}
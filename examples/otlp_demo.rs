@@ -0,0 +1,151 @@
+//! The `parse_book_ticker` spans of [`parse_book_ticker_traced`] sent to an OpenTelemetry
+//! collector over OTLP/HTTP, in its JSON encoding.
+//!
+//! Run with `cargo run --example otlp_demo --features opentelemetry [path]`, parsing JSON lines
+//! of book tickers from `path` or the test fixture without one. The spans go to
+//! `$OTEL_EXPORTER_OTLP_ENDPOINT/v1/traces`, by default `http://localhost:4318/v1/traces`.
+//!
+//! The exporter here is just enough for the demo, a plain HTTP request of every span at once. In
+//! a service the [`Tracer`] would rather wrap the tracer of the `opentelemetry` crate, batching
+//! and exporting them on a thread of its own.
+
+use std::{
+    cell::RefCell,
+    io::{Read, Write},
+    net::TcpStream,
+    rc::Rc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use binance_json_parsing::{
+    detect_config,
+    trace::{Attribute, Span, Tracer, parse_book_ticker_traced},
+};
+use serde_json::{Value, json};
+
+/// Collects the ended spans in the OTLP JSON encoding, to be sent by [`OtlpTracer::export`].
+struct OtlpTracer {
+    trace_id: String,
+    spans: Rc<RefCell<Vec<Value>>>,
+}
+
+struct OtlpSpan {
+    span: Value,
+    spans: Rc<RefCell<Vec<Value>>>,
+}
+
+fn now_nanos() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+}
+
+impl OtlpTracer {
+    fn new() -> Self {
+        // One trace for the whole run, unique enough for a demo.
+        let trace_id = format!(
+            "{:032x}",
+            now_nanos() ^ (u128::from(std::process::id()) << 96)
+        );
+        Self {
+            trace_id,
+            spans: Rc::default(),
+        }
+    }
+
+    fn export(&self, endpoint: &str) -> std::io::Result<String> {
+        let body = json!({
+            "resourceSpans": [{
+                "resource": {
+                    "attributes": [{
+                        "key": "service.name",
+                        "value": { "stringValue": "otlp_demo" },
+                    }],
+                },
+                "scopeSpans": [{
+                    "scope": { "name": "binance_json_parsing" },
+                    "spans": *self.spans.borrow(),
+                }],
+            }],
+        })
+        .to_string();
+
+        let authority = endpoint
+            .strip_prefix("http://")
+            .ok_or_else(|| std::io::Error::other("only http:// endpoints are supported"))?
+            .trim_end_matches('/');
+        let mut stream = TcpStream::connect(authority)?;
+        write!(
+            stream,
+            "POST /v1/traces HTTP/1.1\r\nHost: {authority}\r\nContent-Type: application/json\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response)?;
+        Ok(response.lines().next().unwrap_or_default().to_owned())
+    }
+}
+
+impl Tracer for OtlpTracer {
+    type Span = OtlpSpan;
+
+    fn start(&self, name: &'static str) -> OtlpSpan {
+        let span_id = self.spans.borrow().len() + 1;
+        OtlpSpan {
+            span: json!({
+                "traceId": self.trace_id,
+                "spanId": format!("{span_id:016x}"),
+                "name": name,
+                // SPAN_KIND_INTERNAL
+                "kind": 1,
+                "startTimeUnixNano": now_nanos().to_string(),
+                "attributes": [],
+            }),
+            spans: Rc::clone(&self.spans),
+        }
+    }
+}
+
+impl Span for OtlpSpan {
+    fn set_attribute(&mut self, key: &'static str, value: Attribute<'_>) {
+        let value = match value {
+            Attribute::Str(value) => json!({ "stringValue": value }),
+            // 64-bit integers are strings in the JSON encoding.
+            Attribute::U64(value) => json!({ "intValue": value.to_string() }),
+            _ => return,
+        };
+        self.span["attributes"]
+            .as_array_mut()
+            .unwrap()
+            .push(json!({ "key": key, "value": value }));
+    }
+
+    fn end(mut self) {
+        self.span["endTimeUnixNano"] = now_nanos().to_string().into();
+        self.spans.borrow_mut().push(self.span);
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let payloads = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../tests/fixtures/book_ticker.jsonl").to_owned(),
+    };
+    let endpoint = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT")
+        .unwrap_or_else(|_| "http://localhost:4318".to_owned());
+
+    let tracer = OtlpTracer::new();
+    for json in payloads.lines() {
+        parse_book_ticker_traced(json, detect_config(json)?, &tracer)?;
+    }
+
+    let spans = tracer.spans.borrow().len();
+    let status = tracer.export(&endpoint)?;
+    println!(
+        "sent {spans} spans of trace {} to {endpoint}: {status}",
+        tracer.trace_id
+    );
+    Ok(())
+}
@@ -0,0 +1,30 @@
+//! Printing the book tickers of a stream as they come, straight from the frames they're parsed
+//! from.
+//!
+//! Run with `cargo run --example print_handler [url]`, defaulting to the BTCUSDT stream.
+
+use binance_json_parsing::{
+    BookTicker, ParseError, ParsingConfig,
+    handler::{TickHandler, run_with_handler},
+};
+
+/// Prints every tick and error.
+struct PrintHandler;
+
+impl TickHandler for PrintHandler {
+    fn on_tick(&mut self, tick: BookTicker<'_>) {
+        println!("{tick:?}");
+    }
+
+    fn on_error(&mut self, err: ParseError) {
+        eprintln!("failed to parse book ticker: {err}");
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let url = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "wss://fstream.binance.com/ws/btcusdt@bookTicker".to_owned());
+    run_with_handler(&url, ParsingConfig::default(), &mut PrintHandler).await
+}
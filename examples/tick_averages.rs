@@ -0,0 +1,29 @@
+//! The average bid and ask prices of the recorded ticks, taken over their columns.
+//!
+//! Run with `cargo run --example tick_averages [path]`, reading JSON lines of book tickers from
+//! `path` or the test fixture without one.
+
+use binance_json_parsing::{
+    BookTickerOwned, columnar::to_columns, detect_config, parse_book_ticker_dynamic,
+};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let payloads = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../tests/fixtures/book_ticker.jsonl").to_owned(),
+    };
+    let ticks = payloads
+        .lines()
+        .map(|json| {
+            let ticker = parse_book_ticker_dynamic(json, detect_config(json)?)?;
+            BookTickerOwned::try_from(ticker)
+        })
+        .collect::<Result<Vec<_>, binance_json_parsing::ParseError>>()?;
+
+    let columns = to_columns(&ticks)?;
+    let avg = |column: &[f64]| column.iter().sum::<f64>() / column.len() as f64;
+    println!("rows   {}", columns.len());
+    println!("avg b  {}", avg(&columns.b));
+    println!("avg a  {}", avg(&columns.a));
+    Ok(())
+}
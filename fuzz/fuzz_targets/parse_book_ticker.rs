@@ -0,0 +1,38 @@
+//! Run with `cargo +nightly fuzz run parse_book_ticker` from the repository root.
+
+#![no_main]
+
+use binance_json_parsing::{
+    ParsingConfig, SchemaVersion, parse_book_ticker_dynamic, parse_book_ticker_fixed,
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // The first bytes pick the config, so that both the configured path and the fallback to
+    // detection get exercised. The corpus is raw payloads, which just makes for odd configs.
+    let [start, price_precision, volume_precision, time_digits, symbol_len, payload @ ..] = data
+    else {
+        return;
+    };
+    let json = match std::str::from_utf8(payload) {
+        Ok(json) => json,
+        Err(err) => std::str::from_utf8(&payload[..err.valid_up_to()]).unwrap(),
+    };
+    let config = ParsingConfig::new(
+        *start as usize,
+        *price_precision as usize % 16,
+        *volume_precision as usize % 16,
+        *time_digits as usize % 24,
+        *symbol_len as usize % 32,
+        // The bits left over from the symbol length.
+        [
+            SchemaVersion::Futures,
+            SchemaVersion::CoinMFutures,
+            SchemaVersion::Spot,
+        ][*symbol_len as usize / 32 % 3],
+    );
+
+    // Anything goes as long as it's an error rather than a panic.
+    let _ = parse_book_ticker_dynamic(json, config);
+    let _ = parse_book_ticker_fixed(json, config);
+});
@@ -0,0 +1,134 @@
+#ifndef BINANCE_JSON_PARSING_H
+#define BINANCE_JSON_PARSING_H
+
+/* Generated by cbindgen from src/ffi.rs, don't edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Capacity of [`CBookTicker::e`], including the terminating NUL.
+ */
+#define BJP_EVENT_CAP 16
+
+/**
+ * Capacity of [`CBookTicker::s`], including the terminating NUL.
+ */
+#define BJP_SYMBOL_CAP 32
+
+/**
+ * Capacity of the price and quantity fields of [`CBookTicker`], including the terminating NUL.
+ */
+#define BJP_DECIMAL_CAP 32
+
+/**
+ * Parsed successfully.
+ */
+#define BJP_OK 0
+
+/**
+ * `json` or `out` is null.
+ */
+#define BJP_ERR_NULL -1
+
+/**
+ * The payload isn't valid UTF-8.
+ */
+#define BJP_ERR_UTF8 -2
+
+/**
+ * A field is missing, see [`ParseError::FieldNotFound`].
+ */
+#define BJP_ERR_FIELD_NOT_FOUND -3
+
+/**
+ * A field ends unexpectedly, see [`ParseError::InvalidBoundary`].
+ */
+#define BJP_ERR_INVALID_BOUNDARY -4
+
+/**
+ * A timestamp, id or decimal isn't a number.
+ */
+#define BJP_ERR_NUMBER -5
+
+/**
+ * A string field doesn't fit into its array in [`CBookTicker`].
+ */
+#define BJP_ERR_TOO_LONG -6
+
+/**
+ * [`CParsingConfig::schema_version`] isn't one of the `BJP_SCHEMA_*` values.
+ */
+#define BJP_ERR_SCHEMA -7
+
+/**
+ * The payload is another event or a response, see [`ParseError::WrongEventType`].
+ */
+#define BJP_ERR_WRONG_EVENT -8
+
+/**
+ * [`SchemaVersion::Futures`].
+ */
+#define BJP_SCHEMA_FUTURES 0
+
+/**
+ * [`SchemaVersion::CoinMFutures`].
+ */
+#define BJP_SCHEMA_COIN_M_FUTURES 1
+
+/**
+ * [`SchemaVersion::Spot`].
+ */
+#define BJP_SCHEMA_SPOT 2
+
+/**
+ * Same as [`ParsingConfig`].
+ */
+typedef struct CParsingConfig {
+  size_t start;
+  size_t price_precision;
+  size_t volume_precision;
+  size_t transaction_time_digits;
+  size_t symbol_len;
+  /**
+   * One of the `BJP_SCHEMA_*` values.
+   */
+  uint8_t schema_version;
+} CParsingConfig;
+
+/**
+ * Same as [`BookTicker`](crate::BookTicker), with the strings copied into NUL-terminated
+ * arrays.
+ */
+typedef struct CBookTicker {
+  char e[BJP_EVENT_CAP];
+  uint64_t u;
+  char s[BJP_SYMBOL_CAP];
+  uint64_t T;
+  char b[BJP_DECIMAL_CAP];
+  char B[BJP_DECIMAL_CAP];
+  char a[BJP_DECIMAL_CAP];
+  char A[BJP_DECIMAL_CAP];
+  uint64_t E;
+} CBookTicker;
+
+/**
+ * Parses the `json_len` bytes at `json_ptr` into `out`, returning [`BJP_OK`] or one of the
+ * negative `BJP_ERR_*` codes, in which case `out` is left in an unspecified state.
+ *
+ * Like [`parse_book_ticker_dynamic`], falls back to detecting the config if `start` doesn't match.
+ *
+ * # Safety
+ *
+ * `json_ptr` has to point to `json_len` readable bytes, and `out` to a writable
+ * [`CBookTicker`], unless they're null.
+ */
+int32_t parse_book_ticker_c(const char *json_ptr,
+                            size_t json_len,
+                            struct CParsingConfig config,
+                            struct CBookTicker *out);
+
+#endif /* BINANCE_JSON_PARSING_H */
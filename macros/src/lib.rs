@@ -0,0 +1,114 @@
+//! Compile time checks for `binance-json-parsing`, re-exported from there.
+//!
+//! Kept as a separate crate so that the checks don't depend on the parser they're checking.
+
+#![no_std]
+
+/// Checks a `ParsingConfig` against an example payload at compile time, failing the build if
+/// `start` doesn't point at `b` or any precision, digit count or symbol length differs.
+///
+/// Both arguments have to be constants, and expand into a `const _: () = { ... };` item. See
+/// `ParsingConfig` for an example.
+#[macro_export]
+macro_rules! assert_config {
+    ($config:expr, $json:expr $(,)?) => {
+        const _: () = {
+            let config = $config;
+            let json: &[u8] = $json.as_bytes();
+            assert!(
+                json.len() > config.start && json[config.start] == b'b',
+                "`start` doesn't point at \"b\""
+            );
+            assert!(
+                $crate::__private::precision(json, config.start + 4) == config.price_precision,
+                "`price_precision` doesn't match the \"b\" value"
+            );
+            assert!(
+                $crate::__private::precision(
+                    json,
+                    $crate::__private::value_start(json, b"\"B\":\"")
+                ) == config.volume_precision,
+                "`volume_precision` doesn't match the \"B\" value"
+            );
+            // Spot has no times, and then has to have no digits either.
+            assert!(
+                match $crate::__private::find(json, b"\"T\":") {
+                    Some(start) => $crate::__private::digits(json, start),
+                    None => 0,
+                } == config.transaction_time_digits,
+                "`transaction_time_digits` doesn't match the \"T\" value"
+            );
+            assert!(
+                $crate::__private::quoted_len(
+                    json,
+                    $crate::__private::value_start(json, b"\"s\":\"")
+                ) == config.symbol_len,
+                "`symbol_len` doesn't match the \"s\" value"
+            );
+        };
+    };
+}
+
+/// Const counterparts of what `detect_config` does at runtime, panicking instead of returning
+/// errors since they only run during compilation.
+#[doc(hidden)]
+pub mod __private {
+    /// Where the value after the first `key` starts, if there's one.
+    pub const fn find(json: &[u8], key: &[u8]) -> Option<usize> {
+        let mut i = 0;
+        while i + key.len() <= json.len() {
+            let mut j = 0;
+            while j < key.len() && json[i + j] == key[j] {
+                j += 1;
+            }
+            if j == key.len() {
+                return Some(i + key.len());
+            }
+            i += 1;
+        }
+        None
+    }
+
+    /// Where the value after the first `key` starts.
+    pub const fn value_start(json: &[u8], key: &[u8]) -> usize {
+        match find(json, key) {
+            Some(start) => start,
+            None => panic!("field not found in the example payload"),
+        }
+    }
+
+    /// How many bytes there are from `start` up to the closing quote.
+    pub const fn quoted_len(json: &[u8], start: usize) -> usize {
+        let mut end = start;
+        while end < json.len() && json[end] != b'"' {
+            end += 1;
+        }
+        assert!(
+            end < json.len(),
+            "unterminated string in the example payload"
+        );
+        end - start
+    }
+
+    /// How many fractional digits the quoted decimal starting at `start` has.
+    pub const fn precision(json: &[u8], start: usize) -> usize {
+        let len = quoted_len(json, start);
+        let mut i = 0;
+        while i < len {
+            if json[start + i] == b'.' {
+                return len - i - 1;
+            }
+            i += 1;
+        }
+        0
+    }
+
+    /// How many digits there are in a row from `start`.
+    pub const fn digits(json: &[u8], start: usize) -> usize {
+        let mut end = start;
+        while end < json.len() && json[end].is_ascii_digit() {
+            end += 1;
+        }
+        end - start
+    }
+}
@@ -0,0 +1,205 @@
+//! Python bindings of the book ticker parser, installed with `pip install .` through maturin.
+//!
+//! Python strings own their contents, so the fields can't borrow the payload like
+//! [`binance_json_parsing::BookTicker`] does. Instead the parsed ticker keeps the payload and
+//! the positions of its fields, and each field becomes a `str` only once it's accessed.
+
+#![allow(non_snake_case)]
+
+use std::ops::Range;
+
+use binance_json_parsing as parsing;
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyString};
+
+/// Same as [`parsing::ParsingConfig`], defaulting to the layout of BTCUSDT.
+#[pyclass(name = "ParsingConfig", eq)]
+#[derive(Clone, Copy, PartialEq)]
+struct ParsingConfig(parsing::ParsingConfig);
+
+#[pymethods]
+impl ParsingConfig {
+    #[new]
+    #[pyo3(signature = (
+        start = 51,
+        price_precision = 2,
+        volume_precision = 3,
+        transaction_time_digits = 13,
+        symbol_len = 7,
+    ))]
+    fn new(
+        start: usize,
+        price_precision: usize,
+        volume_precision: usize,
+        transaction_time_digits: usize,
+        symbol_len: usize,
+    ) -> Self {
+        Self(parsing::ParsingConfig::new(
+            start,
+            price_precision,
+            volume_precision,
+            transaction_time_digits,
+            symbol_len,
+            // Only futures can be configured by hand, others are left to `detect_config`.
+            parsing::SchemaVersion::Futures,
+        ))
+    }
+
+    #[getter]
+    fn start(&self) -> usize {
+        self.0.start
+    }
+
+    #[getter]
+    fn price_precision(&self) -> usize {
+        self.0.price_precision
+    }
+
+    #[getter]
+    fn volume_precision(&self) -> usize {
+        self.0.volume_precision
+    }
+
+    #[getter]
+    fn transaction_time_digits(&self) -> usize {
+        self.0.transaction_time_digits
+    }
+
+    #[getter]
+    fn symbol_len(&self) -> usize {
+        self.0.symbol_len
+    }
+
+    fn __repr__(&self) -> String {
+        let parsing::ParsingConfig {
+            start,
+            price_precision,
+            volume_precision,
+            transaction_time_digits,
+            symbol_len,
+            ..
+        } = self.0;
+        format!(
+            "ParsingConfig(start={start}, price_precision={price_precision}, \
+             volume_precision={volume_precision}, \
+             transaction_time_digits={transaction_time_digits}, symbol_len={symbol_len})"
+        )
+    }
+}
+
+/// Best bid and ask of a symbol, with attributes named after the keys of the payload.
+#[pyclass(name = "BookTicker", frozen)]
+struct BookTicker {
+    json: Py<PyString>,
+    e: Range<usize>,
+    s: Range<usize>,
+    b: Range<usize>,
+    B: Range<usize>,
+    a: Range<usize>,
+    A: Range<usize>,
+    #[pyo3(get)]
+    u: u64,
+    #[pyo3(get)]
+    T: u64,
+    #[pyo3(get)]
+    E: u64,
+}
+
+impl BookTicker {
+    fn field<'py>(&self, py: Python<'py>, range: &Range<usize>) -> PyResult<Bound<'py, PyString>> {
+        let json = self.json.bind(py).to_str()?;
+        Ok(PyString::new(py, &json[range.clone()]))
+    }
+}
+
+#[pymethods]
+impl BookTicker {
+    #[getter]
+    fn e<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyString>> {
+        self.field(py, &self.e)
+    }
+
+    #[getter]
+    fn s<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyString>> {
+        self.field(py, &self.s)
+    }
+
+    #[getter]
+    fn b<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyString>> {
+        self.field(py, &self.b)
+    }
+
+    #[getter]
+    fn B<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyString>> {
+        self.field(py, &self.B)
+    }
+
+    #[getter]
+    fn a<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyString>> {
+        self.field(py, &self.a)
+    }
+
+    #[getter]
+    fn A<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyString>> {
+        self.field(py, &self.A)
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(format!(
+            "BookTicker(s={}, b={}, B={}, a={}, A={}, T={})",
+            self.s(py)?,
+            self.b(py)?,
+            self.B(py)?,
+            self.a(py)?,
+            self.A(py)?,
+            self.T
+        ))
+    }
+}
+
+fn value_error(err: parsing::ParseError) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
+/// Where `field` is within `json`, which it borrows from.
+fn range_of(json: &str, field: &str) -> Range<usize> {
+    let start = field.as_ptr() as usize - json.as_ptr() as usize;
+    start..start + field.len()
+}
+
+/// Scans `json` for the config to pass to [`parse_book_ticker`], which is meant to be reused
+/// for the following payloads of the same symbol.
+#[pyfunction]
+fn detect_config(json: &str) -> PyResult<ParsingConfig> {
+    parsing::detect_config(json)
+        .map(ParsingConfig)
+        .map_err(value_error)
+}
+
+/// Parses `json` with `config`, raising `ValueError` if it doesn't match the layout.
+#[pyfunction]
+fn parse_book_ticker(json: Bound<'_, PyString>, config: ParsingConfig) -> PyResult<BookTicker> {
+    // Borrows the UTF-8 buffer of the string rather than copying it.
+    let text = json.to_str()?;
+    let ticker = parsing::parse_book_ticker_dynamic(text, config.0).map_err(value_error)?;
+    Ok(BookTicker {
+        e: range_of(text, ticker.e),
+        s: range_of(text, ticker.s),
+        b: range_of(text, ticker.b),
+        B: range_of(text, ticker.B),
+        a: range_of(text, ticker.a),
+        A: range_of(text, ticker.A),
+        u: ticker.u,
+        T: ticker.T,
+        E: ticker.E,
+        json: json.clone().unbind(),
+    })
+}
+
+#[pymodule]
+fn binance_json_parsing(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<ParsingConfig>()?;
+    module.add_class::<BookTicker>()?;
+    module.add_function(wrap_pyfunction!(detect_config, module)?)?;
+    module.add_function(wrap_pyfunction!(parse_book_ticker, module)?)?;
+    Ok(())
+}
@@ -0,0 +1,48 @@
+"""The bindings against json.loads, on the same kind of payloads as tests/differential.rs."""
+
+import json
+from pathlib import Path
+
+import pytest
+
+from binance_json_parsing import BookTicker, ParsingConfig, detect_config, parse_book_ticker
+
+FIXTURE = Path(__file__).parents[2] / "tests" / "fixtures" / "book_ticker.jsonl"
+
+BTCUSDT = (
+    '{"e":"bookTicker","u":7283946601071,"s":"BTCUSDT","b":"83604.80","B":"10.746",'
+    '"a":"83604.90","A":"9.514","T":1744760290967,"E":1744760290968}'
+)
+
+FIELDS = ["e", "u", "s", "b", "B", "a", "A", "T", "E"]
+
+
+def payloads():
+    return [line for line in FIXTURE.read_text().splitlines() if line]
+
+
+def test_default_config_is_btcusdt():
+    assert detect_config(BTCUSDT) == ParsingConfig()
+
+
+def test_parses_btcusdt():
+    ticker = parse_book_ticker(BTCUSDT, ParsingConfig())
+
+    assert isinstance(ticker, BookTicker)
+    assert ticker.s == "BTCUSDT"
+    assert (ticker.b, ticker.B) == ("83604.80", "10.746")
+    assert (ticker.a, ticker.A) == ("83604.90", "9.514")
+    assert ticker.T == 1744760290967
+
+
+@pytest.mark.parametrize("payload", payloads())
+def test_agrees_with_json_loads(payload):
+    ticker = parse_book_ticker(payload, detect_config(payload))
+    expected = json.loads(payload)
+
+    assert {field: getattr(ticker, field) for field in FIELDS} == expected
+
+
+def test_rejects_changed_precision():
+    with pytest.raises(ValueError):
+        parse_book_ticker(BTCUSDT, ParsingConfig(price_precision=3))
@@ -0,0 +1,95 @@
+//! Account updates of the user data stream, sent on balance or position changes.
+
+// {
+//   "e": "ACCOUNT_UPDATE",           // event type
+//   "E": 1744760291000,              // event time
+//   "T": 1744760290999,              // transaction time
+//   "a": {
+//     "m": "ORDER",                  // reason
+//     "B": [{
+//       "a": "USDT",                 // asset
+//       "wb": "122624.12345678",     // wallet balance
+//       "cw": "100.12345678",        // cross wallet balance
+//       "bc": "50.12345678"          // balance change except PnL and commission
+//     }],
+//     "P": [{
+//       "s": "BTCUSDT",              // symbol
+//       "pa": "0.014",               // position amount
+//       "ep": "83000.00",            // entry price
+//       "bep": "83010.50",           // breakeven price
+//       "cr": "200.00",              // accumulated realized
+//       "up": "8.51",                // unrealized PnL
+//       "mt": "cross",               // margin type
+//       "iw": "0",                   // isolated wallet
+//       "ps": "BOTH"                 // position side
+//     }]
+//   }
+// }
+//
+// Only sent on changes, so it's parsed by serde rather than by its layout.
+
+use alloc::vec::Vec;
+
+use crate::ParseError;
+
+/// Balances and positions changed by an event. Fields are named after their keys, see the
+/// layout above, with those of `a` moved to the top.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub struct AccountUpdate<'a> {
+    pub E: u64,
+    pub T: u64,
+    pub m: &'a str,
+    pub B: Vec<Balance<'a>>,
+    pub P: Vec<Position<'a>>,
+}
+
+/// Balance of an asset.
+#[derive(serde::Deserialize, Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub struct Balance<'a> {
+    pub a: &'a str,
+    pub wb: &'a str,
+    pub cw: &'a str,
+}
+
+/// Position in a symbol, negative amounts being shorts.
+#[derive(serde::Deserialize, Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub struct Position<'a> {
+    pub s: &'a str,
+    pub pa: &'a str,
+    pub up: &'a str,
+}
+
+#[derive(serde::Deserialize)]
+struct Event<'a> {
+    E: u64,
+    T: u64,
+    #[serde(borrow)]
+    a: Update<'a>,
+}
+
+#[derive(serde::Deserialize)]
+struct Update<'a> {
+    m: &'a str,
+    #[serde(borrow)]
+    B: Vec<Balance<'a>>,
+    #[serde(borrow)]
+    P: Vec<Position<'a>>,
+}
+
+/// Parses an `ACCOUNT_UPDATE` payload, borrowing its strings, which are never escaped.
+pub fn parse_account_update(json: &str) -> Result<AccountUpdate<'_>, ParseError> {
+    let event: Event = serde_json::from_str(json).map_err(|err| ParseError::InvalidJson {
+        line: err.line(),
+        column: err.column(),
+    })?;
+    Ok(AccountUpdate {
+        E: event.E,
+        T: event.T,
+        m: event.a.m,
+        B: event.a.B,
+        P: event.a.P,
+    })
+}
@@ -0,0 +1,74 @@
+//! Pinning the receiving thread to one core, so that it isn't migrated in the middle of a
+//! measurement and doesn't start over with cold caches.
+//!
+//! Only Linux supports it, elsewhere [`pin_to_core`] just warns and leaves the thread as is.
+
+use std::io;
+
+/// Why the calling thread couldn't be pinned.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum AffinityError {
+    /// There are only `cores` cores, numbered from 0.
+    OutOfRange { core_id: usize, cores: usize },
+    /// `sched_setaffinity` failed, e.g. because the core isn't in the cpuset of the process.
+    Syscall(io::Error),
+}
+
+impl core::fmt::Display for AffinityError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::OutOfRange { core_id, cores } => {
+                write!(f, "core {core_id} out of range of {cores} cores")
+            }
+            Self::Syscall(err) => write!(f, "failed to set the affinity: {err}"),
+        }
+    }
+}
+
+impl core::error::Error for AffinityError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::OutOfRange { .. } => None,
+            Self::Syscall(err) => Some(err),
+        }
+    }
+}
+
+/// Pins the calling thread to the core `core_id`, which is where it'll run from then on.
+#[cfg(target_os = "linux")]
+pub fn pin_to_core(core_id: usize) -> Result<(), AffinityError> {
+    // Every configured core, even those that are offline or outside the cpuset, which the
+    // syscall then rejects.
+    let cores = match unsafe { libc::sysconf(libc::_SC_NPROCESSORS_CONF) } {
+        -1 => return Err(AffinityError::Syscall(io::Error::last_os_error())),
+        cores => (cores as usize).min(libc::CPU_SETSIZE as usize),
+    };
+    if core_id >= cores {
+        return Err(AffinityError::OutOfRange { core_id, cores });
+    }
+
+    // SAFETY: `cpu_set_t` is a plain bit mask, and `core_id` is within it.
+    unsafe {
+        let mut set = core::mem::zeroed::<libc::cpu_set_t>();
+        libc::CPU_SET(core_id, &mut set);
+        // The calling thread, rather than the whole process.
+        if libc::sched_setaffinity(0, size_of::<libc::cpu_set_t>(), &set) != 0 {
+            return Err(AffinityError::Syscall(io::Error::last_os_error()));
+        }
+    }
+    Ok(())
+}
+
+/// Pins the calling thread to the core `core_id`, which is where it'll run from then on.
+#[cfg(not(target_os = "linux"))]
+pub fn pin_to_core(core_id: usize) -> Result<(), AffinityError> {
+    let cores = std::thread::available_parallelism().map_or(1, usize::from);
+    if core_id >= cores {
+        return Err(AffinityError::OutOfRange { core_id, cores });
+    }
+    crate::logging::log_warn!(
+        "pinning threads isn't supported here, not pinning to core {core_id}"
+    );
+    Ok(())
+}
@@ -0,0 +1,111 @@
+//! Aggregate trade stream, e.g. `btcusdt@aggTrade`.
+
+// {
+//   "e": "aggTrade",       // event type
+//   "E": 1744760290967,    // event time
+//   "a": 2686443975,       // aggregate trade id
+//   "s": "BTCUSDT",        // symbol
+//   "p": "83604.80",       // price
+//   "q": "0.019",          // quantity
+//   "f": 6186376619,       // first trade id
+//   "l": 6186376620,       // last trade id
+//   "T": 1744760290965,    // trade time
+//   "m": true              // is the buyer the market maker?
+// }
+
+use crate::{ParseError, expect_byte, id_field, parse_bool_field, quoted_end};
+
+/// Trades at the same price and taker side aggregated into one. Fields are named after their
+/// keys, see the layout above.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub struct AggTrade<'a> {
+    pub s: &'a str,
+    pub p: &'a str,
+    pub q: &'a str,
+    pub f: u64,
+    pub l: u64,
+    pub T: u64,
+    pub m: bool,
+}
+
+/// All parameters here change very rarely.
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub struct AggTradeConfig {
+    /// Position of `s`, as in "s":"
+    pub start: usize,
+    /// How many bytes are in the symbol, e.g. 7 for BTCUSDT.
+    pub symbol_len: usize,
+    /// How many digits are in the trade time.
+    pub transaction_time_digits: usize,
+}
+
+impl AggTradeConfig {
+    /// Field by field, in the order they're declared in.
+    pub const fn new(start: usize, symbol_len: usize, transaction_time_digits: usize) -> Self {
+        Self {
+            start,
+            symbol_len,
+            transaction_time_digits,
+        }
+    }
+}
+
+/// Parses an aggregate trade payload laid out as described by `config`.
+pub fn parse_agg_trade(json: &str, config: AggTradeConfig) -> Result<AggTrade<'_>, ParseError> {
+    let AggTradeConfig {
+        start,
+        symbol_len,
+        transaction_time_digits,
+    } = config;
+    expect_byte(json, start, b's', "s")?;
+
+    // Skip 4 chars: s":"
+    let s_start = start + 4;
+    let s_end = s_start + symbol_len;
+    expect_byte(json, s_end, b'"', "s")?;
+    let s = json
+        .get(s_start..s_end)
+        .ok_or(ParseError::FieldNotFound("s"))?;
+
+    // Unlike in book ticker, the number of fractional digits isn't relied upon here, so look for
+    // the closing quote instead.
+    // Skip 7 chars: ","p":"
+    let p_start = s_end + 7;
+    let p_end = quoted_end(json, p_start, "p")?;
+    let p = &json[p_start..p_end];
+
+    // Skip 7 chars: ","q":"
+    let q_start = p_end + 7;
+    let q_end = quoted_end(json, q_start, "q")?;
+    let q = &json[q_start..q_end];
+
+    // Skip 6 chars: ","f":
+    let (f, f_end) = id_field(json, q_end + 6, b',', "f")?;
+    // Skip 5 chars: ,"l":
+    let (l, l_end) = id_field(json, f_end + 5, b',', "l")?;
+
+    // Skip 5 chars: ,"T":
+    let T_start = l_end + 5;
+    let T_end = T_start + transaction_time_digits;
+    expect_byte(json, T_end, b',', "T")?;
+    let T = json
+        .get(T_start..T_end)
+        .ok_or(ParseError::FieldNotFound("T"))?
+        .parse()
+        .map_err(ParseError::TimestampParse)?;
+
+    // Skip 5 chars: ,"m":
+    let m = parse_bool_field(json, T_end + 5)?;
+
+    Ok(AggTrade {
+        s,
+        p,
+        q,
+        f,
+        l,
+        T,
+        m,
+    })
+}
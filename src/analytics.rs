@@ -0,0 +1,396 @@
+//! Signals derived from the state of the book and the trades.
+
+use alloc::{boxed::Box, collections::VecDeque};
+
+use crate::{
+    ParseError,
+    agg_trade::AggTrade,
+    decimal::{FixedPoint, parse_fixed},
+    order_book::OrderBook,
+    parser::BookTicker,
+};
+
+/// Bid-ask imbalance `(bid_vol - ask_vol) / (bid_vol + ask_vol)` over the best `levels` levels
+/// of each side, from -1 with only asks to 1 with only bids, or NaN if the book is empty.
+pub fn imbalance(book: &OrderBook, levels: usize) -> f64 {
+    let bid_vol = total(book.bids.values().take(levels)).to_f64();
+    let ask_vol = total(book.asks.values().take(levels)).to_f64();
+    // 0 / 0 already is NaN, which is what an empty book gets.
+    (bid_vol - ask_vol) / (bid_vol + ask_vol)
+}
+
+fn total<'a>(quantities: impl Iterator<Item = &'a FixedPoint>) -> FixedPoint {
+    let zero = FixedPoint {
+        mantissa: 0,
+        exponent: 0,
+    };
+    quantities.fold(zero, |sum, &qty| sum + qty)
+}
+
+/// Distance between `bid` and `ask` in basis points of the mid price.
+pub fn spread_bps(bid: FixedPoint, ask: FixedPoint) -> f64 {
+    // Both subtracted and added exactly first, so that only the ratio is rounded.
+    (ask - bid).to_f64() / (ask + bid).to_f64() * 20_000.0
+}
+
+/// Calls `on_wide` with the spread of ticks wider than `threshold_bps`, at most once per second,
+/// see [`SpreadAlert::check`].
+pub struct SpreadAlert {
+    pub threshold_bps: f64,
+    pub on_wide: Box<dyn Fn(f64) + Send>,
+    /// Time of the tick that last fired the alert, in milliseconds.
+    last_fired: Option<u64>,
+    /// What ticks with neither a transaction nor an event time are timed from.
+    #[cfg(feature = "std")]
+    created: std::time::Instant,
+}
+
+impl SpreadAlert {
+    /// How long after firing the alert stays quiet, in milliseconds.
+    pub const DEBOUNCE_MS: u64 = 1000;
+
+    pub fn new(threshold_bps: f64, on_wide: impl Fn(f64) + Send + 'static) -> Self {
+        Self {
+            threshold_bps,
+            on_wide: Box::new(on_wide),
+            last_fired: None,
+            #[cfg(feature = "std")]
+            created: std::time::Instant::now(),
+        }
+    }
+
+    /// Checks the spread of `tick`, returning whether the alert fired.
+    ///
+    /// Debounced on the transaction time of the ticks, or their event time without one. Spot
+    /// tickers have neither, so they're timed with a monotonic clock as they're checked instead,
+    /// or without `std` not debounced at all.
+    pub fn check(&mut self, tick: &BookTicker<'_>) -> Result<bool, ParseError> {
+        let spread = spread_bps(parse_fixed(tick.b)?, parse_fixed(tick.a)?);
+        if spread <= self.threshold_bps {
+            return Ok(false);
+        }
+        let now = match (tick.T, tick.E) {
+            (0, 0) => self.clock_ms(),
+            (0, E) => Some(E),
+            (T, _) => Some(T),
+        };
+        let debounced = self
+            .last_fired
+            .zip(now)
+            .is_some_and(|(last, now)| now < last.saturating_add(Self::DEBOUNCE_MS));
+        if debounced {
+            return Ok(false);
+        }
+        self.last_fired = now;
+        (self.on_wide)(spread);
+        Ok(true)
+    }
+
+    #[cfg(feature = "std")]
+    fn clock_ms(&self) -> Option<u64> {
+        Some(self.created.elapsed().as_millis() as u64)
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn clock_ms(&self) -> Option<u64> {
+        None
+    }
+}
+
+#[cfg(feature = "std")]
+impl crate::handler::TickHandler for SpreadAlert {
+    fn on_tick(&mut self, tick: BookTicker<'_>) {
+        if let Err(err) = self.check(&tick) {
+            self.on_error(err);
+        }
+    }
+}
+
+/// Volume weighted average price of the trades within the last `window_ms` milliseconds, as of
+/// the latest one.
+#[derive(Clone, Debug)]
+pub struct VwapAccumulator {
+    window_ms: u64,
+    /// Timestamp, notional and volume of each trade, oldest first.
+    trades: VecDeque<(u64, f64, f64)>,
+    notional: f64,
+    volume: f64,
+}
+
+impl VwapAccumulator {
+    pub fn new(window_ms: u64) -> Self {
+        Self {
+            window_ms,
+            trades: VecDeque::new(),
+            notional: 0.0,
+            volume: 0.0,
+        }
+    }
+
+    /// Adds a trade, evicting the ones that are now older than the window. Timestamps are
+    /// expected not to go backwards, as is the case within one stream.
+    pub fn update(&mut self, price: FixedPoint, volume: FixedPoint, timestamp_ms: u64) {
+        let volume = volume.to_f64();
+        let notional = price.to_f64() * volume;
+        self.trades.push_back((timestamp_ms, notional, volume));
+        self.notional += notional;
+        self.volume += volume;
+
+        while let Some(&(time, notional, volume)) = self.trades.front() {
+            if time.saturating_add(self.window_ms) > timestamp_ms {
+                break;
+            }
+            self.trades.pop_front();
+            self.notional -= notional;
+            self.volume -= volume;
+        }
+    }
+
+    /// Adds an aggregate trade at its trade time.
+    pub fn update_agg_trade(&mut self, trade: &AggTrade<'_>) -> Result<(), ParseError> {
+        self.update(parse_fixed(trade.p)?, parse_fixed(trade.q)?, trade.T);
+        Ok(())
+    }
+
+    /// `None` until there's some volume in the window.
+    pub fn vwap(&self) -> Option<f64> {
+        (self.volume > 0.0).then(|| self.notional / self.volume)
+    }
+}
+
+/// Exponential moving average, e.g. of the mid price.
+#[derive(Clone, Copy, Debug)]
+pub struct Ema {
+    /// Weight of each new value, in `(0, 1]`.
+    pub alpha: f64,
+    pub value: Option<f64>,
+}
+
+impl Ema {
+    pub fn new(alpha: f64) -> Self {
+        Self { alpha, value: None }
+    }
+
+    /// Starts from `x` itself the first time.
+    pub fn update(&mut self, x: f64) {
+        self.value = Some(match self.value {
+            Some(value) => value + self.alpha * (x - value),
+            None => x,
+        });
+    }
+
+    /// Updates with the mid price of `ticker`.
+    pub fn update_book_ticker(&mut self, ticker: &BookTicker<'_>) -> Result<(), ParseError> {
+        let bid = parse_fixed(ticker.b)?.to_f64();
+        let ask = parse_fixed(ticker.a)?.to_f64();
+        self.update((bid + ask) / 2.0);
+        Ok(())
+    }
+
+    pub fn get(&self) -> Option<f64> {
+        self.value
+    }
+}
+
+/// The alpha with which the weight of a value halves after `halflife_ticks` more updates, e.g.
+/// 50 for a halflife of 5s at 10 ticks per second.
+#[cfg(feature = "std")]
+pub fn alpha_from_halflife(halflife_ticks: f64) -> f64 {
+    1.0 - 0.5f64.powf(1.0 / halflife_ticks)
+}
+
+/// Watches `ask_a / bid_b - 1` of a pair of symbols, calling `on_divergence` with how many
+/// standard deviations the ratio is off its EMA whenever that's more than `threshold_sigma`.
+///
+/// The standard deviation is of the last `window` ratios, kept up to date with Welford's
+/// algorithm, and the alert stays quiet until there are that many. A diverging ratio is only
+/// taken into the averages after being checked, so that it doesn't hide itself.
+#[cfg(feature = "std")]
+pub struct SpreadMonitor {
+    pub symbol_a: String,
+    pub symbol_b: String,
+    pub ratio_ema: Ema,
+    pub threshold_sigma: f64,
+    pub on_divergence: Box<dyn Fn(f64) + Send>,
+    ratios: RollingVariance,
+    ask_a: Option<f64>,
+    bid_b: Option<f64>,
+}
+
+#[cfg(feature = "std")]
+impl SpreadMonitor {
+    pub fn new(
+        symbol_a: impl Into<String>,
+        symbol_b: impl Into<String>,
+        alpha: f64,
+        window: usize,
+        threshold_sigma: f64,
+        on_divergence: impl Fn(f64) + Send + 'static,
+    ) -> Self {
+        Self {
+            symbol_a: symbol_a.into(),
+            symbol_b: symbol_b.into(),
+            ratio_ema: Ema::new(alpha),
+            threshold_sigma,
+            on_divergence: Box::new(on_divergence),
+            ratios: RollingVariance::new(window),
+            ask_a: None,
+            bid_b: None,
+        }
+    }
+
+    /// Takes the ask of `symbol_a` or the bid of `symbol_b` from `tick`, ignoring other
+    /// symbols, and checks the ratio once both are known. Returns whether the alert fired.
+    pub fn update(&mut self, tick: &BookTicker<'_>) -> Result<bool, ParseError> {
+        if tick.s == self.symbol_a {
+            self.ask_a = Some(parse_fixed(tick.a)?.to_f64());
+        } else if tick.s == self.symbol_b {
+            self.bid_b = Some(parse_fixed(tick.b)?.to_f64());
+        } else {
+            return Ok(false);
+        }
+        let (Some(ask_a), Some(bid_b)) = (self.ask_a, self.bid_b) else {
+            return Ok(false);
+        };
+        let ratio = ask_a / bid_b - 1.0;
+
+        let sigmas = match (self.ratio_ema.get(), self.ratios.std_dev()) {
+            (Some(mean), Some(std_dev)) if self.ratios.is_full() && std_dev > 0.0 => {
+                (ratio - mean) / std_dev
+            }
+            _ => 0.0,
+        };
+        self.ratio_ema.update(ratio);
+        self.ratios.update(ratio);
+
+        let fired = sigmas.abs() > self.threshold_sigma;
+        if fired {
+            (self.on_divergence)(sigmas);
+        }
+        Ok(fired)
+    }
+
+    /// The latest `ask_a / bid_b - 1`, once both symbols have ticked.
+    pub fn ratio(&self) -> Option<f64> {
+        Some(self.ask_a? / self.bid_b? - 1.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl crate::handler::TickHandler for SpreadMonitor {
+    fn on_tick(&mut self, tick: BookTicker<'_>) {
+        if let Err(err) = self.update(&tick) {
+            self.on_error(err);
+        }
+    }
+}
+
+/// Mean and variance of the last `window` values, added and removed one at a time with
+/// Welford's algorithm rather than summed over the window again.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+struct RollingVariance {
+    window: usize,
+    values: VecDeque<f64>,
+    mean: f64,
+    /// Sum of the squared differences from the mean.
+    m2: f64,
+}
+
+#[cfg(feature = "std")]
+impl RollingVariance {
+    fn new(window: usize) -> Self {
+        assert!(window > 1, "window must hold at least two values");
+        Self {
+            window,
+            values: VecDeque::with_capacity(window + 1),
+            mean: 0.0,
+            m2: 0.0,
+        }
+    }
+
+    fn update(&mut self, x: f64) {
+        self.values.push_back(x);
+        let delta = x - self.mean;
+        self.mean += delta / self.values.len() as f64;
+        self.m2 += delta * (x - self.mean);
+
+        if self.values.len() > self.window {
+            let oldest = self.values.pop_front().unwrap();
+            let delta = oldest - self.mean;
+            self.mean -= delta / self.values.len() as f64;
+            // Rounding may take it just below zero otherwise.
+            self.m2 = (self.m2 - delta * (oldest - self.mean)).max(0.0);
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.values.len() == self.window
+    }
+
+    /// Sample standard deviation, once there are two values.
+    fn std_dev(&self) -> Option<f64> {
+        let n = self.values.len();
+        (n > 1).then(|| (self.m2 / (n - 1) as f64).sqrt())
+    }
+}
+
+/// Lowest and highest price of the last `window` ticks, kept in monotonic deques so that each
+/// update is O(1) amortized.
+#[derive(Clone, Debug)]
+pub struct RollingMinMax {
+    window: usize,
+    /// Tick indices and prices, increasing in price from the front.
+    deque_min: VecDeque<(usize, FixedPoint)>,
+    /// Tick indices and prices, decreasing in price from the front.
+    deque_max: VecDeque<(usize, FixedPoint)>,
+    /// Index of the next tick.
+    idx: usize,
+}
+
+impl RollingMinMax {
+    pub fn new(window: usize) -> Self {
+        assert!(window > 0, "window must hold at least one tick");
+        Self {
+            window,
+            deque_min: VecDeque::new(),
+            deque_max: VecDeque::new(),
+            idx: 0,
+        }
+    }
+
+    pub fn update(&mut self, price: FixedPoint) {
+        // Prices that can't be the extreme anymore, since this one outlives them.
+        while self.deque_min.back().is_some_and(|&(_, p)| p >= price) {
+            self.deque_min.pop_back();
+        }
+        while self.deque_max.back().is_some_and(|&(_, p)| p <= price) {
+            self.deque_max.pop_back();
+        }
+        self.deque_min.push_back((self.idx, price));
+        self.deque_max.push_back((self.idx, price));
+
+        // Each deque only gains one entry per tick, so at most one falls out of the window.
+        if self.idx >= self.window {
+            let oldest = self.idx - self.window;
+            if self.deque_min.front().is_some_and(|&(i, _)| i == oldest) {
+                self.deque_min.pop_front();
+            }
+            if self.deque_max.front().is_some_and(|&(i, _)| i == oldest) {
+                self.deque_max.pop_front();
+            }
+        }
+        self.idx += 1;
+    }
+
+    pub fn min(&self) -> Option<FixedPoint> {
+        self.deque_min.front().map(|&(_, price)| price)
+    }
+
+    pub fn max(&self) -> Option<FixedPoint> {
+        self.deque_max.front().map(|&(_, price)| price)
+    }
+}
+
+#[cfg(test)]
+mod tests;
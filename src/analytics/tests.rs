@@ -0,0 +1,380 @@
+use alloc::sync::Arc;
+use core::cmp::Reverse;
+use std::sync::Mutex;
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use super::*;
+use crate::{
+    agg_trade::AggTrade,
+    decimal::{FixedPoint, parse_fixed},
+    parse_book_ticker_dynamic,
+};
+
+/// Decimals written out here are always valid.
+fn fixed(s: &str) -> FixedPoint {
+    parse_fixed(s).unwrap()
+}
+
+fn book(bids: &[(&str, &str)], asks: &[(&str, &str)]) -> OrderBook {
+    OrderBook {
+        bids: bids
+            .iter()
+            .map(|&(price, qty)| (Reverse(fixed(price)), fixed(qty)))
+            .collect(),
+        asks: asks
+            .iter()
+            .map(|&(price, qty)| (fixed(price), fixed(qty)))
+            .collect(),
+        last_update_id: Some(1),
+    }
+}
+
+#[test]
+fn sums_top_levels() {
+    let book = book(
+        &[("100.0", "3"), ("99.5", "1.5"), ("99.0", "100")],
+        &[("100.5", "1"), ("101.0", "0.5"), ("102.0", "100")],
+    );
+
+    // (3 - 1) / (3 + 1)
+    assert_eq!(imbalance(&book, 1), 0.5);
+    // (4.5 - 1.5) / (4.5 + 1.5)
+    assert_eq!(imbalance(&book, 2), 0.5);
+    // (104.5 - 101.5) / (104.5 + 101.5)
+    assert_eq!(imbalance(&book, 3), 3.0 / 206.0);
+    // Same as 3, since there are no more levels.
+    assert_eq!(imbalance(&book, 10), imbalance(&book, 3));
+}
+
+#[test]
+fn is_one_sided_with_only_one_side() {
+    assert_eq!(imbalance(&book(&[("100.0", "3")], &[]), 5), 1.0);
+    assert_eq!(imbalance(&book(&[], &[("100.5", "2")]), 5), -1.0);
+}
+
+#[test]
+fn is_nan_when_empty() {
+    assert!(imbalance(&book(&[], &[]), 5).is_nan());
+    assert!(imbalance(&book(&[("100.0", "3")], &[("100.5", "1")]), 0).is_nan());
+}
+
+#[test]
+fn matches_book_ticker_at_one_level() {
+    let json = r#"{"e":"bookTicker","u":7283946601071,"s":"BTCUSDT","b":"83604.80","B":"10.746","a":"83604.90","A":"9.514","T":1744760290967,"E":1744760290968}"#;
+    let ticker = parse_book_ticker_dynamic(json, crate::ParsingConfig::default()).unwrap();
+    let book = book(&[(ticker.b, ticker.B)], &[(ticker.a, ticker.A)]);
+
+    let bid_vol: f64 = ticker.B.parse().unwrap();
+    let ask_vol: f64 = ticker.A.parse().unwrap();
+    assert_eq!(
+        imbalance(&book, 1),
+        (bid_vol - ask_vol) / (bid_vol + ask_vol)
+    );
+}
+
+fn assert_close(actual: Option<f64>, expected: f64) {
+    let actual = actual.unwrap();
+    assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+}
+
+#[test]
+fn vwap_of_window() {
+    let mut vwap = VwapAccumulator::new(1000);
+    assert_eq!(vwap.vwap(), None);
+
+    vwap.update(fixed("100.0"), fixed("1"), 0);
+    vwap.update(fixed("101.0"), fixed("3"), 400);
+    // (100 * 1 + 101 * 3) / 4
+    assert_close(vwap.vwap(), 100.75);
+
+    vwap.update(fixed("99.50"), fixed("0.5"), 999);
+    // (100 * 1 + 101 * 3 + 99.5 * 0.5) / 4.5
+    assert_close(vwap.vwap(), 452.75 / 4.5);
+
+    // The first trade is exactly a window old now.
+    vwap.update(fixed("102.0"), fixed("2"), 1000);
+    // (101 * 3 + 99.5 * 0.5 + 102 * 2) / 5.5
+    assert_close(vwap.vwap(), 556.75 / 5.5);
+
+    // Only the latest trade is left.
+    vwap.update(fixed("98.0"), fixed("1"), 5000);
+    assert_close(vwap.vwap(), 98.0);
+}
+
+#[test]
+fn vwap_of_agg_trades() {
+    let mut vwap = VwapAccumulator::new(60_000);
+    for (p, q, T) in [
+        ("83604.80", "0.019", 1744760290965),
+        ("83605.10", "0.381", 1744760291002),
+    ] {
+        vwap.update_agg_trade(&AggTrade {
+            s: "BTCUSDT",
+            p,
+            q,
+            f: 6186376619,
+            l: 6186376620,
+            T,
+            m: true,
+        })
+        .unwrap();
+    }
+    assert_close(vwap.vwap(), (83604.80 * 0.019 + 83605.10 * 0.381) / 0.4);
+}
+
+#[test]
+fn vwap_is_send() {
+    fn assert_send<T: Send>() {}
+    assert_send::<VwapAccumulator>();
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn ema_converges_to_constant() {
+    let alpha = alpha_from_halflife(10.0);
+    let mut ema = Ema::new(alpha);
+    ema.update(83000.0);
+    for _ in 0..1000 {
+        ema.update(83604.85);
+    }
+    // The step rounds to nothing a few ulps away from the value, so it settles there.
+    let error = (ema.get().unwrap() - 83604.85).abs() / 83604.85;
+    assert!(error <= 16.0 * f64::EPSILON, "{error}");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn ema_halves_weight_after_halflife() {
+    let mut ema = Ema::new(alpha_from_halflife(10.0));
+    ema.update(1.0);
+    for _ in 0..10 {
+        ema.update(0.0);
+    }
+    assert!((ema.get().unwrap() - 0.5).abs() < 1e-12);
+}
+
+#[test]
+fn ema_of_book_ticker_mid() {
+    let json = r#"{"e":"bookTicker","u":7283946601071,"s":"BTCUSDT","b":"83604.80","B":"10.746","a":"83604.90","A":"9.514","T":1744760290967,"E":1744760290968}"#;
+    let ticker = parse_book_ticker_dynamic(json, crate::ParsingConfig::default()).unwrap();
+    let mut ema = Ema::new(0.1);
+    assert_eq!(ema.get(), None);
+    ema.update_book_ticker(&ticker).unwrap();
+    assert_close(ema.get(), 83604.85);
+}
+
+#[test]
+fn rolling_min_max_matches_brute_force() {
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+    for window in [1, 2, 3, 10, 100] {
+        let mut tracker = RollingMinMax::new(window);
+        assert_eq!((tracker.min(), tracker.max()), (None, None));
+
+        let mut prices = Vec::new();
+        let mut mantissa: i64 = 8_360_480;
+        for _ in 0..2000 {
+            // A random walk with plenty of repeated prices, in varying precisions.
+            mantissa += rng.random_range(-3..=3);
+            let price = if rng.random_bool(0.5) {
+                FixedPoint {
+                    mantissa,
+                    exponent: -2,
+                }
+            } else {
+                FixedPoint {
+                    mantissa: mantissa * 10,
+                    exponent: -3,
+                }
+            };
+            prices.push(price);
+            tracker.update(price);
+
+            let recent = &prices[prices.len().saturating_sub(window)..];
+            assert_eq!(
+                tracker.min(),
+                recent.iter().copied().min(),
+                "window {window}"
+            );
+            assert_eq!(
+                tracker.max(),
+                recent.iter().copied().max(),
+                "window {window}"
+            );
+        }
+    }
+}
+
+fn tick<'a>(b: &'a str, a: &'a str, T: u64) -> BookTicker<'a> {
+    BookTicker {
+        e: "bookTicker",
+        u: 7283946601071,
+        s: "BTCUSDT",
+        pair: None,
+        T,
+        b,
+        B: "10.746",
+        a,
+        A: "9.514",
+        E: T + 1,
+    }
+}
+
+#[test]
+fn mid_price_past_the_mantissa() {
+    let mid = book(&[("100.0", "1")], &[("100.5", "1")]).mid_price();
+    assert_eq!(mid, Some(fixed("100.25")));
+
+    // The sum times 5 is past `i64`.
+    let max = "999999999999999999";
+    let mid = book(&[(max, "1")], &[(max, "1")]).mid_price();
+    assert_eq!(mid, Some(fixed(max)));
+    assert_eq!(book(&[], &[(max, "1")]).mid_price(), None);
+}
+
+#[test]
+fn spread_in_bps() {
+    assert_eq!(spread_bps(fixed("99.95"), fixed("100.05")), 10.0);
+    assert_eq!(spread_bps(fixed("100"), fixed("100")), 0.0);
+}
+
+#[test]
+fn spread_alert_fires_debounced() {
+    let fired = Arc::new(Mutex::new(Vec::new()));
+    let mut alert = SpreadAlert::new(5.0, {
+        let fired = Arc::clone(&fired);
+        move |spread| fired.lock().unwrap().push(spread)
+    });
+
+    // 1 bps, narrow enough.
+    assert!(!alert.check(&tick("99.995", "100.005", 0)).unwrap());
+    // 10 bps.
+    assert!(alert.check(&tick("99.95", "100.05", 100)).unwrap());
+    // Still wide, but within a second of firing.
+    assert!(!alert.check(&tick("99.90", "100.10", 500)).unwrap());
+    assert!(!alert.check(&tick("99.95", "100.05", 1099)).unwrap());
+    // A second later.
+    assert!(alert.check(&tick("99.90", "100.10", 1100)).unwrap());
+    // Narrow again, so nothing to debounce either.
+    assert!(!alert.check(&tick("99.995", "100.005", 5000)).unwrap());
+
+    let fired = fired.lock().unwrap();
+    assert_eq!(fired.len(), 2);
+    assert_close(Some(fired[0]), 10.0);
+    assert_close(Some(fired[1]), 20.0);
+}
+
+#[test]
+fn spread_alert_falls_back_on_event_time() {
+    let mut alert = SpreadAlert::new(5.0, |_| {});
+    let tick = |E| BookTicker {
+        E,
+        ..tick("99.95", "100.05", 0)
+    };
+    assert!(alert.check(&tick(100)).unwrap());
+    assert!(!alert.check(&tick(1099)).unwrap());
+    assert!(alert.check(&tick(1100)).unwrap());
+}
+
+/// Spot tickers, with neither a transaction nor an event time, timed as they're checked.
+#[cfg(feature = "std")]
+#[test]
+fn spread_alert_fires_again_on_spot() {
+    let mut alert = SpreadAlert::new(5.0, |_| {});
+    let spot = BookTicker {
+        E: 0,
+        ..tick("99.95", "100.05", 0)
+    };
+    assert!(alert.check(&spot).unwrap());
+    assert!(!alert.check(&spot).unwrap());
+
+    std::thread::sleep(core::time::Duration::from_millis(
+        SpreadAlert::DEBOUNCE_MS + 50,
+    ));
+    assert!(alert.check(&spot).unwrap());
+    assert!(!alert.check(&spot).unwrap());
+}
+
+/// Prices of a pair moving together, B quoted around `premium` over A with some noise of its
+/// own, e.g. a perpetual and its quarterly future.
+#[cfg(feature = "std")]
+fn correlated_prices(rng: &mut StdRng, n: usize, premium: f64) -> Vec<(String, String)> {
+    let mut price = 100.0;
+    (0..n)
+        .map(|_| {
+            price *= 1.0 + rng.random_range(-0.001..0.001);
+            let other = price * (1.0 + premium + rng.random_range(-0.0001..0.0001));
+            (format!("{price:.4}"), format!("{other:.4}"))
+        })
+        .collect()
+}
+
+#[cfg(feature = "std")]
+fn pair_tick<'a>(s: &'a str, b: &'a str, a: &'a str) -> BookTicker<'a> {
+    BookTicker { s, ..tick(b, a, 0) }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn spread_monitor_ignores_correlated_moves() {
+    let fired = Arc::new(Mutex::new(Vec::new()));
+    let mut monitor = SpreadMonitor::new("BTCUSDT", "BTCUSDT_250627", 0.05, 100, 6.0, {
+        let fired = Arc::clone(&fired);
+        move |sigmas| fired.lock().unwrap().push(sigmas)
+    });
+
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+    // Both sides at the same price, since only the ask of A and the bid of B matter.
+    for (a, b) in correlated_prices(&mut rng, 5000, 0.002) {
+        assert!(!monitor.update(&pair_tick("BTCUSDT", &a, &a)).unwrap());
+        assert!(
+            !monitor
+                .update(&pair_tick("BTCUSDT_250627", &b, &b))
+                .unwrap()
+        );
+    }
+    assert!(fired.lock().unwrap().is_empty());
+    // Off by about the noise, as A may have moved on since B last ticked.
+    let ema = monitor.ratio_ema.get().unwrap();
+    assert!((ema - (1.0 / 1.002 - 1.0)).abs() < 2e-4, "{ema}");
+    // Other symbols don't count.
+    assert!(!monitor.update(&pair_tick("ETHUSDT", "1.0", "1.0")).unwrap());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn spread_monitor_fires_on_divergence() {
+    let fired = Arc::new(Mutex::new(Vec::new()));
+    let mut monitor = SpreadMonitor::new("BTCUSDT", "BTCUSDT_250627", 0.05, 100, 6.0, {
+        let fired = Arc::clone(&fired);
+        move |sigmas| fired.lock().unwrap().push(sigmas)
+    });
+
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+    let prices = correlated_prices(&mut rng, 1000, 0.002);
+    for (a, b) in &prices {
+        monitor.update(&pair_tick("BTCUSDT", a, a)).unwrap();
+        monitor.update(&pair_tick("BTCUSDT_250627", b, b)).unwrap();
+    }
+    assert!(fired.lock().unwrap().is_empty());
+
+    // B falls 1% behind A while A stands still, far outside the noise.
+    let (a, b) = prices.last().unwrap();
+    let diverged = format!("{:.4}", b.parse::<f64>().unwrap() * 0.99);
+    assert!(
+        monitor
+            .update(&pair_tick("BTCUSDT_250627", &diverged, &diverged))
+            .unwrap()
+    );
+    assert!(fired.lock().unwrap()[0] > 6.0);
+
+    // Not before there's a full window to compare against.
+    let mut fresh = SpreadMonitor::new("BTCUSDT", "BTCUSDT_250627", 0.05, 100, 6.0, |_| {});
+    fresh.update(&pair_tick("BTCUSDT", a, a)).unwrap();
+    fresh.update(&pair_tick("BTCUSDT_250627", b, b)).unwrap();
+    assert!(
+        !fresh
+            .update(&pair_tick("BTCUSDT_250627", &diverged, &diverged))
+            .unwrap()
+    );
+}
@@ -0,0 +1,97 @@
+//! [`ParsingConfig`] shared between threads, so that the one detecting layout changes can swap
+//! in a new config while others keep parsing.
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+
+use crate::{ParsingConfig, SchemaVersion};
+
+/// The whole config is packed into a single `u64`, so swapping it is a plain atomic store and
+/// readers never see a half-updated config, even with `Relaxed` ordering, and never wait.
+///
+/// Bit layout, from the most significant: 8 bits of `schema_version`, 24 bits of `start`, then
+/// 8 bits each of `price_precision`, `volume_precision`, `transaction_time_digits` and
+/// `symbol_len`.
+#[derive(Clone, Debug)]
+pub struct AtomicConfig(Arc<AtomicU64>);
+
+impl AtomicConfig {
+    pub fn new(config: ParsingConfig) -> Result<Self, FieldTooLarge> {
+        Ok(Self(Arc::new(AtomicU64::new(pack(config)?))))
+    }
+
+    pub fn load(&self) -> ParsingConfig {
+        unpack(self.0.load(Ordering::Relaxed))
+    }
+
+    /// Makes `config` visible to all the clones, unless some field doesn't fit its bits, which
+    /// no real payload comes close to. The config seen by the clones is then left as is.
+    pub fn store(&self, config: ParsingConfig) -> Result<(), FieldTooLarge> {
+        self.0.store(pack(config)?, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+/// A field of a [`ParsingConfig`] that doesn't fit the bits [`AtomicConfig`] has for it: 24 for
+/// `start` and 8 for each of the others.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub struct FieldTooLarge {
+    pub field: &'static str,
+    pub value: usize,
+    pub max: usize,
+}
+
+impl core::fmt::Display for FieldTooLarge {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let Self { field, value, max } = self;
+        write!(f, "{field} of {value} is over {max}")
+    }
+}
+
+impl core::error::Error for FieldTooLarge {}
+
+impl From<&AtomicConfig> for ParsingConfig {
+    fn from(config: &AtomicConfig) -> Self {
+        config.load()
+    }
+}
+
+fn pack(config: ParsingConfig) -> Result<u64, FieldTooLarge> {
+    fn bits(value: usize, bits: u32, field: &'static str) -> Result<u64, FieldTooLarge> {
+        let max = (1 << bits) - 1;
+        if value > max {
+            return Err(FieldTooLarge { field, value, max });
+        }
+        Ok(value as u64)
+    }
+
+    let schema_version = match config.schema_version {
+        SchemaVersion::Futures => 0,
+        SchemaVersion::CoinMFutures => 1,
+        SchemaVersion::Spot => 2,
+    };
+    Ok(schema_version << 56
+        | bits(config.start, 24, "start")? << 32
+        | bits(config.price_precision, 8, "price_precision")? << 24
+        | bits(config.volume_precision, 8, "volume_precision")? << 16
+        | bits(config.transaction_time_digits, 8, "transaction_time_digits")? << 8
+        | bits(config.symbol_len, 8, "symbol_len")?)
+}
+
+fn unpack(packed: u64) -> ParsingConfig {
+    ParsingConfig {
+        start: (packed >> 32) as usize & 0xff_ffff,
+        price_precision: (packed >> 24) as u8 as usize,
+        volume_precision: (packed >> 16) as u8 as usize,
+        transaction_time_digits: (packed >> 8) as u8 as usize,
+        symbol_len: packed as u8 as usize,
+        schema_version: match (packed >> 56) as u8 {
+            0 => SchemaVersion::Futures,
+            1 => SchemaVersion::CoinMFutures,
+            _ => SchemaVersion::Spot,
+        },
+    }
+}
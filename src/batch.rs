@@ -0,0 +1,64 @@
+//! Parsing a burst of buffered messages on several threads at once, for when a single one would
+//! fall behind.
+//!
+//! The messages are split into one contiguous chunk per thread, each parsed on its own with no
+//! state shared between them, and the results are put back in the original order.
+
+use std::{num::NonZeroUsize, thread};
+
+use crate::{BookTickerOwned, ParseError, ParsingConfig, parse_book_ticker_dynamic, prefetch};
+
+/// Below this many messages per thread, spawning them costs more than it saves.
+const MIN_CHUNK: usize = 256;
+
+/// Parses every message with `config` on as many threads as there are logical CPUs, see
+/// [`parse_batch_with_threads`].
+pub fn parse_batch(
+    messages: &[String],
+    config: ParsingConfig,
+) -> Vec<Result<BookTickerOwned, ParseError>> {
+    let threads = thread::available_parallelism().unwrap_or(NonZeroUsize::MIN);
+    parse_batch_with_threads(messages, config, threads)
+}
+
+/// Parses every message with `config` on up to `threads` threads, giving the results in the
+/// order of the messages.
+pub fn parse_batch_with_threads(
+    messages: &[String],
+    config: ParsingConfig,
+    threads: NonZeroUsize,
+) -> Vec<Result<BookTickerOwned, ParseError>> {
+    let chunk_len = messages.len().div_ceil(threads.get()).max(MIN_CHUNK);
+    if chunk_len >= messages.len() {
+        return parse_chunk(messages, config);
+    }
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = messages
+            .chunks(chunk_len)
+            .map(|chunk| scope.spawn(move || parse_chunk(chunk, config)))
+            .collect();
+        let mut results = Vec::with_capacity(messages.len());
+        for handle in handles {
+            results.extend(handle.join().unwrap());
+        }
+        results
+    })
+}
+
+fn parse_chunk(
+    messages: &[String],
+    config: ParsingConfig,
+) -> Vec<Result<BookTickerOwned, ParseError>> {
+    messages
+        .iter()
+        .enumerate()
+        .map(|(i, message)| {
+            // Each message is allocated on its own, so the next one is unlikely to be in cache.
+            if let Some(next) = messages.get(i + 1) {
+                prefetch::prefetch_bytes(next.as_bytes());
+            }
+            parse_book_ticker_dynamic(message, config).and_then(BookTickerOwned::try_from)
+        })
+        .collect()
+}
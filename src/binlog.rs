@@ -0,0 +1,144 @@
+//! Recording raw WebSocket frames for lossless replay.
+//!
+//! The file starts with [`MAGIC`] and a version byte, followed by every frame as its length in
+//! little-endian `u32` and its bytes.
+
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, ErrorKind, Read, Write},
+    path::Path,
+};
+
+mod mmap;
+#[cfg(feature = "ws")]
+mod replay;
+pub use mmap::mmap_replay;
+#[cfg(feature = "ws")]
+pub use replay::{replay_from_binary_log, replay_realtime};
+
+/// First bytes of every binary log.
+pub const MAGIC: [u8; 4] = *b"BJPL";
+/// Format version written after [`MAGIC`], bumped on incompatible changes.
+pub const VERSION: u8 = 1;
+
+/// Longest frame written or read, well over the [`MAX_MESSAGE_BYTES`] the parsers take, so that
+/// a corrupt length isn't trusted with an allocation of up to 4 GiB.
+///
+/// [`MAX_MESSAGE_BYTES`]: crate::MAX_MESSAGE_BYTES
+pub const MAX_FRAME_LEN: usize = 1 << 20;
+
+const HEADER_LEN: usize = MAGIC.len() + 1;
+
+fn check_header(header: &[u8; HEADER_LEN]) -> io::Result<()> {
+    if header[..MAGIC.len()] != MAGIC {
+        return Err(io::Error::new(
+            ErrorKind::InvalidData,
+            "not a binary log: bad magic",
+        ));
+    }
+    if header[MAGIC.len()] != VERSION {
+        return Err(io::Error::new(
+            ErrorKind::InvalidData,
+            format!("unsupported binary log version {}", header[MAGIC.len()]),
+        ));
+    }
+    Ok(())
+}
+
+fn check_frame_len(len: usize, kind: ErrorKind) -> io::Result<()> {
+    if len > MAX_FRAME_LEN {
+        return Err(io::Error::new(
+            kind,
+            format!("frame of {len} bytes is over {MAX_FRAME_LEN}"),
+        ));
+    }
+    Ok(())
+}
+
+/// Appends frames to a binary log.
+pub struct BinaryLog {
+    writer: BufWriter<File>,
+}
+
+impl BinaryLog {
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?))
+    }
+
+    /// Writes the header right away.
+    pub fn new(mut writer: BufWriter<File>) -> io::Result<Self> {
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        Ok(Self { writer })
+    }
+
+    /// Fails without writing anything if `frame` is over [`MAX_FRAME_LEN`].
+    pub fn write_frame(&mut self, frame: &str) -> io::Result<()> {
+        check_frame_len(frame.len(), ErrorKind::InvalidInput)?;
+        self.writer.write_all(&(frame.len() as u32).to_le_bytes())?;
+        self.writer.write_all(frame.as_bytes())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Yields the recorded frames in order.
+pub struct BinaryLogReader {
+    reader: BufReader<File>,
+}
+
+impl BinaryLogReader {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+
+    /// Checks the header right away.
+    pub fn new(mut reader: BufReader<File>) -> io::Result<Self> {
+        let mut header = [0u8; HEADER_LEN];
+        reader.read_exact(&mut header)?;
+        check_header(&header)?;
+        Ok(Self { reader })
+    }
+
+    fn read_frame(&mut self) -> io::Result<Option<String>> {
+        let mut len = [0u8; 4];
+        let mut read = 0;
+        while read < len.len() {
+            match self.reader.read(&mut len[read..]) {
+                Ok(0) => break,
+                Ok(n) => read += n,
+                Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        match read {
+            // A clean end of file can only happen between frames.
+            0 => return Ok(None),
+            4 => {}
+            _ => {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("frame length cut short after {read} bytes"),
+                ));
+            }
+        }
+
+        let len = u32::from_le_bytes(len) as usize;
+        check_frame_len(len, ErrorKind::InvalidData)?;
+        let mut frame = vec![0u8; len];
+        self.reader.read_exact(&mut frame)?;
+        String::from_utf8(frame)
+            .map(Some)
+            .map_err(|err| io::Error::new(ErrorKind::InvalidData, err))
+    }
+}
+
+impl Iterator for BinaryLogReader {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_frame().transpose()
+    }
+}
@@ -0,0 +1,170 @@
+//! Binary logs mapped into memory, so that replaying one isn't bound by copying it through a
+//! read buffer.
+
+use std::{io, path::Path};
+
+use crate::{
+    BookTickerOwned, ParseError, ParsingConfig, logging::log_warn, parse_book_ticker_dynamic,
+};
+
+/// Parses every frame recorded at `path` straight from the mapped file, as the frames are
+/// reached rather than scanning the file first.
+///
+/// Like [`replay_from_binary_log`], the iterator ends at the first frame that can't be read,
+/// e.g. one cut short by the recorder being killed. Without `mmap`, as on anything but Linux, the
+/// frames are read through [`BinaryLogReader`] instead.
+///
+/// [`replay_from_binary_log`]: super::replay_from_binary_log
+/// [`BinaryLogReader`]: super::BinaryLogReader
+#[cfg(target_os = "linux")]
+pub fn mmap_replay(
+    path: &Path,
+    config: ParsingConfig,
+) -> io::Result<impl Iterator<Item = Result<BookTickerOwned, ParseError>> + use<>> {
+    let map = Mmap::open(path)?;
+    let header = map
+        .bytes()
+        .first_chunk()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "no binary log header"))?;
+    super::check_header(header)?;
+    Ok(MmapReplay {
+        map,
+        pos: super::HEADER_LEN,
+        config,
+    })
+}
+
+/// Parses every frame recorded at `path`, read through [`BinaryLogReader`] since there's no
+/// `mmap` here.
+///
+/// [`BinaryLogReader`]: super::BinaryLogReader
+#[cfg(not(target_os = "linux"))]
+pub fn mmap_replay(
+    path: &Path,
+    config: ParsingConfig,
+) -> io::Result<impl Iterator<Item = Result<BookTickerOwned, ParseError>> + use<>> {
+    let frames = super::BinaryLogReader::open(path)?.map_while(|frame| {
+        frame
+            .inspect_err(|err| log_warn!("failed to read the binary log: {err}"))
+            .ok()
+    });
+    Ok(frames.map(move |frame| {
+        parse_book_ticker_dynamic(&frame, config).and_then(BookTickerOwned::try_from)
+    }))
+}
+
+/// A whole file mapped read-only.
+#[cfg(target_os = "linux")]
+struct Mmap {
+    ptr: *const u8,
+    len: usize,
+}
+
+#[cfg(target_os = "linux")]
+impl Mmap {
+    fn open(path: &Path) -> io::Result<Self> {
+        use std::os::fd::AsRawFd;
+
+        let file = std::fs::File::open(path)?;
+        let len = usize::try_from(file.metadata()?.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "file too large to map"))?;
+        // Empty mappings aren't allowed, but an empty file has no header anyway.
+        if len == 0 {
+            return Ok(Self {
+                ptr: core::ptr::NonNull::dangling().as_ptr(),
+                len,
+            });
+        }
+
+        // SAFETY: a new private mapping, checked for failure below. The file may be closed once
+        // it's mapped.
+        let ptr = unsafe {
+            libc::mmap(
+                core::ptr::null_mut(),
+                len,
+                libc::PROT_READ,
+                libc::MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        // Frames are read in order, so the kernel can read ahead aggressively. It's only advice.
+        unsafe { libc::madvise(ptr, len, libc::MADV_SEQUENTIAL) };
+        Ok(Self {
+            ptr: ptr.cast(),
+            len,
+        })
+    }
+
+    fn bytes(&self) -> &[u8] {
+        // SAFETY: `len` bytes are mapped for as long as `self` lives. Like with any mapping,
+        // that only holds as long as nobody truncates the file meanwhile.
+        unsafe { core::slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        if self.len > 0 {
+            unsafe { libc::munmap(self.ptr.cast_mut().cast(), self.len) };
+        }
+    }
+}
+
+/// Book tickers parsed from the frames of a mapped binary log, from `pos` on.
+#[cfg(target_os = "linux")]
+struct MmapReplay {
+    map: Mmap,
+    pos: usize,
+    config: ParsingConfig,
+}
+
+#[cfg(target_os = "linux")]
+impl MmapReplay {
+    /// The frame at `pos`, moving past it, or `None` at the end of the file.
+    fn next_frame(&mut self) -> io::Result<Option<&str>> {
+        let rest = &self.map.bytes()[self.pos..];
+        let Some((len, rest)) = rest.split_first_chunk::<4>() else {
+            return match rest.len() {
+                // A clean end of file can only happen between frames.
+                0 => Ok(None),
+                read => Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("frame length cut short after {read} bytes"),
+                )),
+            };
+        };
+        let len = u32::from_le_bytes(*len) as usize;
+        super::check_frame_len(len, io::ErrorKind::InvalidData)?;
+        let frame = rest
+            .get(..len)
+            .ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))?;
+        self.pos += 4 + len;
+        core::str::from_utf8(frame)
+            .map(Some)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Iterator for MmapReplay {
+    type Item = Result<BookTickerOwned, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let config = self.config;
+        match self.next_frame() {
+            Ok(frame) => {
+                Some(parse_book_ticker_dynamic(frame?, config).and_then(BookTickerOwned::try_from))
+            }
+            Err(err) => {
+                log_warn!("failed to read the binary log: {err}");
+                self.pos = self.map.len;
+                None
+            }
+        }
+    }
+}
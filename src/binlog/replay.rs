@@ -0,0 +1,58 @@
+//! Binary logs as streams of book ticker updates.
+
+use std::{io, path::Path, time::Duration};
+
+use futures_util::{Stream, StreamExt, stream};
+use tokio::time::Instant;
+
+use super::BinaryLogReader;
+use crate::{
+    BookTickerOwned, ParseError, ParsingConfig, logging::log_warn, parse_book_ticker_dynamic,
+};
+
+/// Parses every frame recorded at `path`, as [`book_ticker_stream`] would have done live.
+///
+/// The stream ends at the first frame that can't be read.
+///
+/// [`book_ticker_stream`]: crate::stream::book_ticker_stream
+pub fn replay_from_binary_log(
+    path: &Path,
+    config: ParsingConfig,
+) -> io::Result<impl Stream<Item = Result<BookTickerOwned, ParseError>> + use<>> {
+    let frames = BinaryLogReader::open(path)?.map_while(|frame| {
+        frame
+            .inspect_err(|err| log_warn!("failed to read the binary log: {err}"))
+            .ok()
+    });
+    Ok(stream::iter(frames.map(move |frame| {
+        parse_book_ticker_dynamic(&frame, config).and_then(BookTickerOwned::try_from)
+    })))
+}
+
+/// Same as [`replay_from_binary_log`], but spaced out by the ticks' `T` like they originally
+/// arrived, sped up `speed` times.
+///
+/// Ticks that failed to parse, or went back in time, are yielded right away.
+pub fn replay_realtime(
+    path: &Path,
+    config: ParsingConfig,
+    speed: f64,
+) -> io::Result<impl Stream<Item = Result<BookTickerOwned, ParseError>> + use<>> {
+    assert!(speed > 0.0, "replay speed must be positive");
+
+    // Transaction time and instant of the first tick.
+    let mut origin: Option<(u64, Instant)> = None;
+    Ok(replay_from_binary_log(path, config)?.then(move |tick| {
+        let deadline = tick.as_ref().ok().map(|tick| {
+            let (T0, start) = *origin.get_or_insert((tick.T, Instant::now()));
+            let offset = Duration::from_millis(tick.T.saturating_sub(T0));
+            start + offset.div_f64(speed)
+        });
+        async move {
+            if let Some(deadline) = deadline {
+                tokio::time::sleep_until(deadline).await;
+            }
+            tick
+        }
+    }))
+}
@@ -0,0 +1,117 @@
+//! Fans book ticker updates out to any number of subscribers.
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+
+use futures_util::StreamExt;
+use tokio::{
+    sync::broadcast::{self, error::RecvError},
+    task::JoinHandle,
+};
+
+use crate::{
+    BookTickerOwned,
+    cache::ConfigCache,
+    logging::log_warn,
+    reconnect::{ReconnectConfig, ReconnectingStream},
+};
+
+/// Drives the WebSocket at `url` on its own task and broadcasts every parsed tick.
+///
+/// Subscribers that fall more than `capacity` ticks behind skip the ones they missed, which is
+/// counted by [`TickerBroadcaster::lagged`].
+pub struct TickerBroadcaster {
+    sender: broadcast::Sender<BookTickerOwned>,
+    lagged: Arc<AtomicU64>,
+    task: JoinHandle<()>,
+}
+
+impl TickerBroadcaster {
+    pub fn spawn(url: impl Into<String>, capacity: usize) -> Self {
+        let url = url.into();
+        let (sender, _) = broadcast::channel(capacity);
+
+        let task = tokio::spawn({
+            let sender = sender.clone();
+            async move {
+                let mut ws_stream = ReconnectingStream::new(&url, ReconnectConfig::default());
+                let mut configs = ConfigCache::default();
+
+                while let Some(msg) = ws_stream.next().await {
+                    let msg = match msg {
+                        Ok(msg) => msg,
+                        Err(err) => {
+                            log_warn!("giving up on {url}: {err}");
+                            break;
+                        }
+                    };
+                    if !msg.is_text() {
+                        continue;
+                    }
+                    let text = msg.to_text().unwrap();
+
+                    match configs
+                        .parse(&url, text)
+                        .and_then(BookTickerOwned::try_from)
+                    {
+                        // Fails only when there are no subscribers at the moment.
+                        Ok(book_ticker) => _ = sender.send(book_ticker),
+                        Err(err) => log_warn!("failed to parse book ticker: {err}"),
+                    }
+                }
+            }
+        });
+
+        Self {
+            sender,
+            lagged: Arc::new(AtomicU64::new(0)),
+            task,
+        }
+    }
+
+    pub fn subscribe(&self) -> TickerReceiver {
+        TickerReceiver {
+            receiver: self.sender.subscribe(),
+            lagged: Arc::clone(&self.lagged),
+        }
+    }
+
+    /// How many times subscribers have fallen behind and skipped ticks.
+    pub fn lagged(&self) -> u64 {
+        self.lagged.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for TickerBroadcaster {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Receiving end of [`TickerBroadcaster`], which reports lagging back to it.
+pub struct TickerReceiver {
+    receiver: broadcast::Receiver<BookTickerOwned>,
+    lagged: Arc<AtomicU64>,
+}
+
+impl TickerReceiver {
+    /// Waits for the next tick, skipping the missed ones if lagging behind. Returns `None` once
+    /// the broadcaster is gone.
+    pub async fn recv(&mut self) -> Option<BookTickerOwned> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(book_ticker) => return Some(book_ticker),
+                Err(RecvError::Lagged(_)) => {
+                    self.lagged.fetch_add(1, Ordering::Relaxed);
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    pub fn into_inner(self) -> broadcast::Receiver<BookTickerOwned> {
+        self.receiver
+    }
+}
@@ -0,0 +1,64 @@
+//! Parsing configs of many symbols, each with its own layout and precisions.
+
+use std::collections::HashMap;
+
+use crate::{BookTicker, ParseError, ParsingConfig, detect_config, parse_book_ticker_dynamic};
+
+/// Parsing configs keyed by symbol or stream name, detected on first use.
+#[derive(Default, Debug)]
+pub struct ConfigCache {
+    configs: HashMap<String, ParsingConfig>,
+}
+
+impl ConfigCache {
+    /// The cached config of `symbol`, detected from `json` if there is none yet or if the layout
+    /// has drifted.
+    pub fn get_or_detect(
+        &mut self,
+        symbol: &str,
+        json: &str,
+    ) -> Result<&ParsingConfig, ParseError> {
+        // Looked up twice so that the key is only allocated for new symbols.
+        let cached = self.configs.get(symbol).is_some_and(|c| c.matches(json));
+        if !cached {
+            let detected = detect_config(json)?;
+            match self.configs.get_mut(symbol) {
+                Some(config) => *config = detected,
+                None => {
+                    self.configs.insert(symbol.to_owned(), detected);
+                }
+            }
+        }
+        Ok(&self.configs[symbol])
+    }
+
+    /// The cached config of `symbol`, if any, without checking it against a payload.
+    pub fn get(&self, symbol: &str) -> Option<&ParsingConfig> {
+        self.configs.get(symbol)
+    }
+
+    /// Sets the config of `symbol`, e.g. one known upfront, which is still checked against each
+    /// payload like a detected one.
+    pub fn insert(&mut self, symbol: &str, config: ParsingConfig) {
+        self.configs.insert(symbol.to_owned(), config);
+    }
+
+    /// Forgets the config of `symbol`, so that it's detected again next time. Meant for when
+    /// parsing fails at a boundary, e.g. because the precision has changed but `start` hasn't.
+    pub fn evict(&mut self, symbol: &str) {
+        self.configs.remove(symbol);
+    }
+
+    /// Parses with the cached config of `symbol`, detecting it again once if it turns out stale.
+    pub fn parse<'a>(&mut self, symbol: &str, json: &'a str) -> Result<BookTicker<'a>, ParseError> {
+        let config = *self.get_or_detect(symbol, json)?;
+        match parse_book_ticker_dynamic(json, config) {
+            Err(ParseError::InvalidBoundary { .. }) => {
+                self.evict(symbol);
+                let config = *self.get_or_detect(symbol, json)?;
+                parse_book_ticker_dynamic(json, config)
+            }
+            res => res,
+        }
+    }
+}
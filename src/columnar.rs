@@ -0,0 +1,70 @@
+//! Ticks laid out column by column, for averages and other aggregates over a captured history
+//! that only need some of the fields, each in one contiguous buffer.
+//!
+//! Plain `Vec`s, not Arrow arrays: the Arrow and DataFusion integration isn't delivered, see
+//! `NOT_DELIVERED.md`.
+
+use alloc::vec::Vec;
+use core::num::ParseFloatError;
+
+use crate::BookTickerOwned;
+
+/// The `T`, `b`, `B`, `a` and `A` of ticks, in the order they were pushed.
+#[derive(Clone, PartialEq, Debug, Default)]
+#[non_exhaustive]
+pub struct TickColumns {
+    pub T: Vec<u64>,
+    pub b: Vec<f64>,
+    pub B: Vec<f64>,
+    pub a: Vec<f64>,
+    pub A: Vec<f64>,
+}
+
+impl TickColumns {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(rows: usize) -> Self {
+        Self {
+            T: Vec::with_capacity(rows),
+            b: Vec::with_capacity(rows),
+            B: Vec::with_capacity(rows),
+            a: Vec::with_capacity(rows),
+            A: Vec::with_capacity(rows),
+        }
+    }
+
+    /// Appends a row for `tick`, leaving the columns as they were if a decimal doesn't parse.
+    pub fn push(&mut self, tick: &BookTickerOwned) -> Result<(), ParseFloatError> {
+        let (b, B, a, A) = (
+            tick.b.parse()?,
+            tick.B.parse()?,
+            tick.a.parse()?,
+            tick.A.parse()?,
+        );
+        self.T.push(tick.T);
+        self.b.push(b);
+        self.B.push(B);
+        self.a.push(a);
+        self.A.push(A);
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.T.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.T.is_empty()
+    }
+}
+
+/// The columns of every tick in `ticks`.
+pub fn to_columns(ticks: &[BookTickerOwned]) -> Result<TickColumns, ParseFloatError> {
+    let mut columns = TickColumns::with_capacity(ticks.len());
+    for tick in ticks {
+        columns.push(tick)?;
+    }
+    Ok(columns)
+}
@@ -0,0 +1,16 @@
+//! Combined streams, e.g. `/stream?streams=btcusdt@bookTicker/ethusdt@bookTicker`, which wrap
+//! every event as in `{"stream":"btcusdt@bookTicker","data":{...}}`.
+
+use crate::simd::simd_find_byte;
+
+/// Splits a combined stream event into the name of the stream and the event itself.
+pub fn strip_combined_wrapper(json: &str) -> Option<(&str, &str)> {
+    // Skip 11 chars: {"stream":"
+    let rest = json.strip_prefix("{\"stream\":\"")?;
+    let stream_end = simd_find_byte(rest.as_bytes(), b'"')?;
+    let stream = &rest[..stream_end];
+
+    // Skip 9 chars: ","data":
+    let inner = rest.get(stream_end + 9..)?.strip_suffix('}')?;
+    inner.starts_with('{').then_some((stream, inner))
+}
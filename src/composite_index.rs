@@ -0,0 +1,150 @@
+//! Composite index stream, e.g. `defiusdt@compositeIndex`.
+
+// {
+//   "e": "compositeIndex",     // event type
+//   "E": 1602310596000,        // event time
+//   "s": "DEFIUSDT",           // symbol
+//   "p": "USDT",               // quote asset
+//   "c": [                     // components
+//     {
+//       "b": "AAVE",           // base asset
+//       "w": "0.05707234",     // weight in quantity
+//       "W": "5.70723400",     // weight in percentage
+//       "i": "77.82000000"     // index price
+//     }
+//   ],
+//   "i": "4431.76000000"       // index price
+// }
+
+use alloc::vec::Vec;
+
+use crate::{ParseError, expect_byte, id_field, quoted_end, quoted_field};
+
+/// A composite index and what it's made of. Fields are named after their keys, see the layout
+/// above, except for the components.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub struct CompositeIndex<'a> {
+    pub E: u64,
+    pub s: &'a str,
+    pub p: &'a str,
+    pub components: Vec<IndexComponent<'a>>,
+    pub i: &'a str,
+}
+
+/// One asset of a [`CompositeIndex`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub struct IndexComponent<'a> {
+    pub b: &'a str,
+    pub w: &'a str,
+    pub W: &'a str,
+    pub i: &'a str,
+}
+
+const PREFIX: &str = r#"{"e":"compositeIndex","E":"#;
+
+/// Parses a composite index payload. The components vary in number and length, so the fields
+/// after them are found by scanning to the end of the array, while everything else is still
+/// laid out as above.
+pub fn parse_composite_index(json: &str) -> Result<CompositeIndex<'_>, ParseError> {
+    if !json.starts_with(PREFIX) {
+        return Err(ParseError::WrongEventType);
+    }
+    let (E, E_end) = id_field(json, PREFIX.len(), b',', "E")?;
+
+    // Skip 6 chars: ,"s":"
+    let (s, s_end) = quoted_field(json, E_end + 6, "s")?;
+    expect_byte(json, s_end + 3, b'p', "p")?;
+
+    // Skip 7 chars: ","p":"
+    let (p, p_end) = quoted_field(json, s_end + 7, "p")?;
+    expect_byte(json, p_end + 3, b'c', "c")?;
+
+    // Skip 6 chars: ","c":
+    let c_start = p_end + 6;
+    expect_byte(json, c_start, b'[', "c")?;
+    let c_end = array_end(json, c_start, "c")?;
+    let components = parse_components(json, c_start, c_end)?;
+
+    // Skip 6 chars: ,"i":"
+    expect_byte(json, c_end + 2, b'i', "i")?;
+    let (i, i_end) = quoted_field(json, c_end + 6, "i")?;
+    expect_byte(json, i_end + 1, b'}', "i")?;
+
+    Ok(CompositeIndex {
+        E,
+        s,
+        p,
+        components,
+        i,
+    })
+}
+
+/// Parses the objects of the array at `start..end`, brackets included.
+fn parse_components(
+    json: &str,
+    start: usize,
+    end: usize,
+) -> Result<Vec<IndexComponent<'_>>, ParseError> {
+    let mut components = Vec::new();
+    if end == start + 2 {
+        return Ok(components);
+    }
+
+    let mut pos = start + 1;
+    loop {
+        expect_byte(json, pos, b'{', "c")?;
+        expect_byte(json, pos + 2, b'b', "b")?;
+
+        // Skip 6 chars: {"b":"
+        let (b, b_end) = quoted_field(json, pos + 6, "b")?;
+        expect_byte(json, b_end + 3, b'w', "w")?;
+
+        // Skip 7 chars: ","w":"
+        let (w, w_end) = quoted_field(json, b_end + 7, "w")?;
+        expect_byte(json, w_end + 3, b'W', "W")?;
+
+        // Skip 7 chars: ","W":"
+        let (W, W_end) = quoted_field(json, w_end + 7, "W")?;
+        expect_byte(json, W_end + 3, b'i', "i")?;
+
+        // Skip 7 chars: ","i":"
+        let (i, i_end) = quoted_field(json, W_end + 7, "i")?;
+        expect_byte(json, i_end + 1, b'}', "c")?;
+        components.push(IndexComponent { b, w, W, i });
+
+        // Skip 2 chars: "}
+        pos = i_end + 2;
+        match json.as_bytes().get(pos) {
+            // The array was scanned up to here already, so this is its end.
+            Some(b']') if pos + 1 == end => return Ok(components),
+            Some(b',') => pos += 1,
+            _ => return Err(ParseError::InvalidBoundary { field: "c", pos }),
+        }
+    }
+}
+
+/// Finds the end of the array starting at `start`, past its closing bracket, by counting the
+/// brackets in between. Strings are skipped whole, so that brackets in them don't count, which
+/// is enough for the asset names and decimals here, none of them escaped.
+fn array_end(json: &str, start: usize, field: &'static str) -> Result<usize, ParseError> {
+    let bytes = json.as_bytes();
+    let mut depth = 0usize;
+    let mut pos = start;
+    while let Some(&byte) = bytes.get(pos) {
+        match byte {
+            b'[' => depth += 1,
+            b']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(pos + 1);
+                }
+            }
+            b'"' => pos = quoted_end(json, pos + 1, field)?,
+            _ => {}
+        }
+        pos += 1;
+    }
+    Err(ParseError::FieldNotFound(field))
+}
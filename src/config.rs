@@ -0,0 +1,124 @@
+//! Settings loaded at startup from a TOML file, e.g.
+//!
+//! ```toml
+//! validate_every = 1000
+//!
+//! [connection]
+//! url = "wss://fstream.binance.com/ws/ethusdt@bookTicker"
+//! max_retries = 5
+//!
+//! [parsing]
+//! start = 51
+//! price_precision = 2
+//! volume_precision = 3
+//! transaction_time_digits = 13
+//! symbol_len = 7
+//! ```
+//!
+//! Anything left out keeps its default. Only tables of strings, numbers and booleans are read,
+//! without arrays, inline tables or dates, as that's all a config is made of.
+
+mod toml;
+
+use std::{fs, io, path::Path, time::Duration};
+
+use crate::{ParsingConfig, reconnect::ReconnectConfig};
+
+/// Everything the binary can be configured with.
+#[derive(serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct Config {
+    pub connection: ConnectionConfig,
+    /// Only a starting point, it's detected again if the layout turns out different.
+    pub parsing: ParsingConfig,
+    /// How many messages apart to check the cached config with [`validate_config`], 0 to never.
+    ///
+    /// [`validate_config`]: crate::validate_config
+    pub validate_every: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            connection: ConnectionConfig::default(),
+            parsing: ParsingConfig::default(),
+            validate_every: 1000,
+        }
+    }
+}
+
+impl Config {
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_toml(&fs::read_to_string(path)?)
+    }
+
+    pub fn from_toml(text: &str) -> io::Result<Self> {
+        let value =
+            toml::from_str(text).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let config: Self = serde_json::from_value(value)?;
+        let jitter = config.connection.jitter;
+        if !(0.0..=1.0).contains(&jitter) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("jitter of {jitter} is not between 0 and 1"),
+            ));
+        }
+        Ok(config)
+    }
+
+    /// The config as [`load`](Self::load) reads it back.
+    pub fn to_toml(&self) -> String {
+        toml::to_string(&serde_json::to_value(self).expect("configs are plain data"))
+    }
+}
+
+/// Durations are in milliseconds, see [`ReconnectConfig`] for what they mean.
+#[derive(serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct ConnectionConfig {
+    pub url: String,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    /// Between 0 and 1, anything else fails to load.
+    pub jitter: f64,
+    pub max_retries: Option<u32>,
+    /// 0 to never ping.
+    pub ping_interval_ms: u64,
+    pub pong_timeout_ms: u64,
+    /// Asks for `permessage-deflate`, see [`ReconnectConfig::deflate`].
+    pub deflate: bool,
+}
+
+impl ConnectionConfig {
+    pub fn reconnect(&self) -> ReconnectConfig {
+        ReconnectConfig {
+            initial_backoff: Duration::from_millis(self.initial_backoff_ms),
+            max_backoff: Duration::from_millis(self.max_backoff_ms),
+            jitter: self.jitter,
+            max_retries: self.max_retries,
+            ping_interval: (self.ping_interval_ms != 0)
+                .then(|| Duration::from_millis(self.ping_interval_ms)),
+            pong_timeout: Duration::from_millis(self.pong_timeout_ms),
+            cert_sha256: None,
+            deflate: self.deflate,
+        }
+    }
+}
+
+impl Default for ConnectionConfig {
+    fn default() -> Self {
+        let reconnect = ReconnectConfig::default();
+        Self {
+            url: "wss://fstream.binance.com/ws/btcusdt@bookTicker".to_owned(),
+            initial_backoff_ms: reconnect.initial_backoff.as_millis() as u64,
+            max_backoff_ms: reconnect.max_backoff.as_millis() as u64,
+            jitter: reconnect.jitter,
+            max_retries: reconnect.max_retries,
+            ping_interval_ms: reconnect
+                .ping_interval
+                .map_or(0, |interval| interval.as_millis() as u64),
+            pong_timeout_ms: reconnect.pong_timeout.as_millis() as u64,
+            deflate: reconnect.deflate,
+        }
+    }
+}
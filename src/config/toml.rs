@@ -0,0 +1,290 @@
+//! Just enough of TOML for [`Config`]: tables, dotted ones included, of keys set to strings,
+//! integers, floats or booleans. Files are read into a [`Value`] for serde to take the config
+//! from, and written back from one.
+//!
+//! What's read:
+//!
+//! - Bare keys of ASCII letters, digits, `_` and `-`, and `[table]` or `[dotted.table]` headers
+//!   of them.
+//! - Basic strings with the escapes of TOML, and literal strings, each on one line.
+//! - Decimal integers and floats, with `_` between digits, an optional sign and exponents.
+//! - `true` and `false`, and `#` comments.
+//!
+//! Quoted and dotted keys, multiline strings, arrays, inline tables and arrays of tables are
+//! rejected with an error saying so, rather than misread. Hex, octal and binary integers, `inf`,
+//! `nan` and dates are rejected as invalid values.
+//!
+//! TOML has no null, so `None` is written by leaving the key out, which reads back as its
+//! default.
+//!
+//! [`Config`]: super::Config
+
+use std::fmt::{self, Write as _};
+
+use serde_json::{Map, Number, Value, map::Entry};
+
+/// Why a file isn't in the subset of TOML read here, at line `line` counting from 1.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub(super) struct TomlError {
+    pub(super) line: usize,
+    pub(super) msg: &'static str,
+}
+
+impl fmt::Display for TomlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.msg)
+    }
+}
+
+impl std::error::Error for TomlError {}
+
+pub(super) fn from_str(text: &str) -> Result<Value, TomlError> {
+    let mut root = Map::new();
+    // Path of the table the keys go into, the root one until the first header.
+    let mut table: Vec<String> = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let err = |msg| TomlError { line: i + 1, msg };
+        let line = line.trim_start();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('[') {
+            if header.starts_with('[') {
+                return Err(err("arrays of tables aren't supported"));
+            }
+            let (header, rest) = header.split_once(']').ok_or(err("unclosed table header"))?;
+            if header.contains(['"', '\'']) {
+                return Err(err("quoted keys aren't supported"));
+            }
+            expect_end(rest).ok_or(err("unexpected characters after the table header"))?;
+            table = header
+                .split('.')
+                .map(|key| bare_key(key.trim()).map(str::to_owned))
+                .collect::<Option<_>>()
+                .ok_or(err("invalid table name"))?;
+            let mut map = &mut root;
+            for (depth, key) in table.iter().enumerate() {
+                let last = depth + 1 == table.len();
+                match map.entry(key.as_str()) {
+                    Entry::Occupied(_) if last => return Err(err("table defined twice")),
+                    entry => {
+                        let value = entry.or_insert_with(|| Value::Object(Map::new()));
+                        map = value.as_object_mut().ok_or(err("key defined twice"))?;
+                    }
+                }
+            }
+            continue;
+        }
+
+        if line.starts_with(['"', '\'']) {
+            return Err(err("quoted keys aren't supported"));
+        }
+        let (key, value) = line.split_once('=').ok_or(err("expected `key = value`"))?;
+        let key = key.trim();
+        if key.contains('.') {
+            return Err(err("dotted keys aren't supported"));
+        }
+        let key = bare_key(key).ok_or(err("invalid key"))?;
+        let (value, rest) = parse_value(value.trim_start()).map_err(err)?;
+        expect_end(rest).ok_or(err("unexpected characters after the value"))?;
+
+        let mut map = &mut root;
+        for key in &table {
+            map = map[key.as_str()]
+                .as_object_mut()
+                .expect("tables are objects");
+        }
+        if map.insert(key.to_owned(), value).is_some() {
+            return Err(err("key defined twice"));
+        }
+    }
+    Ok(Value::Object(root))
+}
+
+/// Keys made of ASCII letters, digits, `_` and `-`, as TOML calls bare ones. Quoted keys aren't
+/// supported.
+fn bare_key(key: &str) -> Option<&str> {
+    let valid = |b: u8| b.is_ascii_alphanumeric() || b == b'_' || b == b'-';
+    (!key.is_empty() && key.bytes().all(valid)).then_some(key)
+}
+
+/// Whether only whitespace and maybe a comment are left on the line.
+fn expect_end(rest: &str) -> Option<()> {
+    let rest = rest.trim_start();
+    (rest.is_empty() || rest.starts_with('#')).then_some(())
+}
+
+/// The value at the start of `text`, and what's left after it.
+fn parse_value(text: &str) -> Result<(Value, &str), &'static str> {
+    // Otherwise read as an empty string followed by more quotes.
+    if text.starts_with(r#"""""#) || text.starts_with("'''") {
+        return Err("multiline strings aren't supported");
+    }
+    if let Some(rest) = text.strip_prefix('"') {
+        return basic_string(rest);
+    }
+    if let Some(rest) = text.strip_prefix('\'') {
+        let (value, rest) = rest.split_once('\'').ok_or("unclosed string")?;
+        return Ok((Value::String(value.to_owned()), rest));
+    }
+    if text.starts_with(['[', '{']) {
+        return Err("arrays and inline tables aren't supported");
+    }
+
+    let end = text
+        .find(|c: char| c.is_whitespace() || c == '#')
+        .unwrap_or(text.len());
+    let (token, rest) = text.split_at(end);
+    let value = match token {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => number(token).ok_or("invalid value")?,
+    };
+    Ok((value, rest))
+}
+
+/// The rest of a `"` string, up to its closing quote.
+fn basic_string(text: &str) -> Result<(Value, &str), &'static str> {
+    let mut value = String::new();
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Ok((Value::String(value), &text[i + 1..])),
+            '\\' => {
+                let escaped = match chars.next().ok_or("unclosed string")?.1 {
+                    '"' => '"',
+                    '\\' => '\\',
+                    'n' => '\n',
+                    't' => '\t',
+                    'r' => '\r',
+                    'b' => '\u{8}',
+                    'f' => '\u{c}',
+                    'u' => {
+                        let hex: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                        u32::from_str_radix(&hex, 16)
+                            .ok()
+                            .filter(|_| hex.len() == 4)
+                            .and_then(char::from_u32)
+                            .ok_or("invalid unicode escape")?
+                    }
+                    _ => return Err("invalid escape"),
+                };
+                value.push(escaped);
+            }
+            c if c.is_control() && c != '\t' => return Err("control character in string"),
+            c => value.push(c),
+        }
+    }
+    Err("unclosed string")
+}
+
+/// Decimal integers and floats, with `_` allowed between digits.
+fn number(token: &str) -> Option<Value> {
+    let bytes = token.as_bytes();
+    let digit_around = |i: usize| {
+        i > 0 && bytes[i - 1].is_ascii_digit() && bytes.get(i + 1).is_some_and(u8::is_ascii_digit)
+    };
+    if bytes
+        .iter()
+        .enumerate()
+        .any(|(i, &b)| b == b'_' && !digit_around(i))
+    {
+        return None;
+    }
+    let token = token.replace('_', "");
+    let unsigned = token.strip_prefix(['+', '-']).unwrap_or(&token);
+    if !unsigned.starts_with(|c: char| c.is_ascii_digit())
+        || unsigned.len() > 1
+            && unsigned.starts_with('0')
+            && !unsigned[1..].starts_with(['.', 'e', 'E'])
+    {
+        return None;
+    }
+
+    if unsigned.bytes().all(|b| b.is_ascii_digit()) {
+        return Some(match token.parse::<i64>() {
+            Ok(int) => Value::from(int),
+            // Only unsigned ones have a chance past `i64`.
+            Err(_) => Value::from(
+                token
+                    .strip_prefix('+')
+                    .unwrap_or(&token)
+                    .parse::<u64>()
+                    .ok()?,
+            ),
+        });
+    }
+    // A dot has digits on both sides in TOML.
+    if let Some((_, frac)) = unsigned.split_once('.')
+        && !frac.starts_with(|c: char| c.is_ascii_digit())
+    {
+        return None;
+    }
+    let float: f64 = token.parse().ok()?;
+    Number::from_f64(float).map(Value::Number)
+}
+
+/// Writes `value`, an object as serde makes of a config, back as TOML: its scalars at the top,
+/// then a table for each object in it. Nulls are left out.
+pub(super) fn to_string(value: &Value) -> String {
+    let mut out = String::new();
+    if let Value::Object(map) = value {
+        write_table(&mut out, "", map);
+    }
+    out
+}
+
+fn write_table(out: &mut String, path: &str, map: &Map<String, Value>) {
+    let (tables, keys): (Vec<_>, Vec<_>) = map.iter().partition(|(_, value)| value.is_object());
+    if !path.is_empty() {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        _ = writeln!(out, "[{path}]");
+    }
+    for (key, value) in keys {
+        match value {
+            Value::Null => {}
+            Value::Bool(value) => _ = writeln!(out, "{key} = {value}"),
+            Value::Number(number) => {
+                let number = number.to_string();
+                // Whole floats keep a fraction, so that they read back as floats.
+                let float = number.contains(['.', 'e', 'E']) || !value.is_f64();
+                let suffix = if float { "" } else { ".0" };
+                _ = writeln!(out, "{key} = {number}{suffix}");
+            }
+            Value::String(value) => {
+                out.push_str(key);
+                out.push_str(" = ");
+                write_string(out, value);
+                out.push('\n');
+            }
+            // Not in any config, so not supported either.
+            Value::Array(_) | Value::Object(_) => {}
+        }
+    }
+    for (key, value) in tables {
+        let path = match path {
+            "" => key.clone(),
+            _ => format!("{path}.{key}"),
+        };
+        write_table(out, &path, value.as_object().expect("partitioned by it"));
+    }
+}
+
+fn write_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c if c.is_control() => _ = write!(out, "\\u{:04X}", c as u32),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
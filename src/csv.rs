@@ -0,0 +1,109 @@
+//! Recording ticks into a CSV file, e.g. for backtesting.
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    task::{Context, Waker},
+    time::Duration,
+};
+
+use tokio::time::{Instant, Interval, MissedTickBehavior};
+
+use crate::{BookTicker, handler::TickHandler, logging::log_warn};
+
+/// When [`CsvSink`] flushes, whichever comes first.
+#[derive(Clone, Copy, Debug)]
+pub struct FlushPolicy {
+    /// Flush after this many rows since the last flush.
+    pub every_rows: usize,
+    /// Flush on the first row after this much time since the last flush.
+    pub every: Duration,
+}
+
+impl Default for FlushPolicy {
+    fn default() -> Self {
+        Self {
+            every_rows: 1000,
+            every: Duration::from_millis(500),
+        }
+    }
+}
+
+/// Writes a `T,b,B,a,A` row per tick.
+///
+/// Must be created and used within a Tokio runtime, as the timed flushes rely on its timer.
+pub struct CsvSink {
+    writer: BufWriter<File>,
+    policy: FlushPolicy,
+    interval: Interval,
+    unflushed_rows: usize,
+}
+
+impl CsvSink {
+    pub fn create(path: impl AsRef<Path>, policy: FlushPolicy) -> io::Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?), policy)
+    }
+
+    /// Writes the header right away.
+    pub fn new(mut writer: BufWriter<File>, policy: FlushPolicy) -> io::Result<Self> {
+        writer.write_all(b"T,b,B,a,A\n")?;
+
+        let mut interval = tokio::time::interval_at(Instant::now() + policy.every, policy.every);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        Ok(Self {
+            writer,
+            policy,
+            interval,
+            unflushed_rows: 0,
+        })
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.unflushed_rows = 0;
+        self.writer.flush()
+    }
+
+    fn write_row(&mut self, tick: &BookTicker<'_>) -> io::Result<()> {
+        // Formatted on the stack and handed to the writer at once, a row is well within 128
+        // bytes unless the fields are garbage.
+        let mut row = [0u8; 128];
+        let capacity = row.len();
+        let mut rest = &mut row[..];
+        let fits = writeln!(
+            rest,
+            "{},{},{},{},{}",
+            tick.T, tick.b, tick.B, tick.a, tick.A
+        );
+        let len = capacity - rest.len();
+        match fits {
+            Ok(()) => self.writer.write_all(&row[..len])?,
+            Err(_) => writeln!(
+                self.writer,
+                "{},{},{},{},{}",
+                tick.T, tick.b, tick.B, tick.a, tick.A
+            )?,
+        }
+        self.unflushed_rows += 1;
+
+        if self.unflushed_rows >= self.policy.every_rows || self.interval_elapsed() {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Checks the interval without waiting for it.
+    fn interval_elapsed(&mut self) -> bool {
+        let mut cx = Context::from_waker(Waker::noop());
+        self.interval.poll_tick(&mut cx).is_ready()
+    }
+}
+
+impl TickHandler for CsvSink {
+    fn on_tick(&mut self, tick: BookTicker<'_>) {
+        if let Err(err) = self.write_row(&tick) {
+            log_warn!("failed to write tick to csv: {err}");
+        }
+    }
+}
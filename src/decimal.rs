@@ -0,0 +1,284 @@
+//! Fixed-point decimals, so prices and volumes can take part in arithmetic without going through
+//! floats.
+
+use core::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::{Add, Neg, Sub},
+};
+
+use crate::ParseError;
+
+/// The value is `mantissa * 10^exponent`, e.g. "83604.80" is `8360480 * 10^-2`.
+#[derive(Clone, Copy, Debug)]
+pub struct FixedPoint {
+    pub mantissa: i64,
+    pub exponent: i8,
+}
+
+/// Most significant digits a mantissa can take from [`parse_fixed`], which always fit in an
+/// `i64`. Leading zeros don't count, so e.g. "0.00000001" has only one.
+pub const MAX_SIGNIFICANT_DIGITS: usize = 18;
+
+/// Parses a decimal like "83604.80" or "-0.0001", keeping all the digits it has.
+///
+/// Anything but an optional sign, digits and at most one point with digits on both sides is
+/// rejected, as is a decimal of more than [`MAX_SIGNIFICANT_DIGITS`], since the input comes
+/// straight from the network.
+pub fn parse_fixed(s: &str) -> Result<FixedPoint, ParseError> {
+    let (negative, digits) = match s.as_bytes() {
+        [b'-', rest @ ..] => (true, rest),
+        bytes => (false, bytes),
+    };
+    let (int, frac) = match digits.iter().position(|&byte| byte == b'.') {
+        Some(dot) => (&digits[..dot], &digits[dot + 1..]),
+        None => (digits, &[][..]),
+    };
+    let has_dot = int.len() < digits.len();
+    if int.is_empty() || (has_dot && frac.is_empty()) {
+        return Err(ParseError::InvalidDecimal);
+    }
+    // Also rejects a second point, left in the fractional part.
+    if !int.iter().chain(frac).all(u8::is_ascii_digit) {
+        return Err(ParseError::InvalidDecimal);
+    }
+    let exponent = i8::try_from(frac.len())
+        .ok()
+        .and_then(i8::checked_neg)
+        .ok_or(ParseError::InvalidDecimal)?;
+
+    let significant = int.iter().chain(frac).skip_while(|&&byte| byte == b'0');
+    if significant.clone().count() > MAX_SIGNIFICANT_DIGITS {
+        return Err(ParseError::InvalidDecimal);
+    }
+    let mantissa = significant.fold(0i64, |mantissa, &byte| mantissa * 10 + (byte - b'0') as i64);
+
+    Ok(FixedPoint {
+        mantissa: if negative { -mantissa } else { mantissa },
+        exponent,
+    })
+}
+
+impl FixedPoint {
+    /// Nearest float, e.g. for ratios that don't need to be exact.
+    pub fn to_f64(self) -> f64 {
+        // Powers of 10 past `u64` scaled one digit at a time, only ever the case for values far
+        // off anything traded.
+        let scale = (0..self.exponent.unsigned_abs()).fold(1.0, |scale, _| scale * 10.0);
+        if self.exponent >= 0 {
+            self.mantissa as f64 * scale
+        } else {
+            self.mantissa as f64 / scale
+        }
+    }
+
+    /// The same value with the trailing zeros of the mantissa divided out, e.g. `1000 * 10^-3`
+    /// becomes `1 * 10^0`, so that equal values have equal fields. Zero is always `0 * 10^0`.
+    pub fn normalize(&self) -> FixedPoint {
+        if self.mantissa == 0 {
+            return FixedPoint {
+                mantissa: 0,
+                exponent: 0,
+            };
+        }
+        let mut normalized = *self;
+        while normalized.mantissa % 10 == 0 && normalized.exponent < i8::MAX {
+            normalized.mantissa /= 10;
+            normalized.exponent += 1;
+        }
+        normalized
+    }
+
+    /// The mantissa to use with a smaller or equal `exponent` to represent the same value, which
+    /// fits as long as they're at most 19 digits apart.
+    fn mantissa_at(self, exponent: i8) -> i128 {
+        debug_assert!(exponent <= self.exponent);
+        self.mantissa as i128 * 10i128.pow((self.exponent as i32 - exponent as i32) as u32)
+    }
+
+    /// Both mantissas at a common exponent, for adding or subtracting them. Exponents more than
+    /// 18 apart are brought closer by dropping the lowest digits of the smaller value, which
+    /// couldn't be kept in the `i64` of the result anyway.
+    fn aligned(self, other: Self) -> (i128, i128, i8) {
+        let exponent = self
+            .exponent
+            .min(other.exponent)
+            .max(self.exponent.max(other.exponent).saturating_sub(18));
+        let at = |value: Self| {
+            if value.exponent >= exponent {
+                value.mantissa_at(exponent)
+            } else {
+                let dropped = (exponent as i32 - value.exponent as i32) as u32;
+                10i128
+                    .checked_pow(dropped)
+                    .map_or(0, |scale| value.mantissa as i128 / scale)
+            }
+        };
+        (at(self), at(other), exponent)
+    }
+
+    /// Drops the lowest digits of `mantissa` until it fits, truncating toward zero, and those
+    /// below `10^-128` too, which leaves zero of a value any smaller. `None` if the exponent is
+    /// still over `i8::MAX`.
+    pub(crate) fn from_wide(mut mantissa: i128, mut exponent: i32) -> Option<Self> {
+        while i64::try_from(mantissa).is_err() || (exponent < i8::MIN.into() && mantissa != 0) {
+            mantissa /= 10;
+            exponent += 1;
+        }
+        // Only zero is left below that.
+        if exponent < i8::MIN.into() {
+            exponent = 0;
+        }
+        Some(FixedPoint {
+            mantissa: mantissa as i64,
+            exponent: i8::try_from(exponent).ok()?,
+        })
+    }
+
+    /// `self * rhs`, exact as long as the mantissa fits, e.g. a price times a quantity, otherwise
+    /// its lowest digits are dropped as with [`Add`]. `None` if it's too large for any exponent.
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        FixedPoint::from_wide(
+            self.mantissa as i128 * rhs.mantissa as i128,
+            self.exponent as i32 + rhs.exponent as i32,
+        )
+    }
+
+    /// Position of the leading digit, e.g. 4 for "83604.80", for comparing the magnitudes
+    /// of values without scaling either.
+    fn leading_digit(self) -> i32 {
+        self.mantissa.unsigned_abs().ilog10() as i32 + self.exponent as i32
+    }
+}
+
+impl PartialEq for FixedPoint {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for FixedPoint {}
+
+/// Of the normalized form, to agree with the equality by value.
+impl Hash for FixedPoint {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let FixedPoint { mantissa, exponent } = self.normalize();
+        mantissa.hash(state);
+        exponent.hash(state);
+    }
+}
+
+impl PartialOrd for FixedPoint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// By value, so e.g. "83604.80" and "83604.8" are equal.
+impl Ord for FixedPoint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let by_sign = self.mantissa.signum().cmp(&other.mantissa.signum());
+        if by_sign.is_ne() || self.mantissa == 0 {
+            return by_sign;
+        }
+        let by_magnitude = self.leading_digit().cmp(&other.leading_digit());
+        if by_magnitude.is_ne() {
+            return if self.mantissa > 0 {
+                by_magnitude
+            } else {
+                by_magnitude.reverse()
+            };
+        }
+        // Leading digits in the same place, so the exponents are at most 18 apart.
+        let exponent = self.exponent.min(other.exponent);
+        self.mantissa_at(exponent).cmp(&other.mantissa_at(exponent))
+    }
+}
+
+/// Exact as long as the result fits in an `i64` mantissa, otherwise its lowest digits are
+/// dropped, like those of a value orders of magnitude below the other.
+///
+/// Panics past `i64::MAX * 10^127`, which nothing from [`parse_fixed`] comes near.
+impl Add for FixedPoint {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        let (lhs, rhs, exponent) = self.aligned(rhs);
+        FixedPoint::from_wide(lhs + rhs, exponent.into()).expect("sum out of range")
+    }
+}
+
+/// Same as [`Add`].
+impl Sub for FixedPoint {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        let (lhs, rhs, exponent) = self.aligned(rhs);
+        FixedPoint::from_wide(lhs - rhs, exponent.into()).expect("difference out of range")
+    }
+}
+
+impl Neg for FixedPoint {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        FixedPoint {
+            mantissa: -self.mantissa,
+            exponent: self.exponent,
+        }
+    }
+}
+
+impl fmt::Display for FixedPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.exponent >= 0 && self.mantissa != 0 {
+            return write!(
+                f,
+                "{}{:0<width$}",
+                self.mantissa,
+                "",
+                width = self.exponent as usize
+            );
+        }
+
+        if self.exponent >= 0 {
+            return write!(f, "0");
+        }
+
+        let abs = self.mantissa.unsigned_abs();
+        // Past 10^19 the whole mantissa is fractional digits.
+        let (int, frac) = match 10u64.checked_pow(self.exponent.unsigned_abs() as u32) {
+            Some(scale) => (abs / scale, abs % scale),
+            None => (0, abs),
+        };
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        write!(
+            f,
+            "{sign}{int}.{frac:0width$}",
+            width = self.exponent.unsigned_abs() as usize
+        )
+    }
+}
+
+/// From the quoted decimals Binance sends, as [`parse_fixed`] takes them.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FixedPoint {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl serde::de::Visitor<'_> for Visitor {
+            type Value = FixedPoint;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "a decimal string like \"83604.80\"")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, s: &str) -> Result<FixedPoint, E> {
+                parse_fixed(s).map_err(|_| E::invalid_value(serde::de::Unexpected::Str(s), &self))
+            }
+        }
+
+        deserializer.deserialize_str(Visitor)
+    }
+}
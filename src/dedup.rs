@@ -0,0 +1,55 @@
+//! Suppression of book tickers re-sent unchanged.
+
+use crate::BookTicker;
+
+/// The part of a tick that tells repeats apart, with the prices hashed to keep it small and
+/// cheap to compare.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct Key {
+    T: u64,
+    b: u64,
+    a: u64,
+}
+
+/// Remembers the last tick to report an identical one right after it as a duplicate.
+#[derive(Default, Debug)]
+pub struct Deduplicator {
+    last: Option<Key>,
+}
+
+impl Deduplicator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether `tick` has the same transaction time, bid and ask as the previous one, in which
+    /// case it should be skipped. Otherwise it becomes the one to compare against next.
+    pub fn is_duplicate(&mut self, tick: &BookTicker<'_>) -> bool {
+        let key = Key {
+            T: tick.T,
+            b: fx_hash(tick.b.as_bytes()),
+            a: fx_hash(tick.a.as_bytes()),
+        };
+        if self.last == Some(key) {
+            return true;
+        }
+        self.last = Some(key);
+        false
+    }
+}
+
+/// The hash used by rustc, FxHash, which is fast on short inputs like prices and good enough
+/// for telling apart consecutive ones.
+fn fx_hash(bytes: &[u8]) -> u64 {
+    const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+    let add = |hash: u64, word: u64| (hash.rotate_left(5) ^ word).wrapping_mul(SEED);
+    let mut chunks = bytes.chunks_exact(8);
+    let mut hash = chunks.by_ref().fold(0, |hash, chunk| {
+        add(hash, u64::from_le_bytes(chunk.try_into().unwrap()))
+    });
+    for &byte in chunks.remainder() {
+        hash = add(hash, byte as u64);
+    }
+    hash
+}
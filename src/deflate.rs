@@ -0,0 +1,386 @@
+//! Inflating of raw DEFLATE streams (RFC 1951), as the payloads of WebSocket messages are once
+//! `permessage-deflate` (RFC 7692) is negotiated, which `DeflateStream` does with the `ws`
+//! feature.
+//!
+//! Only decompression is here: the server compresses what it sends, but nothing requires the
+//! client to compress what it sends back.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+#[cfg(feature = "ws")]
+mod stream;
+#[cfg(feature = "ws")]
+pub use stream::{DeflateStream, MAX_MESSAGE_LEN, offer_deflate};
+
+/// How far back a match can reach, the most any `server_max_window_bits` allows.
+pub const WINDOW: usize = 1 << 15;
+
+/// What the sender strips off the end of each message, the empty stored block of a sync flush.
+const SYNC_TAIL: [u8; 4] = [0x00, 0x00, 0xff, 0xff];
+
+/// Why a message couldn't be inflated.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum InflateError {
+    /// The input ended in the middle of a block.
+    Truncated,
+    /// A block of the reserved type 3.
+    InvalidBlockType,
+    /// The length of a stored block didn't match its one's complement.
+    InvalidStoredLength,
+    /// The code lengths of a dynamic block don't make up a Huffman code.
+    InvalidCodeLengths,
+    /// A code that isn't assigned to any symbol, or one of the unused ones.
+    InvalidSymbol,
+    /// A match reaching further back than anything inflated so far.
+    DistanceTooFar { distance: usize },
+    /// The message inflates to more than the limit it was given.
+    TooLong { max: usize },
+}
+
+impl fmt::Display for InflateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "deflate stream cut short"),
+            Self::InvalidBlockType => write!(f, "invalid deflate block type"),
+            Self::InvalidStoredLength => write!(f, "invalid stored block length"),
+            Self::InvalidCodeLengths => write!(f, "invalid Huffman code lengths"),
+            Self::InvalidSymbol => write!(f, "invalid Huffman code"),
+            Self::DistanceTooFar { distance } => {
+                write!(f, "match distance of {distance} is too far back")
+            }
+            Self::TooLong { max } => write!(f, "message inflates to over {max} bytes"),
+        }
+    }
+}
+
+impl core::error::Error for InflateError {}
+
+/// Inflates the messages of one connection, keeping the last [`WINDOW`] bytes of them for the
+/// next to refer back to, as senders do unless `server_no_context_takeover` was negotiated.
+#[derive(Clone, Default, Debug)]
+pub struct Inflater {
+    /// Everything inflated, trimmed down to the window now and then rather than after every
+    /// message.
+    history: Vec<u8>,
+    input: Vec<u8>,
+}
+
+impl Inflater {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forgets the previous messages, for a sender not taking its context over to the next.
+    pub fn reset(&mut self) {
+        self.history.clear();
+    }
+
+    /// Inflates the payload of one message, as sent with the trailing `00 00 ff ff` left out,
+    /// appending it to `out`. Fails if it inflates to more than `max_len` bytes.
+    pub fn inflate_message(
+        &mut self,
+        payload: &[u8],
+        out: &mut Vec<u8>,
+        max_len: usize,
+    ) -> Result<(), InflateError> {
+        // What's sent of an empty message may be nothing at all.
+        if payload.is_empty() {
+            return Ok(());
+        }
+        self.input.clear();
+        self.input.extend_from_slice(payload);
+        self.input.extend_from_slice(&SYNC_TAIL);
+
+        let start = self.history.len();
+        let res = Decoder {
+            bits: Bits::new(&self.input),
+            out: &mut self.history,
+            start,
+            max_len,
+        }
+        .blocks();
+        if let Err(err) = res {
+            // Nothing left to refer back to reliably.
+            self.history.clear();
+            return Err(err);
+        }
+
+        out.extend_from_slice(&self.history[start..]);
+        if self.history.len() > 2 * WINDOW {
+            self.history.drain(..self.history.len() - WINDOW);
+        }
+        Ok(())
+    }
+}
+
+/// Bits of the input, least significant first, taken a byte at a time as needed.
+struct Bits<'a> {
+    input: &'a [u8],
+    pos: usize,
+    buf: u32,
+    count: u32,
+}
+
+impl<'a> Bits<'a> {
+    fn new(input: &'a [u8]) -> Self {
+        Self {
+            input,
+            pos: 0,
+            buf: 0,
+            count: 0,
+        }
+    }
+
+    fn take(&mut self, n: u32) -> Result<u32, InflateError> {
+        while self.count < n {
+            let byte = *self.input.get(self.pos).ok_or(InflateError::Truncated)?;
+            self.buf |= u32::from(byte) << self.count;
+            self.pos += 1;
+            self.count += 8;
+        }
+        let value = self.buf & ((1 << n) - 1);
+        self.buf >>= n;
+        self.count -= n;
+        Ok(value)
+    }
+
+    /// Drops the bits left of the current byte.
+    fn align(&mut self) {
+        self.buf = 0;
+        self.count = 0;
+    }
+
+    /// Whether nothing but the padding of the last byte is left.
+    fn at_end(&self) -> bool {
+        self.pos == self.input.len()
+    }
+}
+
+/// A canonical Huffman code, by how many codes there are of each length and their symbols in
+/// code order.
+struct Huffman {
+    counts: [u16; 16],
+    symbols: [u16; 288],
+}
+
+impl Huffman {
+    /// Allows incomplete codes, whose unassigned codes only fail once read, as a block with a
+    /// single distance code needs.
+    fn new(lengths: &[u8]) -> Result<Self, InflateError> {
+        let mut counts = [0; 16];
+        for &len in lengths {
+            counts[usize::from(len)] += 1;
+        }
+        counts[0] = 0;
+        let mut left = 1i32;
+        for &count in &counts[1..] {
+            left = (left << 1) - i32::from(count);
+            if left < 0 {
+                return Err(InflateError::InvalidCodeLengths);
+            }
+        }
+
+        let mut offsets = [0; 16];
+        for len in 1..15 {
+            offsets[len + 1] = offsets[len] + counts[len];
+        }
+        let mut symbols = [0; 288];
+        for (symbol, &len) in lengths.iter().enumerate() {
+            if len != 0 {
+                symbols[usize::from(offsets[usize::from(len)])] = symbol as u16;
+                offsets[usize::from(len)] += 1;
+            }
+        }
+        Ok(Self { counts, symbols })
+    }
+
+    fn fixed() -> (Self, Self) {
+        let mut lengths = [0; 288];
+        lengths[..144].fill(8);
+        lengths[144..256].fill(9);
+        lengths[256..280].fill(7);
+        lengths[280..].fill(8);
+        let lit = Self::new(&lengths).expect("complete code");
+        let dist = Self::new(&[5; 30]).expect("incomplete code");
+        (lit, dist)
+    }
+
+    fn decode(&self, bits: &mut Bits<'_>) -> Result<u16, InflateError> {
+        // The first code of each length and the index of its symbol.
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for &count in &self.counts[1..] {
+            code |= bits.take(1)? as i32;
+            let count = i32::from(count);
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(InflateError::InvalidSymbol)
+    }
+}
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+/// The order code length code lengths come in, the likeliest first.
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+struct Decoder<'a, 'b> {
+    bits: Bits<'a>,
+    out: &'b mut Vec<u8>,
+    /// Where in `out` the message starts, everything before being previous ones.
+    start: usize,
+    max_len: usize,
+}
+
+impl Decoder<'_, '_> {
+    /// Every block up to the final one, or up to the end of the input for a sync flushed
+    /// message, which has none.
+    fn blocks(&mut self) -> Result<(), InflateError> {
+        loop {
+            let last = self.bits.take(1)? == 1;
+            match self.bits.take(2)? {
+                0 => self.stored()?,
+                1 => {
+                    let (lit, dist) = Huffman::fixed();
+                    self.codes(&lit, &dist)?;
+                }
+                2 => {
+                    let (lit, dist) = self.dynamic()?;
+                    self.codes(&lit, &dist)?;
+                }
+                _ => return Err(InflateError::InvalidBlockType),
+            }
+            if last || self.bits.at_end() {
+                return Ok(());
+            }
+        }
+    }
+
+    fn stored(&mut self) -> Result<(), InflateError> {
+        self.bits.align();
+        let len = self.bits.take(16)?;
+        if self.bits.take(16)? != !len & 0xffff {
+            return Err(InflateError::InvalidStoredLength);
+        }
+        let bits = &mut self.bits;
+        let bytes = bits
+            .input
+            .get(bits.pos..bits.pos + len as usize)
+            .ok_or(InflateError::Truncated)?;
+        bits.pos += bytes.len();
+        self.reserve(bytes.len())?;
+        self.out.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    fn dynamic(&mut self) -> Result<(Huffman, Huffman), InflateError> {
+        let lit_count = self.bits.take(5)? as usize + 257;
+        let dist_count = self.bits.take(5)? as usize + 1;
+        let code_length_count = self.bits.take(4)? as usize + 4;
+        if lit_count > 286 || dist_count > 30 {
+            return Err(InflateError::InvalidCodeLengths);
+        }
+
+        let mut lengths = [0; 19];
+        for &symbol in &CODE_LENGTH_ORDER[..code_length_count] {
+            lengths[symbol] = self.bits.take(3)? as u8;
+        }
+        let code_lengths = Huffman::new(&lengths)?;
+
+        let mut lengths = [0; 286 + 30];
+        let mut i = 0;
+        while i < lit_count + dist_count {
+            let (len, repeat) = match code_lengths.decode(&mut self.bits)? {
+                len @ 0..=15 => (len as u8, 1),
+                16 => {
+                    let previous = *i
+                        .checked_sub(1)
+                        .and_then(|i| lengths.get(i))
+                        .ok_or(InflateError::InvalidCodeLengths)?;
+                    (previous, 3 + self.bits.take(2)?)
+                }
+                17 => (0, 3 + self.bits.take(3)?),
+                _ => (0, 11 + self.bits.take(7)?),
+            };
+            let end = i + repeat as usize;
+            lengths
+                .get_mut(i..end)
+                .filter(|_| end <= lit_count + dist_count)
+                .ok_or(InflateError::InvalidCodeLengths)?
+                .fill(len);
+            i = end;
+        }
+        // Without an end of block code there's no telling where it ends.
+        if lengths[256] == 0 {
+            return Err(InflateError::InvalidCodeLengths);
+        }
+        let lit = Huffman::new(&lengths[..lit_count])?;
+        let dist = Huffman::new(&lengths[lit_count..lit_count + dist_count])?;
+        Ok((lit, dist))
+    }
+
+    fn codes(&mut self, lit: &Huffman, dist: &Huffman) -> Result<(), InflateError> {
+        loop {
+            let symbol = usize::from(lit.decode(&mut self.bits)?);
+            if symbol < 256 {
+                self.reserve(1)?;
+                self.out.push(symbol as u8);
+                continue;
+            }
+            if symbol == 256 {
+                return Ok(());
+            }
+
+            let symbol = symbol - 257;
+            let (Some(&base), Some(&extra)) = (LENGTH_BASE.get(symbol), LENGTH_EXTRA.get(symbol))
+            else {
+                return Err(InflateError::InvalidSymbol);
+            };
+            let len = usize::from(base) + self.bits.take(u32::from(extra))? as usize;
+            let symbol = usize::from(dist.decode(&mut self.bits)?);
+            let (Some(&base), Some(&extra)) =
+                (DISTANCE_BASE.get(symbol), DISTANCE_EXTRA.get(symbol))
+            else {
+                return Err(InflateError::InvalidSymbol);
+            };
+            let distance = usize::from(base) + self.bits.take(u32::from(extra))? as usize;
+            if distance > self.out.len() {
+                return Err(InflateError::DistanceTooFar { distance });
+            }
+
+            self.reserve(len)?;
+            // Byte by byte, since a match may overlap the bytes it produces.
+            let from = self.out.len() - distance;
+            for i in from..from + len {
+                self.out.push(self.out[i]);
+            }
+        }
+    }
+
+    fn reserve(&mut self, len: usize) -> Result<(), InflateError> {
+        if self.out.len() - self.start + len > self.max_len {
+            return Err(InflateError::TooLong { max: self.max_len });
+        }
+        Ok(())
+    }
+}
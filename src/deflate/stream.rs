@@ -0,0 +1,285 @@
+//! `permessage-deflate` underneath tungstenite, which has no support for it: frames are taken
+//! apart as they're read from the connection, and the compressed messages handed on inflated.
+
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll, ready},
+};
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio_tungstenite::tungstenite::{handshake::client::Request, http::HeaderValue};
+
+use super::Inflater;
+
+/// Longest message inflated, or frame passed on, the same as tungstenite's default limit.
+pub const MAX_MESSAGE_LEN: usize = 64 << 20;
+
+/// Longest handshake response taken before giving up on finding its end.
+const MAX_RESPONSE_LEN: usize = 64 << 10;
+
+const OPCODE_CONTINUATION: u8 = 0x0;
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_BINARY: u8 = 0x2;
+const FIN: u8 = 0x80;
+const RSV1: u8 = 0x40;
+const MASKED: u8 = 0x80;
+
+/// Asks the server for `permessage-deflate` in the handshake of `request`, to be made over a
+/// [`DeflateStream`].
+pub fn offer_deflate(request: &mut Request) {
+    request.headers_mut().insert(
+        "Sec-WebSocket-Extensions",
+        HeaderValue::from_static("permessage-deflate"),
+    );
+}
+
+enum State {
+    /// Waiting for the end of the handshake response, to see whether the server agreed.
+    Handshake,
+    Inflating,
+    /// Without the extension there's nothing to do, so bytes are read straight through.
+    Plain,
+}
+
+/// A connection to make the WebSocket handshake over, e.g. with `client_async`, which inflates
+/// the messages of the server once it agrees to `permessage-deflate`, as offered with
+/// [`offer_deflate`]. Frames of other messages, and anything else, are passed on as they are.
+///
+/// Only the server's messages are compressed, what the client writes goes out as it is.
+pub struct DeflateStream<S> {
+    inner: S,
+    state: State,
+    /// The server's `server_no_context_takeover`, each message then starting afresh.
+    no_context_takeover: bool,
+    inflater: Inflater,
+    /// Read from the connection but not taken apart yet, as it's only part of a frame.
+    raw: Vec<u8>,
+    /// Ready to be read, from `out_pos` on.
+    out: Vec<u8>,
+    out_pos: usize,
+    /// The opcode and payload so far of a compressed message still waiting for frames.
+    message: Option<(u8, Vec<u8>)>,
+    inflated: Vec<u8>,
+}
+
+impl<S> DeflateStream<S> {
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            state: State::Handshake,
+            no_context_takeover: false,
+            inflater: Inflater::new(),
+            raw: Vec::new(),
+            out: Vec::new(),
+            out_pos: 0,
+            message: None,
+            inflated: Vec::new(),
+        }
+    }
+
+    /// Whether the server agreed to `permessage-deflate`, only known once the handshake is.
+    pub fn is_inflating(&self) -> bool {
+        matches!(self.state, State::Inflating)
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Moves what can be of `raw` over to `out`.
+    fn process(&mut self) -> io::Result<()> {
+        if let State::Handshake = self.state {
+            let Some(end) = self.raw.windows(4).position(|w| w == b"\r\n\r\n") else {
+                if self.raw.len() > MAX_RESPONSE_LEN {
+                    return Err(invalid_data("handshake response too long"));
+                }
+                return Ok(());
+            };
+            let response = self.raw.drain(..end + 4);
+            match negotiated(response.as_slice()) {
+                Some(no_context_takeover) => {
+                    self.state = State::Inflating;
+                    self.no_context_takeover = no_context_takeover;
+                }
+                None => self.state = State::Plain,
+            }
+            self.out.extend(response);
+        }
+        if let State::Plain = self.state {
+            self.out.append(&mut self.raw);
+            return Ok(());
+        }
+
+        let mut pos = 0;
+        while let Some((header_len, payload_len)) = frame_len(&self.raw[pos..]) {
+            if payload_len > MAX_MESSAGE_LEN {
+                return Err(invalid_data("frame too long"));
+            }
+            let end = pos + header_len + payload_len;
+            let Some(frame) = self.raw.get(pos..end) else {
+                break;
+            };
+            let (first, masked) = (frame[0], frame[1] & MASKED != 0);
+            let (fin, rsv1, opcode) = (first & FIN != 0, first & RSV1 != 0, first & 0x0f);
+            let payload = &frame[header_len..];
+
+            // Masked frames from the server, and any other misuse of the bits, are left for
+            // tungstenite to fail the connection over.
+            match &mut self.message {
+                None if rsv1 && !masked && matches!(opcode, OPCODE_TEXT | OPCODE_BINARY) => {
+                    if fin {
+                        self.emit(opcode, None, pos..end, header_len)?;
+                    } else {
+                        self.message = Some((opcode, payload.to_vec()));
+                    }
+                }
+                Some((_, message)) if opcode == OPCODE_CONTINUATION && !rsv1 && !masked => {
+                    if message.len() + payload.len() > MAX_MESSAGE_LEN {
+                        return Err(invalid_data("message too long"));
+                    }
+                    message.extend_from_slice(payload);
+                    if fin {
+                        let (opcode, message) = self.message.take().expect("matched");
+                        self.emit(opcode, Some(message), pos..end, header_len)?;
+                    }
+                }
+                _ => self.out.extend_from_slice(frame),
+            }
+            pos = end;
+        }
+        self.raw.drain(..pos);
+        Ok(())
+    }
+
+    /// Writes out the inflated message as a single uncompressed frame, taking the payload from
+    /// `message` if it came in several, or else from the one frame at `frame` in `raw`.
+    fn emit(
+        &mut self,
+        opcode: u8,
+        message: Option<Vec<u8>>,
+        frame: std::ops::Range<usize>,
+        header_len: usize,
+    ) -> io::Result<()> {
+        let payload = match &message {
+            Some(message) => message.as_slice(),
+            None => &self.raw[frame.start + header_len..frame.end],
+        };
+        if self.no_context_takeover {
+            self.inflater.reset();
+        }
+        self.inflated.clear();
+        self.inflater
+            .inflate_message(payload, &mut self.inflated, MAX_MESSAGE_LEN)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        let len = self.inflated.len();
+        self.out.push(FIN | opcode);
+        if len < 126 {
+            self.out.push(len as u8);
+        } else if let Ok(len) = u16::try_from(len) {
+            self.out.push(126);
+            self.out.extend_from_slice(&len.to_be_bytes());
+        } else {
+            self.out.push(127);
+            self.out.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+        self.out.extend_from_slice(&self.inflated);
+        Ok(())
+    }
+}
+
+/// Whether the handshake `response` agrees to `permessage-deflate`, and if so with
+/// `server_no_context_takeover` or not.
+fn negotiated(response: &[u8]) -> Option<bool> {
+    let response = std::str::from_utf8(response).ok()?;
+    response
+        .split("\r\n")
+        .filter_map(|line| line.split_once(':'))
+        .filter(|(name, _)| name.trim().eq_ignore_ascii_case("sec-websocket-extensions"))
+        .flat_map(|(_, value)| value.split(','))
+        .find_map(|extension| {
+            let mut params = extension.split(';').map(str::trim);
+            (params.next() == Some("permessage-deflate"))
+                .then(|| params.any(|param| param == "server_no_context_takeover"))
+        })
+}
+
+/// The header and payload lengths of the frame at the start of `raw`, once its header is all
+/// there.
+fn frame_len(raw: &[u8]) -> Option<(usize, usize)> {
+    let second = *raw.get(1)?;
+    let mask_len = if second & MASKED != 0 { 4 } else { 0 };
+    let (len_len, payload_len) = match second & 0x7f {
+        126 => (
+            2,
+            u64::from(u16::from_be_bytes(raw.get(2..4)?.try_into().ok()?)),
+        ),
+        127 => (8, u64::from_be_bytes(raw.get(2..10)?.try_into().ok()?)),
+        len => (0, u64::from(len)),
+    };
+    // Anything past `usize` is over the limit anyway.
+    let payload_len = usize::try_from(payload_len).unwrap_or(usize::MAX);
+    Some((2 + len_len + mask_len, payload_len))
+}
+
+fn invalid_data(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for DeflateStream<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            if this.out_pos < this.out.len() {
+                let n = buf.remaining().min(this.out.len() - this.out_pos);
+                buf.put_slice(&this.out[this.out_pos..this.out_pos + n]);
+                this.out_pos += n;
+                if this.out_pos == this.out.len() {
+                    this.out.clear();
+                    this.out_pos = 0;
+                }
+                return Poll::Ready(Ok(()));
+            }
+            if let State::Plain = this.state {
+                return Pin::new(&mut this.inner).poll_read(cx, buf);
+            }
+
+            let mut chunk = [0; 16 << 10];
+            let mut read = ReadBuf::new(&mut chunk);
+            ready!(Pin::new(&mut this.inner).poll_read(cx, &mut read))?;
+            if read.filled().is_empty() {
+                // Whatever is left is cut short, for tungstenite to tell.
+                if this.raw.is_empty() {
+                    return Poll::Ready(Ok(()));
+                }
+                this.out.append(&mut this.raw);
+                continue;
+            }
+            this.raw.extend_from_slice(read.filled());
+            this.process()?;
+        }
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for DeflateStream<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
@@ -0,0 +1,245 @@
+//! Diff depth stream, e.g. `btcusdt@depth`.
+
+// {
+//   "e": "depthUpdate",    // event type
+//   "E": 1744760290967,    // event time
+//   "T": 1744760290965,    // transaction time
+//   "s": "BTCUSDT",        // symbol
+//   "U": 7283946601071,    // first update id in event
+//   "u": 7283946601420,    // final update id in event
+//   "pu": 7283946600990,   // final update id in last stream
+//   "b": [                 // bids to be updated
+//     [
+//       "83604.80",        // price level to be updated
+//       "10.746"           // quantity
+//     ]
+//   ],
+//   "a": [                 // asks to be updated
+//     [
+//       "83604.90",        // price level to be updated
+//       "9.514"            // quantity
+//     ]
+//   ]
+// }
+
+use alloc::vec::Vec;
+
+use crate::{
+    ParseError, decimal::FixedPoint, decimal::parse_fixed, expect_byte, simd::simd_find_byte,
+};
+
+/// Changed price levels, with the bids and asks left as raw arrays for [`iter_levels`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub struct DepthUpdate<'a> {
+    pub symbol: &'a str,
+    pub first_update_id: u64,
+    pub last_update_id: u64,
+    /// Raw JSON array of `[price, qty]` pairs, walk it with [`iter_levels`].
+    pub bids: &'a str,
+    /// Raw JSON array of `[price, qty]` pairs, walk it with [`iter_levels`].
+    pub asks: &'a str,
+}
+
+/// All parameters here change very rarely.
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub struct DepthConfig {
+    /// Position of `s`, as in "s":"
+    pub start: usize,
+    /// How many bytes are in the symbol, e.g. 7 for BTCUSDT.
+    pub symbol_len: usize,
+    /// How many digits are in the update ids.
+    pub update_id_digits: usize,
+}
+
+impl DepthConfig {
+    /// Field by field, in the order they're declared in.
+    pub const fn new(start: usize, symbol_len: usize, update_id_digits: usize) -> Self {
+        Self {
+            start,
+            symbol_len,
+            update_id_digits,
+        }
+    }
+}
+
+/// Parses a depth update payload laid out as described by `config`.
+pub fn parse_depth_update(json: &str, config: DepthConfig) -> Result<DepthUpdate<'_>, ParseError> {
+    let DepthConfig {
+        start,
+        symbol_len,
+        update_id_digits,
+    } = config;
+    expect_byte(json, start, b's', "s")?;
+
+    // Skip 4 chars: s":"
+    let s_start = start + 4;
+    let s_end = s_start + symbol_len;
+    expect_byte(json, s_end, b'"', "s")?;
+    let symbol = json
+        .get(s_start..s_end)
+        .ok_or(ParseError::FieldNotFound("s"))?;
+
+    // Skip 6 chars: ","U":
+    let U_start = s_end + 6;
+    let U_end = U_start + update_id_digits;
+    expect_byte(json, U_end, b',', "U")?;
+    let first_update_id = parse_update_id(json, U_start, U_end, "U")?;
+
+    // Skip 5 chars: ,"u":
+    let u_start = U_end + 5;
+    let u_end = u_start + update_id_digits;
+    expect_byte(json, u_end, b',', "u")?;
+    let last_update_id = parse_update_id(json, u_start, u_end, "u")?;
+
+    // Futures streams have `pu` in between, so just look for the start of the array.
+    let b_start = u_end
+        + simd_find_byte(&json.as_bytes()[u_end..], b'[').ok_or(ParseError::FieldNotFound("b"))?;
+    // Skip 3 chars back: b":
+    expect_byte(json, b_start.saturating_sub(3), b'b', "b")?;
+    let b_end = array_end(json, b_start, "b")?;
+    let bids = &json[b_start..b_end];
+
+    // Skip 5 chars: ,"a":
+    let a_start = b_end + 5;
+    expect_byte(json, a_start, b'[', "a")?;
+    let a_end = array_end(json, a_start, "a")?;
+    let asks = &json[a_start..a_end];
+
+    Ok(DepthUpdate {
+        symbol,
+        first_update_id,
+        last_update_id,
+        bids,
+        asks,
+    })
+}
+
+// {
+//   "lastUpdateId": 7283946601070,
+//   "E": 1744760290967,
+//   "T": 1744760290965,
+//   "bids": [["83604.80", "10.746"]],
+//   "asks": [["83604.90", "9.514"]]
+// }
+
+/// The whole book as of `last_update_id`, from the REST `/fapi/v1/depth` endpoint. Levels are
+/// `(price, qty)` pairs, best first.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[non_exhaustive]
+pub struct DepthSnapshot {
+    #[cfg_attr(feature = "serde", serde(rename = "lastUpdateId"))]
+    pub last_update_id: u64,
+    pub bids: Vec<(FixedPoint, FixedPoint)>,
+    pub asks: Vec<(FixedPoint, FixedPoint)>,
+}
+
+/// Parses a depth snapshot. Snapshots are only fetched once per (re)sync, so unlike the
+/// updates they're scanned by key rather than parsed by layout.
+pub fn parse_depth_snapshot(json: &str) -> Result<DepthSnapshot, ParseError> {
+    let id_start = json
+        .find("\"lastUpdateId\":")
+        .ok_or(ParseError::FieldNotFound("lastUpdateId"))?
+        + 15;
+    let id_end = id_start
+        + json[id_start..]
+            .bytes()
+            .take_while(u8::is_ascii_digit)
+            .count();
+    let last_update_id = parse_update_id(json, id_start, id_end, "lastUpdateId")?;
+
+    let bids_start = json
+        .find("\"bids\":")
+        .ok_or(ParseError::FieldNotFound("bids"))?
+        + 7;
+    expect_byte(json, bids_start, b'[', "bids")?;
+    let bids = iter_levels(&json[bids_start..array_end(json, bids_start, "bids")?])
+        .collect::<Result<_, _>>()?;
+
+    let asks_start = json
+        .find("\"asks\":")
+        .ok_or(ParseError::FieldNotFound("asks"))?
+        + 7;
+    expect_byte(json, asks_start, b'[', "asks")?;
+    let asks = iter_levels(&json[asks_start..array_end(json, asks_start, "asks")?])
+        .collect::<Result<_, _>>()?;
+
+    Ok(DepthSnapshot {
+        last_update_id,
+        bids,
+        asks,
+    })
+}
+
+fn parse_update_id(
+    json: &str,
+    start: usize,
+    end: usize,
+    field: &'static str,
+) -> Result<u64, ParseError> {
+    json.get(start..end)
+        .ok_or(ParseError::FieldNotFound(field))?
+        .parse()
+        .map_err(ParseError::IdParse)
+}
+
+/// Finds the end of the array of levels starting at `start`, past its closing bracket.
+fn array_end(json: &str, start: usize, field: &'static str) -> Result<usize, ParseError> {
+    let bytes = json.as_bytes();
+    if bytes.get(start + 1) == Some(&b']') {
+        return Ok(start + 2);
+    }
+
+    // Levels only contain strings, so the first "]]" closes the last level and the array.
+    let mut pos = start;
+    loop {
+        pos +=
+            1 + simd_find_byte(&bytes[pos + 1..], b']').ok_or(ParseError::FieldNotFound(field))?;
+        if bytes.get(pos + 1) == Some(&b']') {
+            return Ok(pos + 2);
+        }
+    }
+}
+
+/// Walks the `[price, qty]` pairs of a raw levels array, as in [`DepthUpdate::bids`]. A pair
+/// that isn't made of decimals, or is cut short, ends the walk with an error positioned in `raw`.
+pub fn iter_levels(
+    raw: &str,
+) -> impl Iterator<Item = Result<(FixedPoint, FixedPoint), ParseError>> + '_ {
+    let mut pos = 0;
+    core::iter::from_fn(move || {
+        let price_start = pos + simd_find_byte(raw.as_bytes().get(pos..)?, b'"')? + 1;
+        let level = parse_level(raw, price_start);
+        pos = match level {
+            Ok((_, end)) => end,
+            Err(_) => raw.len(),
+        };
+        Some(level.map(|(level, _)| level))
+    })
+}
+
+/// Parses the pair whose price starts at `price_start`, along with where it ends.
+fn parse_level(
+    raw: &str,
+    price_start: usize,
+) -> Result<((FixedPoint, FixedPoint), usize), ParseError> {
+    let bytes = raw.as_bytes();
+    let price_end = price_start
+        + simd_find_byte(&bytes[price_start..], b'"').ok_or(ParseError::FieldNotFound("level"))?;
+    // Skip 3 chars: ","
+    if bytes.get(price_end..price_end + 3) != Some(b"\",\"".as_slice()) {
+        return Err(ParseError::InvalidBoundary {
+            field: "level",
+            pos: price_end,
+        });
+    }
+    let qty_start = price_end + 3;
+    let qty_end = qty_start
+        + simd_find_byte(&bytes[qty_start..], b'"').ok_or(ParseError::FieldNotFound("level"))?;
+
+    let price = parse_fixed(&raw[price_start..price_end])?;
+    let qty = parse_fixed(&raw[qty_start..qty_end])?;
+    Ok(((price, qty), qty_end + 1))
+}
@@ -0,0 +1,88 @@
+//! Where the WebSocket streams and REST APIs of the different Binance markets are.
+
+use alloc::{borrow::Cow, format, string::String};
+
+use crate::SchemaVersion;
+
+/// Market to stream from.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "snake_case")
+)]
+#[non_exhaustive]
+pub enum StreamEndpoint {
+    UsdMFutures,
+    CoinMFutures,
+    Spot,
+    /// Spot on binance.us.
+    SpotUs,
+    /// The USD-M futures testnet, with its own API keys and no real funds.
+    Testnet,
+    /// Any other base URL, e.g. of a local server.
+    Custom(String),
+}
+
+impl StreamEndpoint {
+    /// The URL that stream paths are appended to, without a trailing slash.
+    pub fn base_url(&self) -> &str {
+        match self {
+            Self::UsdMFutures => "wss://fstream.binance.com",
+            Self::CoinMFutures => "wss://dstream.binance.com",
+            Self::Spot => "wss://stream.binance.com:9443",
+            Self::SpotUs => "wss://stream.binance.us:9443",
+            Self::Testnet => "wss://stream.binancefuture.com",
+            Self::Custom(url) => url.trim_end_matches('/'),
+        }
+    }
+
+    /// The URL that REST paths are appended to, without a trailing slash, e.g. for
+    /// [`RestClient::for_endpoint`](crate::rest::RestClient::for_endpoint). That of a custom
+    /// endpoint is its base URL with `https://` or `http://` in place of `wss://` or `ws://`.
+    pub fn rest_url(&self) -> Cow<'_, str> {
+        match self {
+            Self::UsdMFutures => "https://fapi.binance.com".into(),
+            Self::CoinMFutures => "https://dapi.binance.com".into(),
+            Self::Spot => "https://api.binance.com".into(),
+            Self::SpotUs => "https://api.binance.us".into(),
+            Self::Testnet => "https://testnet.binancefuture.com".into(),
+            Self::Custom(_) => {
+                let base_url = self.base_url();
+                match base_url.split_once("://") {
+                    Some(("wss", rest)) => format!("https://{rest}").into(),
+                    Some(("ws", rest)) => format!("http://{rest}").into(),
+                    _ => base_url.into(),
+                }
+            }
+        }
+    }
+
+    /// The URL of a single raw stream, e.g. `btcusdt@bookTicker`.
+    pub fn stream_url(&self, stream: &str) -> String {
+        format!("{}/ws/{stream}", self.base_url())
+    }
+
+    /// The URL of a combined stream of `streams`, whose events come wrapped with their stream
+    /// name, see [`strip_combined_wrapper`].
+    ///
+    /// [`strip_combined_wrapper`]: crate::combined::strip_combined_wrapper
+    pub fn combined_url(&self, streams: &[&str]) -> String {
+        format!("{}/stream?streams={}", self.base_url(), streams.join("/"))
+    }
+
+    /// The URL of the book ticker stream of `symbol`, in any case.
+    pub fn book_ticker_url(&self, symbol: &str) -> String {
+        self.stream_url(&format!("{}@bookTicker", symbol.to_ascii_lowercase()))
+    }
+
+    /// The schema of the book tickers sent, assuming a custom endpoint mirrors USD-M futures
+    /// like the testnet does.
+    pub fn schema_version(&self) -> SchemaVersion {
+        match self {
+            Self::UsdMFutures | Self::Testnet | Self::Custom(_) => SchemaVersion::Futures,
+            Self::CoinMFutures => SchemaVersion::CoinMFutures,
+            Self::Spot | Self::SpotUs => SchemaVersion::Spot,
+        }
+    }
+}
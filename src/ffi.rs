@@ -0,0 +1,177 @@
+//! C interface to the book ticker parser, with the header generated by cbindgen into
+//! `include/binance_json_parsing.h`.
+//!
+//! Build the library to link against with e.g.
+//! `cargo rustc --release --features ffi --crate-type cdylib`, keeping `std` for its panic
+//! handler.
+
+use core::ffi::c_char;
+
+use crate::{ParseError, ParsingConfig, SchemaVersion, parse_book_ticker_dynamic};
+
+/// Capacity of [`CBookTicker::e`], including the terminating NUL.
+pub const BJP_EVENT_CAP: usize = 16;
+/// Capacity of [`CBookTicker::s`], including the terminating NUL.
+pub const BJP_SYMBOL_CAP: usize = 32;
+/// Capacity of the price and quantity fields of [`CBookTicker`], including the terminating NUL.
+pub const BJP_DECIMAL_CAP: usize = 32;
+
+/// Parsed successfully.
+pub const BJP_OK: i32 = 0;
+/// `json` or `out` is null.
+pub const BJP_ERR_NULL: i32 = -1;
+/// The payload isn't valid UTF-8.
+pub const BJP_ERR_UTF8: i32 = -2;
+/// A field is missing, see [`ParseError::FieldNotFound`].
+pub const BJP_ERR_FIELD_NOT_FOUND: i32 = -3;
+/// A field ends unexpectedly, see [`ParseError::InvalidBoundary`].
+pub const BJP_ERR_INVALID_BOUNDARY: i32 = -4;
+/// A timestamp, id or decimal isn't a number.
+pub const BJP_ERR_NUMBER: i32 = -5;
+/// A string field doesn't fit into its array in [`CBookTicker`].
+pub const BJP_ERR_TOO_LONG: i32 = -6;
+/// [`CParsingConfig::schema_version`] isn't one of the `BJP_SCHEMA_*` values.
+pub const BJP_ERR_SCHEMA: i32 = -7;
+/// The payload is another event or a response, see [`ParseError::WrongEventType`].
+pub const BJP_ERR_WRONG_EVENT: i32 = -8;
+
+/// [`SchemaVersion::Futures`].
+pub const BJP_SCHEMA_FUTURES: u8 = 0;
+/// [`SchemaVersion::CoinMFutures`].
+pub const BJP_SCHEMA_COIN_M_FUTURES: u8 = 1;
+/// [`SchemaVersion::Spot`].
+pub const BJP_SCHEMA_SPOT: u8 = 2;
+
+/// Same as [`ParsingConfig`].
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct CParsingConfig {
+    pub start: usize,
+    pub price_precision: usize,
+    pub volume_precision: usize,
+    pub transaction_time_digits: usize,
+    pub symbol_len: usize,
+    /// One of the `BJP_SCHEMA_*` values.
+    pub schema_version: u8,
+}
+
+impl TryFrom<CParsingConfig> for ParsingConfig {
+    type Error = i32;
+
+    fn try_from(config: CParsingConfig) -> Result<Self, i32> {
+        let schema_version = match config.schema_version {
+            BJP_SCHEMA_FUTURES => SchemaVersion::Futures,
+            BJP_SCHEMA_COIN_M_FUTURES => SchemaVersion::CoinMFutures,
+            BJP_SCHEMA_SPOT => SchemaVersion::Spot,
+            _ => return Err(BJP_ERR_SCHEMA),
+        };
+        Ok(Self {
+            start: config.start,
+            price_precision: config.price_precision,
+            volume_precision: config.volume_precision,
+            transaction_time_digits: config.transaction_time_digits,
+            symbol_len: config.symbol_len,
+            schema_version,
+        })
+    }
+}
+
+/// Same as [`BookTicker`](crate::BookTicker), with the strings copied into NUL-terminated
+/// arrays.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct CBookTicker {
+    pub e: [c_char; BJP_EVENT_CAP],
+    pub u: u64,
+    pub s: [c_char; BJP_SYMBOL_CAP],
+    pub T: u64,
+    pub b: [c_char; BJP_DECIMAL_CAP],
+    pub B: [c_char; BJP_DECIMAL_CAP],
+    pub a: [c_char; BJP_DECIMAL_CAP],
+    pub A: [c_char; BJP_DECIMAL_CAP],
+    pub E: u64,
+}
+
+fn error_code(err: ParseError) -> i32 {
+    match err {
+        ParseError::FieldNotFound(_) => BJP_ERR_FIELD_NOT_FOUND,
+        ParseError::InvalidBoundary { .. } => BJP_ERR_INVALID_BOUNDARY,
+        ParseError::TimestampParse(_) | ParseError::IdParse(_) | ParseError::InvalidDecimal => {
+            BJP_ERR_NUMBER
+        }
+        ParseError::MessageTooLarge { .. } | ParseError::DecimalTooLong { .. } => BJP_ERR_TOO_LONG,
+        ParseError::MessageTooShort { .. } => BJP_ERR_FIELD_NOT_FOUND,
+        ParseError::WrongEventType => BJP_ERR_WRONG_EVENT,
+        // Book tickers have neither booleans nor sides, and aren't parsed by serde.
+        ParseError::InvalidBool { .. }
+        | ParseError::InvalidSide { .. }
+        | ParseError::InvalidJson { .. } => BJP_ERR_INVALID_BOUNDARY,
+    }
+}
+
+/// Copies `value` into `dst` with a terminating NUL.
+fn copy_str<const N: usize>(dst: &mut [c_char; N], value: &str) -> Result<(), i32> {
+    if value.len() >= N {
+        return Err(BJP_ERR_TOO_LONG);
+    }
+    for (dst, &byte) in dst.iter_mut().zip(value.as_bytes()) {
+        *dst = byte as c_char;
+    }
+    dst[value.len()] = 0;
+    Ok(())
+}
+
+/// Parses the `json_len` bytes at `json_ptr` into `out`, returning [`BJP_OK`] or one of the
+/// negative `BJP_ERR_*` codes, in which case `out` is left in an unspecified state.
+///
+/// Like [`parse_book_ticker_dynamic`], falls back to detecting the config if `start` doesn't match.
+///
+/// # Safety
+///
+/// `json_ptr` has to point to `json_len` readable bytes, and `out` to a writable
+/// [`CBookTicker`], unless they're null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn parse_book_ticker_c(
+    json_ptr: *const c_char,
+    json_len: usize,
+    config: CParsingConfig,
+    out: *mut CBookTicker,
+) -> i32 {
+    if json_ptr.is_null() || out.is_null() {
+        return BJP_ERR_NULL;
+    }
+    // SAFETY: both are non-null and valid by the contract of this function.
+    let (json, out) = unsafe {
+        (
+            core::slice::from_raw_parts(json_ptr.cast::<u8>(), json_len),
+            &mut *out,
+        )
+    };
+    let Ok(json) = core::str::from_utf8(json) else {
+        return BJP_ERR_UTF8;
+    };
+
+    let config = match ParsingConfig::try_from(config) {
+        Ok(config) => config,
+        Err(code) => return code,
+    };
+    let ticker = match parse_book_ticker_dynamic(json, config) {
+        Ok(ticker) => ticker,
+        Err(err) => return error_code(err),
+    };
+    let copied = (|| {
+        copy_str(&mut out.e, ticker.e)?;
+        copy_str(&mut out.s, ticker.s)?;
+        copy_str(&mut out.b, ticker.b)?;
+        copy_str(&mut out.B, ticker.B)?;
+        copy_str(&mut out.a, ticker.a)?;
+        copy_str(&mut out.A, ticker.A)
+    })();
+    if let Err(code) = copied {
+        return code;
+    }
+    out.u = ticker.u;
+    out.T = ticker.T;
+    out.E = ticker.E;
+    BJP_OK
+}
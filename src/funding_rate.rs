@@ -0,0 +1,85 @@
+//! Funding rate stream, e.g. `btcusdt@fundingRate`.
+
+// {
+//   "e": "fundingRate",        // event type
+//   "E": 1744760291000,        // event time
+//   "s": "BTCUSDT",            // symbol
+//   "r": "0.00010000",         // funding rate, negative when shorts pay longs
+//   "T": 1744761600000         // next funding time
+// }
+
+use crate::{
+    ParseError,
+    decimal::{FixedPoint, parse_fixed},
+    expect_byte, quoted_end, timestamp_field,
+};
+
+/// Funding rate of a symbol until its next funding time.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub struct FundingRate<'a> {
+    pub symbol: &'a str,
+    pub rate: FixedPoint,
+    pub next_funding_time: u64,
+}
+
+/// All parameters here change very rarely.
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub struct FundingRateConfig {
+    /// Position of `s`, as in "s":"
+    pub start: usize,
+    /// How many bytes are in the symbol, e.g. 7 for BTCUSDT.
+    pub symbol_len: usize,
+    /// How many digits are in the next funding time.
+    pub transaction_time_digits: usize,
+}
+
+impl FundingRateConfig {
+    /// Field by field, in the order they're declared in.
+    pub const fn new(start: usize, symbol_len: usize, transaction_time_digits: usize) -> Self {
+        Self {
+            start,
+            symbol_len,
+            transaction_time_digits,
+        }
+    }
+}
+
+/// Parses a funding rate payload laid out as described by `config`. The rate has no fixed
+/// length, as it may or may not have a sign.
+pub fn parse_funding_rate(
+    json: &str,
+    config: FundingRateConfig,
+) -> Result<FundingRate<'_>, ParseError> {
+    let FundingRateConfig {
+        start,
+        symbol_len,
+        transaction_time_digits,
+    } = config;
+    expect_byte(json, start, b's', "s")?;
+
+    // Skip 4 chars: s":"
+    let s_start = start + 4;
+    let s_end = s_start + symbol_len;
+    expect_byte(json, s_end, b'"', "s")?;
+    let symbol = json
+        .get(s_start..s_end)
+        .ok_or(ParseError::FieldNotFound("s"))?;
+
+    // Skip 7 chars: ","r":"
+    expect_byte(json, s_end + 3, b'r', "r")?;
+    let r_start = s_end + 7;
+    let r_end = quoted_end(json, r_start, "r")?;
+    let rate = parse_fixed(&json[r_start..r_end])?;
+
+    // Skip 6 chars: ","T":
+    expect_byte(json, r_end + 3, b'T', "T")?;
+    let next_funding_time = timestamp_field(json, r_end + 6, transaction_time_digits, b'}', "T")?;
+
+    Ok(FundingRate {
+        symbol,
+        rate,
+        next_funding_time,
+    })
+}
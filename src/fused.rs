@@ -0,0 +1,171 @@
+//! Mid prices of a symbol from its book ticker and mark price streams, fused on one combined
+//! connection.
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+
+use futures_util::StreamExt;
+use tokio::task::JoinHandle;
+
+use crate::{
+    ParseError,
+    cache::ConfigCache,
+    combined::strip_combined_wrapper,
+    decimal::parse_fixed,
+    endpoint::StreamEndpoint,
+    logging::log_warn,
+    mark_price::{MarkPriceConfig, detect_mark_price_config, parse_mark_price},
+    reconnect::{ReconnectConfig, ReconnectingStream},
+};
+
+/// What an atomic holds until its first value, as a float that can't come from the streams.
+const NONE: u64 = f64::NAN.to_bits();
+
+/// The latest values of both streams as float bits, to be read lock-free from any thread.
+///
+/// Each value is stored on its own, so a read racing an update may pair a new bid with the
+/// previous ask, which the prices being this close to each other makes harmless.
+#[derive(Debug)]
+pub struct FusedPrices {
+    bid: AtomicU64,
+    ask: AtomicU64,
+    premium_index: AtomicU64,
+}
+
+impl FusedPrices {
+    fn new() -> Self {
+        Self {
+            bid: AtomicU64::new(NONE),
+            ask: AtomicU64::new(NONE),
+            premium_index: AtomicU64::new(NONE),
+        }
+    }
+
+    /// `(bid + ask) / 2` of the latest book ticker, if there has been one.
+    pub fn mid_price(&self) -> Option<f64> {
+        let bid = load(&self.bid)?;
+        let ask = load(&self.ask)?;
+        Some((bid + ask) / 2.0)
+    }
+
+    /// `(mark - index) / index` of the latest mark price, if there has been one.
+    pub fn premium_index(&self) -> Option<f64> {
+        load(&self.premium_index)
+    }
+
+    /// The mid price times `1 + premium_index`, once both streams have sent something.
+    pub fn adjusted_mid_price(&self) -> Option<f64> {
+        Some(self.mid_price()? * (1.0 + self.premium_index()?))
+    }
+}
+
+fn load(value: &AtomicU64) -> Option<f64> {
+    let value = f64::from_bits(value.load(Ordering::Acquire));
+    (!value.is_nan()).then_some(value)
+}
+
+fn store(atomic: &AtomicU64, value: f64) {
+    atomic.store(value.to_bits(), Ordering::Release);
+}
+
+/// Follows `symbol@bookTicker` and `symbol@markPrice` on a task of its own, reconnecting as
+/// needed, while [`FusedPrices`] keeps the latest values of both.
+pub struct FusedPriceStream {
+    prices: Arc<FusedPrices>,
+    task: JoinHandle<()>,
+}
+
+impl FusedPriceStream {
+    /// Connects to the combined stream of `symbol` on `endpoint`, in any case. Needs to be
+    /// called on a Tokio runtime.
+    pub fn new(endpoint: &StreamEndpoint, symbol: &str) -> Self {
+        let symbol = symbol.to_ascii_lowercase();
+        let url = endpoint.combined_url(&[
+            &format!("{symbol}@bookTicker"),
+            &format!("{symbol}@markPrice"),
+        ]);
+        let prices = Arc::new(FusedPrices::new());
+        let task = tokio::spawn(run(url, Arc::clone(&prices)));
+        Self { prices, task }
+    }
+
+    /// The prices to read from elsewhere, e.g. an analytics thread, updated for as long as the
+    /// stream is alive.
+    pub fn prices(&self) -> Arc<FusedPrices> {
+        Arc::clone(&self.prices)
+    }
+
+    pub fn mid_price(&self) -> Option<f64> {
+        self.prices.mid_price()
+    }
+
+    pub fn adjusted_mid_price(&self) -> Option<f64> {
+        self.prices.adjusted_mid_price()
+    }
+}
+
+impl Drop for FusedPriceStream {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn run(url: String, prices: Arc<FusedPrices>) {
+    let mut ws_stream = ReconnectingStream::new(url, ReconnectConfig::default());
+    let mut book_ticker_configs = ConfigCache::default();
+    let mut mark_price_config = None;
+    while let Some(msg) = ws_stream.next().await {
+        let msg = match msg {
+            Ok(msg) => msg,
+            Err(err) => {
+                log_warn!("giving up on the connection: {err}");
+                break;
+            }
+        };
+        let Ok(text) = msg.to_text() else {
+            continue;
+        };
+        let Some((stream, data)) = strip_combined_wrapper(text) else {
+            continue;
+        };
+        let updated = if stream.ends_with("@bookTicker") {
+            book_ticker_configs.parse(stream, data).and_then(|ticker| {
+                let (bid, ask) = (parse_fixed(ticker.b)?, parse_fixed(ticker.a)?);
+                store(&prices.bid, bid.to_f64());
+                store(&prices.ask, ask.to_f64());
+                Ok(())
+            })
+        } else if stream.ends_with("@markPrice") {
+            update_premium_index(&prices, &mut mark_price_config, data)
+        } else {
+            continue;
+        };
+        if let Err(err) = updated {
+            log_warn!("failed to parse {stream}: {err}");
+        }
+    }
+}
+
+/// Parses with the cached config, detecting it again once if it turns out stale.
+fn update_premium_index(
+    prices: &FusedPrices,
+    config: &mut Option<MarkPriceConfig>,
+    json: &str,
+) -> Result<(), ParseError> {
+    let cached = match *config {
+        Some(cached) => cached,
+        None => *config.insert(detect_mark_price_config(json)?),
+    };
+    let mark_price = match parse_mark_price(json, cached) {
+        Err(ParseError::InvalidBoundary { .. }) => {
+            parse_mark_price(json, *config.insert(detect_mark_price_config(json)?))?
+        }
+        res => res?,
+    };
+    let mark = parse_fixed(mark_price.p)?.to_f64();
+    let index = parse_fixed(mark_price.i)?.to_f64();
+    store(&prices.premium_index, (mark - index) / index);
+    Ok(())
+}
@@ -0,0 +1,61 @@
+//! Callback interface for consuming ticks straight from the frame buffer, without copying them
+//! anywhere.
+
+use crate::{BookTicker, ParseError};
+#[cfg(feature = "ws")]
+use crate::{
+    ParsingConfig, detect_config, parse_book_ticker_dynamic,
+    reconnect::{ReconnectConfig, ReconnectingStream},
+};
+
+/// Consumer of book ticker updates, e.g. driven by `run_with_handler`, as in
+/// `examples/print_handler.rs`.
+pub trait TickHandler {
+    /// Called with every tick, borrowing from the frame it was parsed from.
+    fn on_tick(&mut self, tick: BookTicker<'_>);
+
+    /// Called with every frame that failed to parse, ignoring it by default.
+    fn on_error(&mut self, err: ParseError) {
+        let _ = err;
+    }
+}
+
+/// Parses every text frame of the WebSocket at `url` into `handler`, reconnecting as needed.
+/// Returns once the connection can't be restored.
+#[cfg(feature = "ws")]
+pub async fn run_with_handler(
+    url: &str,
+    config: ParsingConfig,
+    handler: &mut impl TickHandler,
+) -> anyhow::Result<()> {
+    use futures_util::StreamExt;
+
+    let mut ws_stream = ReconnectingStream::new(url, ReconnectConfig::default());
+    let mut config = config;
+
+    while let Some(msg) = ws_stream.next().await {
+        let msg = msg?;
+        if !msg.is_text() {
+            continue;
+        }
+        let text = msg.to_text().unwrap();
+
+        // Remember the new layout so it isn't detected again on every frame.
+        if !config.matches(text) {
+            match detect_config(text) {
+                Ok(detected) => config = detected,
+                Err(err) => {
+                    handler.on_error(err);
+                    continue;
+                }
+            }
+        }
+
+        match parse_book_ticker_dynamic(text, config) {
+            Ok(tick) => handler.on_tick(tick),
+            Err(err) => handler.on_error(err),
+        }
+    }
+
+    Ok(())
+}
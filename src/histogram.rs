@@ -0,0 +1,61 @@
+//! Latency distribution with power of two resolution.
+
+pub(crate) const BUCKETS: usize = u64::BITS as usize;
+
+/// Counts of measurements in buckets `[2^i, 2^(i + 1))`, with zero going to the first one.
+#[derive(Clone, Debug)]
+pub struct LatencyHistogram {
+    buckets: [u64; BUCKETS],
+    count: u64,
+}
+
+impl LatencyHistogram {
+    pub fn new() -> Self {
+        Self {
+            buckets: [0; BUCKETS],
+            count: 0,
+        }
+    }
+
+    pub fn record(&mut self, cycles: u64) {
+        self.buckets[bucket(cycles)] += 1;
+        self.count += 1;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Upper bound of the bucket holding the `p`th percentile, e.g. `percentile(99.0)`, or 0
+    /// if nothing was recorded yet.
+    pub fn percentile(&self, p: f64) -> u64 {
+        if self.count == 0 {
+            return 0;
+        }
+
+        // Rank of the measurement we're after, counting from 1.
+        let rank = ((p / 100.0 * self.count as f64).ceil() as u64).clamp(1, self.count);
+        let mut seen = 0;
+        for (i, &n) in self.buckets.iter().enumerate() {
+            seen += n;
+            if seen >= rank {
+                return bucket_upper_bound(i);
+            }
+        }
+        unreachable!("buckets sum up to the count")
+    }
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub(crate) fn bucket(cycles: u64) -> usize {
+    (u64::BITS - 1 - (cycles | 1).leading_zeros()) as usize
+}
+
+pub(crate) fn bucket_upper_bound(i: usize) -> u64 {
+    u64::MAX >> (BUCKETS - 1 - i)
+}
@@ -0,0 +1,142 @@
+//! Writing ticks to InfluxDB v2 as line protocol, e.g. to chart prices next to latencies.
+//!
+//! Every tick is a point of the `bookTicker` measurement tagged with its symbol:
+//!
+//! ```text
+//! bookTicker,symbol=BTCUSDT bid=83604.80,ask=83604.90,bidQty=10.746,askQty=9.514 1744760290967000000
+//! ```
+//!
+//! timestamped with `T` in nanoseconds, or by the server for spot tickers, which have no time.
+
+use std::{fmt::Write, io};
+
+use crate::{BookTicker, rest::RestClient};
+
+/// Which bucket [`InfluxSink`] writes to and how.
+#[derive(Clone, Debug)]
+pub struct InfluxConfig {
+    /// `http://` or `https://` followed by the host and port of the server.
+    pub url: String,
+    pub org: String,
+    pub bucket: String,
+    /// API token allowed to write to the bucket.
+    pub token: String,
+    /// Lines to buffer before writing them all in one request.
+    pub batch_size: usize,
+}
+
+impl InfluxConfig {
+    pub fn new(
+        url: impl Into<String>,
+        org: impl Into<String>,
+        bucket: impl Into<String>,
+        token: impl Into<String>,
+    ) -> Self {
+        Self {
+            url: url.into(),
+            org: org.into(),
+            bucket: bucket.into(),
+            token: token.into(),
+            batch_size: 100,
+        }
+    }
+}
+
+/// Buffers a line per tick, posting them to `/api/v2/write` every
+/// [`InfluxConfig::batch_size`] lines.
+pub struct InfluxSink {
+    client: RestClient,
+    path: String,
+    authorization: String,
+    batch_size: usize,
+    lines: String,
+    buffered: usize,
+}
+
+impl InfluxSink {
+    pub fn new(config: InfluxConfig) -> io::Result<Self> {
+        Ok(Self {
+            client: RestClient::new(&config.url)?,
+            path: format!(
+                "/api/v2/write?org={}&bucket={}&precision=ns",
+                encode(&config.org),
+                encode(&config.bucket)
+            ),
+            authorization: format!("Token {}", config.token),
+            batch_size: config.batch_size,
+            lines: String::with_capacity(config.batch_size * 100),
+            buffered: 0,
+        })
+    }
+
+    /// Buffers the line of `tick`, writing the batch if it's full.
+    pub async fn write(&mut self, tick: &BookTicker<'_>) -> io::Result<()> {
+        write_line(&mut self.lines, tick);
+        self.buffered += 1;
+        if self.buffered >= self.batch_size {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// Writes the lines buffered so far, if any. They're dropped even if the write fails, so
+    /// that a server that's down doesn't make them pile up.
+    pub async fn flush(&mut self) -> io::Result<()> {
+        if self.buffered == 0 {
+            return Ok(());
+        }
+        let headers = [
+            ("Authorization", self.authorization.as_str()),
+            ("Content-Type", "text/plain; charset=utf-8"),
+        ];
+        let written = self
+            .client
+            .post(&self.path, &headers, self.lines.as_bytes())
+            .await;
+        self.lines.clear();
+        self.buffered = 0;
+        written.map(drop)
+    }
+}
+
+/// Percent-encodes `s` for the query, e.g. a bucket with spaces.
+fn encode(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            _ = write!(encoded, "%{byte:02X}");
+        }
+    }
+    encoded
+}
+
+/// The line of `tick`, without the trailing newline.
+pub fn to_line_protocol(tick: &BookTicker<'_>) -> String {
+    let mut line = String::with_capacity(100);
+    write_line(&mut line, tick);
+    line.pop();
+    line
+}
+
+fn write_line(buf: &mut String, tick: &BookTicker<'_>) {
+    buf.push_str("bookTicker,symbol=");
+    // Tag values can't hold these unescaped, not that symbols do.
+    for c in tick.s.chars() {
+        if matches!(c, ',' | '=' | ' ') {
+            buf.push('\\');
+        }
+        buf.push(c);
+    }
+    // Decimals without the `i` suffix are floats, even without a decimal point.
+    _ = write!(
+        buf,
+        " bid={},ask={},bidQty={},askQty={}",
+        tick.b, tick.a, tick.B, tick.A
+    );
+    if tick.T != 0 {
+        _ = write!(buf, " {}", u128::from(tick.T) * 1_000_000);
+    }
+    buf.push('\n');
+}
@@ -0,0 +1,176 @@
+//! Publishing ticks to a Kafka topic, for risk systems and analytics pipelines to consume.
+//!
+//! Each tick is sent as a record keyed by its symbol, with the ticker encoded in MessagePack as
+//! a map named after the keys, see [`to_msgpack`]. The client is a minimal one speaking the
+//! produce API to the leader of a single partition over plain TCP, so there's no TLS, SASL or
+//! partitioning by key.
+
+use std::{
+    io,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use tokio::{
+    sync::mpsc::{self, error::TrySendError},
+    task::JoinHandle,
+};
+
+use crate::{BookTicker, BookTickerOwned, handler::TickHandler, logging::log_warn};
+
+mod msgpack;
+mod protocol;
+#[cfg(test)]
+mod tests;
+
+pub use msgpack::to_msgpack;
+use protocol::{Connection, Record};
+
+/// Where [`KafkaSink`] publishes to and how much it buffers meanwhile.
+#[derive(Clone, Debug)]
+pub struct KafkaConfig {
+    /// `host:port` of the brokers to look the leader of the partition up from, tried in order.
+    pub brokers: Vec<String>,
+    pub topic: String,
+    pub partition: i32,
+    /// Ticks waiting to be sent beyond which new ones are dropped rather than queued.
+    pub max_queued: usize,
+    /// How many of the queued ticks to send per request at most.
+    pub max_batch: usize,
+    /// How long the leader may wait for the record to be written before failing the request.
+    pub timeout: Duration,
+    pub client_id: String,
+}
+
+impl KafkaConfig {
+    pub fn new(
+        brokers: impl IntoIterator<Item = impl Into<String>>,
+        topic: impl Into<String>,
+    ) -> Self {
+        Self {
+            brokers: brokers.into_iter().map(Into::into).collect(),
+            topic: topic.into(),
+            partition: 0,
+            max_queued: 10_000,
+            max_batch: 500,
+            timeout: Duration::from_secs(5),
+            client_id: "binance-json-parsing".to_owned(),
+        }
+    }
+}
+
+/// Queues ticks to be published by a task of its own, so that sending never waits on Kafka.
+///
+/// Must be created and used within a Tokio runtime.
+pub struct KafkaSink {
+    queue: mpsc::Sender<Record>,
+    dropped: Arc<AtomicU64>,
+    task: JoinHandle<()>,
+}
+
+impl KafkaSink {
+    /// Looks up the leader of the partition and connects to it.
+    pub async fn connect(config: KafkaConfig) -> io::Result<Self> {
+        let conn = connect_to_leader(&config).await?;
+        let (queue, queued) = mpsc::channel(config.max_queued);
+        let dropped = Arc::new(AtomicU64::new(0));
+        let task = tokio::spawn(publish(config, conn, queued, Arc::clone(&dropped)));
+
+        Ok(Self {
+            queue,
+            dropped,
+            task,
+        })
+    }
+
+    /// Queues `tick`, or drops it if [`KafkaConfig::max_queued`] are already waiting. Returns
+    /// whether it was queued.
+    pub fn send(&self, tick: &BookTickerOwned) -> bool {
+        let record = Record {
+            key: tick.s.clone(),
+            value: to_msgpack(tick),
+            timestamp: tick.E as i64,
+        };
+        match self.queue.try_send(record) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_) | TrySendError::Closed(_)) => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                false
+            }
+        }
+    }
+
+    /// Ticks dropped so far, either because the queue was full or because sending them failed.
+    pub fn dropped_messages(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Waits for the queued ticks to be sent.
+    pub async fn close(self) {
+        drop(self.queue);
+        if let Err(err) = self.task.await {
+            log_warn!("kafka publisher panicked: {err}");
+        }
+    }
+}
+
+impl TickHandler for KafkaSink {
+    fn on_tick(&mut self, tick: BookTicker<'_>) {
+        match BookTickerOwned::try_from(tick) {
+            Ok(tick) => _ = self.send(&tick),
+            Err(err) => self.on_error(err),
+        }
+    }
+}
+
+async fn connect_to_leader(config: &KafkaConfig) -> io::Result<Connection> {
+    let mut last_err = io::Error::new(io::ErrorKind::InvalidInput, "no brokers given");
+    for broker in &config.brokers {
+        let leader = match Connection::connect(broker, &config.client_id).await {
+            Ok(mut conn) => conn.leader(&config.topic, config.partition).await,
+            Err(err) => Err(err),
+        };
+        match leader {
+            Ok(leader) => return Connection::connect(&leader, &config.client_id).await,
+            Err(err) => last_err = err,
+        }
+    }
+    Err(last_err)
+}
+
+/// Sends the queued ticks in batches until the sink is closed, reconnecting after failures.
+async fn publish(
+    config: KafkaConfig,
+    conn: Connection,
+    mut queued: mpsc::Receiver<Record>,
+    dropped: Arc<AtomicU64>,
+) {
+    let mut conn = Some(conn);
+    let mut batch = Vec::with_capacity(config.max_batch);
+    while queued.recv_many(&mut batch, config.max_batch).await > 0 {
+        let sent = match &mut conn {
+            Some(conn) => {
+                conn.produce(&config.topic, config.partition, &batch, config.timeout)
+                    .await
+            }
+            None => match connect_to_leader(&config).await {
+                Ok(new) => {
+                    conn.insert(new)
+                        .produce(&config.topic, config.partition, &batch, config.timeout)
+                        .await
+                }
+                Err(err) => Err(err),
+            },
+        };
+        if let Err(err) = sent {
+            log_warn!("failed to publish {} ticks to kafka: {err}", batch.len());
+            dropped.fetch_add(batch.len() as u64, Ordering::Relaxed);
+            // The leader may have moved, so look it up again for the next batch.
+            conn = None;
+        }
+        batch.clear();
+    }
+}
@@ -0,0 +1,74 @@
+//! The bits of MessagePack needed to encode a ticker, see
+//! <https://github.com/msgpack/msgpack/blob/master/spec.md>.
+
+use crate::BookTickerOwned;
+
+/// Encodes `tick` as a map of its keys to their values, just like serializing it with serde
+/// into MessagePack with the field names would, e.g. with `rmp_serde::to_vec_named`. Integers
+/// take the fewest bytes they fit in.
+pub fn to_msgpack(tick: &BookTickerOwned) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(128);
+    let len = if tick.pair.is_some() { 10 } else { 9 };
+    // fixmap
+    buf.push(0x80 | len);
+    entry_str(&mut buf, "e", &tick.e);
+    entry_uint(&mut buf, "u", tick.u);
+    entry_str(&mut buf, "s", &tick.s);
+    if let Some(pair) = &tick.pair {
+        entry_str(&mut buf, "ps", pair);
+    }
+    entry_uint(&mut buf, "T", tick.T);
+    entry_str(&mut buf, "b", &tick.b);
+    entry_str(&mut buf, "B", &tick.B);
+    entry_str(&mut buf, "a", &tick.a);
+    entry_str(&mut buf, "A", &tick.A);
+    entry_uint(&mut buf, "E", tick.E);
+    buf
+}
+
+fn entry_str(buf: &mut Vec<u8>, key: &str, value: &str) {
+    str(buf, key);
+    str(buf, value);
+}
+
+fn entry_uint(buf: &mut Vec<u8>, key: &str, value: u64) {
+    str(buf, key);
+    uint(buf, value);
+}
+
+fn str(buf: &mut Vec<u8>, s: &str) {
+    let len = s.len();
+    if len < 32 {
+        // fixstr
+        buf.push(0xa0 | len as u8);
+    } else if let Ok(len) = u8::try_from(len) {
+        buf.push(0xd9);
+        buf.push(len);
+    } else if let Ok(len) = u16::try_from(len) {
+        buf.push(0xda);
+        buf.extend_from_slice(&len.to_be_bytes());
+    } else {
+        buf.push(0xdb);
+        buf.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+    buf.extend_from_slice(s.as_bytes());
+}
+
+fn uint(buf: &mut Vec<u8>, value: u64) {
+    if value < 0x80 {
+        // positive fixint
+        buf.push(value as u8);
+    } else if let Ok(value) = u8::try_from(value) {
+        buf.push(0xcc);
+        buf.push(value);
+    } else if let Ok(value) = u16::try_from(value) {
+        buf.push(0xcd);
+        buf.extend_from_slice(&value.to_be_bytes());
+    } else if let Ok(value) = u32::try_from(value) {
+        buf.push(0xce);
+        buf.extend_from_slice(&value.to_be_bytes());
+    } else {
+        buf.push(0xcf);
+        buf.extend_from_slice(&value.to_be_bytes());
+    }
+}
@@ -0,0 +1,336 @@
+//! The two requests of the Kafka protocol the sink needs: metadata, to find the leader of the
+//! partition, and produce, with the records in a v2 record batch. See
+//! <https://kafka.apache.org/protocol> for the layouts.
+
+use std::{io, time::Duration};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+const PRODUCE: i16 = 0;
+const METADATA: i16 = 3;
+/// The first versions with record batches and with the brokers' ids, still supported by every
+/// broker since.
+const PRODUCE_VERSION: i16 = 3;
+const METADATA_VERSION: i16 = 1;
+
+/// Waiting for the leader only, trading durability for latency.
+const ACKS: i16 = 1;
+
+/// One record, keyed by the symbol, with the time in milliseconds.
+pub(super) struct Record {
+    pub key: String,
+    pub value: Vec<u8>,
+    pub timestamp: i64,
+}
+
+/// A connection to one broker, sending one request at a time.
+pub(super) struct Connection {
+    stream: TcpStream,
+    client_id: String,
+    correlation_id: i32,
+}
+
+impl Connection {
+    pub async fn connect(addr: &str, client_id: &str) -> io::Result<Self> {
+        let stream = TcpStream::connect(addr).await?;
+        stream.set_nodelay(true)?;
+        Ok(Self {
+            stream,
+            client_id: client_id.to_owned(),
+            correlation_id: 0,
+        })
+    }
+
+    /// `host:port` of the leader of `partition` of `topic`.
+    pub async fn leader(&mut self, topic: &str, partition: i32) -> io::Result<String> {
+        let mut body = Vec::new();
+        put_i32(&mut body, 1);
+        put_str(&mut body, topic);
+        let response = self.request(METADATA, METADATA_VERSION, &body).await?;
+
+        let mut r = Reader(&response);
+        let mut brokers = Vec::new();
+        for _ in 0..r.i32()? {
+            let node_id = r.i32()?;
+            let host = r.str()?.to_owned();
+            let port = r.i32()?;
+            let _rack = r.nullable_str()?;
+            brokers.push((node_id, format!("{host}:{port}")));
+        }
+        let _controller_id = r.i32()?;
+        for _ in 0..r.i32()? {
+            check_error(r.i16()?, topic)?;
+            let name = r.str()?;
+            let _is_internal = r.i8()?;
+            for _ in 0..r.i32()? {
+                let error = r.i16()?;
+                let index = r.i32()?;
+                let leader = r.i32()?;
+                r.skip_i32_array()?;
+                r.skip_i32_array()?;
+                if name == topic && index == partition {
+                    check_error(error, topic)?;
+                    return brokers
+                        .into_iter()
+                        .find(|&(node_id, _)| node_id == leader)
+                        .map(|(_, addr)| addr)
+                        .ok_or_else(|| invalid_data(format!("unknown leader {leader}")));
+                }
+            }
+        }
+        Err(invalid_data(format!("no partition {partition} of {topic}")))
+    }
+
+    /// Sends `records` in one batch, waiting for the leader to have written it.
+    pub async fn produce(
+        &mut self,
+        topic: &str,
+        partition: i32,
+        records: &[Record],
+        timeout: Duration,
+    ) -> io::Result<()> {
+        let batch = record_batch(records);
+        let mut body = Vec::with_capacity(batch.len() + 64);
+        // No transactional id.
+        put_i16(&mut body, -1);
+        put_i16(&mut body, ACKS);
+        put_i32(
+            &mut body,
+            timeout.as_millis().try_into().unwrap_or(i32::MAX),
+        );
+        put_i32(&mut body, 1);
+        put_str(&mut body, topic);
+        put_i32(&mut body, 1);
+        put_i32(&mut body, partition);
+        put_i32(&mut body, batch.len() as i32);
+        body.extend_from_slice(&batch);
+        let response = self.request(PRODUCE, PRODUCE_VERSION, &body).await?;
+
+        let mut r = Reader(&response);
+        for _ in 0..r.i32()? {
+            let _topic = r.str()?;
+            for _ in 0..r.i32()? {
+                let _partition = r.i32()?;
+                check_error(r.i16()?, topic)?;
+                let _base_offset = r.i64()?;
+                let _log_append_time = r.i64()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends a request and returns the body of its response.
+    async fn request(
+        &mut self,
+        api_key: i16,
+        api_version: i16,
+        body: &[u8],
+    ) -> io::Result<Vec<u8>> {
+        self.correlation_id = self.correlation_id.wrapping_add(1);
+        let mut header = Vec::with_capacity(16 + self.client_id.len());
+        put_i32(&mut header, 0);
+        put_i16(&mut header, api_key);
+        put_i16(&mut header, api_version);
+        put_i32(&mut header, self.correlation_id);
+        put_str(&mut header, &self.client_id);
+        let len = i32::try_from(header.len() - 4 + body.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "request too large"))?;
+        header[..4].copy_from_slice(&len.to_be_bytes());
+        self.stream.write_all(&header).await?;
+        self.stream.write_all(body).await?;
+
+        let len = self.stream.read_i32().await?;
+        let mut response =
+            vec![0; usize::try_from(len).map_err(|_| invalid_data("negative size"))?];
+        self.stream.read_exact(&mut response).await?;
+        let mut r = Reader(&response);
+        if r.i32()? != self.correlation_id {
+            return Err(invalid_data("response to another request"));
+        }
+        let header_len = response.len() - r.0.len();
+        response.drain(..header_len);
+        Ok(response)
+    }
+}
+
+/// Record batch of the `records`, uncompressed and without a producer id.
+pub(super) fn record_batch(records: &[Record]) -> Vec<u8> {
+    let base_timestamp = records.first().map_or(0, |record| record.timestamp);
+    let max_timestamp = records
+        .iter()
+        .map(|record| record.timestamp)
+        .max()
+        .unwrap_or(0);
+
+    let mut batch = Vec::with_capacity(61 + records.len() * 128);
+    // Base offset, assigned by the broker.
+    put_i64(&mut batch, 0);
+    // Length of the rest, filled in below.
+    put_i32(&mut batch, 0);
+    // Partition leader epoch, also assigned by the broker.
+    put_i32(&mut batch, -1);
+    // Magic
+    batch.push(2);
+    // CRC of the rest, filled in below.
+    put_i32(&mut batch, 0);
+    let crc_start = batch.len();
+    // Attributes: no compression, create time, not transactional.
+    put_i16(&mut batch, 0);
+    put_i32(&mut batch, records.len() as i32 - 1);
+    put_i64(&mut batch, base_timestamp);
+    put_i64(&mut batch, max_timestamp);
+    // Producer id, epoch and base sequence, all unset without idempotence.
+    put_i64(&mut batch, -1);
+    put_i16(&mut batch, -1);
+    put_i32(&mut batch, -1);
+    put_i32(&mut batch, records.len() as i32);
+
+    let mut record = Vec::with_capacity(128);
+    for (offset_delta, r) in records.iter().enumerate() {
+        record.clear();
+        // Attributes, unused.
+        record.push(0);
+        put_varint(&mut record, r.timestamp - base_timestamp);
+        put_varint(&mut record, offset_delta as i64);
+        put_varint(&mut record, r.key.len() as i64);
+        record.extend_from_slice(r.key.as_bytes());
+        put_varint(&mut record, r.value.len() as i64);
+        record.extend_from_slice(&r.value);
+        // No headers.
+        put_varint(&mut record, 0);
+
+        put_varint(&mut batch, record.len() as i64);
+        batch.extend_from_slice(&record);
+    }
+
+    let len = (batch.len() - 12) as i32;
+    batch[8..12].copy_from_slice(&len.to_be_bytes());
+    let crc = crc32c(&batch[crc_start..]);
+    batch[crc_start - 4..crc_start].copy_from_slice(&crc.to_be_bytes());
+    batch
+}
+
+/// CRC-32C, the Castagnoli one rather than the one of zlib.
+pub(super) fn crc32c(bytes: &[u8]) -> u32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u32;
+            let mut bit = 0;
+            while bit < 8 {
+                crc = if crc & 1 == 1 {
+                    (crc >> 1) ^ 0x82F6_3B78
+                } else {
+                    crc >> 1
+                };
+                bit += 1;
+            }
+            table[i] = crc;
+            i += 1;
+        }
+        table
+    };
+
+    !bytes.iter().fold(!0, |crc, &byte| {
+        TABLE[((crc ^ u32::from(byte)) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+fn check_error(code: i16, topic: &str) -> io::Result<()> {
+    match code {
+        0 => Ok(()),
+        code => Err(io::Error::other(format!("kafka error {code} for {topic}"))),
+    }
+}
+
+fn invalid_data(msg: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
+
+fn put_i16(buf: &mut Vec<u8>, value: i16) {
+    buf.extend_from_slice(&value.to_be_bytes());
+}
+
+fn put_i32(buf: &mut Vec<u8>, value: i32) {
+    buf.extend_from_slice(&value.to_be_bytes());
+}
+
+fn put_i64(buf: &mut Vec<u8>, value: i64) {
+    buf.extend_from_slice(&value.to_be_bytes());
+}
+
+fn put_str(buf: &mut Vec<u8>, s: &str) {
+    put_i16(buf, s.len() as i16);
+    buf.extend_from_slice(s.as_bytes());
+}
+
+/// Zigzag encoded, 7 bits at a time.
+pub(super) fn put_varint(buf: &mut Vec<u8>, value: i64) {
+    let mut zigzag = ((value << 1) ^ (value >> 63)) as u64;
+    while zigzag >= 0x80 {
+        buf.push(zigzag as u8 | 0x80);
+        zigzag >>= 7;
+    }
+    buf.push(zigzag as u8);
+}
+
+/// Big-endian fields of a response, failing on the ones cut short.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let (bytes, rest) = self
+            .0
+            .split_first_chunk()
+            .ok_or_else(|| invalid_data("response cut short"))?;
+        self.0 = rest;
+        Ok(*bytes)
+    }
+
+    fn i8(&mut self) -> io::Result<i8> {
+        self.take().map(i8::from_be_bytes)
+    }
+
+    fn i16(&mut self) -> io::Result<i16> {
+        self.take().map(i16::from_be_bytes)
+    }
+
+    fn i32(&mut self) -> io::Result<i32> {
+        self.take().map(i32::from_be_bytes)
+    }
+
+    fn i64(&mut self) -> io::Result<i64> {
+        self.take().map(i64::from_be_bytes)
+    }
+
+    fn nullable_str(&mut self) -> io::Result<Option<&'a str>> {
+        let len = self.i16()?;
+        let Ok(len) = usize::try_from(len) else {
+            return Ok(None);
+        };
+        if self.0.len() < len {
+            return Err(invalid_data("response cut short"));
+        }
+        let (s, rest) = self.0.split_at(len);
+        self.0 = rest;
+        core::str::from_utf8(s)
+            .map(Some)
+            .map_err(|err| invalid_data(err.to_string()))
+    }
+
+    fn str(&mut self) -> io::Result<&'a str> {
+        self.nullable_str()?
+            .ok_or_else(|| invalid_data("null string"))
+    }
+
+    fn skip_i32_array(&mut self) -> io::Result<()> {
+        for _ in 0..self.i32()? {
+            self.i32()?;
+        }
+        Ok(())
+    }
+}
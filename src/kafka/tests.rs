@@ -0,0 +1,43 @@
+use super::protocol::{Record, crc32c, put_varint, record_batch};
+
+#[test]
+fn crc32c_matches_check_value() {
+    assert_eq!(crc32c(b""), 0);
+    assert_eq!(crc32c(b"123456789"), 0xE306_9283);
+}
+
+#[test]
+fn varints_are_zigzag_encoded() {
+    let encoded = |value| {
+        let mut buf = Vec::new();
+        put_varint(&mut buf, value);
+        buf
+    };
+    assert_eq!(encoded(0), [0x00]);
+    assert_eq!(encoded(-1), [0x01]);
+    assert_eq!(encoded(1), [0x02]);
+    assert_eq!(encoded(-64), [0x7f]);
+    assert_eq!(encoded(64), [0x80, 0x01]);
+    assert_eq!(encoded(300), [0xd8, 0x04]);
+}
+
+#[test]
+fn record_batch_has_its_length_and_crc() {
+    let records: Vec<_> = (0..3)
+        .map(|i| Record {
+            key: "BTCUSDT".to_owned(),
+            value: vec![i; 10],
+            timestamp: 1_744_760_290_967 + i64::from(i),
+        })
+        .collect();
+    let batch = record_batch(&records);
+
+    let len = i32::from_be_bytes(batch[8..12].try_into().unwrap());
+    assert_eq!(len as usize, batch.len() - 12);
+    // Magic, then the CRC of everything after it.
+    assert_eq!(batch[16], 2);
+    let crc = u32::from_be_bytes(batch[17..21].try_into().unwrap());
+    assert_eq!(crc, crc32c(&batch[21..]));
+    // Records after the 61 bytes of the batch header.
+    assert_eq!(i32::from_be_bytes(batch[57..61].try_into().unwrap()), 3);
+}
@@ -0,0 +1,165 @@
+//! Kline/candlestick stream, e.g. `btcusdt@kline_1m`.
+
+// {
+//   "e": "kline",              // event type
+//   "E": 1744760290967,        // event time
+//   "s": "BTCUSDT",            // symbol
+//   "k": {
+//     "t": 1744760280000,      // kline start time
+//     "T": 1744760339999,      // kline close time
+//     "s": "BTCUSDT",          // symbol
+//     "i": "1m",               // interval
+//     "f": 6186376000,         // first trade id
+//     "L": 6186376620,         // last trade id
+//     "o": "83600.00",         // open price
+//     "c": "83604.80",         // close price
+//     "h": "83610.00",         // high price
+//     "l": "83590.10",         // low price
+//     "v": "123.456",          // base asset volume
+//     "n": 620,                // number of trades
+//     "x": false,              // is this kline closed?
+//     "q": "10321000.12",      // quote asset volume
+//     "V": "60.000",           // taker buy base asset volume
+//     "Q": "5016000.00",       // taker buy quote asset volume
+//     "B": "0"                 // ignore
+//   }
+// }
+
+use crate::{
+    ParseError, expect_byte, id_field, parse_bool_field, quoted_end, simd::simd_find_byte,
+    timestamp_field,
+};
+
+/// Candlestick of a symbol and interval. Fields are named after their keys, see the layout
+/// above.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub struct Kline<'a> {
+    pub t: u64,
+    pub T: u64,
+    pub s: &'a str,
+    pub i: &'a str,
+    pub o: &'a str,
+    pub c: &'a str,
+    pub h: &'a str,
+    pub l: &'a str,
+    pub v: &'a str,
+    pub n: u64,
+    pub x: bool,
+}
+
+/// All parameters here change very rarely.
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub struct KlineConfig {
+    /// Position of the opening brace of `k`, as in "k":{
+    /// Everything before it is the outer object, which repeats the symbol and is skipped.
+    pub start: usize,
+    /// How many bytes are in the symbol, e.g. 7 for BTCUSDT.
+    pub symbol_len: usize,
+    /// How many digits are in the kline start and close times.
+    pub time_digits: usize,
+}
+
+impl KlineConfig {
+    /// Field by field, in the order they're declared in.
+    pub const fn new(start: usize, symbol_len: usize, time_digits: usize) -> Self {
+        Self {
+            start,
+            symbol_len,
+            time_digits,
+        }
+    }
+}
+
+/// Parses a kline payload laid out as described by `config`.
+pub fn parse_kline(json: &str, config: KlineConfig) -> Result<Kline<'_>, ParseError> {
+    let KlineConfig {
+        start,
+        symbol_len,
+        time_digits,
+    } = config;
+    expect_byte(json, start, b'{', "k")?;
+
+    // Skip 5 chars: {"t":
+    let t_start = start + 5;
+    let t = timestamp_field(json, t_start, time_digits, b',', "t")?;
+
+    // Skip 5 chars: ,"T":
+    let T_start = t_start + time_digits + 5;
+    let T = timestamp_field(json, T_start, time_digits, b',', "T")?;
+
+    // Skip 6 chars: ,"s":"
+    let s_start = T_start + time_digits + 6;
+    let s_end = s_start + symbol_len;
+    expect_byte(json, s_end, b'"', "s")?;
+    let s = json
+        .get(s_start..s_end)
+        .ok_or(ParseError::FieldNotFound("s"))?;
+
+    // Skip 7 chars: ","i":"
+    let i_start = s_end + 7;
+    let i_end = quoted_end(json, i_start, "i")?;
+    let i = &json[i_start..i_end];
+
+    // Trade ids aren't needed, so just skip past them.
+    // Skip 6 chars: ","f":
+    let f_start = i_end + 6;
+    let f_end = f_start
+        + json
+            .get(f_start..)
+            .and_then(|value| simd_find_byte(value.as_bytes(), b','))
+            .ok_or(ParseError::FieldNotFound("f"))?;
+    // Skip 5 chars: ,"L":
+    let L_start = f_end + 5;
+    let L_end = L_start
+        + json
+            .get(L_start..)
+            .and_then(|value| simd_find_byte(value.as_bytes(), b','))
+            .ok_or(ParseError::FieldNotFound("L"))?;
+
+    // Skip 6 chars: ,"o":"
+    let o_start = L_end + 6;
+    let o_end = quoted_end(json, o_start, "o")?;
+    let o = &json[o_start..o_end];
+
+    // Skip 7 chars: ","c":"
+    let c_start = o_end + 7;
+    let c_end = quoted_end(json, c_start, "c")?;
+    let c = &json[c_start..c_end];
+
+    // Skip 7 chars: ","h":"
+    let h_start = c_end + 7;
+    let h_end = quoted_end(json, h_start, "h")?;
+    let h = &json[h_start..h_end];
+
+    // Skip 7 chars: ","l":"
+    let l_start = h_end + 7;
+    let l_end = quoted_end(json, l_start, "l")?;
+    let l = &json[l_start..l_end];
+
+    // Skip 7 chars: ","v":"
+    let v_start = l_end + 7;
+    let v_end = quoted_end(json, v_start, "v")?;
+    let v = &json[v_start..v_end];
+
+    // Skip 6 chars: ","n":
+    let (n, n_end) = id_field(json, v_end + 6, b',', "n")?;
+
+    // Skip 5 chars: ,"x":
+    let x = parse_bool_field(json, n_end + 5)?;
+
+    Ok(Kline {
+        t,
+        T,
+        s,
+        i,
+        o,
+        c,
+        h,
+        l,
+        v,
+        n,
+        x,
+    })
+}
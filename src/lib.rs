@@ -0,0 +1,123 @@
+//! Parsing of Binance futures WebSocket payloads by their known layout, along with a client to
+//! receive them and the tools to measure, record and replay them.
+//!
+//! The book ticker parser lives at the root: see [`parse_book_ticker`] for symbols of known
+//! precisions, or [`parse_book_ticker_dynamic`] and [`detect_config`] for the others.
+//! Other streams each have a module of their own.
+//!
+//! Without the `std` feature only the parsers are left, which need nothing but `core` and
+//! `alloc`, so they can run on embedded targets too.
+//!
+//! Parsed payloads, layout configs and error enums are all `#[non_exhaustive]`, so that Binance
+//! adding a field or a new way to fail isn't a breaking change here. Outside this crate the
+//! configs are built with their `new` rather than struct literals, which no longer compile, and
+//! matches on the enums need a wildcard arm.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+#![allow(non_snake_case)]
+
+extern crate alloc;
+
+#[cfg(feature = "serde")]
+pub mod account_update;
+#[cfg(feature = "std")]
+pub mod affinity;
+pub mod agg_trade;
+pub mod analytics;
+#[cfg(feature = "arena")]
+pub mod arena;
+#[cfg(feature = "std")]
+pub mod atomic_config;
+#[cfg(feature = "std")]
+pub mod batch;
+#[cfg(feature = "std")]
+pub mod binlog;
+#[cfg(feature = "ws")]
+pub mod broadcaster;
+#[cfg(feature = "std")]
+pub mod cache;
+pub mod columnar;
+pub mod combined;
+pub mod composite_index;
+#[cfg(all(feature = "serde", feature = "ws"))]
+pub mod config;
+#[cfg(feature = "ws")]
+pub mod csv;
+pub mod decimal;
+pub mod dedup;
+pub mod deflate;
+pub mod depth;
+pub mod endpoint;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod funding_rate;
+#[cfg(feature = "ws")]
+pub mod fused;
+#[cfg(feature = "std")]
+pub mod handler;
+#[cfg(feature = "std")]
+pub mod histogram;
+#[cfg(feature = "influxdb")]
+pub mod influxdb;
+#[cfg(feature = "kafka")]
+pub mod kafka;
+pub mod kline;
+pub mod liquidation;
+#[cfg(feature = "std")]
+pub mod logging;
+pub mod mark_price;
+#[cfg(feature = "ws")]
+pub mod metrics;
+pub mod mini_ticker;
+pub mod open_interest;
+pub mod order_book;
+#[cfg(feature = "serde")]
+pub mod order_update;
+mod parser;
+#[cfg(feature = "ws")]
+pub mod pinning;
+#[cfg(feature = "std")]
+pub mod position;
+pub mod prefetch;
+#[cfg(feature = "std")]
+pub mod queue;
+#[cfg(feature = "std")]
+pub mod rate;
+#[cfg(feature = "ws")]
+pub mod rate_limit;
+#[cfg(feature = "ws")]
+pub mod reconnect;
+#[cfg(feature = "std")]
+pub mod recv_latency;
+#[cfg(feature = "redis")]
+pub mod redis;
+#[cfg(all(feature = "serde", feature = "ws"))]
+pub mod rest;
+pub mod sequence;
+#[cfg(feature = "ws")]
+pub mod shutdown;
+pub mod simd;
+pub mod small_string;
+#[cfg(feature = "ws")]
+pub mod stream;
+#[cfg(all(feature = "serde", feature = "ws"))]
+pub mod subscription;
+pub mod swar;
+#[cfg(feature = "std")]
+pub mod timing;
+#[cfg(feature = "opentelemetry")]
+pub mod trace;
+#[cfg(all(feature = "serde", feature = "ws"))]
+pub mod user_data;
+
+pub use binance_json_parsing_macros::assert_config;
+pub use parser::{
+    BookTicker, BookTickerFixed, BookTickerOwned, BookTickerOwnedFixed, ConfigValidation,
+    DECIMAL_CAPACITY, DYNAMIC_PRECISION, DiscoveryError, LayoutConfig, MAX_MESSAGE_BYTES,
+    MIN_MESSAGE_BYTES, ParseError, ParsingConfig, SchemaVersion, check_len, detect_config,
+    discover_config, exchange_latency_ms, is_book_ticker_frame, parse_book_ticker,
+    parse_book_ticker_dynamic, parse_book_ticker_fixed, parse_book_ticker_nom, validate_config,
+};
+pub(crate) use parser::{
+    expect_byte, id_field, parse_bool_field, quoted_end, quoted_field, timestamp_field,
+};
@@ -0,0 +1,145 @@
+//! Liquidation order stream, e.g. `btcusdt@forceOrder` or `!forceOrder@arr`.
+
+// {
+//   "e": "forceOrder",         // event type
+//   "E": 1744760291000,        // event time
+//   "o": {
+//     "s": "BTCUSDT",          // symbol
+//     "S": "SELL",             // side
+//     "o": "LIMIT",            // order type
+//     "f": "IOC",              // time in force
+//     "q": "0.014",            // original quantity
+//     "p": "83000.00",         // price
+//     "ap": "83010.50",        // average price
+//     "X": "FILLED",           // order status
+//     "l": "0.014",            // order last filled quantity
+//     "z": "0.014",            // order filled accumulated quantity
+//     "T": 1744760290999       // order trade time
+//   }
+// }
+
+use crate::{ParseError, expect_byte, quoted_end, quoted_field, timestamp_field};
+
+/// Order side, as in `"S":"SELL"`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+impl Side {
+    /// Tells the side by the first byte of "BUY" or "SELL".
+    pub fn from_byte(b: u8) -> Option<Side> {
+        match b {
+            b'B' => Some(Side::Buy),
+            b'S' => Some(Side::Sell),
+            _ => None,
+        }
+    }
+}
+
+/// Forced liquidation order. Fields are named after their keys, see the layout above.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub struct LiquidationOrder<'a> {
+    pub s: &'a str,
+    pub S: Side,
+    pub o: &'a str,
+    pub f: &'a str,
+    pub q: &'a str,
+    pub p: &'a str,
+    pub ap: &'a str,
+    pub X: &'a str,
+    pub l: &'a str,
+    pub z: &'a str,
+    pub T: u64,
+}
+
+/// All parameters here change very rarely.
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub struct LiquidationConfig {
+    /// Position of the opening brace of `o`, as in "o":{
+    pub start: usize,
+    /// How many bytes are in the symbol, e.g. 7 for BTCUSDT.
+    pub symbol_len: usize,
+    /// How many digits are in the order trade time.
+    pub transaction_time_digits: usize,
+}
+
+impl LiquidationConfig {
+    /// Field by field, in the order they're declared in.
+    pub const fn new(start: usize, symbol_len: usize, transaction_time_digits: usize) -> Self {
+        Self {
+            start,
+            symbol_len,
+            transaction_time_digits,
+        }
+    }
+}
+
+/// Parses a liquidation order payload laid out as described by `config`.
+pub fn parse_liquidation(
+    json: &str,
+    config: LiquidationConfig,
+) -> Result<LiquidationOrder<'_>, ParseError> {
+    let LiquidationConfig {
+        start,
+        symbol_len,
+        transaction_time_digits,
+    } = config;
+    expect_byte(json, start, b'{', "o")?;
+
+    // Skip 6 chars: {"s":"
+    let s_start = start + 6;
+    let s_end = s_start + symbol_len;
+    expect_byte(json, s_end, b'"', "s")?;
+    let s = json
+        .get(s_start..s_end)
+        .ok_or(ParseError::FieldNotFound("s"))?;
+
+    // Skip 7 chars: ","S":"
+    let S_start = s_end + 7;
+    let S = json
+        .as_bytes()
+        .get(S_start)
+        .copied()
+        .and_then(Side::from_byte)
+        .ok_or(ParseError::InvalidSide { pos: S_start })?;
+    let S_end = quoted_end(json, S_start, "S")?;
+
+    // Skip 7 chars: ","o":"
+    let (o, o_end) = quoted_field(json, S_end + 7, "o")?;
+    // Skip 7 chars: ","f":"
+    let (f, f_end) = quoted_field(json, o_end + 7, "f")?;
+    // Skip 7 chars: ","q":"
+    let (q, q_end) = quoted_field(json, f_end + 7, "q")?;
+    // Skip 7 chars: ","p":"
+    let (p, p_end) = quoted_field(json, q_end + 7, "p")?;
+    // Skip 8 chars: ","ap":"
+    let (ap, ap_end) = quoted_field(json, p_end + 8, "ap")?;
+    // Skip 7 chars: ","X":"
+    let (X, X_end) = quoted_field(json, ap_end + 7, "X")?;
+    // Skip 7 chars: ","l":"
+    let (l, l_end) = quoted_field(json, X_end + 7, "l")?;
+    // Skip 7 chars: ","z":"
+    let (z, z_end) = quoted_field(json, l_end + 7, "z")?;
+
+    // Skip 6 chars: ","T":
+    let T = timestamp_field(json, z_end + 6, transaction_time_digits, b'}', "T")?;
+
+    Ok(LiquidationOrder {
+        s,
+        S,
+        o,
+        f,
+        q,
+        p,
+        ap,
+        X,
+        l,
+        z,
+        T,
+    })
+}
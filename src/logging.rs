@@ -0,0 +1,121 @@
+//! Diagnostics going through the `log` facade as JSON lines on stderr with the `log` feature, or
+//! plain lines on stderr without it.
+//!
+//! The `log_*!` macros take the same arguments as `println!`. Without the feature `log_debug!`
+//! compiles to nothing, though its arguments are still type checked, so it can be used on the
+//! hot path.
+
+#[cfg(feature = "log")]
+mod json {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use log::{LevelFilter, Log, Metadata, Record};
+
+    struct JsonLogger;
+
+    impl Log for JsonLogger {
+        fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+            metadata.level() <= log::max_level()
+        }
+
+        fn log(&self, record: &Record<'_>) {
+            if !self.enabled(record.metadata()) {
+                return;
+            }
+            let time = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis();
+            let target = serde_json::to_string(record.target()).unwrap();
+            let message = serde_json::to_string(&record.args().to_string()).unwrap();
+            eprintln!(
+                r#"{{"time":{time},"level":"{}","target":{target},"message":{message}}}"#,
+                record.level()
+            );
+        }
+
+        fn flush(&self) {}
+    }
+
+    /// Installs the JSON logger, filtering by the `LOG_LEVEL` environment variable, e.g.
+    /// `LOG_LEVEL=debug`, defaulting to `info`.
+    pub fn init() {
+        let level = std::env::var("LOG_LEVEL")
+            .ok()
+            .and_then(|level| level.parse().ok())
+            .unwrap_or(LevelFilter::Info);
+        if log::set_logger(&JsonLogger).is_ok() {
+            log::set_max_level(level);
+        }
+    }
+}
+
+#[cfg(feature = "log")]
+pub use json::init;
+
+/// Nothing to set up without the `log` feature.
+#[cfg(not(feature = "log"))]
+pub fn init() {}
+
+#[cfg(feature = "log")]
+#[doc(hidden)]
+pub use log as __log;
+
+#[cfg(feature = "log")]
+/// Logs a debug event, with the same arguments as `println!`.
+#[macro_export]
+macro_rules! log_debug {
+    ($($arg:tt)*) => {
+        $crate::logging::__log::debug!($($arg)*)
+    };
+}
+
+#[cfg(not(feature = "log"))]
+/// Logs a debug event, with the same arguments as `println!`.
+#[macro_export]
+macro_rules! log_debug {
+    ($($arg:tt)*) => {
+        // Still type checks the arguments, but is optimized away.
+        if false {
+            ::std::eprintln!($($arg)*);
+        }
+    };
+}
+
+#[cfg(feature = "log")]
+/// Logs an info event, with the same arguments as `println!`.
+#[macro_export]
+macro_rules! log_info {
+    ($($arg:tt)*) => {
+        $crate::logging::__log::info!($($arg)*)
+    };
+}
+
+#[cfg(not(feature = "log"))]
+/// Logs an info event, with the same arguments as `println!`.
+#[macro_export]
+macro_rules! log_info {
+    ($($arg:tt)*) => {
+        ::std::eprintln!($($arg)*)
+    };
+}
+
+#[cfg(feature = "log")]
+/// Logs a warning, with the same arguments as `println!`.
+#[macro_export]
+macro_rules! log_warn {
+    ($($arg:tt)*) => {
+        $crate::logging::__log::warn!($($arg)*)
+    };
+}
+
+#[cfg(not(feature = "log"))]
+/// Logs a warning, with the same arguments as `println!`.
+#[macro_export]
+macro_rules! log_warn {
+    ($($arg:tt)*) => {
+        ::std::eprintln!($($arg)*)
+    };
+}
+
+pub use crate::{log_debug, log_info, log_warn};
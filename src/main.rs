@@ -1,180 +1,251 @@
-#![allow(non_snake_case)]
-
-use std::{
-    arch::x86_64::{_mm_lfence, _rdtsc},
-    hint::black_box,
+use std::{collections::HashMap, hint::black_box, sync::Arc, time::SystemTime};
+
+use anyhow::Context;
+use binance_json_parsing::{
+    ConfigValidation, ParseError,
+    affinity::pin_to_core,
+    cache::ConfigCache,
+    combined::strip_combined_wrapper,
+    config::Config,
+    exchange_latency_ms,
+    histogram::LatencyHistogram,
+    logging::{self, log_debug, log_info, log_warn},
+    metrics::{Metrics, MetricsServer},
+    parse_book_ticker_dynamic,
+    rate::RateCounter,
+    reconnect::ReconnectingStream,
+    recv_latency::{RecvLatencyTracker, receive_latency_ms},
+    shutdown::ShutdownToken,
+    stream::book_ticker_stream,
+    timing::measure,
+    validate_config,
 };
-
 use futures_util::StreamExt;
-use mimalloc::MiMalloc;
-use tokio_tungstenite::connect_async;
 
+#[cfg(feature = "mimalloc")]
 #[global_allocator]
-static GLOBAL: MiMalloc = MiMalloc;
-
-fn counter_start() -> u64 {
-    unsafe {
-        _mm_lfence();
-        _rdtsc()
-    }
-}
+static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
-fn counter_elapsed(start: u64) -> u64 {
-    unsafe { _rdtsc() - start }
-}
-
-// {
-//   "e": "bookTicker",  // event type
-//   "u": 7283946601071, // order book updateId
-//   "s": "BTCUSDT",     // symbol
-//   "b": "83604.80",    // best bid price
-//   "B": "10.746",      // best bid qty
-//   "a": "83604.90",    // best ask price
-//   "A": "9.514",       // best ask qty
-//   "T": 1744760290967, // transaction time
-//   "E": 1744760290967  // event time
-// }
-
-#[derive(serde::Deserialize, PartialEq, Eq, Debug)]
-struct BookTickerOwned {
-    T: u64,
-    b: String,
-    B: String,
-    a: String,
-    A: String,
-}
-
-#[derive(serde::Deserialize, PartialEq, Eq, Debug)]
-struct BookTicker<'a> {
-    T: u64,
-    #[serde(borrow)]
-    b: &'a str,
-    #[serde(borrow)]
-    B: &'a str,
-    #[serde(borrow)]
-    a: &'a str,
-    #[serde(borrow)]
-    A: &'a str,
-}
-
-/// All parameters here change very rarely.
-#[derive(Clone, Copy, Debug)]
-struct ParsingConfig {
-    /// Position of `b`, as in "b":"
-    start: usize,
-    /// How many fractional digits are in the price values.
-    price_precision: usize,
-    /// How many fractional digits are in the volume values.
-    volume_precision: usize,
-    /// How many digits are in the transaction time.
-    transaction_time_digits: usize,
-}
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    logging::init();
 
-fn parse_book_ticker(
-    json: &str,
-    ParsingConfig {
-        start,
-        price_precision,
-        volume_precision,
-        transaction_time_digits,
-    }: ParsingConfig,
-) -> BookTicker {
-    assert_eq!(json.as_bytes()[start], b'b');
-
-    // Skip 4 chars: b":"
-    let b_start = start + 4;
-    let dot_pos = b_start + json[b_start..].find('.').unwrap();
-    let b_end = dot_pos + price_precision + 1;
-    assert_eq!(json.as_bytes()[b_end], b'\"');
-    let b = &json[b_start..b_end];
-
-    // Skip 7 chars: ","B":"
-    let B_start = b_end + 7;
-    let dot_pos = B_start + json[B_start..].find('.').unwrap();
-    let B_end = dot_pos + volume_precision + 1;
-    assert_eq!(json.as_bytes()[B_end], b'\"');
-    let B = &json[B_start..B_end];
-
-    // Skip 7 chars: ","a":"
-    let a_start = B_end + 7;
-    let dot_pos = a_start + json[a_start..].find('.').unwrap();
-    let a_end = dot_pos + price_precision + 1;
-    assert_eq!(json.as_bytes()[a_end], b'\"');
-    let a = &json[a_start..a_end];
-
-    // Skip 7 chars: ","A":"
-    let A_start = a_end + 7;
-    let dot_pos = A_start + json[A_start..].find('.').unwrap();
-    let A_end = dot_pos + volume_precision + 1;
-    assert_eq!(json.as_bytes()[A_end], b'\"');
-    let A = &json[A_start..A_end];
-
-    // Skip 6 chars: ","T":
-    let T_start = A_end + 6;
-    // Skip 13 digits, for example, 1744848607537 will be 13 digits for a long time until this
-    // parameter would have to change.
-    let T_end = T_start + transaction_time_digits;
-    let T = json[T_start..T_end].parse().unwrap();
-    assert_eq!(json.as_bytes()[T_end], b',');
-
-    BookTicker { T, b, B, a, A }
-}
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let config = match args.iter().position(|arg| arg == "--config") {
+        Some(i) => {
+            let path = args.get(i + 1).context("--config needs a path")?;
+            Config::load(path).with_context(|| format!("failed to load config from {path}"))?
+        }
+        None => Config::default(),
+    };
+    let core = match args.iter().position(|arg| arg == "--core") {
+        Some(i) => {
+            let core = args.get(i + 1).context("--core needs a core id")?;
+            Some(
+                core.parse::<usize>()
+                    .with_context(|| format!("invalid core id {core}"))?,
+            )
+        }
+        None => None,
+    };
 
-fn measure<T>(f: impl Fn() -> T) -> (u64, T) {
-    let start = counter_start();
-    let res = f();
-    let elapsed = counter_elapsed(start);
+    if args.first().map(String::as_str) == Some("stream") {
+        stream_demo(&config).await;
+        return Ok(());
+    }
 
-    (elapsed, res)
-}
+    // Either a single stream or a combined one, e.g.
+    // wss://fstream.binance.com/stream?streams=btcusdt@bookTicker/ethusdt@bookTicker
+    let url = config.connection.url.as_str();
 
-#[tokio::main]
-async fn main() -> anyhow::Result<()> {
-    let url = "wss://fstream.binance.com/ws/btcusdt@bookTicker";
+    let shutdown = ShutdownToken::new();
+    shutdown.cancel_on_signal();
 
-    let (mut ws_stream, _) = connect_async(url).await?;
+    let mut ws_stream = ReconnectingStream::new(url, config.connection.reconnect());
 
-    let mut ticks_acc = 0;
-    let mut measurements_num = 0;
+    // Events of a single stream are told apart by the url instead of their stream name.
+    let mut configs = ConfigCache::default();
+    configs.insert(url, config.parsing);
+    let mut stats: HashMap<String, StreamStats> = HashMap::new();
+    let mut rate = RateCounter::new();
+    let mut received: u64 = 0;
 
-    let config = ParsingConfig {
-        start: 51,
-        price_precision: 2,
-        volume_precision: 3,
-        transaction_time_digits: 13,
+    // Scraped by Prometheus and health checks if given an address to listen on, e.g.
+    // `METRICS_ADDR=0.0.0.0:9100`.
+    let metrics = Arc::new(Metrics::tracking(ws_stream.connected()));
+    let _metrics_server = match std::env::var("METRICS_ADDR") {
+        Ok(addr) => Some(MetricsServer::spawn(addr.parse()?, Arc::clone(&metrics)).await?),
+        Err(_) => None,
     };
 
-    while let Some(msg) = ws_stream.next().await {
+    // This loop runs on the thread blocking on `main`, so it stays where it's pinned, unlike
+    // the tasks spawned on the workers.
+    if let Some(core) = core {
+        pin_to_core(core).with_context(|| format!("failed to pin to core {core}"))?;
+        log_info!("pinned to core {core}");
+    }
+
+    loop {
+        let msg = tokio::select! {
+            msg = ws_stream.next() => msg,
+            () = shutdown.cancelled() => break,
+        };
+        let Some(msg) = msg else { break };
         let msg = msg?;
+        // Before anything else, so that none of the handling counts towards the latency.
+        let received_at = SystemTime::now();
         if msg.is_text() {
-            println!("{msg}");
+            log_debug!("{msg}");
+            rate.tick();
+            metrics.message_received();
+            received += 1;
 
             let text = msg.to_text().unwrap();
+            let (stream, text) = strip_combined_wrapper(text).unwrap_or((url, text));
 
             // This way we find the start of the interesting part of payload.
             // So the next time we parse the payload we can just skip to this position.
-            // We would just need to check that the same start position still holds the same data
-            // we expect, that is, the letter `b`, otherwise we'd need to recompute the start of
+            // We just need to check that the same start position still holds the same data
+            // we expect, that is, the letter `b`, otherwise we recompute the start of
             // the interesting part of payload and remember it.
-            dbg!(text.find("b\":"));
+            // Every so often the whole config is checked as well, since a changed precision
+            // only shows once parsing fails at a boundary, if at all.
+            if received.is_multiple_of(config.validate_every)
+                && let Some(&cached) = configs.get(stream)
+            {
+                let validation = validate_config(text, cached);
+                if validation != ConfigValidation::Ok {
+                    log_warn!("parsing config of {stream} is stale: {validation:?}");
+                    configs.evict(stream);
+                }
+            }
+            let config = match configs.get_or_detect(stream, text) {
+                Ok(config) => *config,
+                Err(err) => {
+                    log_warn!("failed to detect parsing config of {stream}: {err}");
+                    metrics.parse_error();
+                    continue;
+                }
+            };
 
             // let (elapsed, book_ticker): (u64, BookTicker) = measure(|| serde_json::from_str(text).unwrap());
             // let (elapsed, book_ticker): (u64, BookTicker) =
             //    measure(|| sonic_rs::from_str(text).unwrap());
-            let (elapsed, book_ticker) = measure(|| parse_book_ticker(text, black_box(config)));
-
-            ticks_acc += elapsed;
-            measurements_num += 1;
-
-            println!("duration now: {elapsed} ticks");
-            println!("duration avg: {} ticks", ticks_acc / measurements_num);
+            let (elapsed, book_ticker) =
+                measure(|| parse_book_ticker_dynamic(text, black_box(config)));
+            let book_ticker = match book_ticker {
+                Ok(book_ticker) => book_ticker,
+                Err(err) => {
+                    log_warn!("failed to parse book ticker of {stream}: {err}");
+                    metrics.parse_error();
+                    // Most likely the precision has changed, so detect the config again next time.
+                    if let ParseError::InvalidBoundary { .. } = err {
+                        configs.evict(stream);
+                    }
+                    continue;
+                }
+            };
+
+            if !stats.contains_key(stream) {
+                stats.insert(stream.to_owned(), StreamStats::default());
+            }
+            let stats = stats.get_mut(stream).unwrap();
+            stats.ticks_acc += elapsed;
+            stats.measurements_num += 1;
+            stats.histogram.record(elapsed);
+            stats.record_exchange_latency(exchange_latency_ms(&book_ticker));
+            stats
+                .recv_latency
+                .record(receive_latency_ms(&book_ticker, received_at));
+            metrics.parsed(elapsed);
+
+            log_debug!("{stream} duration now: {elapsed} ticks");
+            log_debug!(
+                "{stream} duration avg: {} ticks",
+                stats.ticks_acc / stats.measurements_num
+            );
+            if stats.measurements_num.is_multiple_of(PERCENTILES_EVERY) {
+                let h = &stats.histogram;
+                log_info!(
+                    "{stream} duration p50/p90/p99/p99.9: {}/{}/{}/{} ticks",
+                    h.percentile(50.0),
+                    h.percentile(90.0),
+                    h.percentile(99.0),
+                    h.percentile(99.9)
+                );
+                let h = &stats.exchange_latency;
+                log_info!(
+                    "{stream} exchange latency p50/p99: {}/{} ms, {} clock skews",
+                    h.percentile(50.0),
+                    h.percentile(99.0),
+                    stats.clock_skews
+                );
+                let recv = stats.recv_latency.stats();
+                log_info!(
+                    "{stream} receive latency p50/p90/p99/max: {}/{}/{}/{} ms",
+                    recv.p50,
+                    recv.p90,
+                    recv.p99,
+                    recv.max
+                );
+            }
 
             do_nothing(black_box(book_ticker));
             // dbg!(book_ticker);
         }
     }
 
+    if let Err(err) = ws_stream.close().await {
+        log_warn!("failed to close the connection: {err}");
+    }
+    for (stream, stats) in &stats {
+        log_info!(
+            "{stream} final: {} measurements, avg {} ticks, p99 {} ticks",
+            stats.measurements_num,
+            stats.ticks_acc / stats.measurements_num,
+            stats.histogram.percentile(99.0)
+        );
+    }
+
     Ok(())
 }
 
+/// Same updates, but through [`book_ticker_stream`], in exchange for not measuring anything.
+async fn stream_demo(config: &Config) {
+    book_ticker_stream(&config.connection.url, config.parsing)
+        .filter_map(|book_ticker| async move {
+            book_ticker
+                .inspect_err(|err| log_warn!("failed to parse book ticker: {err}"))
+                .ok()
+        })
+        .take(100)
+        .for_each(|book_ticker| async move {
+            println!("{} {} / {}", book_ticker.s, book_ticker.b, book_ticker.a);
+        })
+        .await;
+}
+
+/// How many measurements of a stream to take between printing its latency percentiles.
+const PERCENTILES_EVERY: u64 = 10_000;
+
+#[derive(Default)]
+struct StreamStats {
+    ticks_acc: u64,
+    measurements_num: u64,
+    histogram: LatencyHistogram,
+    /// Of [`exchange_latency_ms`], with the negative ones counted in `clock_skews` instead.
+    exchange_latency: LatencyHistogram,
+    clock_skews: u64,
+    recv_latency: RecvLatencyTracker,
+}
+
+impl StreamStats {
+    fn record_exchange_latency(&mut self, latency_ms: i64) {
+        match u64::try_from(latency_ms) {
+            Ok(latency_ms) => self.exchange_latency.record(latency_ms),
+            Err(_) => self.clock_skews += 1,
+        }
+    }
+}
+
 fn do_nothing<T>(_: T) {}
@@ -0,0 +1,122 @@
+//! Mark price stream, e.g. `btcusdt@markPrice`.
+
+// {
+//   "e": "markPriceUpdate",    // event type
+//   "E": 1744760291000,        // event time
+//   "s": "BTCUSDT",            // symbol
+//   "p": "83604.80000000",     // mark price
+//   "i": "83610.12345678",     // index price
+//   "P": "83590.00000000",     // estimated settle price
+//   "r": "0.00010000",         // funding rate
+//   "T": 1744761600000         // next funding time
+// }
+
+use crate::{
+    ParseError,
+    decimal::{FixedPoint, parse_fixed},
+    expect_byte, quoted_end, timestamp_field,
+};
+
+/// Mark price and funding rate of a symbol. Fields are named after their keys, see the layout
+/// above.
+#[derive(Clone, PartialEq, Debug)]
+#[non_exhaustive]
+pub struct MarkPrice<'a> {
+    pub s: &'a str,
+    pub p: &'a str,
+    pub i: &'a str,
+    pub P: &'a str,
+    pub r: FixedPoint,
+    pub T: u64,
+}
+
+/// All parameters here change very rarely.
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub struct MarkPriceConfig {
+    /// Position of `s`, as in "s":"
+    pub start: usize,
+    /// How many bytes are in the symbol, e.g. 7 for BTCUSDT.
+    pub symbol_len: usize,
+    /// How many digits are in the next funding time.
+    pub transaction_time_digits: usize,
+}
+
+impl MarkPriceConfig {
+    /// Field by field, in the order they're declared in.
+    pub const fn new(start: usize, symbol_len: usize, transaction_time_digits: usize) -> Self {
+        Self {
+            start,
+            symbol_len,
+            transaction_time_digits,
+        }
+    }
+}
+
+/// Parses a mark price payload laid out as described by `config`.
+pub fn parse_mark_price(json: &str, config: MarkPriceConfig) -> Result<MarkPrice<'_>, ParseError> {
+    let MarkPriceConfig {
+        start,
+        symbol_len,
+        transaction_time_digits,
+    } = config;
+    expect_byte(json, start, b's', "s")?;
+
+    // Skip 4 chars: s":"
+    let s_start = start + 4;
+    let s_end = s_start + symbol_len;
+    expect_byte(json, s_end, b'"', "s")?;
+    let s = json
+        .get(s_start..s_end)
+        .ok_or(ParseError::FieldNotFound("s"))?;
+
+    // Skip 7 chars: ","p":"
+    let p_start = s_end + 7;
+    let p_end = quoted_end(json, p_start, "p")?;
+    let p = &json[p_start..p_end];
+
+    // Skip 7 chars: ","i":"
+    let i_start = p_end + 7;
+    let i_end = quoted_end(json, i_start, "i")?;
+    let i = &json[i_start..i_end];
+
+    // Skip 7 chars: ","P":"
+    let P_start = i_end + 7;
+    let P_end = quoted_end(json, P_start, "P")?;
+    let P = &json[P_start..P_end];
+
+    // Skip 7 chars: ","r":"
+    let r_start = P_end + 7;
+    let r_end = quoted_end(json, r_start, "r")?;
+    let r = parse_fixed(&json[r_start..r_end])?;
+
+    // Skip 6 chars: ","T":
+    let T = timestamp_field(json, r_end + 6, transaction_time_digits, b'}', "T")?;
+
+    Ok(MarkPrice { s, p, i, P, r, T })
+}
+
+/// Scans a mark price payload for the positions and lengths [`parse_mark_price`] relies on, to
+/// be cached like [`detect_config`] for book tickers.
+///
+/// [`detect_config`]: crate::detect_config
+pub fn detect_mark_price_config(json: &str) -> Result<MarkPriceConfig, ParseError> {
+    // Skip 1 char: "
+    let start = json
+        .find("\"s\":\"")
+        .ok_or(ParseError::FieldNotFound("s"))?
+        + 1;
+    let symbol_len = quoted_end(json, start + 4, "s")? - (start + 4);
+
+    let T_start = json.rfind("\"T\":").ok_or(ParseError::FieldNotFound("T"))? + 4;
+    let transaction_time_digits = json[T_start..]
+        .bytes()
+        .take_while(u8::is_ascii_digit)
+        .count();
+
+    Ok(MarkPriceConfig {
+        start,
+        symbol_len,
+        transaction_time_digits,
+    })
+}
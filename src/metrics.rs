@@ -0,0 +1,270 @@
+//! Prometheus metrics and a health check served over plain HTTP.
+
+use std::{
+    fmt::Write as _,
+    io,
+    net::SocketAddr,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+
+use crate::{
+    histogram::{BUCKETS, bucket, bucket_upper_bound},
+    logging::log_warn,
+};
+
+/// Counters shared between the receiving loop and [`MetricsServer`].
+#[derive(Debug)]
+pub struct Metrics {
+    /// Parse durations in the same power of two buckets as [`LatencyHistogram`], not
+    /// cumulative yet.
+    ///
+    /// [`LatencyHistogram`]: crate::histogram::LatencyHistogram
+    parse_duration_buckets: [AtomicU64; BUCKETS],
+    parse_duration_sum: AtomicU64,
+    messages_received: AtomicU64,
+    parse_errors: AtomicU64,
+    /// Milliseconds between `created` and the last message, or `u64::MAX` before the first one.
+    last_message_ms: AtomicU64,
+    /// Whether the stream is connected, as set by [`ReconnectingStream`].
+    ///
+    /// [`ReconnectingStream`]: crate::reconnect::ReconnectingStream
+    connected: Arc<AtomicBool>,
+    created: Instant,
+}
+
+/// Health turns degraded once nothing has been received for this long.
+const STALE_AFTER: Duration = Duration::from_secs(5);
+
+impl Metrics {
+    /// Without a stream to track, so health stays degraded until [`Metrics::set_connected`].
+    pub fn new() -> Self {
+        Self::tracking(Arc::new(AtomicBool::new(false)))
+    }
+
+    /// Healthy only while `connected` is set, e.g. by [`ReconnectingStream::connected`].
+    ///
+    /// [`ReconnectingStream::connected`]: crate::reconnect::ReconnectingStream::connected
+    pub fn tracking(connected: Arc<AtomicBool>) -> Self {
+        Self {
+            parse_duration_buckets: [const { AtomicU64::new(0) }; BUCKETS],
+            parse_duration_sum: AtomicU64::new(0),
+            messages_received: AtomicU64::new(0),
+            parse_errors: AtomicU64::new(0),
+            last_message_ms: AtomicU64::new(u64::MAX),
+            connected,
+            created: Instant::now(),
+        }
+    }
+
+    /// For streams other than [`ReconnectingStream`], which sets it by itself.
+    ///
+    /// [`ReconnectingStream`]: crate::reconnect::ReconnectingStream
+    pub fn set_connected(&self, connected: bool) {
+        self.connected.store(connected, Ordering::Relaxed);
+    }
+
+    pub fn message_received(&self) {
+        self.messages_received.fetch_add(1, Ordering::Relaxed);
+        let now = self.created.elapsed().as_millis() as u64;
+        self.last_message_ms.store(now, Ordering::Relaxed);
+    }
+
+    /// How long ago the last message was received, if any was.
+    pub fn last_message_age(&self) -> Option<Duration> {
+        let last = self.last_message_ms.load(Ordering::Relaxed);
+        (last != u64::MAX).then(|| {
+            self.created
+                .elapsed()
+                .saturating_sub(Duration::from_millis(last))
+        })
+    }
+
+    pub fn parsed(&self, cycles: u64) {
+        self.parse_duration_buckets[bucket(cycles)].fetch_add(1, Ordering::Relaxed);
+        self.parse_duration_sum.fetch_add(cycles, Ordering::Relaxed);
+    }
+
+    pub fn parse_error(&self) {
+        self.parse_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str("# HELP binance_parse_duration_cycles Time spent parsing a message.\n");
+        out.push_str("# TYPE binance_parse_duration_cycles histogram\n");
+        let mut count = 0;
+        for (i, n) in self.parse_duration_buckets.iter().enumerate() {
+            count += n.load(Ordering::Relaxed);
+            let le = bucket_upper_bound(i);
+            _ = writeln!(
+                out,
+                "binance_parse_duration_cycles_bucket{{le=\"{le}\"}} {count}"
+            );
+        }
+        _ = writeln!(
+            out,
+            "binance_parse_duration_cycles_bucket{{le=\"+Inf\"}} {count}"
+        );
+        _ = writeln!(
+            out,
+            "binance_parse_duration_cycles_sum {}",
+            self.parse_duration_sum.load(Ordering::Relaxed)
+        );
+        _ = writeln!(out, "binance_parse_duration_cycles_count {count}");
+
+        out.push_str("# HELP binance_messages_received_total Text frames received.\n");
+        out.push_str("# TYPE binance_messages_received_total counter\n");
+        _ = writeln!(
+            out,
+            "binance_messages_received_total {}",
+            self.messages_received.load(Ordering::Relaxed)
+        );
+
+        out.push_str("# HELP binance_parse_errors_total Messages that failed to parse.\n");
+        out.push_str("# TYPE binance_parse_errors_total counter\n");
+        _ = writeln!(
+            out,
+            "binance_parse_errors_total {}",
+            self.parse_errors.load(Ordering::Relaxed)
+        );
+
+        out
+    }
+
+    /// Status code and JSON body of the health check, which is ok while connected and receiving
+    /// messages.
+    pub fn health(&self) -> (&'static str, String) {
+        let connected = self.connected.load(Ordering::Relaxed);
+        let age = self.last_message_age();
+        let (status, code) = match age {
+            Some(age) if connected && age < STALE_AFTER => ("ok", "200 OK"),
+            _ => ("degraded", "503 Service Unavailable"),
+        };
+        let age = match age {
+            Some(age) => age.as_millis().to_string(),
+            None => "null".to_owned(),
+        };
+        (
+            code,
+            format!(
+                r#"{{"status":"{status}","connected":{connected},"last_message_age_ms":{age}}}"#
+            ),
+        )
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Serves `GET /metrics` and `GET /health` on its own task, answering anything else with 404.
+pub struct MetricsServer {
+    addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+impl MetricsServer {
+    pub async fn spawn(addr: SocketAddr, metrics: Arc<Metrics>) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+
+        let task = tokio::spawn(async move {
+            loop {
+                let (conn, _) = match listener.accept().await {
+                    Ok(accepted) => accepted,
+                    Err(err) => {
+                        log_warn!("failed to accept metrics connection: {err}");
+                        continue;
+                    }
+                };
+                let metrics = Arc::clone(&metrics);
+                tokio::spawn(async move {
+                    if let Err(err) = serve(conn, &metrics).await {
+                        log_warn!("failed to serve metrics: {err}");
+                    }
+                });
+            }
+        });
+
+        Ok(Self { addr, task })
+    }
+
+    /// Address actually listened on, e.g. when spawned on port 0.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+impl Drop for MetricsServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Answers a single request and closes the connection.
+async fn serve(mut conn: TcpStream, metrics: &Metrics) -> io::Result<()> {
+    // Only the request line matters, the rest of the head is read and ignored.
+    let mut head = [0u8; 4096];
+    let mut len = 0;
+    while !head[..len].windows(4).any(|w| w == b"\r\n\r\n") {
+        if len == head.len() {
+            return respond(&mut conn, "431 Request Header Fields Too Large", TEXT, "").await;
+        }
+        let n = conn.read(&mut head[len..]).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        len += n;
+    }
+
+    let request_line = head[..len]
+        .split(|&b| b == b'\r')
+        .next()
+        .unwrap_or_default();
+    let mut parts = request_line.split(|&b| b == b' ');
+    match (parts.next(), parts.next()) {
+        (Some(b"GET"), Some(b"/metrics")) => {
+            respond(&mut conn, "200 OK", TEXT, &metrics.render()).await
+        }
+        (Some(b"GET"), Some(b"/health")) => {
+            let (status, body) = metrics.health();
+            respond(&mut conn, status, JSON, &body).await
+        }
+        _ => respond(&mut conn, "404 Not Found", TEXT, "").await,
+    }
+}
+
+const TEXT: &str = "text/plain; version=0.0.4";
+const JSON: &str = "application/json";
+
+async fn respond(
+    conn: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &str,
+) -> io::Result<()> {
+    let head = format!(
+        "HTTP/1.1 {status}\r\n\
+         Content-Type: {content_type}\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\r\n",
+        body.len()
+    );
+    conn.write_all(head.as_bytes()).await?;
+    conn.write_all(body.as_bytes()).await?;
+    conn.shutdown().await
+}
@@ -0,0 +1,90 @@
+//! 24hr mini ticker stream, e.g. `btcusdt@miniTicker`.
+
+// {
+//   "e": "24hrMiniTicker",     // event type
+//   "E": 1744760291000,        // event time
+//   "s": "BTCUSDT",            // symbol
+//   "c": "83604.80",           // close price
+//   "o": "82000.00",           // open price
+//   "h": "84000.00",           // high price
+//   "l": "81000.00",           // low price
+//   "v": "123456.789",         // total traded base asset volume
+//   "q": "10321000000.12"      // total traded quote asset volume
+// }
+
+use crate::{ParseError, expect_byte, quoted_field};
+
+/// 24 hour rolling statistics of a symbol. Fields are named after their keys, see the layout
+/// above.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub struct MiniTicker<'a> {
+    pub s: &'a str,
+    pub c: &'a str,
+    pub o: &'a str,
+    pub h: &'a str,
+    pub l: &'a str,
+    pub v: &'a str,
+    pub q: &'a str,
+}
+
+/// All parameters here change very rarely.
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub struct MiniTickerConfig {
+    /// Position of `s`, as in "s":"
+    pub start: usize,
+    /// How many bytes are in the symbol, e.g. 7 for BTCUSDT.
+    pub symbol_len: usize,
+}
+
+impl MiniTickerConfig {
+    /// Field by field, in the order they're declared in.
+    pub const fn new(start: usize, symbol_len: usize) -> Self {
+        Self { start, symbol_len }
+    }
+}
+
+/// Parses a mini ticker payload laid out as described by `config`.
+pub fn parse_mini_ticker(
+    json: &str,
+    config: MiniTickerConfig,
+) -> Result<MiniTicker<'_>, ParseError> {
+    let MiniTickerConfig { start, symbol_len } = config;
+    expect_byte(json, start, b's', "s")?;
+
+    // Skip 4 chars: s":"
+    let s_start = start + 4;
+    let s_end = s_start + symbol_len;
+    expect_byte(json, s_end, b'"', "s")?;
+    let s = json
+        .get(s_start..s_end)
+        .ok_or(ParseError::FieldNotFound("s"))?;
+
+    // The 24h prices and especially volumes drift far enough for their integer parts to gain or
+    // lose digits, so every value is scanned up to its closing quote rather than assumed to have
+    // a fixed length.
+    // Skip 7 chars: ","c":"
+    let (c, c_end) = quoted_field(json, s_end + 7, "c")?;
+    // Skip 7 chars: ","o":"
+    let (o, o_end) = quoted_field(json, c_end + 7, "o")?;
+    // Skip 7 chars: ","h":"
+    let (h, h_end) = quoted_field(json, o_end + 7, "h")?;
+    // Skip 7 chars: ","l":"
+    let (l, l_end) = quoted_field(json, h_end + 7, "l")?;
+    // Skip 7 chars: ","v":"
+    let (v, v_end) = quoted_field(json, l_end + 7, "v")?;
+    // Skip 7 chars: ","q":"
+    let (q, q_end) = quoted_field(json, v_end + 7, "q")?;
+    expect_byte(json, q_end + 1, b'}', "q")?;
+
+    Ok(MiniTicker {
+        s,
+        c,
+        o,
+        h,
+        l,
+        v,
+        q,
+    })
+}
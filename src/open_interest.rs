@@ -0,0 +1,87 @@
+//! Open interest stream, e.g. `btcusdt@openInterest`.
+
+// {
+//   "e": "openInterest",       // event type
+//   "E": 1744760291000,        // event time
+//   "s": "BTCUSDT",            // symbol
+//   "o": "123456.789",         // open interest in base asset
+//   "T": 1744760290967         // transaction time
+// }
+
+use crate::{
+    ParseError,
+    decimal::{FixedPoint, parse_fixed},
+    expect_byte, quoted_end, timestamp_field,
+};
+
+/// Total open interest of a symbol.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub struct OpenInterest<'a> {
+    pub symbol: &'a str,
+    pub open_interest: FixedPoint,
+    pub transaction_time: u64,
+}
+
+/// All parameters here change very rarely.
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub struct OpenInterestConfig {
+    /// Position of `s`, as in "s":"
+    pub start: usize,
+    /// How many bytes are in the symbol, e.g. 7 for BTCUSDT.
+    pub symbol_len: usize,
+    /// How many digits are in the transaction time.
+    pub transaction_time_digits: usize,
+}
+
+impl OpenInterestConfig {
+    /// Field by field, in the order they're declared in.
+    pub const fn new(start: usize, symbol_len: usize, transaction_time_digits: usize) -> Self {
+        Self {
+            start,
+            symbol_len,
+            transaction_time_digits,
+        }
+    }
+}
+
+/// Parses an open interest payload laid out as described by `config`.
+///
+/// Unlike prices, the open interest has no length to expect, as it grows by whole digits along
+/// with the positions, so it runs up to its closing quote.
+pub fn parse_open_interest(
+    json: &str,
+    config: OpenInterestConfig,
+) -> Result<OpenInterest<'_>, ParseError> {
+    let OpenInterestConfig {
+        start,
+        symbol_len,
+        transaction_time_digits,
+    } = config;
+    expect_byte(json, start, b's', "s")?;
+
+    // Skip 4 chars: s":"
+    let s_start = start + 4;
+    let s_end = s_start + symbol_len;
+    expect_byte(json, s_end, b'"', "s")?;
+    let symbol = json
+        .get(s_start..s_end)
+        .ok_or(ParseError::FieldNotFound("s"))?;
+
+    // Skip 7 chars: ","o":"
+    expect_byte(json, s_end + 3, b'o', "o")?;
+    let o_start = s_end + 7;
+    let o_end = quoted_end(json, o_start, "o")?;
+    let open_interest = parse_fixed(&json[o_start..o_end])?;
+
+    // Skip 6 chars: ","T":
+    expect_byte(json, o_end + 3, b'T', "T")?;
+    let transaction_time = timestamp_field(json, o_end + 6, transaction_time_digits, b'}', "T")?;
+
+    Ok(OpenInterest {
+        symbol,
+        open_interest,
+        transaction_time,
+    })
+}
@@ -0,0 +1,134 @@
+//! Local copy of the book of one symbol, kept in sync from a depth snapshot and the diff depth
+//! stream that follows it.
+
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::cmp::Reverse;
+
+use crate::{
+    ParseError, analytics,
+    decimal::FixedPoint,
+    depth::{DepthSnapshot, DepthUpdate, iter_levels},
+};
+
+/// Why an update couldn't be applied, after which the book has to be synced again from a new
+/// snapshot.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum SeqError {
+    /// No snapshot was applied yet to apply the update on top of.
+    NoSnapshot,
+    /// Updates between `expected` and `got` were missed.
+    Gap { expected: u64, got: u64 },
+    /// A level of the update isn't made of decimals, so none of them were applied.
+    InvalidLevel(ParseError),
+}
+
+impl core::fmt::Display for SeqError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::NoSnapshot => write!(f, "no snapshot to apply the update to"),
+            Self::Gap { expected, got } => {
+                write!(f, "expected update {expected} but got {got}")
+            }
+            Self::InvalidLevel(err) => write!(f, "invalid level: {err}"),
+        }
+    }
+}
+
+impl core::error::Error for SeqError {}
+
+/// Price levels mapped to their quantities, ordered from the best one on both sides.
+#[derive(Clone, Default, Debug)]
+pub struct OrderBook {
+    pub bids: BTreeMap<Reverse<FixedPoint>, FixedPoint>,
+    pub asks: BTreeMap<FixedPoint, FixedPoint>,
+    /// Id of the last update applied, `None` until the first snapshot.
+    pub last_update_id: Option<u64>,
+}
+
+impl OrderBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces the whole book with `snap`.
+    pub fn apply_snapshot(&mut self, snap: &DepthSnapshot) {
+        self.bids.clear();
+        self.asks.clear();
+        for &(price, qty) in &snap.bids {
+            set_level(&mut self.bids, Reverse(price), qty);
+        }
+        for &(price, qty) in &snap.asks {
+            set_level(&mut self.asks, price, qty);
+        }
+        self.last_update_id = Some(snap.last_update_id);
+    }
+
+    /// Applies the changed levels of `update`, skipping it if it's already covered by the book,
+    /// e.g. when it was buffered while the snapshot was being fetched.
+    pub fn apply_update(&mut self, update: &DepthUpdate<'_>) -> Result<(), SeqError> {
+        let last_update_id = self.last_update_id.ok_or(SeqError::NoSnapshot)?;
+        if update.last_update_id <= last_update_id {
+            return Ok(());
+        }
+        if update.first_update_id > last_update_id + 1 {
+            return Err(SeqError::Gap {
+                expected: last_update_id + 1,
+                got: update.first_update_id,
+            });
+        }
+
+        // All parsed first, so that a malformed level leaves the book as it was.
+        let levels = |raw| iter_levels(raw).collect::<Result<Vec<_>, _>>();
+        let bids = levels(update.bids).map_err(SeqError::InvalidLevel)?;
+        let asks = levels(update.asks).map_err(SeqError::InvalidLevel)?;
+        for (price, qty) in bids {
+            set_level(&mut self.bids, Reverse(price), qty);
+        }
+        for (price, qty) in asks {
+            set_level(&mut self.asks, price, qty);
+        }
+        self.last_update_id = Some(update.last_update_id);
+        Ok(())
+    }
+
+    /// Highest bid price and its quantity.
+    pub fn best_bid(&self) -> Option<(FixedPoint, FixedPoint)> {
+        self.bids
+            .first_key_value()
+            .map(|(Reverse(price), qty)| (*price, *qty))
+    }
+
+    /// Lowest ask price and its quantity.
+    pub fn best_ask(&self) -> Option<(FixedPoint, FixedPoint)> {
+        self.asks
+            .first_key_value()
+            .map(|(price, qty)| (*price, *qty))
+    }
+
+    /// Halfway between the best bid and ask, exactly as long as the mantissa fits, otherwise its
+    /// lowest digit is dropped.
+    pub fn mid_price(&self) -> Option<FixedPoint> {
+        let (bid, _) = self.best_bid()?;
+        let (ask, _) = self.best_ask()?;
+        let sum = bid + ask;
+        // Halving is multiplying by 5 and moving the point one digit left.
+        FixedPoint::from_wide(sum.mantissa as i128 * 5, sum.exponent as i32 - 1)
+    }
+
+    /// Distance between the best bid and ask, in basis points of the mid price.
+    pub fn spread_bps(&self) -> Option<f64> {
+        let (bid, _) = self.best_bid()?;
+        let (ask, _) = self.best_ask()?;
+        Some(analytics::spread_bps(bid, ask))
+    }
+}
+
+/// A quantity of zero removes the level.
+fn set_level<K: Ord>(side: &mut BTreeMap<K, FixedPoint>, price: K, qty: FixedPoint) {
+    if qty.mantissa == 0 {
+        side.remove(&price);
+    } else {
+        side.insert(price, qty);
+    }
+}
@@ -0,0 +1,118 @@
+//! Order updates of the user data stream, sent as orders are placed, filled or cancelled.
+
+// {
+//   "e": "ORDER_TRADE_UPDATE",       // event type
+//   "E": 1744760291000,              // event time
+//   "T": 1744760290999,              // transaction time
+//   "o": {
+//     "s": "BTCUSDT",                // symbol
+//     "c": "web_AbCdEf",             // client order id
+//     "S": "BUY",                    // side
+//     "o": "LIMIT",                  // order type
+//     "f": "GTC",                    // time in force
+//     "q": "0.014",                  // original quantity
+//     "p": "83000.00",               // original price
+//     "ap": "83000.00",              // average price
+//     "sp": "0",                     // stop price
+//     "x": "TRADE",                  // execution type
+//     "X": "PARTIALLY_FILLED",       // order status
+//     "i": 8886774,                  // order id
+//     "l": "0.004",                  // order last filled quantity
+//     "z": "0.004",                  // order filled accumulated quantity
+//     "L": "83000.00",               // last filled price
+//     "T": 1744760290999,            // order trade time
+//     "t": 5146970,                  // trade id
+//     ...                            // and more, like the commission
+//   }
+// }
+//
+// Parsed by serde as account updates are, for the same reasons.
+
+use crate::{ParseError, liquidation::Side};
+
+/// Change of an order. Fields are named after their keys, see the layout above, with those of
+/// `o` moved to the top.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub struct OrderTradeUpdate<'a> {
+    pub E: u64,
+    pub T: u64,
+    pub s: &'a str,
+    pub c: &'a str,
+    pub S: Side,
+    pub o: &'a str,
+    pub q: &'a str,
+    pub p: &'a str,
+    /// `TRADE` for fills, of `l` at `L`.
+    pub x: &'a str,
+    pub X: &'a str,
+    pub i: u64,
+    pub l: &'a str,
+    pub z: &'a str,
+    pub L: &'a str,
+}
+
+impl OrderTradeUpdate<'_> {
+    /// Whether the update is for a fill, rather than e.g. a new or cancelled order.
+    pub fn is_fill(&self) -> bool {
+        self.x == "TRADE"
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct Event<'a> {
+    E: u64,
+    T: u64,
+    #[serde(borrow)]
+    o: Order<'a>,
+}
+
+#[derive(serde::Deserialize)]
+struct Order<'a> {
+    s: &'a str,
+    c: &'a str,
+    #[serde(deserialize_with = "side")]
+    S: Side,
+    o: &'a str,
+    q: &'a str,
+    p: &'a str,
+    x: &'a str,
+    X: &'a str,
+    i: u64,
+    l: &'a str,
+    z: &'a str,
+    L: &'a str,
+}
+
+fn side<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Side, D::Error> {
+    let side: &str = serde::Deserialize::deserialize(deserializer)?;
+    match side {
+        "BUY" => Ok(Side::Buy),
+        "SELL" => Ok(Side::Sell),
+        _ => Err(serde::de::Error::unknown_variant(side, &["BUY", "SELL"])),
+    }
+}
+
+/// Parses an `ORDER_TRADE_UPDATE` payload, borrowing its strings, which are never escaped.
+pub fn parse_order_trade_update(json: &str) -> Result<OrderTradeUpdate<'_>, ParseError> {
+    let Event { E, T, o } = serde_json::from_str(json).map_err(|err| ParseError::InvalidJson {
+        line: err.line(),
+        column: err.column(),
+    })?;
+    Ok(OrderTradeUpdate {
+        E,
+        T,
+        s: o.s,
+        c: o.c,
+        S: o.S,
+        o: o.o,
+        q: o.q,
+        p: o.p,
+        x: o.x,
+        X: o.X,
+        i: o.i,
+        l: o.l,
+        z: o.z,
+        L: o.L,
+    })
+}
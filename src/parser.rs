@@ -0,0 +1,1179 @@
+//! Parsing of book ticker payloads by their known layout, instead of scanning through them like
+//! a general JSON parser does.
+
+use alloc::{
+    borrow::ToOwned,
+    string::{String, ToString},
+};
+
+use crate::{
+    decimal::{FixedPoint, parse_fixed},
+    simd::simd_find_byte,
+    small_string::SmallString,
+    swar::{all_digits, parse_u64_fixed_digits},
+};
+
+// {
+//   "e": "bookTicker",  // event type
+//   "u": 7283946601071, // order book updateId
+//   "s": "BTCUSDT",     // symbol
+//   "b": "83604.80",    // best bid price
+//   "B": "10.746",      // best bid qty
+//   "a": "83604.90",    // best ask price
+//   "A": "9.514",       // best ask qty
+//   "T": 1744760290967, // transaction time
+//   "E": 1744760290967  // event time
+// }
+//
+// COIN-M futures have the pair in between, as in "s":"BTCUSD_PERP","ps":"BTCUSD","b":... Spot
+// has neither the event type nor the times, ending right after "A".
+
+/// Longest price or volume [`BookTickerOwned`] can hold, as long as any Binance sends. With the
+/// length that makes 16 bytes, so the four of them fill a cache line.
+pub const DECIMAL_CAPACITY: usize = 15;
+
+/// Same as [`BookTicker`] but not borrowing the payload, e.g. to be sent elsewhere. Prices and
+/// volumes are kept inline, so only the event type and symbol are allocated.
+///
+/// Converting a [`BookTicker`] with a price or volume longer than [`DECIMAL_CAPACITY`] fails
+/// with [`ParseError::DecimalTooLong`].
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[non_exhaustive]
+pub struct BookTickerOwned {
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub e: String,
+    pub u: u64,
+    pub s: String,
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "ps", default, skip_serializing_if = "Option::is_none")
+    )]
+    pub pair: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub T: u64,
+    pub b: SmallString<DECIMAL_CAPACITY>,
+    pub B: SmallString<DECIMAL_CAPACITY>,
+    pub a: SmallString<DECIMAL_CAPACITY>,
+    pub A: SmallString<DECIMAL_CAPACITY>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub E: u64,
+}
+
+/// Stands for the precisions of a [`BookTicker`] only known at runtime, as parsed by
+/// [`parse_book_ticker_dynamic`].
+pub const DYNAMIC_PRECISION: usize = usize::MAX;
+
+/// Best bid and ask of a symbol, borrowing the payload it was parsed from. Fields are named
+/// after their keys, see the layout above, except for `pair` which only COIN-M sends as `ps`.
+/// On spot `e` is empty and the times are 0, since they're not sent.
+///
+/// `PP` and `VP` are the price and volume precisions when known upfront, see
+/// [`parse_book_ticker`], so that tickers of symbols with different ones can't be mixed up.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[non_exhaustive]
+pub struct BookTicker<'a, const PP: usize = DYNAMIC_PRECISION, const VP: usize = DYNAMIC_PRECISION>
+{
+    #[cfg_attr(feature = "serde", serde(borrow, default))]
+    pub e: &'a str,
+    pub u: u64,
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub s: &'a str,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            borrow,
+            rename = "ps",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub pair: Option<&'a str>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub T: u64,
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub b: &'a str,
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub B: &'a str,
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub a: &'a str,
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub A: &'a str,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub E: u64,
+}
+
+impl<'a, const PP: usize, const VP: usize> BookTicker<'a, PP, VP> {
+    /// Forgets the precisions, e.g. to pass the ticker on to code handling any symbol.
+    pub fn into_dynamic(self) -> BookTicker<'a> {
+        let BookTicker {
+            e,
+            u,
+            s,
+            pair,
+            T,
+            b,
+            B,
+            a,
+            A,
+            E,
+        } = self;
+        BookTicker {
+            e,
+            u,
+            s,
+            pair,
+            T,
+            b,
+            B,
+            a,
+            A,
+            E,
+        }
+    }
+}
+
+/// How long the exchange took from matching to sending, `E - T` in milliseconds. Signed, since
+/// the two clocks are not quite in sync, so a skew shows up as a negative latency.
+///
+/// Spot tickers have neither time, which makes this 0 for them.
+pub fn exchange_latency_ms<const PP: usize, const VP: usize>(tick: &BookTicker<'_, PP, VP>) -> i64 {
+    tick.E as i64 - tick.T as i64
+}
+
+impl<const PP: usize, const VP: usize> TryFrom<BookTicker<'_, PP, VP>> for BookTickerOwned {
+    type Error = ParseError;
+
+    fn try_from(ticker: BookTicker<'_, PP, VP>) -> Result<Self, ParseError> {
+        Ok(Self {
+            e: ticker.e.to_owned(),
+            u: ticker.u,
+            s: ticker.s.to_owned(),
+            pair: ticker.pair.map(ToOwned::to_owned),
+            T: ticker.T,
+            b: decimal(ticker.b)?,
+            B: decimal(ticker.B)?,
+            a: decimal(ticker.a)?,
+            A: decimal(ticker.A)?,
+            E: ticker.E,
+        })
+    }
+}
+
+/// Borrows the fields, e.g. to pass a retained ticker on to code taking the borrowed one.
+impl<'a> From<&'a BookTickerOwned> for BookTicker<'a> {
+    fn from(ticker: &'a BookTickerOwned) -> Self {
+        Self {
+            e: &ticker.e,
+            u: ticker.u,
+            s: &ticker.s,
+            pair: ticker.pair.as_deref(),
+            T: ticker.T,
+            b: ticker.b.as_str(),
+            B: ticker.B.as_str(),
+            a: ticker.a.as_str(),
+            A: ticker.A.as_str(),
+            E: ticker.E,
+        }
+    }
+}
+
+/// Ordered by transaction time, then by update id and bid price to break ties within the same
+/// millisecond, with the other fields last only so that the order agrees with `Eq`. Wrap in
+/// [`Reverse`](core::cmp::Reverse) for a `BinaryHeap` popping the earliest ticker first.
+impl Ord for BookTickerOwned {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        fn rest(t: &BookTickerOwned) -> (&str, &str, Option<&str>, [&str; 4], u64) {
+            let quotes = [&t.b, &t.B, &t.a, &t.A].map(|quote| quote.as_str());
+            (&t.e, &t.s, t.pair.as_deref(), quotes, t.E)
+        }
+        self.T
+            .cmp(&other.T)
+            .then(self.u.cmp(&other.u))
+            // Bids that aren't decimals, only ever built by hand, go first.
+            .then_with(|| parse_fixed(&self.b).ok().cmp(&parse_fixed(&other.b).ok()))
+            .then_with(|| rest(self).cmp(&rest(other)))
+    }
+}
+
+impl PartialOrd for BookTickerOwned {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// One line of the best bid and ask, e.g.
+/// `BTCUSDT  83604.80 × 10.746  |  83604.90 × 9.514  @ 2025-04-15 23:38:10.967 UTC`, without
+/// the time on spot which doesn't send it.
+impl<const PP: usize, const VP: usize> core::fmt::Display for BookTicker<'_, PP, VP> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        fmt_bbo(f, self.s, [self.b, self.B, self.a, self.A], self.T)
+    }
+}
+
+impl<const PP: usize, const VP: usize> BookTicker<'_, PP, VP> {
+    /// The [`Display`](core::fmt::Display) line, for when a `String` is wanted outright.
+    pub fn to_display_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Same line as for [`BookTicker`].
+impl core::fmt::Display for BookTickerOwned {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let quotes = [&self.b, &self.B, &self.a, &self.A].map(|quote| quote.as_str());
+        fmt_bbo(f, &self.s, quotes, self.T)
+    }
+}
+
+impl BookTickerOwned {
+    /// The [`Display`](core::fmt::Display) line, for when a `String` is wanted outright.
+    pub fn to_display_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+fn fmt_bbo(
+    f: &mut core::fmt::Formatter<'_>,
+    s: &str,
+    [b, B, a, A]: [&str; 4],
+    T: u64,
+) -> core::fmt::Result {
+    write!(f, "{s}  {b} × {B}  |  {a} × {A}")?;
+    if T == 0 {
+        return Ok(());
+    }
+    write!(f, "  @ ")?;
+    fmt_utc_millis(f, T)
+}
+
+/// Formats milliseconds since the epoch as `2025-04-15 23:38:10.967 UTC`, converting days to
+/// dates as in <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+fn fmt_utc_millis(f: &mut core::fmt::Formatter<'_>, millis: u64) -> core::fmt::Result {
+    let secs = millis / 1000;
+    let (days, secs_of_day) = (secs / 86_400, secs % 86_400);
+
+    // Counted from 0000-03-01, so that leap days come last in their 400-year era.
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    };
+    let year = era * 400 + year_of_era + u64::from(month <= 2);
+
+    write!(
+        f,
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}.{:03} UTC",
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+        millis % 1000
+    )
+}
+
+fn decimal(value: &str) -> Result<SmallString<DECIMAL_CAPACITY>, ParseError> {
+    SmallString::new(value).ok_or(ParseError::DecimalTooLong { len: value.len() })
+}
+
+/// Same as [`BookTicker`] but with prices and volumes ready for arithmetic.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[non_exhaustive]
+pub struct BookTickerFixed<'a> {
+    pub e: &'a str,
+    pub u: u64,
+    pub s: &'a str,
+    pub T: u64,
+    pub b: FixedPoint,
+    pub B: FixedPoint,
+    pub a: FixedPoint,
+    pub A: FixedPoint,
+    pub E: u64,
+}
+
+/// Same as [`BookTickerFixed`] but not borrowing the payload.
+#[derive(Clone, PartialEq, Debug)]
+#[non_exhaustive]
+pub struct BookTickerOwnedFixed {
+    pub e: String,
+    pub u: u64,
+    pub s: String,
+    pub T: u64,
+    pub b: FixedPoint,
+    pub B: FixedPoint,
+    pub a: FixedPoint,
+    pub A: FixedPoint,
+    pub E: u64,
+}
+
+impl From<BookTickerFixed<'_>> for BookTickerOwnedFixed {
+    fn from(ticker: BookTickerFixed<'_>) -> Self {
+        Self {
+            e: ticker.e.to_owned(),
+            u: ticker.u,
+            s: ticker.s.to_owned(),
+            T: ticker.T,
+            b: ticker.b,
+            B: ticker.B,
+            a: ticker.a,
+            A: ticker.A,
+            E: ticker.E,
+        }
+    }
+}
+
+/// All parameters here change very rarely.
+///
+/// A config known upfront can be checked against an example payload with [`assert_config!`],
+/// which fails the build rather than the first parse:
+///
+/// ```
+/// use binance_json_parsing::{ParsingConfig, SchemaVersion, assert_config};
+///
+/// const ETHUSDT: ParsingConfig = ParsingConfig::new(51, 2, 3, 13, 7, SchemaVersion::Futures);
+///
+/// assert_config!(
+///     ETHUSDT,
+///     r#"{"e":"bookTicker","u":7283946603312,"s":"ETHUSDT","b":"1589.37","B":"112.294","a":"1589.38","A":"27.310","T":1744760291012,"E":1744760291014}"#
+/// );
+/// ```
+///
+/// SOLUSDT has 4 fractional price digits, so the same config doesn't compile for it:
+///
+/// ```compile_fail
+/// # use binance_json_parsing::{ParsingConfig, SchemaVersion, assert_config};
+/// # const ETHUSDT: ParsingConfig = ParsingConfig::new(51, 2, 3, 13, 7, SchemaVersion::Futures);
+/// assert_config!(
+///     ETHUSDT,
+///     r#"{"e":"bookTicker","u":7283946605501,"s":"SOLUSDT","b":"131.1500","B":"43","a":"131.1600","A":"388","T":1744760291201,"E":1744760291202}"#
+/// );
+/// ```
+///
+/// The config is `#[non_exhaustive]`, so that parameters can be added without breaking anyone.
+/// Outside this crate it can't be built with a struct literal anymore, which used to compile,
+/// but with [`ParsingConfig::new`] or [`Default`] and the fields set afterwards.
+///
+/// [`assert_config!`]: crate::assert_config
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[non_exhaustive]
+pub struct ParsingConfig {
+    /// Position of `b`, as in "b":"
+    pub start: usize,
+    /// How many fractional digits are in the price values, 0 for integers without a dot.
+    pub price_precision: usize,
+    /// How many fractional digits are in the volume values, 0 for integers as well.
+    pub volume_precision: usize,
+    /// How many digits are in the transaction and event times.
+    pub transaction_time_digits: usize,
+    /// How many bytes are in the symbol, e.g. 7 for BTCUSDT.
+    pub symbol_len: usize,
+    /// Which fields there are around the positional ones.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub schema_version: SchemaVersion,
+}
+
+/// The layout of BTCUSDT.
+impl Default for ParsingConfig {
+    fn default() -> Self {
+        Self {
+            start: 51,
+            price_precision: 2,
+            volume_precision: 3,
+            transaction_time_digits: 13,
+            symbol_len: 7,
+            schema_version: SchemaVersion::Futures,
+        }
+    }
+}
+
+/// The book ticker schemas of the different Binance markets, see the layout above.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "snake_case")
+)]
+#[non_exhaustive]
+pub enum SchemaVersion {
+    /// USD-M futures, also used by its testnet.
+    #[default]
+    Futures,
+    /// COIN-M futures, with the pair after the symbol.
+    CoinMFutures,
+    /// Spot, on both binance.com and binance.us, without the event type and times.
+    Spot,
+}
+
+impl ParsingConfig {
+    /// Field by field, in the order they're declared in.
+    pub const fn new(
+        start: usize,
+        price_precision: usize,
+        volume_precision: usize,
+        transaction_time_digits: usize,
+        symbol_len: usize,
+        schema_version: SchemaVersion,
+    ) -> Self {
+        Self {
+            start,
+            price_precision,
+            volume_precision,
+            transaction_time_digits,
+            symbol_len,
+            schema_version,
+        }
+    }
+
+    /// Whether `start` still points at `b` in the given payload.
+    pub fn matches(&self, json: &str) -> bool {
+        json.as_bytes().get(self.start) == Some(&b'b')
+    }
+
+    /// The config without the precisions, for [`parse_book_ticker`].
+    pub fn layout(&self) -> LayoutConfig {
+        LayoutConfig {
+            start: self.start,
+            transaction_time_digits: self.transaction_time_digits,
+            symbol_len: self.symbol_len,
+            schema_version: self.schema_version,
+        }
+    }
+}
+
+/// Same as [`ParsingConfig`] but without the precisions, which [`parse_book_ticker`] takes as
+/// const parameters instead. Just as `#[non_exhaustive]`, see [`LayoutConfig::new`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[non_exhaustive]
+pub struct LayoutConfig {
+    /// Position of `b`, as in "b":"
+    pub start: usize,
+    /// How many digits are in the transaction and event times.
+    pub transaction_time_digits: usize,
+    /// How many bytes are in the symbol, e.g. 7 for BTCUSDT.
+    pub symbol_len: usize,
+    /// Which fields there are around the positional ones.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub schema_version: SchemaVersion,
+}
+
+impl LayoutConfig {
+    /// Field by field, in the order they're declared in.
+    pub const fn new(
+        start: usize,
+        transaction_time_digits: usize,
+        symbol_len: usize,
+        schema_version: SchemaVersion,
+    ) -> Self {
+        Self {
+            start,
+            transaction_time_digits,
+            symbol_len,
+            schema_version,
+        }
+    }
+
+    /// Whether `start` still points at `b` in the given payload.
+    pub fn matches(&self, json: &str) -> bool {
+        json.as_bytes().get(self.start) == Some(&b'b')
+    }
+
+    fn with_precisions(self, price_precision: usize, volume_precision: usize) -> ParsingConfig {
+        ParsingConfig {
+            start: self.start,
+            price_precision,
+            volume_precision,
+            transaction_time_digits: self.transaction_time_digits,
+            symbol_len: self.symbol_len,
+            schema_version: self.schema_version,
+        }
+    }
+}
+
+/// The layout of BTCUSDT.
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        ParsingConfig::default().layout()
+    }
+}
+
+/// Why a payload couldn't be parsed, usually because its layout doesn't match the config.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum ParseError {
+    /// The field or some part of it, like the decimal point, is missing.
+    FieldNotFound(&'static str),
+    /// The byte at `pos` is not the delimiter expected at the end of `field`.
+    InvalidBoundary { field: &'static str, pos: usize },
+    /// The timestamp isn't a number, e.g. because it has a different number of digits.
+    TimestampParse(core::num::ParseIntError),
+    /// The id isn't a number.
+    IdParse(core::num::ParseIntError),
+    /// A price, quantity or rate isn't a decimal [`parse_fixed`] takes, e.g. "10.74a", or has
+    /// more than [`MAX_SIGNIFICANT_DIGITS`](crate::decimal::MAX_SIGNIFICANT_DIGITS).
+    InvalidDecimal,
+    /// A price or volume of `len` bytes is over [`DECIMAL_CAPACITY`], so doesn't fit in a
+    /// [`BookTickerOwned`].
+    DecimalTooLong { len: usize },
+    /// The value at `pos` is neither `true` nor `false`.
+    InvalidBool { pos: usize },
+    /// The value at `pos` is neither "BUY" nor "SELL".
+    InvalidSide { pos: usize },
+    /// Only from the payloads parsed by serde, which doesn't tell the field.
+    InvalidJson { line: usize, column: usize },
+    /// Over [`MAX_MESSAGE_BYTES`], so not even looked at.
+    MessageTooLarge { len: usize },
+    /// Under [`MIN_MESSAGE_BYTES`], too short to hold every field.
+    MessageTooShort { len: usize },
+    /// Not a book ticker at all, see [`is_book_ticker_frame`].
+    WrongEventType,
+}
+
+impl core::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::FieldNotFound(field) => write!(f, "field `{field}` not found"),
+            Self::InvalidBoundary { field, pos } => {
+                write!(
+                    f,
+                    "unexpected byte at {pos} at the boundary of field `{field}`"
+                )
+            }
+            Self::TimestampParse(err) => write!(f, "invalid timestamp: {err}"),
+            Self::IdParse(err) => write!(f, "invalid id: {err}"),
+            Self::InvalidDecimal => write!(f, "invalid decimal"),
+            Self::DecimalTooLong { len } => {
+                write!(f, "decimal of {len} bytes is over {DECIMAL_CAPACITY}")
+            }
+            Self::InvalidBool { pos } => write!(f, "expected a boolean at {pos}"),
+            Self::InvalidSide { pos } => write!(f, "expected an order side at {pos}"),
+            Self::InvalidJson { line, column } => {
+                write!(f, "invalid JSON at line {line}, column {column}")
+            }
+            Self::MessageTooLarge { len } => {
+                write!(f, "message of {len} bytes is over {MAX_MESSAGE_BYTES}")
+            }
+            Self::MessageTooShort { len } => {
+                write!(f, "message of {len} bytes is under {MIN_MESSAGE_BYTES}")
+            }
+            Self::WrongEventType => write!(f, "message isn't a book ticker"),
+        }
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+/// Scans the payload to find all the positions and lengths [`parse_book_ticker_dynamic`] relies on.
+/// This is slow compared to the parsing itself, so the result is meant to be cached and only
+/// recomputed when the payload layout changes.
+pub fn detect_config(json: &str) -> Result<ParsingConfig, ParseError> {
+    let s_start = json
+        .find("\"s\":\"")
+        .ok_or(ParseError::FieldNotFound("s"))?
+        + 5;
+    let symbol_len =
+        simd_find_byte(&json.as_bytes()[s_start..], b'"').ok_or(ParseError::FieldNotFound("s"))?;
+
+    // Skip 1 char: "
+    let start = json
+        .find("\"b\":\"")
+        .ok_or(ParseError::FieldNotFound("b"))?
+        + 1;
+    let price_precision = detect_precision(json, start + 4, "b")?;
+
+    let B_start = json
+        .find("\"B\":\"")
+        .ok_or(ParseError::FieldNotFound("B"))?
+        + 5;
+    let volume_precision = detect_precision(json, B_start, "B")?;
+
+    let schema_version = if json.starts_with("{\"u\":") {
+        SchemaVersion::Spot
+    } else if json.contains("\"ps\":\"") {
+        SchemaVersion::CoinMFutures
+    } else {
+        SchemaVersion::Futures
+    };
+
+    let transaction_time_digits = if schema_version == SchemaVersion::Spot {
+        0
+    } else {
+        let T_start = json.find("\"T\":").ok_or(ParseError::FieldNotFound("T"))? + 4;
+        json[T_start..]
+            .bytes()
+            .take_while(u8::is_ascii_digit)
+            .count()
+    };
+
+    Ok(ParsingConfig {
+        start,
+        price_precision,
+        volume_precision,
+        transaction_time_digits,
+        symbol_len,
+        schema_version,
+    })
+}
+
+/// How a cached config compares to the one detected from a payload, see [`validate_config`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum ConfigValidation {
+    /// The payload has the layout and precisions of the config.
+    Ok,
+    /// The fields have moved, e.g. because the symbol or a timestamp got longer.
+    PositionDrift { new_start: usize },
+    /// The values have other precisions, whether or not the fields have moved as well.
+    PrecisionChange {
+        new_price_precision: usize,
+        new_volume_precision: usize,
+    },
+    /// The payload isn't a book ticker to detect a config from.
+    Undetected(ParseError),
+}
+
+/// Checks `config` still fits `json` by detecting the config again, which is too slow to do on
+/// every payload but catches layouts that [`ParsingConfig::matches`] doesn't, e.g. a changed
+/// precision.
+pub fn validate_config(json: &str, config: ParsingConfig) -> ConfigValidation {
+    let detected = match detect_config(json) {
+        Ok(detected) => detected,
+        Err(err) => return ConfigValidation::Undetected(err),
+    };
+    if (detected.price_precision, detected.volume_precision)
+        != (config.price_precision, config.volume_precision)
+    {
+        ConfigValidation::PrecisionChange {
+            new_price_precision: detected.price_precision,
+            new_volume_precision: detected.volume_precision,
+        }
+    } else if detected != config {
+        ConfigValidation::PositionDrift {
+            new_start: detected.start,
+        }
+    } else {
+        ConfigValidation::Ok
+    }
+}
+
+/// Why [`discover_config`] couldn't come up with a config for a sample payload.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum DiscoveryError {
+    /// The sample has no such key, so it's not a book ticker.
+    MissingField(&'static str),
+    /// The config was found but parsing the sample with it failed, e.g. because the ask has
+    /// another precision than the bid.
+    Validation(ParseError),
+    /// Parsing the sample with the config gave another value of `field` than its key does.
+    Mismatch(&'static str),
+}
+
+impl core::fmt::Display for DiscoveryError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::MissingField(field) => write!(f, "field `{field}` not found"),
+            Self::Validation(err) => write!(f, "discovered config doesn't parse the sample: {err}"),
+            Self::Mismatch(field) => {
+                write!(
+                    f,
+                    "discovered config misplaces field `{field}` in the sample"
+                )
+            }
+        }
+    }
+}
+
+impl core::error::Error for DiscoveryError {}
+
+/// Same as [`detect_config`], but checked by parsing `sample` with the config found and
+/// comparing each field to the value found by its key, so that it can be trusted instead of
+/// working out the positions by hand.
+///
+/// ```
+/// use binance_json_parsing::{discover_config, parse_book_ticker_dynamic};
+///
+/// let sample = r#"{"e":"bookTicker","u":7283946601071,"s":"BTCUSDT","b":"83604.80","B":"10.746","a":"83604.90","A":"9.514","T":1744760290967,"E":1744760290968}"#;
+/// let config = discover_config(sample).unwrap();
+///
+/// assert_eq!(config.start, 51);
+/// assert_eq!(parse_book_ticker_dynamic(sample, config).unwrap().b, "83604.80");
+/// ```
+pub fn discover_config(sample: &str) -> Result<ParsingConfig, DiscoveryError> {
+    const KEYS: [(&str, &str); 5] = [
+        ("\"s\":\"", "s"),
+        ("\"b\":\"", "b"),
+        ("\"B\":\"", "B"),
+        ("\"a\":\"", "a"),
+        ("\"A\":\"", "A"),
+    ];
+    let mut values = [""; 5];
+    for (value, (key, field)) in values.iter_mut().zip(KEYS) {
+        let start = sample
+            .find(key)
+            .ok_or(DiscoveryError::MissingField(field))?
+            + key.len();
+        let len = simd_find_byte(&sample.as_bytes()[start..], b'"')
+            .ok_or(DiscoveryError::MissingField(field))?;
+        *value = &sample[start..start + len];
+    }
+    // Spot has no times to check.
+    let T = if sample.starts_with("{\"u\":") {
+        None
+    } else {
+        let T_start = sample
+            .find("\"T\":")
+            .ok_or(DiscoveryError::MissingField("T"))?
+            + 4;
+        let T_len = sample[T_start..]
+            .bytes()
+            .take_while(u8::is_ascii_digit)
+            .count();
+        Some(&sample[T_start..T_start + T_len])
+    };
+
+    let config = detect_config(sample).map_err(|err| match err {
+        ParseError::FieldNotFound(field) => DiscoveryError::MissingField(field),
+        err => DiscoveryError::Validation(err),
+    })?;
+    let ticker = parse_positional(sample, config).map_err(DiscoveryError::Validation)?;
+
+    let parsed = [ticker.s, ticker.b, ticker.B, ticker.a, ticker.A];
+    for ((value, parsed), (_, field)) in values.into_iter().zip(parsed).zip(KEYS) {
+        if value != parsed {
+            return Err(DiscoveryError::Mismatch(field));
+        }
+    }
+    if T.is_some_and(|T| T.parse() != Ok(ticker.T)) {
+        return Err(DiscoveryError::Mismatch("T"));
+    }
+    Ok(config)
+}
+
+/// Counts the fractional digits of the quoted decimal value starting at `start`.
+pub(crate) fn detect_precision(
+    json: &str,
+    start: usize,
+    field: &'static str,
+) -> Result<usize, ParseError> {
+    let value = &json.as_bytes()[start..];
+    let end = simd_find_byte(value, b'"').ok_or(ParseError::FieldNotFound(field))?;
+    // Integers, e.g. volumes of coins that can't be split, have no dot at all.
+    Ok(simd_find_byte(&value[..end], b'.').map_or(0, |dot_pos| end - dot_pos - 1))
+}
+
+/// Finds where the quoted decimal value starting at `start` ends, assuming it has exactly
+/// `precision` fractional digits.
+pub(crate) fn decimal_end(
+    json: &str,
+    start: usize,
+    precision: usize,
+    field: &'static str,
+) -> Result<usize, ParseError> {
+    if precision == 0 {
+        // There's no dot to skip to, so look for the closing quote instead, checking that the
+        // value hasn't gained a fractional part since.
+        let end = quoted_end(json, start, field)?;
+        if simd_find_byte(&json.as_bytes()[start..end], b'.').is_some() {
+            return Err(ParseError::InvalidBoundary { field, pos: end });
+        }
+        return Ok(end);
+    }
+
+    let value = json
+        .get(start..)
+        .ok_or(ParseError::FieldNotFound(field))?
+        .as_bytes();
+    let dot_pos = match simd_find_byte(value, b'.') {
+        // The integer digits are few, so checking that the dot is still within the value is
+        // cheap.
+        Some(dot_pos) if !value[..dot_pos].contains(&b'"') => start + dot_pos,
+        // An integer, which only a precision of 0 fits.
+        _ => {
+            let end = quoted_end(json, start, field)?;
+            return Err(ParseError::InvalidBoundary { field, pos: end });
+        }
+    };
+    let end = dot_pos + precision + 1;
+    expect_byte(json, end, b'"', field)?;
+    Ok(end)
+}
+
+pub(crate) fn expect_byte(
+    json: &str,
+    pos: usize,
+    byte: u8,
+    field: &'static str,
+) -> Result<(), ParseError> {
+    if json.as_bytes().get(pos) == Some(&byte) {
+        Ok(())
+    } else {
+        Err(ParseError::InvalidBoundary { field, pos })
+    }
+}
+
+/// Finds the closing quote of the string value starting at `start`.
+pub(crate) fn quoted_end(
+    json: &str,
+    start: usize,
+    field: &'static str,
+) -> Result<usize, ParseError> {
+    let len = json
+        .get(start..)
+        .and_then(|value| simd_find_byte(value.as_bytes(), b'"'))
+        .ok_or(ParseError::FieldNotFound(field))?;
+    Ok(start + len)
+}
+
+/// The string value starting at `start` and where it ends.
+pub(crate) fn quoted_field<'a>(
+    json: &'a str,
+    start: usize,
+    field: &'static str,
+) -> Result<(&'a str, usize), ParseError> {
+    let end = quoted_end(json, start, field)?;
+    Ok((&json[start..end], end))
+}
+
+/// Parses the unquoted id starting at `start`, which has a variable number of digits, so it
+/// runs up to the next `delimiter`. Returns the id and where it ends.
+pub(crate) fn id_field(
+    json: &str,
+    start: usize,
+    delimiter: u8,
+    field: &'static str,
+) -> Result<(u64, usize), ParseError> {
+    let end = start
+        + json
+            .get(start..)
+            .and_then(|value| simd_find_byte(value.as_bytes(), delimiter))
+            .ok_or(ParseError::FieldNotFound(field))?;
+    let id = json[start..end].parse().map_err(ParseError::IdParse)?;
+    Ok((id, end))
+}
+
+/// Parses the timestamp of `digits` digits starting at `start`, checking that it's followed by
+/// `delimiter`.
+pub(crate) fn timestamp_field(
+    json: &str,
+    start: usize,
+    digits: usize,
+    delimiter: u8,
+    field: &'static str,
+) -> Result<u64, ParseError> {
+    let end = start + digits;
+    expect_byte(json, end, delimiter, field)?;
+    parse_timestamp(json, start, end, field)
+}
+
+/// Parses the timestamp at `start..end` eight digits at a time, only going through the standard
+/// parser for its error if it's not all digits.
+fn parse_timestamp(
+    json: &str,
+    start: usize,
+    end: usize,
+    field: &'static str,
+) -> Result<u64, ParseError> {
+    let value = json
+        .get(start..end)
+        .ok_or(ParseError::FieldNotFound(field))?;
+    let digits = value.len();
+    // 20 digits may not fit.
+    if (1..20).contains(&digits) && all_digits(value.as_bytes(), digits) {
+        return Ok(parse_u64_fixed_digits(value.as_bytes(), digits));
+    }
+    value.parse().map_err(ParseError::TimestampParse)
+}
+
+/// Parses the unquoted `true` or `false` starting at `pos`.
+pub(crate) fn parse_bool_field(json: &str, pos: usize) -> Result<bool, ParseError> {
+    let value = json.as_bytes().get(pos..).unwrap_or_default();
+    if value.starts_with(b"true") {
+        Ok(true)
+    } else if value.starts_with(b"false") {
+        Ok(false)
+    } else {
+        Err(ParseError::InvalidBool { pos })
+    }
+}
+
+/// Parses a book ticker of a symbol whose price and volume precisions are known upfront, e.g.
+/// `parse_book_ticker::<2, 3>(json, config)` for BTCUSDT. The ticker carries them in its type,
+/// so it can't be passed where one of another symbol is expected:
+///
+/// ```compile_fail
+/// use binance_json_parsing::{BookTicker, LayoutConfig, parse_book_ticker};
+///
+/// fn on_btcusdt(ticker: BookTicker<'_, 2, 3>) {}
+///
+/// let json = r#"{"e":"bookTicker","u":7283946605501,"s":"SOLUSDT","b":"131.1500","B":"43","a":"131.1600","A":"388","T":1744760291201,"E":1744760291202}"#;
+/// on_btcusdt(parse_book_ticker::<4, 0>(json, LayoutConfig::default()).unwrap());
+/// ```
+///
+/// Like [`parse_book_ticker_dynamic`] this falls back to detecting the layout if `start`
+/// doesn't match, but the precisions never change: payloads with others are rejected.
+pub fn parse_book_ticker<const PP: usize, const VP: usize>(
+    json: &str,
+    config: LayoutConfig,
+) -> Result<BookTicker<'_, PP, VP>, ParseError> {
+    const {
+        assert!(
+            PP != DYNAMIC_PRECISION && VP != DYNAMIC_PRECISION,
+            "use `parse_book_ticker_dynamic` for precisions only known at runtime"
+        )
+    };
+    check_len(json)?;
+    if !is_book_ticker_frame(json) {
+        return Err(ParseError::WrongEventType);
+    }
+    let config = if config.matches(json) {
+        config
+    } else {
+        detect_config(json)?.layout()
+    };
+    parse_with(json, config.with_precisions(PP, VP))
+}
+
+/// Same as [`parse_book_ticker`] for precisions only known at runtime, e.g. detected by
+/// [`detect_config`]. Takes either a [`ParsingConfig`] or an [`AtomicConfig`], which is loaded
+/// once.
+///
+/// With the `nom-parser` feature the config is ignored and [`parse_book_ticker_nom`] is used
+/// instead.
+///
+/// [`AtomicConfig`]: crate::atomic_config::AtomicConfig
+pub fn parse_book_ticker_dynamic(
+    json: &str,
+    config: impl Into<ParsingConfig>,
+) -> Result<BookTicker<'_>, ParseError> {
+    check_len(json)?;
+    if !is_book_ticker_frame(json) {
+        return Err(ParseError::WrongEventType);
+    }
+    if cfg!(feature = "nom-parser") {
+        return parse_book_ticker_nom(json);
+    }
+    parse_positional(json, config.into())
+}
+
+/// Longest payload the parsers accept, well over the about 150 bytes of a book ticker, so that
+/// anything else is turned away before going through it.
+pub const MAX_MESSAGE_BYTES: usize = 4096;
+
+/// Shortest payload the parsers accept, that of a spot ticker with every value a single byte:
+/// `{"u":0,"s":"X","b":"0","B":"0","a":"0","A":"0"}`.
+pub const MIN_MESSAGE_BYTES: usize = 47;
+
+/// Whether `json` starts like a book ticker, either `{"e":"bookTicker",` or the `{"u":<id>,"s":"`
+/// of spot, telling them from responses, errors and other events on the first byte that differs.
+///
+/// Only the start is looked at, so this is cheap enough to run on every payload.
+pub fn is_book_ticker_frame(json: &str) -> bool {
+    let json = json.as_bytes();
+    if json.starts_with(br#"{"e":"bookTicker","#) {
+        return true;
+    }
+    let Some(id) = json.strip_prefix(br#"{"u":"#) else {
+        return false;
+    };
+    let digits = id.iter().take_while(|b| b.is_ascii_digit()).count();
+    digits > 0 && id[digits..].starts_with(br#","s":""#)
+}
+
+/// Checks the length of `json` is within [`MIN_MESSAGE_BYTES`] and [`MAX_MESSAGE_BYTES`].
+pub fn check_len(json: &str) -> Result<(), ParseError> {
+    match json.len() {
+        len if len > MAX_MESSAGE_BYTES => Err(ParseError::MessageTooLarge { len }),
+        len if len < MIN_MESSAGE_BYTES => Err(ParseError::MessageTooShort { len }),
+        _ => Ok(()),
+    }
+}
+
+fn parse_positional(json: &str, config: ParsingConfig) -> Result<BookTicker<'_>, ParseError> {
+    // The layout has drifted, so fall back to scanning the payload. Callers should detect this
+    // with `ParsingConfig::matches` and cache the new config to avoid paying for it every time.
+    let config = if config.matches(json) {
+        config
+    } else {
+        detect_config(json)?
+    };
+    parse_with(json, config)
+}
+
+fn parse_with<const PP: usize, const VP: usize>(
+    json: &str,
+    config: ParsingConfig,
+) -> Result<BookTicker<'_, PP, VP>, ParseError> {
+    let ParsingConfig {
+        start,
+        price_precision,
+        volume_precision,
+        transaction_time_digits,
+        symbol_len,
+        schema_version,
+    } = config;
+
+    let (e, u_start) = if schema_version == SchemaVersion::Spot {
+        // Skip 5 chars: {"u":
+        ("", 5)
+    } else {
+        // Skip 6 chars: {"e":"
+        let e_start = 6;
+        let e_end = e_start
+            + json
+                .get(e_start..)
+                .and_then(|value| simd_find_byte(value.as_bytes(), b'"'))
+                .ok_or(ParseError::FieldNotFound("e"))?;
+        // Skip 6 chars: ","u":
+        (&json[e_start..e_end], e_end + 6)
+    };
+
+    // The update id has a variable number of digits, so walk back from `b` through the symbol,
+    // whose length is known, to find where the update id ends.
+    // Skip 3 chars back: ","
+    let s_end = start.checked_sub(3).ok_or(ParseError::FieldNotFound("s"))?;
+    expect_byte(json, s_end, b'"', "s")?;
+    let (pair, s_end) = if schema_version == SchemaVersion::CoinMFutures {
+        let ps_open = pair_start(json, s_end)?;
+        let pair = &json[ps_open + 1..s_end];
+        // Skip 7 chars back: ","ps":
+        let s_end = ps_open
+            .checked_sub(7)
+            .ok_or(ParseError::FieldNotFound("s"))?;
+        (Some(pair), s_end)
+    } else {
+        (None, s_end)
+    };
+    expect_byte(json, s_end, b'"', "s")?;
+    let s_start = s_end
+        .checked_sub(symbol_len)
+        .ok_or(ParseError::FieldNotFound("s"))?;
+    let s = json
+        .get(s_start..s_end)
+        .ok_or(ParseError::FieldNotFound("s"))?;
+
+    // Skip 6 chars back: ,"s":"
+    let u_end = s_start
+        .checked_sub(6)
+        .ok_or(ParseError::FieldNotFound("u"))?;
+    expect_byte(json, u_end, b',', "u")?;
+    let u = json
+        .get(u_start..u_end)
+        .ok_or(ParseError::FieldNotFound("u"))?
+        .parse()
+        .map_err(ParseError::IdParse)?;
+
+    // Skip 4 chars: b":"
+    let b_start = start + 4;
+    let b_end = decimal_end(json, b_start, price_precision, "b")?;
+    let b = &json[b_start..b_end];
+
+    // Skip 7 chars: ","B":"
+    let B_start = b_end + 7;
+    let B_end = decimal_end(json, B_start, volume_precision, "B")?;
+    let B = &json[B_start..B_end];
+
+    // Skip 7 chars: ","a":"
+    let a_start = B_end + 7;
+    let a_end = decimal_end(json, a_start, price_precision, "a")?;
+    let a = &json[a_start..a_end];
+
+    // Skip 7 chars: ","A":"
+    let A_start = a_end + 7;
+    let A_end = decimal_end(json, A_start, volume_precision, "A")?;
+    let A = &json[A_start..A_end];
+
+    let (T, E) = if schema_version == SchemaVersion::Spot {
+        expect_byte(json, A_end + 1, b'}', "A")?;
+        (0, 0)
+    } else {
+        // Skip 6 chars: ","T":
+        let T_start = A_end + 6;
+        // Skip 13 digits, for example, 1744848607537 will be 13 digits for a long time until this
+        // parameter would have to change.
+        let T_end = T_start + transaction_time_digits;
+        expect_byte(json, T_end, b',', "T")?;
+        let T = parse_timestamp(json, T_start, T_end, "T")?;
+
+        // Skip 5 chars: ,"E":
+        let E_start = T_end + 5;
+        // The event time has as many digits as the transaction time.
+        let E_end = E_start + transaction_time_digits;
+        expect_byte(json, E_end, b'}', "E")?;
+        let E = parse_timestamp(json, E_start, E_end, "E")?;
+
+        (T, E)
+    };
+
+    Ok(BookTicker {
+        e,
+        u,
+        s,
+        pair,
+        T,
+        b,
+        B,
+        a,
+        A,
+        E,
+    })
+}
+
+/// Walks back from the closing quote of the COIN-M pair at `end` to its opening one.
+fn pair_start(json: &str, end: usize) -> Result<usize, ParseError> {
+    let open = json.as_bytes()[..end]
+        .iter()
+        .rposition(|&byte| byte == b'"')
+        .ok_or(ParseError::FieldNotFound("ps"))?;
+    // Skip 4 chars back: ps":
+    expect_byte(json, open.saturating_sub(4), b'p', "ps")?;
+    Ok(open)
+}
+
+/// Same as [`parse_book_ticker_dynamic`], but converts the price and volume slices it finds, so the
+/// payload itself is still scanned only once.
+pub fn parse_book_ticker_fixed(
+    json: &str,
+    config: ParsingConfig,
+) -> Result<BookTickerFixed<'_>, ParseError> {
+    let BookTicker {
+        e,
+        u,
+        s,
+        T,
+        b,
+        B,
+        a,
+        A,
+        E,
+        ..
+    } = parse_book_ticker_dynamic(json, config)?;
+
+    Ok(BookTickerFixed {
+        e,
+        u,
+        s,
+        T,
+        b: parse_fixed(b)?,
+        B: parse_fixed(B)?,
+        a: parse_fixed(a)?,
+        A: parse_fixed(A)?,
+        E,
+    })
+}
+
+mod nom;
+
+pub use nom::parse_book_ticker_nom;
+
+#[cfg(test)]
+mod tests;
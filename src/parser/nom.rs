@@ -0,0 +1,154 @@
+//! Reference parser of the same book tickers, made of nom-style combinators: it goes through the
+//! payload key by key rather than relying on a config, so it's slower but easy to follow and to
+//! check the positional parser against.
+//!
+//! The combinators are the handful of nom ones needed, with the same names and shapes, so that
+//! moving to the nom crate is a matter of importing them from there.
+
+use super::{BookTicker, ParseError};
+
+/// The rest of the input and what was parsed from it, like `nom::IResult`.
+type IResult<'a, O> = Result<(&'a str, O), ParseError>;
+
+fn tag<'a>(tag: &'static str) -> impl Fn(&'a str) -> IResult<'a, &'a str> {
+    move |input: &'a str| match input.strip_prefix(tag) {
+        Some(rest) => Ok((rest, &input[..tag.len()])),
+        None => Err(ParseError::FieldNotFound(tag)),
+    }
+}
+
+fn take_while<'a>(cond: impl Fn(char) -> bool) -> impl Fn(&'a str) -> IResult<'a, &'a str> {
+    move |input: &'a str| {
+        let len = input.find(|c| !cond(c)).unwrap_or(input.len());
+        Ok((&input[len..], &input[..len]))
+    }
+}
+
+fn preceded<'a, O1, O2>(
+    first: impl Fn(&'a str) -> IResult<'a, O1>,
+    second: impl Fn(&'a str) -> IResult<'a, O2>,
+) -> impl Fn(&'a str) -> IResult<'a, O2> {
+    move |input| {
+        let (input, _) = first(input)?;
+        second(input)
+    }
+}
+
+fn terminated<'a, O1, O2>(
+    first: impl Fn(&'a str) -> IResult<'a, O1>,
+    second: impl Fn(&'a str) -> IResult<'a, O2>,
+) -> impl Fn(&'a str) -> IResult<'a, O1> {
+    move |input| {
+        let (input, output) = first(input)?;
+        let (input, _) = second(input)?;
+        Ok((input, output))
+    }
+}
+
+fn delimited<'a, O1, O2, O3>(
+    first: impl Fn(&'a str) -> IResult<'a, O1>,
+    second: impl Fn(&'a str) -> IResult<'a, O2>,
+    third: impl Fn(&'a str) -> IResult<'a, O3>,
+) -> impl Fn(&'a str) -> IResult<'a, O2> {
+    preceded(first, terminated(second, third))
+}
+
+fn separated_pair<'a, O1, O2, O3>(
+    first: impl Fn(&'a str) -> IResult<'a, O1>,
+    sep: impl Fn(&'a str) -> IResult<'a, O2>,
+    second: impl Fn(&'a str) -> IResult<'a, O3>,
+) -> impl Fn(&'a str) -> IResult<'a, (O1, O3)> {
+    move |input| {
+        let (input, first) = first(input)?;
+        let (input, _) = sep(input)?;
+        let (input, second) = second(input)?;
+        Ok((input, (first, second)))
+    }
+}
+
+/// Whatever `parser` gives, or `None` with the input left as is if it fails.
+fn opt<'a, O>(
+    parser: impl Fn(&'a str) -> IResult<'a, O>,
+) -> impl Fn(&'a str) -> IResult<'a, Option<O>> {
+    move |input| match parser(input) {
+        Ok((rest, output)) => Ok((rest, Some(output))),
+        Err(_) => Ok((input, None)),
+    }
+}
+
+fn string<'a>(input: &'a str) -> IResult<'a, &'a str> {
+    delimited(tag("\""), take_while(|c| c != '"'), tag("\""))(input)
+}
+
+fn number<'a>(input: &'a str) -> IResult<'a, &'a str> {
+    take_while(|c| c.is_ascii_digit())(input)
+}
+
+fn id<'a>(input: &'a str) -> IResult<'a, u64> {
+    let (input, digits) = number(input)?;
+    Ok((input, digits.parse().map_err(ParseError::IdParse)?))
+}
+
+fn timestamp<'a>(input: &'a str) -> IResult<'a, u64> {
+    let (input, digits) = number(input)?;
+    Ok((input, digits.parse().map_err(ParseError::TimestampParse)?))
+}
+
+/// The value of the `name` key, as in "name":value, reporting the key as missing otherwise.
+fn key<'a, O>(
+    name: &'static str,
+    value: impl Fn(&'a str) -> IResult<'a, O>,
+) -> impl Fn(&'a str) -> IResult<'a, O> {
+    let value = preceded(delimited(tag("\""), tag(name), tag("\":")), value);
+    move |input| {
+        value(input).map_err(|err| match err {
+            ParseError::FieldNotFound(_) => ParseError::FieldNotFound(name),
+            err => err,
+        })
+    }
+}
+
+/// Parses a book ticker of any schema, walking through its keys in the order Binance sends
+/// them, see the layout in the parent module.
+pub fn parse_book_ticker_nom(json: &str) -> Result<BookTicker<'_>, ParseError> {
+    let (input, _) = tag("{")(json)?;
+    // Spot has no event type nor times.
+    let (input, e) = opt(terminated(key("e", string), tag(",")))(input)?;
+    let (input, u) = terminated(key("u", id), tag(","))(input)?;
+    let (input, s) = key("s", string)(input)?;
+    // Only COIN-M has the pair.
+    let (input, pair) = opt(preceded(tag(","), key("ps", string)))(input)?;
+    let (input, (b, B)) = preceded(
+        tag(","),
+        separated_pair(key("b", string), tag(","), key("B", string)),
+    )(input)?;
+    let (input, (a, A)) = preceded(
+        tag(","),
+        separated_pair(key("a", string), tag(","), key("A", string)),
+    )(input)?;
+    let (input, times) = opt(preceded(
+        tag(","),
+        separated_pair(key("T", timestamp), tag(","), key("E", timestamp)),
+    ))(input)?;
+    let (input, _) = tag("}")(input)?;
+    if !input.is_empty() {
+        return Err(ParseError::InvalidBoundary {
+            field: "}",
+            pos: json.len() - input.len(),
+        });
+    }
+
+    let (T, E) = times.unwrap_or_default();
+    Ok(BookTicker {
+        e: e.unwrap_or_default(),
+        u,
+        s,
+        pair,
+        T,
+        b,
+        B,
+        a,
+        A,
+        E,
+    })
+}
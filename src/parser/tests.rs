@@ -0,0 +1,319 @@
+//! Property tests over payloads generated from random fields, so that the expected result of
+//! parsing them is known upfront.
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use super::*;
+
+const CASES: usize = 10_000;
+
+struct Case {
+    json: String,
+    config: ParsingConfig,
+    expected: BookTickerOwned,
+}
+
+fn rng() -> StdRng {
+    // Fixed, so that failures can be reproduced.
+    StdRng::seed_from_u64(0x5eed)
+}
+
+fn digits(rng: &mut StdRng, len: usize) -> String {
+    (0..len)
+        .map(|_| char::from(b'0' + rng.random_range(0..10)))
+        .collect()
+}
+
+/// A decimal with 1 to 10 integer digits, or just `0` now and then, and exactly `precision`
+/// fractional ones, but no longer than `DECIMAL_CAPACITY` in all.
+fn decimal(rng: &mut StdRng, precision: usize) -> SmallString<DECIMAL_CAPACITY> {
+    let mut value = if rng.random_bool(0.2) {
+        "0".to_owned()
+    } else {
+        let len = rng.random_range(1..=10.min(DECIMAL_CAPACITY - 1 - precision));
+        let first = char::from(b'0' + rng.random_range(1..10));
+        format!("{first}{}", digits(rng, len - 1))
+    };
+    if precision > 0 {
+        value.push('.');
+        value.push_str(&digits(rng, precision));
+    }
+    SmallString::new(&value).unwrap()
+}
+
+/// A timestamp of exactly `digits` digits, up to the 20 of `u64::MAX`.
+fn timestamp(rng: &mut StdRng, digits: u32) -> u64 {
+    let min = 10u64.pow(digits - 1);
+    let max = 10u64.checked_pow(digits).map_or(u64::MAX, |end| end - 1);
+    if rng.random_bool(0.1) {
+        max
+    } else {
+        rng.random_range(min..=max)
+    }
+}
+
+fn case(rng: &mut StdRng) -> Case {
+    let price_precision = rng.random_range(0..=8);
+    let volume_precision = rng.random_range(0..=8);
+    let time_digits = rng.random_range(1..=20);
+
+    let symbol_len = rng.random_range(1..=16);
+    let symbol: String = (0..symbol_len)
+        .map(|_| char::from(b"ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789"[rng.random_range(0..36)]))
+        .collect();
+
+    let expected = BookTickerOwned {
+        e: "bookTicker".to_owned(),
+        u: rng.random(),
+        s: symbol,
+        pair: None,
+        T: timestamp(rng, time_digits),
+        b: decimal(rng, price_precision),
+        B: decimal(rng, volume_precision),
+        a: decimal(rng, price_precision),
+        A: decimal(rng, volume_precision),
+        E: timestamp(rng, time_digits),
+    };
+    let json = format!(
+        r#"{{"e":"{}","u":{},"s":"{}","b":"{}","B":"{}","a":"{}","A":"{}","T":{},"E":{}}}"#,
+        expected.e,
+        expected.u,
+        expected.s,
+        expected.b,
+        expected.B,
+        expected.a,
+        expected.A,
+        expected.T,
+        expected.E
+    );
+    let config = ParsingConfig {
+        start: json.find(r#""b":"#).unwrap() + 1,
+        price_precision,
+        volume_precision,
+        transaction_time_digits: time_digits as usize,
+        symbol_len,
+        schema_version: SchemaVersion::Futures,
+    };
+
+    Case {
+        json,
+        config,
+        expected,
+    }
+}
+
+#[test]
+fn parses_generated_payloads() {
+    let mut rng = rng();
+    for _ in 0..CASES {
+        let case = case(&mut rng);
+        let parsed = parse_book_ticker_dynamic(&case.json, case.config);
+        assert_eq!(
+            parsed.and_then(BookTickerOwned::try_from),
+            Ok(case.expected),
+            "{}",
+            case.json
+        );
+    }
+}
+
+#[test]
+fn detects_generated_layouts() {
+    let mut rng = rng();
+    for _ in 0..CASES {
+        let case = case(&mut rng);
+        assert_eq!(detect_config(&case.json), Ok(case.config), "{}", case.json);
+    }
+}
+
+#[test]
+fn falls_back_to_detection_with_stale_start() {
+    let mut rng = rng();
+    for _ in 0..CASES {
+        let case = case(&mut rng);
+        let stale = ParsingConfig {
+            start: case.config.start + 1,
+            ..case.config
+        };
+        let parsed = parse_book_ticker_dynamic(&case.json, stale);
+        assert_eq!(
+            parsed.and_then(BookTickerOwned::try_from),
+            Ok(case.expected),
+            "{}",
+            case.json
+        );
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn agrees_with_serde_json() {
+    let mut rng = rng();
+    for _ in 0..CASES {
+        let case = case(&mut rng);
+        let parsed =
+            parse_book_ticker_dynamic(&case.json, case.config).and_then(BookTickerOwned::try_from);
+        let deserialized = serde_json::from_str::<BookTickerOwned>(&case.json).unwrap();
+        assert_eq!(parsed, Ok(deserialized), "{}", case.json);
+    }
+}
+
+#[test]
+fn validates_generated_configs() {
+    let mut rng = rng();
+    for _ in 0..CASES {
+        let case = case(&mut rng);
+        assert_eq!(
+            validate_config(&case.json, case.config),
+            ConfigValidation::Ok,
+            "{}",
+            case.json
+        );
+
+        let drifted = ParsingConfig {
+            start: case.config.start - 1,
+            symbol_len: case.config.symbol_len - 1,
+            ..case.config
+        };
+        assert_eq!(
+            validate_config(&case.json, drifted),
+            ConfigValidation::PositionDrift {
+                new_start: case.config.start
+            },
+            "{}",
+            case.json
+        );
+
+        // Caught even though the fields haven't moved.
+        let changed = ParsingConfig {
+            volume_precision: case.config.volume_precision + 1,
+            ..case.config
+        };
+        assert_eq!(
+            validate_config(&case.json, changed),
+            ConfigValidation::PrecisionChange {
+                new_price_precision: case.config.price_precision,
+                new_volume_precision: case.config.volume_precision
+            },
+            "{}",
+            case.json
+        );
+    }
+    assert_eq!(
+        validate_config("{}", ParsingConfig::default()),
+        ConfigValidation::Undetected(ParseError::FieldNotFound("s"))
+    );
+}
+
+// The reference parser doesn't take the precisions from the config.
+#[cfg(not(feature = "nom-parser"))]
+#[test]
+fn rejects_changed_precision() {
+    let mut rng = rng();
+    for _ in 0..CASES {
+        let case = case(&mut rng);
+        // Integers included, which have no dot at all.
+        let wrong = ParsingConfig {
+            price_precision: case.config.price_precision + 1,
+            ..case.config
+        };
+        assert!(
+            matches!(
+                parse_book_ticker_dynamic(&case.json, wrong),
+                Err(ParseError::InvalidBoundary { field: "b", .. })
+            ),
+            "{}",
+            case.json
+        );
+    }
+}
+
+#[cfg(not(feature = "nom-parser"))]
+#[test]
+fn rejects_dropped_fractional_part() {
+    let mut rng = rng();
+    for _ in 0..CASES {
+        let case = case(&mut rng);
+        if case.config.price_precision == 0 {
+            continue;
+        }
+        // Integer prices, so that a dot of the volumes could be mistaken for theirs.
+        let json = case.json.replace(
+            case.expected.b.as_str(),
+            case.expected.b.split('.').next().unwrap(),
+        );
+        let b_end = json[case.config.start + 4..].find('"').unwrap() + case.config.start + 4;
+        assert_eq!(
+            parse_book_ticker_dynamic(&json, case.config),
+            Err(ParseError::InvalidBoundary {
+                field: "b",
+                pos: b_end
+            }),
+            "{json}"
+        );
+        let detected = detect_config(&json).unwrap();
+        assert_eq!(detected.price_precision, 0, "{json}");
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn round_trips_through_serde_json() {
+    let mut rng = rng();
+    for _ in 0..CASES {
+        let case = case(&mut rng);
+        let ticker = parse_book_ticker_dynamic(&case.json, case.config).unwrap();
+        let owned = case.expected;
+
+        let json = serde_json::to_string(&ticker).unwrap();
+        assert_eq!(serde_json::from_str::<BookTicker>(&json).unwrap(), ticker);
+        let json = serde_json::to_string(&owned).unwrap();
+        assert_eq!(
+            serde_json::from_str::<BookTickerOwned>(&json).unwrap(),
+            owned
+        );
+        let json = serde_json::to_string(&case.config).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ParsingConfig>(&json).unwrap(),
+            case.config
+        );
+    }
+}
+
+#[test]
+fn nom_parser_parses_generated_payloads() {
+    let mut rng = rng();
+    for _ in 0..CASES {
+        let case = case(&mut rng);
+        let parsed = parse_book_ticker_nom(&case.json);
+        assert_eq!(
+            parsed.and_then(BookTickerOwned::try_from),
+            Ok(case.expected),
+            "{}",
+            case.json
+        );
+    }
+}
+
+#[test]
+fn parses_bool_fields() {
+    assert_eq!(parse_bool_field(r#"{"m":true}"#, 5), Ok(true));
+    assert_eq!(parse_bool_field(r#"{"m":false}"#, 5), Ok(false));
+    // Cut short or past the end.
+    assert_eq!(
+        parse_bool_field(r#"{"m":fals"#, 5),
+        Err(ParseError::InvalidBool { pos: 5 })
+    );
+    assert_eq!(
+        parse_bool_field(r#"{"m":true}"#, 11),
+        Err(ParseError::InvalidBool { pos: 11 })
+    );
+    for byte in (0..=u8::MAX).filter(|byte| !matches!(byte, b't' | b'f')) {
+        let json = format!(r#"{{"m":{}rue}}"#, char::from(byte));
+        assert_eq!(
+            parse_bool_field(&json, 5),
+            Err(ParseError::InvalidBool { pos: 5 }),
+            "{json}"
+        );
+    }
+}
@@ -0,0 +1,123 @@
+//! Net position of each symbol, kept up to date from fills, e.g. those of the user data stream.
+
+use std::{collections::HashMap, fmt};
+
+use crate::{ParseError, decimal::FixedPoint, liquidation::Side};
+
+const ZERO: FixedPoint = FixedPoint {
+    mantissa: 0,
+    exponent: 0,
+};
+
+/// Why a fill couldn't be applied, in which case the position is left as it was.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum PositionError {
+    /// The quantity or price of the fill isn't a decimal.
+    InvalidFill(ParseError),
+    /// The cost of the position is out of the range of [`FixedPoint`].
+    Overflow,
+}
+
+impl fmt::Display for PositionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidFill(err) => write!(f, "invalid fill: {err}"),
+            Self::Overflow => write!(f, "position cost out of range"),
+        }
+    }
+}
+
+impl core::error::Error for PositionError {}
+
+/// Positions as the sum of the fills, positive for longs and negative for shorts.
+#[derive(Default, Debug)]
+pub struct PositionTracker {
+    positions: HashMap<String, FixedPoint>,
+    /// What the open position of each symbol cost, negative for shorts, out of which the
+    /// average entry price follows.
+    costs: HashMap<String, FixedPoint>,
+}
+
+impl PositionTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a fill of `qty` at `price`. Reducing a position keeps its average entry price, and
+    /// flipping it starts the new one at `price`.
+    pub fn apply_fill(
+        &mut self,
+        symbol: &str,
+        side: Side,
+        qty: FixedPoint,
+        price: FixedPoint,
+    ) -> Result<(), PositionError> {
+        let signed = match side {
+            Side::Buy => qty,
+            Side::Sell => -qty,
+        };
+        let old = self.position(symbol);
+        let new = old + signed;
+        let cost = self.costs.get(symbol).copied().unwrap_or(ZERO);
+
+        let cost = if old.mantissa.signum() != -signed.mantissa.signum() {
+            signed.checked_mul(price).map(|paid| cost + paid)
+        } else if new.mantissa.signum() != -old.mantissa.signum() {
+            scale(cost, new, old)
+        } else {
+            new.checked_mul(price)
+        }
+        .ok_or(PositionError::Overflow)?;
+
+        if new.mantissa == 0 {
+            self.positions.remove(symbol);
+            self.costs.remove(symbol);
+        } else {
+            self.positions.insert(symbol.to_owned(), new);
+            self.costs.insert(symbol.to_owned(), cost);
+        }
+        Ok(())
+    }
+
+    /// Applies `update` if it's a fill, returning whether it was.
+    #[cfg(feature = "serde")]
+    pub fn apply_order_update(
+        &mut self,
+        update: &crate::order_update::OrderTradeUpdate,
+    ) -> Result<bool, PositionError> {
+        use crate::decimal::parse_fixed;
+
+        if !update.is_fill() {
+            return Ok(false);
+        }
+        let qty = parse_fixed(update.l).map_err(PositionError::InvalidFill)?;
+        let price = parse_fixed(update.L).map_err(PositionError::InvalidFill)?;
+        self.apply_fill(update.s, update.S, qty, price)?;
+        Ok(true)
+    }
+
+    /// Zero for symbols without a position.
+    pub fn position(&self, symbol: &str) -> FixedPoint {
+        self.positions.get(symbol).copied().unwrap_or(ZERO)
+    }
+
+    /// What closing the position at `mark_price` would gain, or lose if negative. `None` if
+    /// the value of the position at `mark_price` is out of the range of [`FixedPoint`].
+    pub fn unrealized_pnl(&self, symbol: &str, mark_price: FixedPoint) -> Option<FixedPoint> {
+        let cost = self.costs.get(symbol).copied().unwrap_or(ZERO);
+        Some(self.position(symbol).checked_mul(mark_price)? - cost)
+    }
+}
+
+/// `cost * new / old`, rounded towards zero at the exponent of `cost`, or `None` if `new` and
+/// `old` are too far apart to scale to a common exponent.
+fn scale(cost: FixedPoint, new: FixedPoint, old: FixedPoint) -> Option<FixedPoint> {
+    let exponent = new.exponent.min(old.exponent);
+    let at = |x: FixedPoint| {
+        let scale = 10i128.checked_pow((x.exponent as i32 - exponent as i32) as u32)?;
+        (x.mantissa as i128).checked_mul(scale)
+    };
+    let mantissa = (cost.mantissa as i128).checked_mul(at(new)?)? / at(old)?;
+    FixedPoint::from_wide(mantissa, cost.exponent.into())
+}
@@ -0,0 +1,30 @@
+//! Hints to bring the next buffered message into cache while the current one is being parsed.
+//!
+//! They're only emitted on x86_64, elsewhere these do nothing.
+
+/// Size of a cache line on the targets that get hints.
+pub const CACHE_LINE: usize = 64;
+
+/// Asks for the cache line holding `ptr` to be brought into L1, without waiting for it.
+///
+/// It's only a hint, so any pointer will do, even a dangling one: nothing is ever read from it.
+#[inline(always)]
+pub fn prefetch(ptr: *const u8) {
+    #[cfg(target_arch = "x86_64")]
+    {
+        use core::arch::x86_64::{_MM_HINT_T0, _mm_prefetch};
+
+        // SAFETY: prefetching never faults, and SSE is part of the x86_64 baseline.
+        unsafe { _mm_prefetch::<_MM_HINT_T0>(ptr.cast()) }
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    let _ = ptr;
+}
+
+/// Prefetches every cache line of `bytes`, e.g. the whole of a message rather than its start.
+#[inline(always)]
+pub fn prefetch_bytes(bytes: &[u8]) {
+    for offset in (0..bytes.len()).step_by(CACHE_LINE) {
+        prefetch(bytes[offset..].as_ptr());
+    }
+}
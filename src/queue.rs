@@ -0,0 +1,150 @@
+//! Bounded single-producer single-consumer queue, for handing parsed messages from the task
+//! receiving them to the one processing them.
+
+use std::{
+    cell::{Cell, UnsafeCell},
+    marker::PhantomData,
+    mem::MaybeUninit,
+    ptr,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+/// Keeps the producer and consumer indices on separate cache lines, so they don't invalidate
+/// each other's cache on every operation.
+#[repr(align(64))]
+struct CachePadded<T>(T);
+
+struct Ring<T, const N: usize> {
+    /// Next slot to pop, only written by the consumer.
+    head: CachePadded<AtomicUsize>,
+    /// Next slot to push, only written by the producer.
+    tail: CachePadded<AtomicUsize>,
+    slots: [UnsafeCell<MaybeUninit<T>>; N],
+}
+
+// SAFETY: a slot is only ever accessed by one side at a time, the head and tail hand it over.
+unsafe impl<T: Send, const N: usize> Sync for Ring<T, N> {}
+
+impl<T, const N: usize> Ring<T, N> {
+    fn slot(&self, index: usize) -> *mut T {
+        // Indices wrap around, which is fine as long as `N` divides `usize::MAX + 1`.
+        self.slots[index & (N - 1)].get().cast()
+    }
+}
+
+impl<T, const N: usize> Drop for Ring<T, N> {
+    fn drop(&mut self) {
+        let tail = *self.tail.0.get_mut();
+        let mut head = *self.head.0.get_mut();
+        while head != tail {
+            // SAFETY: the slots between the head and tail are initialized.
+            unsafe { ptr::drop_in_place(self.slot(head)) };
+            head = head.wrapping_add(1);
+        }
+    }
+}
+
+/// Ring buffer of `N` slots, where `N` must be a power of two. Split it into the producer and
+/// consumer halves to use it, each of which can be sent to its own thread.
+pub struct SpscQueue<T, const N: usize> {
+    ring: Arc<Ring<T, N>>,
+    // Not `Sync`, the sides must not be shared.
+    _not_sync: PhantomData<Cell<()>>,
+}
+
+impl<T, const N: usize> SpscQueue<T, N> {
+    pub fn new() -> Self {
+        const { assert!(N.is_power_of_two(), "capacity must be a power of two") };
+
+        Self {
+            ring: Arc::new(Ring {
+                head: CachePadded(AtomicUsize::new(0)),
+                tail: CachePadded(AtomicUsize::new(0)),
+                slots: [const { UnsafeCell::new(MaybeUninit::uninit()) }; N],
+            }),
+            _not_sync: PhantomData,
+        }
+    }
+
+    pub fn split(self) -> (Producer<T, N>, Consumer<T, N>) {
+        let producer = Producer {
+            ring: Arc::clone(&self.ring),
+            cached_head: 0,
+            _not_sync: PhantomData,
+        };
+        let consumer = Consumer {
+            ring: self.ring,
+            cached_tail: 0,
+            _not_sync: PhantomData,
+        };
+        (producer, consumer)
+    }
+}
+
+impl<T, const N: usize> Default for SpscQueue<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Pushing half of [`SpscQueue`].
+pub struct Producer<T, const N: usize> {
+    ring: Arc<Ring<T, N>>,
+    /// Last seen head, so that the consumer's cache line is only touched when the queue looks
+    /// full.
+    cached_head: usize,
+    _not_sync: PhantomData<Cell<()>>,
+}
+
+impl<T, const N: usize> Producer<T, N> {
+    /// Pushes `value`, or gives it back if the queue is full.
+    pub fn try_push(&mut self, value: T) -> Result<(), T> {
+        let tail = self.ring.tail.0.load(Ordering::Relaxed);
+        if tail.wrapping_sub(self.cached_head) == N {
+            self.cached_head = self.ring.head.0.load(Ordering::Acquire);
+            if tail.wrapping_sub(self.cached_head) == N {
+                return Err(value);
+            }
+        }
+
+        // SAFETY: the slot is past the head, so the consumer is done with it.
+        unsafe { ptr::write(self.ring.slot(tail), value) };
+        self.ring
+            .tail
+            .0
+            .store(tail.wrapping_add(1), Ordering::Release);
+        Ok(())
+    }
+}
+
+/// Popping half of [`SpscQueue`].
+pub struct Consumer<T, const N: usize> {
+    ring: Arc<Ring<T, N>>,
+    /// Last seen tail, so that the producer's cache line is only touched when the queue looks
+    /// empty.
+    cached_tail: usize,
+    _not_sync: PhantomData<Cell<()>>,
+}
+
+impl<T, const N: usize> Consumer<T, N> {
+    pub fn try_pop(&mut self) -> Option<T> {
+        let head = self.ring.head.0.load(Ordering::Relaxed);
+        if head == self.cached_tail {
+            self.cached_tail = self.ring.tail.0.load(Ordering::Acquire);
+            if head == self.cached_tail {
+                return None;
+            }
+        }
+
+        // SAFETY: the slot is before the tail, so the producer has initialized it.
+        let value = unsafe { ptr::read(self.ring.slot(head)) };
+        self.ring
+            .head
+            .0
+            .store(head.wrapping_add(1), Ordering::Release);
+        Some(value)
+    }
+}
@@ -0,0 +1,73 @@
+//! Message throughput over the last second.
+
+use std::time::{Duration, Instant};
+
+use crate::logging::log_info;
+
+const SLOTS: usize = 10;
+const SLOT: Duration = Duration::from_millis(100);
+const REPORT_EVERY: Duration = Duration::from_secs(1);
+
+/// Counts messages in a sliding window of the last second, split into slots of 100ms so that
+/// old messages fall out without remembering each of them.
+#[derive(Clone, Debug)]
+pub struct RateCounter {
+    slots: [u64; SLOTS],
+    current: usize,
+    current_start: Instant,
+    last_report: Instant,
+}
+
+impl RateCounter {
+    pub fn new() -> Self {
+        Self::starting_at(Instant::now())
+    }
+
+    /// Same as [`RateCounter::new`] with the window starting at `now`.
+    pub fn starting_at(now: Instant) -> Self {
+        Self {
+            slots: [0; SLOTS],
+            current: 0,
+            current_start: now,
+            last_report: now,
+        }
+    }
+
+    /// Counts a message, printing the rate if a second has passed since the last time.
+    pub fn tick(&mut self) {
+        self.tick_at(Instant::now());
+    }
+
+    /// Same as [`RateCounter::tick`] for a message received at `now`, e.g. its receive timestamp
+    /// when that's at hand already.
+    pub fn tick_at(&mut self, now: Instant) {
+        self.advance(now);
+        self.slots[self.current] += 1;
+
+        if now - self.last_report >= REPORT_EVERY {
+            self.last_report = now;
+            log_info!("messages per second: {}", self.rate());
+        }
+    }
+
+    /// Messages of the last second, give or take the current slot.
+    pub fn rate(&self) -> u64 {
+        self.slots.iter().sum()
+    }
+
+    /// Moves on to the slot `now` falls into, clearing the ones skipped on the way.
+    fn advance(&mut self, now: Instant) {
+        let passed = ((now - self.current_start).as_nanos() / SLOT.as_nanos()) as usize;
+        for _ in 0..passed.min(SLOTS) {
+            self.current = (self.current + 1) % SLOTS;
+            self.slots[self.current] = 0;
+        }
+        self.current_start += SLOT * passed as u32;
+    }
+}
+
+impl Default for RateCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
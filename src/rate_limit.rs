@@ -0,0 +1,79 @@
+//! Keeping outgoing messages under Binance's limit, as going over it gets the connection closed
+//! and repeated offenders banned.
+
+use std::{
+    sync::atomic::{AtomicU32, Ordering},
+    time::Duration,
+};
+
+use tokio::{
+    sync::Mutex,
+    time::{self, Instant, Interval, MissedTickBehavior},
+};
+
+/// How many messages a WebSocket connection may send per second, see
+/// <https://developers.binance.com/docs/derivatives/usds-margined-futures/websocket-market-streams>.
+pub const WS_MESSAGES_PER_SEC: u32 = 10;
+
+/// Token bucket refilled once a second, so that no more than `capacity` messages go out in a
+/// burst and no more than `refill_per_sec` in any second after.
+#[derive(Debug)]
+pub struct RateLimiter {
+    capacity: u32,
+    refill_per_sec: u32,
+    tokens: AtomicU32,
+    /// Only ticked by whoever is waiting for a token, so nothing runs while nobody is.
+    refill: Mutex<Interval>,
+}
+
+impl RateLimiter {
+    /// Starts full, with the first refill a second from now. Needs to be called on a Tokio
+    /// runtime.
+    pub fn new(capacity: u32, refill_per_sec: u32) -> Self {
+        let period = Duration::from_secs(1);
+        let mut refill = time::interval_at(Instant::now() + period, period);
+        // Refills missed while idle don't add up past the capacity anyway.
+        refill.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Self {
+            capacity,
+            refill_per_sec,
+            tokens: AtomicU32::new(capacity),
+            refill: Mutex::new(refill),
+        }
+    }
+
+    /// Takes a token, waiting for the next refill while there are none.
+    pub async fn acquire(&self) {
+        while !self.try_acquire() {
+            let mut refill = self.refill.lock().await;
+            // Someone else may have refilled while this was waiting for the lock.
+            if self.try_acquire() {
+                return;
+            }
+            refill.tick().await;
+            let capacity = self.capacity;
+            let added = self.refill_per_sec;
+            _ = self
+                .tokens
+                .fetch_update(Ordering::AcqRel, Ordering::Acquire, |tokens| {
+                    Some(tokens.saturating_add(added).min(capacity))
+                });
+        }
+    }
+
+    /// Takes a token if there is one.
+    pub fn try_acquire(&self) -> bool {
+        self.tokens
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |tokens| {
+                tokens.checked_sub(1)
+            })
+            .is_ok()
+    }
+}
+
+impl Default for RateLimiter {
+    /// Binance's limit, [`WS_MESSAGES_PER_SEC`].
+    fn default() -> Self {
+        Self::new(WS_MESSAGES_PER_SEC, WS_MESSAGES_PER_SEC)
+    }
+}
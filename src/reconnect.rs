@@ -0,0 +1,326 @@
+//! WebSocket stream that reconnects when the connection drops.
+
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    task::{Context, Poll, ready},
+    time::Duration,
+};
+
+use futures_util::{SinkExt, Stream, StreamExt};
+use tokio::{
+    net::TcpStream,
+    time::{Instant, Interval, MissedTickBehavior, Sleep},
+};
+use tokio_native_tls::{TlsConnector, native_tls};
+use tokio_tungstenite::{
+    MaybeTlsStream, WebSocketStream, client_async,
+    tungstenite::{
+        Error, Message, client::IntoClientRequest, error::TlsError, handshake::client::Request,
+    },
+};
+
+use crate::{
+    deflate::{DeflateStream, offer_deflate},
+    logging::{log_info, log_warn},
+    pinning::open_pinned,
+};
+
+/// How long [`ReconnectingStream::close`] waits for the server to answer.
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(1);
+
+type WsStream = WebSocketStream<DeflateStream<MaybeTlsStream<TcpStream>>>;
+type ConnectFuture = Pin<Box<dyn Future<Output = Result<WsStream, Error>> + Send>>;
+
+/// How [`ReconnectingStream`] retries.
+#[derive(Clone, Copy, Debug)]
+pub struct ReconnectConfig {
+    /// Delay before the first retry, doubled after each failed one.
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Each delay is randomly stretched or shrunk by up to this fraction of itself, so that
+    /// many clients dropped at once don't all come back at the same time. Clamped to 0..=1.
+    pub jitter: f64,
+    /// How many times in a row to retry before giving up, or `None` to retry forever.
+    pub max_retries: Option<u32>,
+    /// How often to ping the server while connected, or `None` to never do so. Otherwise a
+    /// silently dead connection is only noticed once the OS gives up on it.
+    pub ping_interval: Option<Duration>,
+    /// How long to wait for the pong before treating the connection as dropped.
+    pub pong_timeout: Duration,
+    /// Only connects to a server with this certificate if set, see
+    /// [`connect_pinned`](crate::pinning::connect_pinned).
+    pub cert_sha256: Option<[u8; 32]>,
+    /// Asks the server to compress its messages with `permessage-deflate`, see
+    /// [`DeflateStream`]. Fewer bytes come over the wire, for the time taken to inflate them.
+    pub deflate: bool,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(30),
+            jitter: 0.1,
+            max_retries: None,
+            ping_interval: Some(Duration::from_secs(20)),
+            pong_timeout: Duration::from_secs(10),
+            cert_sha256: None,
+            deflate: false,
+        }
+    }
+}
+
+enum State {
+    Connecting(ConnectFuture),
+    Waiting(Pin<Box<Sleep>>),
+    Connected(Box<Connection>),
+    Done,
+}
+
+struct Connection {
+    ws_stream: WsStream,
+    ping: Option<Interval>,
+    /// A ping is due but couldn't be sent yet.
+    ping_pending: bool,
+    pong_deadline: Option<Pin<Box<Sleep>>>,
+}
+
+impl Connection {
+    fn new(ws_stream: WsStream, config: &ReconnectConfig) -> Self {
+        let ping = config.ping_interval.map(|period| {
+            let mut ping = tokio::time::interval_at(Instant::now() + period, period);
+            ping.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            ping
+        });
+        Self {
+            ws_stream,
+            ping,
+            ping_pending: false,
+            pong_deadline: None,
+        }
+    }
+
+    /// Sends pings when due, returning an error once a pong is late.
+    fn poll_keepalive(&mut self, cx: &mut Context<'_>, pong_timeout: Duration) -> Option<Error> {
+        if let Some(ping) = &mut self.ping
+            && ping.poll_tick(cx).is_ready()
+        {
+            self.ping_pending = true;
+        }
+        if self.ping_pending {
+            match self.ws_stream.poll_ready_unpin(cx) {
+                Poll::Ready(Ok(())) => {
+                    let ping = Message::Ping(Default::default());
+                    if let Err(err) = self.ws_stream.start_send_unpin(ping) {
+                        return Some(err);
+                    }
+                    self.ping_pending = false;
+                    if self.pong_deadline.is_none() {
+                        self.pong_deadline = Some(Box::pin(tokio::time::sleep(pong_timeout)));
+                    }
+                }
+                Poll::Ready(Err(err)) => return Some(err),
+                Poll::Pending => {}
+            }
+        }
+        // Anything still buffered is also written out by the next read.
+        if let Poll::Ready(Err(err)) = self.ws_stream.poll_flush_unpin(cx) {
+            return Some(err);
+        }
+
+        // Polled last, so that a deadline set just now is polled too and wakes us up.
+        if let Some(deadline) = &mut self.pong_deadline
+            && deadline.as_mut().poll(cx).is_ready()
+        {
+            return Some(Error::Io(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "no pong received in time",
+            )));
+        }
+        None
+    }
+}
+
+/// Yields the messages of the WebSocket at `url`, transparently reconnecting with exponential
+/// backoff whenever connecting fails or the connection drops.
+///
+/// A connection also counts as dropped when the server doesn't answer a ping in time.
+///
+/// Errors are only yielded once `max_retries` is exceeded, after which the stream ends.
+pub struct ReconnectingStream {
+    url: String,
+    config: ReconnectConfig,
+    state: State,
+    backoff: Duration,
+    retries: u32,
+    connected: Arc<AtomicBool>,
+}
+
+impl ReconnectingStream {
+    pub fn new(url: impl Into<String>, config: ReconnectConfig) -> Self {
+        let url = url.into();
+        Self {
+            state: State::Connecting(Box::pin(connect(url.clone(), config))),
+            url,
+            config,
+            backoff: config.initial_backoff,
+            retries: 0,
+            connected: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Set while connected, cleared as soon as the connection drops or the stream is closed, to
+    /// be shared with whoever reports on it, e.g. [`Metrics::tracking`].
+    ///
+    /// [`Metrics::tracking`]: crate::metrics::Metrics::tracking
+    pub fn connected(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.connected)
+    }
+
+    /// Says goodbye to the server with a close frame, waiting a bit for it to answer, after which
+    /// the stream ends.
+    pub async fn close(&mut self) -> Result<(), Error> {
+        let State::Connected(mut conn) = std::mem::replace(&mut self.state, State::Done) else {
+            return Ok(());
+        };
+        self.connected.store(false, Ordering::Relaxed);
+        conn.ws_stream.close(None).await?;
+        // Whatever was already on the way arrives before the server's close frame.
+        let drain = async { while conn.ws_stream.next().await.is_some_and(|msg| msg.is_ok()) {} };
+        _ = tokio::time::timeout(CLOSE_TIMEOUT, drain).await;
+        Ok(())
+    }
+
+    /// Schedules the next connection attempt, or returns the error back if out of retries.
+    fn retry(&mut self, err: Error) -> Option<Error> {
+        self.connected.store(false, Ordering::Relaxed);
+        if self
+            .config
+            .max_retries
+            .is_some_and(|max| self.retries >= max)
+        {
+            self.state = State::Done;
+            return Some(err);
+        }
+
+        // Past 1 the delay could go negative.
+        let fraction = match self.config.jitter {
+            jitter if jitter.is_nan() => 0.0,
+            jitter => jitter.clamp(0.0, 1.0),
+        };
+        let jitter = 1.0 + fraction * rand::random_range(-1.0..=1.0);
+        let delay = self.backoff.mul_f64(jitter);
+        log_warn!(
+            "connection to {} failed: {err}, retrying in {delay:?}",
+            self.url
+        );
+
+        self.retries += 1;
+        self.backoff = (self.backoff * 2).min(self.config.max_backoff);
+        self.state = State::Waiting(Box::pin(tokio::time::sleep(delay)));
+        None
+    }
+}
+
+async fn connect(url: String, config: ReconnectConfig) -> Result<WsStream, Error> {
+    let mut request = url.as_str().into_client_request()?;
+    if config.deflate {
+        offer_deflate(&mut request);
+    }
+    let stream = match config.cert_sha256 {
+        Some(cert_sha256) => open_pinned(&request, &cert_sha256).await?,
+        None => {
+            log_info!("connecting to {url}");
+            open(&request).await?
+        }
+    };
+    let (ws_stream, _) = client_async(request, DeflateStream::new(stream)).await?;
+    Ok(ws_stream)
+}
+
+/// The connection `connect_async` would make the handshake of `request` over, opened here so
+/// that it can go through [`DeflateStream`] first.
+async fn open(request: &Request) -> Result<MaybeTlsStream<TcpStream>, Error> {
+    let uri = request.uri();
+    let host = uri.host().unwrap_or_default();
+    // Bracketed in URLs, but not when connecting.
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let tls = uri.scheme_str() == Some("wss");
+    let port = uri.port_u16().unwrap_or(if tls { 443 } else { 80 });
+    let stream = TcpStream::connect((host, port)).await?;
+    if !tls {
+        return Ok(MaybeTlsStream::Plain(stream));
+    }
+
+    let connector =
+        native_tls::TlsConnector::new().map_err(|err| Error::Tls(TlsError::Native(err)))?;
+    let stream = TlsConnector::from(connector)
+        .connect(host, stream)
+        .await
+        .map_err(|err| Error::Tls(TlsError::Native(err)))?;
+    Ok(MaybeTlsStream::NativeTls(stream))
+}
+
+impl Stream for ReconnectingStream {
+    type Item = Result<Message, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            match &mut this.state {
+                State::Connecting(connecting) => match ready!(connecting.as_mut().poll(cx)) {
+                    Ok(ws_stream) => {
+                        this.connected.store(true, Ordering::Relaxed);
+                        this.state =
+                            State::Connected(Box::new(Connection::new(ws_stream, &this.config)))
+                    }
+                    Err(err) => {
+                        if let Some(err) = this.retry(err) {
+                            return Poll::Ready(Some(Err(err)));
+                        }
+                    }
+                },
+                State::Waiting(sleep) => {
+                    ready!(sleep.as_mut().poll(cx));
+                    this.state =
+                        State::Connecting(Box::pin(connect(this.url.clone(), this.config)));
+                }
+                State::Connected(conn) => {
+                    if let Some(err) = conn.poll_keepalive(cx, this.config.pong_timeout) {
+                        if let Some(err) = this.retry(err) {
+                            return Poll::Ready(Some(Err(err)));
+                        }
+                        continue;
+                    }
+                    match ready!(conn.ws_stream.poll_next_unpin(cx)) {
+                        Some(Ok(msg)) => {
+                            if msg.is_pong() {
+                                conn.pong_deadline = None;
+                            }
+                            this.backoff = this.config.initial_backoff;
+                            this.retries = 0;
+                            return Poll::Ready(Some(Ok(msg)));
+                        }
+                        Some(Err(err)) => {
+                            if let Some(err) = this.retry(err) {
+                                return Poll::Ready(Some(Err(err)));
+                            }
+                        }
+                        None => {
+                            if let Some(err) = this.retry(Error::ConnectionClosed) {
+                                return Poll::Ready(Some(Err(err)));
+                            }
+                        }
+                    }
+                }
+                State::Done => return Poll::Ready(None),
+            }
+        }
+    }
+}
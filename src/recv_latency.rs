@@ -0,0 +1,82 @@
+//! How long book tickers take to get here from the matching engine, mostly the network.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::BookTicker;
+
+/// How many of the latest latencies [`RecvLatencyTracker`] keeps.
+pub const WINDOW: usize = 1000;
+
+/// Milliseconds from matching `tick` to receiving it at `received_at`, which is best taken
+/// right as the WebSocket message comes in. Signed, since the local clock may be behind the
+/// exchange's.
+///
+/// Spot tickers have no transaction time, which makes this the time since the epoch for them.
+pub fn receive_latency_ms<const PP: usize, const VP: usize>(
+    tick: &BookTicker<'_, PP, VP>,
+    received_at: SystemTime,
+) -> i64 {
+    let received_at_ms = match received_at.duration_since(UNIX_EPOCH) {
+        Ok(since) => since.as_millis() as i64,
+        Err(err) => -(err.duration().as_millis() as i64),
+    };
+    received_at_ms - tick.T as i64
+}
+
+/// Percentiles of the latencies in the window, in milliseconds.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[non_exhaustive]
+pub struct LatencyStats {
+    pub p50: i64,
+    pub p90: i64,
+    pub p99: i64,
+    pub max: i64,
+}
+
+/// Keeps the last [`WINDOW`] latencies, overwriting the oldest one with each new one.
+#[derive(Clone, Debug)]
+pub struct RecvLatencyTracker {
+    samples: Box<[i64; WINDOW]>,
+    next: usize,
+    len: usize,
+}
+
+impl RecvLatencyTracker {
+    pub fn new() -> Self {
+        Self {
+            samples: Box::new([0; WINDOW]),
+            next: 0,
+            len: 0,
+        }
+    }
+
+    pub fn record(&mut self, latency_ms: i64) {
+        self.samples[self.next] = latency_ms;
+        self.next = (self.next + 1) % WINDOW;
+        self.len = (self.len + 1).min(WINDOW);
+    }
+
+    /// Sorts a copy of the window, so meant to be called every so often rather than per tick.
+    /// All zeros if nothing was recorded yet.
+    pub fn stats(&self) -> LatencyStats {
+        if self.len == 0 {
+            return LatencyStats::default();
+        }
+        let mut sorted = self.samples[..self.len].to_vec();
+        sorted.sort_unstable();
+        // Nearest rank, counting from 1.
+        let percentile = |p: usize| sorted[(p * self.len).div_ceil(100).max(1) - 1];
+        LatencyStats {
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+            max: sorted[self.len - 1],
+        }
+    }
+}
+
+impl Default for RecvLatencyTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
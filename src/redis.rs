@@ -0,0 +1,222 @@
+//! Appending ticks to Redis streams, as a bus for other processes to read them from.
+//!
+//! Each tick becomes an entry of `XADD <key> [MAXLEN ~ <n>] * u .. T .. b .. B .. a .. A .. E ..`,
+//! with the key made from the symbol. The client is a minimal one speaking RESP over a single
+//! connection, with no TLS nor cluster support.
+
+use std::io;
+
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+
+use crate::BookTicker;
+
+/// Where [`RedisSink`] appends to.
+#[derive(Clone, Debug)]
+pub struct RedisConfig {
+    /// `redis://[:password@]host[:port][/db]`.
+    pub url: String,
+    /// Key of the stream of each symbol, with `{symbol}` replaced by it.
+    pub key: String,
+    /// Trims the streams to about this many entries, through `MAXLEN ~`.
+    pub max_len: Option<u64>,
+}
+
+impl RedisConfig {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            key: "{symbol}:bookTicker".to_owned(),
+            max_len: None,
+        }
+    }
+}
+
+/// A reply to a command, other than an error.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum Reply {
+    Simple(String),
+    Integer(i64),
+    /// `None` for the null bulk string.
+    Bulk(Option<Vec<u8>>),
+    /// `None` for the null array.
+    Array(Option<Vec<Reply>>),
+}
+
+/// Appends every tick to the stream of its symbol, waiting for Redis to acknowledge each one.
+pub struct RedisSink {
+    stream: BufReader<TcpStream>,
+    config: RedisConfig,
+    buf: Vec<u8>,
+}
+
+impl RedisSink {
+    /// Connects to `config.url`, authenticating and selecting the database given there.
+    pub async fn connect(config: RedisConfig) -> io::Result<Self> {
+        let url_string = config.url.clone();
+        let url = Url::parse(&url_string)?;
+        let stream = TcpStream::connect((url.host, url.port)).await?;
+        stream.set_nodelay(true)?;
+        let mut sink = Self {
+            stream: BufReader::new(stream),
+            config,
+            buf: Vec::with_capacity(256),
+        };
+        if let Some(password) = url.password {
+            sink.command(&[b"AUTH", password.as_bytes()]).await?;
+        }
+        if url.db != 0 {
+            sink.command(&[b"SELECT", url.db.to_string().as_bytes()])
+                .await?;
+        }
+        Ok(sink)
+    }
+
+    /// Key of the stream of `symbol`.
+    pub fn key(&self, symbol: &str) -> String {
+        self.config.key.replace("{symbol}", symbol)
+    }
+
+    /// Appends `tick` to the stream of its symbol, returning the id Redis gave the entry.
+    pub async fn add(&mut self, tick: &BookTicker<'_>) -> io::Result<String> {
+        let key = self.key(tick.s);
+        let (u, T, E) = (tick.u.to_string(), tick.T.to_string(), tick.E.to_string());
+        let max_len = self.config.max_len.map(|max_len| max_len.to_string());
+
+        let mut args: Vec<&[u8]> = vec![b"XADD", key.as_bytes()];
+        if let Some(max_len) = &max_len {
+            args.extend([&b"MAXLEN"[..], b"~", max_len.as_bytes()]);
+        }
+        args.extend([
+            &b"*"[..],
+            b"u",
+            u.as_bytes(),
+            b"T",
+            T.as_bytes(),
+            b"b",
+            tick.b.as_bytes(),
+            b"B",
+            tick.B.as_bytes(),
+            b"a",
+            tick.a.as_bytes(),
+            b"A",
+            tick.A.as_bytes(),
+            b"E",
+            E.as_bytes(),
+        ]);
+        match self.command(&args).await? {
+            Reply::Bulk(Some(id)) => {
+                String::from_utf8(id).map_err(|err| invalid_data(err.to_string()))
+            }
+            reply => Err(invalid_data(format!("unexpected reply to XADD: {reply:?}"))),
+        }
+    }
+
+    /// Sends any command and waits for its reply, with error replies as errors.
+    pub async fn command(&mut self, args: &[&[u8]]) -> io::Result<Reply> {
+        self.buf.clear();
+        self.buf
+            .extend_from_slice(format!("*{}\r\n", args.len()).as_bytes());
+        for arg in args {
+            self.buf
+                .extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+            self.buf.extend_from_slice(arg);
+            self.buf.extend_from_slice(b"\r\n");
+        }
+        self.stream.get_mut().write_all(&self.buf).await?;
+        read_reply(&mut self.stream).await
+    }
+}
+
+async fn read_reply(stream: &mut BufReader<TcpStream>) -> io::Result<Reply> {
+    let mut line = String::new();
+    if stream.read_line(&mut line).await? == 0 {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    let line = line
+        .strip_suffix("\r\n")
+        .ok_or_else(|| invalid_data("reply line without CRLF"))?;
+    let (kind, rest) = line
+        .split_at_checked(1)
+        .ok_or_else(|| invalid_data("empty reply"))?;
+    let len = || -> io::Result<i64> {
+        rest.parse()
+            .map_err(|_| invalid_data(format!("invalid length {rest}")))
+    };
+
+    match kind {
+        "+" => Ok(Reply::Simple(rest.to_owned())),
+        "-" => Err(io::Error::other(rest.to_owned())),
+        ":" => len().map(Reply::Integer),
+        "$" => {
+            let Ok(len) = usize::try_from(len()?) else {
+                return Ok(Reply::Bulk(None));
+            };
+            let mut bulk = vec![0; len + 2];
+            stream.read_exact(&mut bulk).await?;
+            bulk.truncate(len);
+            Ok(Reply::Bulk(Some(bulk)))
+        }
+        "*" => {
+            let Ok(len) = usize::try_from(len()?) else {
+                return Ok(Reply::Array(None));
+            };
+            let mut items = Vec::with_capacity(len);
+            for _ in 0..len {
+                items.push(Box::pin(read_reply(stream)).await?);
+            }
+            Ok(Reply::Array(Some(items)))
+        }
+        kind => Err(invalid_data(format!("unsupported reply type {kind}"))),
+    }
+}
+
+/// The parts of a `redis://` URL.
+struct Url<'a> {
+    host: &'a str,
+    port: u16,
+    password: Option<&'a str>,
+    db: u32,
+}
+
+impl<'a> Url<'a> {
+    fn parse(url: &'a str) -> io::Result<Self> {
+        let invalid = || {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid Redis URL {url}"),
+            )
+        };
+        let rest = url.strip_prefix("redis://").ok_or_else(invalid)?;
+        let (authority, db) = match rest.split_once('/') {
+            Some((authority, "")) => (authority, 0),
+            Some((authority, db)) => (authority, db.parse().map_err(|_| invalid())?),
+            None => (rest, 0),
+        };
+        let (password, address) = match authority.rsplit_once('@') {
+            // The user is ignored, only `AUTH <password>` is sent.
+            Some((user_info, address)) => (
+                user_info.split_once(':').map(|(_, password)| password),
+                address,
+            ),
+            None => (None, authority),
+        };
+        let (host, port) = match address.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().map_err(|_| invalid())?),
+            None => (address, 6379),
+        };
+        Ok(Self {
+            host,
+            port,
+            password,
+            db,
+        })
+    }
+}
+
+fn invalid_data(msg: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
@@ -0,0 +1,288 @@
+//! Minimal client for the few REST endpoints needed next to the streams, like the depth
+//! snapshot to start an [`OrderBook`](crate::order_book::OrderBook) from.
+//!
+//! Requests are one-off, each on a fresh connection, which is plenty for something done once per
+//! (re)sync or every half an hour.
+
+use std::{io, time::Duration};
+
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::TcpStream,
+};
+use tokio_native_tls::{TlsConnector, native_tls};
+
+use crate::{depth::DepthSnapshot, endpoint::StreamEndpoint};
+
+/// Base URL of the futures REST API.
+pub const BINANCE_FUTURES_URL: &str = "https://fapi.binance.com";
+
+/// How long a request may take, from connecting to the end of the response, unless set with
+/// [`RestClient::with_timeout`].
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Longest response taken, headers included, well over the 1000 levels of a full depth snapshot.
+pub const MAX_RESPONSE_LEN: usize = 16 << 20;
+
+/// Where to send requests to, `http://` or `https://` followed by a host and optionally a port.
+pub struct RestClient {
+    tls: bool,
+    host: String,
+    port: u16,
+    connector: TlsConnector,
+    timeout: Duration,
+}
+
+impl RestClient {
+    pub fn new(base_url: &str) -> io::Result<Self> {
+        let (tls, authority) = if let Some(authority) = base_url.strip_prefix("https://") {
+            (true, authority)
+        } else if let Some(authority) = base_url.strip_prefix("http://") {
+            (false, authority)
+        } else {
+            return Err(invalid_data(format!("unsupported URL {base_url}")));
+        };
+        let authority = authority.trim_end_matches('/');
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (
+                host,
+                port.parse()
+                    .map_err(|_| invalid_data(format!("invalid port in {base_url}")))?,
+            ),
+            None => (authority, if tls { 443 } else { 80 }),
+        };
+        let connector = native_tls::TlsConnector::new().map_err(io::Error::other)?;
+
+        Ok(Self {
+            tls,
+            host: host.to_owned(),
+            port,
+            connector: connector.into(),
+            timeout: REQUEST_TIMEOUT,
+        })
+    }
+
+    /// Gives up on requests after `timeout` instead of [`REQUEST_TIMEOUT`].
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Client of [`BINANCE_FUTURES_URL`].
+    pub fn binance() -> io::Result<Self> {
+        Self::new(BINANCE_FUTURES_URL)
+    }
+
+    /// Client of the REST API next to `endpoint`, e.g. [`StreamEndpoint::Testnet`] to not touch
+    /// real funds. The requests here are those of USD-M futures, which the other markets don't
+    /// all have.
+    pub fn for_endpoint(endpoint: &StreamEndpoint) -> io::Result<Self> {
+        Self::new(&endpoint.rest_url())
+    }
+
+    /// The body of a successful response to `GET path`, where `path` includes the query.
+    pub async fn get(&self, path: &str) -> io::Result<Vec<u8>> {
+        let head = format!(
+            "GET {path} HTTP/1.1\r\nHost: {}\r\nAccept: application/json\r\nConnection: close\r\n\r\n",
+            self.host
+        );
+        self.send(head.as_bytes(), &[]).await
+    }
+
+    /// The body of a successful response to `POST path` with `body` and `headers` besides the
+    /// length, e.g. the content type.
+    pub async fn post(
+        &self,
+        path: &str,
+        headers: &[(&str, &str)],
+        body: &[u8],
+    ) -> io::Result<Vec<u8>> {
+        self.send_with_body("POST", path, headers, body).await
+    }
+
+    /// Same as [`post`](Self::post) but with `PUT`.
+    pub async fn put(
+        &self,
+        path: &str,
+        headers: &[(&str, &str)],
+        body: &[u8],
+    ) -> io::Result<Vec<u8>> {
+        self.send_with_body("PUT", path, headers, body).await
+    }
+
+    /// Same as [`post`](Self::post) but with `DELETE` and no body.
+    pub async fn delete(&self, path: &str, headers: &[(&str, &str)]) -> io::Result<Vec<u8>> {
+        self.send_with_body("DELETE", path, headers, &[]).await
+    }
+
+    async fn send_with_body(
+        &self,
+        method: &str,
+        path: &str,
+        headers: &[(&str, &str)],
+        body: &[u8],
+    ) -> io::Result<Vec<u8>> {
+        let mut head = format!(
+            "{method} {path} HTTP/1.1\r\nHost: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+            self.host,
+            body.len()
+        );
+        for (name, value) in headers {
+            head.push_str(&format!("{name}: {value}\r\n"));
+        }
+        head.push_str("\r\n");
+        self.send(head.as_bytes(), body).await
+    }
+
+    async fn send(&self, head: &[u8], body: &[u8]) -> io::Result<Vec<u8>> {
+        tokio::time::timeout(self.timeout, self.send_untimed(head, body))
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "request timed out"))?
+    }
+
+    async fn send_untimed(&self, head: &[u8], body: &[u8]) -> io::Result<Vec<u8>> {
+        let stream = TcpStream::connect((self.host.as_str(), self.port)).await?;
+        if self.tls {
+            let stream = self
+                .connector
+                .connect(&self.host, stream)
+                .await
+                .map_err(io::Error::other)?;
+            request(stream, head, body).await
+        } else {
+            request(stream, head, body).await
+        }
+    }
+}
+
+async fn request(
+    mut stream: impl AsyncRead + AsyncWrite + Unpin,
+    head: &[u8],
+    body: &[u8],
+) -> io::Result<Vec<u8>> {
+    stream.write_all(head).await?;
+    stream.write_all(body).await?;
+
+    // The server closes the connection once it's done, so there's no need to rely on the
+    // length upfront.
+    let mut response = Vec::new();
+    stream
+        .take(MAX_RESPONSE_LEN as u64 + 1)
+        .read_to_end(&mut response)
+        .await?;
+    if response.len() > MAX_RESPONSE_LEN {
+        return Err(invalid_data("response too long"));
+    }
+    parse_response(&response)
+}
+
+/// The body of `response`, if it's a success, like 200 or the 204 of writes.
+fn parse_response(response: &[u8]) -> io::Result<Vec<u8>> {
+    let mut headers = [httparse::EMPTY_HEADER; 64];
+    let mut parsed = httparse::Response::new(&mut headers);
+    let httparse::Status::Complete(body_start) = parsed.parse(response).map_err(invalid_data)?
+    else {
+        return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+    };
+    let body = &response[body_start..];
+
+    let code = parsed.code.unwrap_or_default();
+    if !(200..300).contains(&code) {
+        // Errors come with a JSON body like {"code":-1121,"msg":"Invalid symbol."}.
+        return Err(io::Error::other(format!(
+            "request failed with {code}: {}",
+            String::from_utf8_lossy(body)
+        )));
+    }
+
+    let chunked = parsed.headers.iter().any(|header| {
+        header.name.eq_ignore_ascii_case("transfer-encoding")
+            && header.value.eq_ignore_ascii_case(b"chunked")
+    });
+    if chunked {
+        decode_chunked(body)
+    } else {
+        Ok(body.to_vec())
+    }
+}
+
+fn decode_chunked(mut body: &[u8]) -> io::Result<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(body.len());
+    loop {
+        let httparse::Status::Complete((size_end, size)) =
+            httparse::parse_chunk_size(body).map_err(|_| invalid_data("invalid chunk size"))?
+        else {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+        };
+        if size == 0 {
+            return Ok(decoded);
+        }
+        // Each chunk is followed by \r\n.
+        let chunk_end = usize::try_from(size)
+            .ok()
+            .and_then(|size| size_end.checked_add(size))
+            .ok_or_else(|| invalid_data("chunk size too large"))?;
+        let next = chunk_end
+            .checked_add(2)
+            .ok_or_else(|| invalid_data("chunk size too large"))?;
+        let chunk = body
+            .get(size_end..chunk_end)
+            .ok_or(io::ErrorKind::UnexpectedEof)?;
+        decoded.extend_from_slice(chunk);
+        body = body.get(next..).ok_or(io::ErrorKind::UnexpectedEof)?;
+    }
+}
+
+fn invalid_data(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+/// Fetches up to `limit` levels of each side of the book of `symbol`, e.g. `BTCUSDT` with one
+/// of the limits Binance accepts: 5, 10, 20, 50, 100, 500 or 1000.
+pub async fn fetch_depth_snapshot(
+    symbol: &str,
+    limit: u16,
+    client: &RestClient,
+) -> io::Result<DepthSnapshot> {
+    let body = client
+        .get(&format!("/fapi/v1/depth?symbol={symbol}&limit={limit}"))
+        .await?;
+    Ok(serde_json::from_slice(&body)?)
+}
+
+/// Path of the listen key of the user data stream, see [`create_listen_key`].
+pub const LISTEN_KEY_PATH: &str = "/fapi/v1/listenKey";
+
+#[derive(serde::Deserialize)]
+struct ListenKey {
+    #[serde(rename = "listenKey")]
+    listen_key: String,
+}
+
+/// Starts a user data stream, or returns the listen key of the one already started for the same
+/// `api_key`. Its events then come on the stream named after the key, see
+/// [`StreamEndpoint::stream_url`](crate::endpoint::StreamEndpoint::stream_url).
+pub async fn create_listen_key(client: &RestClient, api_key: &str) -> io::Result<String> {
+    let body = client
+        .post(LISTEN_KEY_PATH, &[("X-MBX-APIKEY", api_key)], &[])
+        .await?;
+    let key: ListenKey = serde_json::from_slice(&body)?;
+    Ok(key.listen_key)
+}
+
+/// Extends the validity of the listen key of `api_key` by 60 minutes, after which the stream
+/// would be closed.
+pub async fn keep_alive_listen_key(client: &RestClient, api_key: &str) -> io::Result<()> {
+    client
+        .put(LISTEN_KEY_PATH, &[("X-MBX-APIKEY", api_key)], &[])
+        .await
+        .map(drop)
+}
+
+/// Closes the user data stream of `api_key`.
+pub async fn close_listen_key(client: &RestClient, api_key: &str) -> io::Result<()> {
+    client
+        .delete(LISTEN_KEY_PATH, &[("X-MBX-APIKEY", api_key)])
+        .await
+        .map(drop)
+}
@@ -0,0 +1,61 @@
+//! Detection of missed messages from their update ids.
+
+/// Updates from `expected` up to but not including `got` were missed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub struct GapEvent {
+    pub expected: u64,
+    pub got: u64,
+}
+
+impl core::fmt::Display for GapEvent {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "expected update {} but got {}", self.expected, self.got)
+    }
+}
+
+impl core::error::Error for GapEvent {}
+
+/// Tracks the last update id of a stream, expecting each next one to follow it directly.
+///
+/// On a gap the local state built from the stream can't be trusted anymore, so e.g. an
+/// [`OrderBook`](crate::order_book::OrderBook) should be synced again from a REST snapshot.
+#[derive(Default, Debug)]
+pub struct SequenceChecker {
+    last: Option<u64>,
+}
+
+impl SequenceChecker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Checks `update_id` against the previous one, accepting any id the first time. Ids that
+    /// don't move forward, e.g. of a re-sent message, are ignored.
+    pub fn check(&mut self, update_id: u64) -> Option<GapEvent> {
+        let Some(last) = self.last else {
+            self.last = Some(update_id);
+            return None;
+        };
+        if update_id <= last {
+            return None;
+        }
+        self.last = Some(update_id);
+        (update_id != last + 1).then_some(GapEvent {
+            expected: last + 1,
+            got: update_id,
+        })
+    }
+
+    /// Same as [`check`](Self::check), calling `on_gap` with the gap if there's one.
+    pub fn check_with(&mut self, update_id: u64, on_gap: impl FnOnce(GapEvent)) {
+        if let Some(gap) = self.check(update_id) {
+            on_gap(gap);
+        }
+    }
+
+    /// Forgets the last id, e.g. after a reconnect, so that the next one is accepted as is.
+    pub fn reset(&mut self) {
+        self.last = None;
+    }
+}
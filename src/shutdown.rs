@@ -0,0 +1,74 @@
+//! Telling tasks to wrap up, e.g. on Ctrl-C.
+
+use std::sync::Arc;
+
+use tokio::sync::watch;
+
+/// Cheap to clone handle, where cancelling any clone cancels all of them.
+#[derive(Clone, Debug)]
+pub struct ShutdownToken {
+    sender: Arc<watch::Sender<bool>>,
+    receiver: watch::Receiver<bool>,
+}
+
+impl ShutdownToken {
+    pub fn new() -> Self {
+        let (sender, receiver) = watch::channel(false);
+        Self {
+            sender: Arc::new(sender),
+            receiver,
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.sender.send_replace(true);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        *self.receiver.borrow()
+    }
+
+    /// Completes once cancelled, right away if that already happened.
+    pub async fn cancelled(&self) {
+        let mut receiver = self.receiver.clone();
+        // Can't fail, as we hold a sender ourselves.
+        _ = receiver.wait_for(|&cancelled| cancelled).await;
+    }
+
+    /// Cancels on the first SIGINT, or SIGTERM on Unix, from a background task.
+    pub fn cancel_on_signal(&self) {
+        let token = self.clone();
+        tokio::spawn(async move {
+            signal().await;
+            token.cancel();
+        });
+    }
+}
+
+impl Default for ShutdownToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(unix)]
+async fn signal() {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut terminate = match signal(SignalKind::terminate()) {
+        Ok(terminate) => terminate,
+        Err(_) => {
+            _ = tokio::signal::ctrl_c().await;
+            return;
+        }
+    };
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate.recv() => {}
+    }
+}
+
+#[cfg(not(unix))]
+async fn signal() {
+    _ = tokio::signal::ctrl_c().await;
+}
@@ -0,0 +1,85 @@
+//! Byte search used for finding field delimiters.
+//!
+//! The vectorized versions are behind the `sse2` and `avx2` features and are only used when the
+//! target supports them, e.g. with `-C target-cpu=native`. Otherwise this falls back to a scalar
+//! loop.
+
+/// Position of the first `needle` in `haystack`.
+#[cfg(all(feature = "avx2", target_arch = "x86_64", target_feature = "avx2"))]
+pub fn simd_find_byte(haystack: &[u8], needle: u8) -> Option<usize> {
+    // SAFETY: the target feature is enabled for the whole build.
+    unsafe { find_avx2(haystack, needle) }
+}
+
+/// Position of the first `needle` in `haystack`.
+#[cfg(all(
+    feature = "sse2",
+    target_arch = "x86_64",
+    target_feature = "sse2",
+    not(all(feature = "avx2", target_feature = "avx2"))
+))]
+pub fn simd_find_byte(haystack: &[u8], needle: u8) -> Option<usize> {
+    // SAFETY: the target feature is enabled for the whole build.
+    unsafe { find_sse2(haystack, needle) }
+}
+
+/// Position of the first `needle` in `haystack`.
+#[cfg(not(all(
+    target_arch = "x86_64",
+    any(
+        all(feature = "avx2", target_feature = "avx2"),
+        all(feature = "sse2", target_feature = "sse2")
+    )
+)))]
+pub fn simd_find_byte(haystack: &[u8], needle: u8) -> Option<usize> {
+    find_scalar(haystack, needle)
+}
+
+fn find_scalar(haystack: &[u8], needle: u8) -> Option<usize> {
+    haystack.iter().position(|&byte| byte == needle)
+}
+
+#[cfg(all(feature = "avx2", target_arch = "x86_64", target_feature = "avx2"))]
+#[target_feature(enable = "avx2")]
+fn find_avx2(haystack: &[u8], needle: u8) -> Option<usize> {
+    use core::arch::x86_64::{
+        __m256i, _mm256_cmpeq_epi8, _mm256_loadu_si256, _mm256_movemask_epi8, _mm256_set1_epi8,
+    };
+
+    let needles = _mm256_set1_epi8(needle as i8);
+    let mut chunks = haystack.chunks_exact(32);
+    for (i, chunk) in chunks.by_ref().enumerate() {
+        // SAFETY: the chunk is exactly 32 bytes long and the load is unaligned.
+        let bytes = unsafe { _mm256_loadu_si256(chunk.as_ptr() as *const __m256i) };
+        let mask = _mm256_movemask_epi8(_mm256_cmpeq_epi8(bytes, needles)) as u32;
+        if mask != 0 {
+            return Some(i * 32 + mask.trailing_zeros() as usize);
+        }
+    }
+
+    // Less than 32 bytes are left, so let the narrower version handle them.
+    let offset = haystack.len() - chunks.remainder().len();
+    find_sse2(chunks.remainder(), needle).map(|pos| offset + pos)
+}
+
+#[cfg(all(feature = "sse2", target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "sse2")]
+fn find_sse2(haystack: &[u8], needle: u8) -> Option<usize> {
+    use core::arch::x86_64::{
+        __m128i, _mm_cmpeq_epi8, _mm_loadu_si128, _mm_movemask_epi8, _mm_set1_epi8,
+    };
+
+    let needles = _mm_set1_epi8(needle as i8);
+    let mut chunks = haystack.chunks_exact(16);
+    for (i, chunk) in chunks.by_ref().enumerate() {
+        // SAFETY: the chunk is exactly 16 bytes long and the load is unaligned.
+        let bytes = unsafe { _mm_loadu_si128(chunk.as_ptr() as *const __m128i) };
+        let mask = _mm_movemask_epi8(_mm_cmpeq_epi8(bytes, needles)) as u32;
+        if mask != 0 {
+            return Some(i * 16 + mask.trailing_zeros() as usize);
+        }
+    }
+
+    let offset = haystack.len() - chunks.remainder().len();
+    find_scalar(chunks.remainder(), needle).map(|pos| offset + pos)
+}
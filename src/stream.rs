@@ -0,0 +1,77 @@
+//! Book ticker updates as a plain [`Stream`], hiding the WebSocket underneath.
+
+use futures_util::{Stream, StreamExt, future};
+
+use crate::{
+    BookTickerOwned, ParseError, ParsingConfig, check_len, detect_config,
+    endpoint::StreamEndpoint,
+    logging::log_warn,
+    parse_book_ticker_dynamic,
+    reconnect::{ReconnectConfig, ReconnectingStream},
+};
+
+/// Parses every text frame of the WebSocket at `url`, reconnecting as needed.
+///
+/// Parse errors are yielded for the caller to decide upon, while the stream ends once the
+/// connection can't be restored.
+pub fn book_ticker_stream(
+    url: &str,
+    config: ParsingConfig,
+) -> impl Stream<Item = Result<BookTickerOwned, ParseError>> + use<> {
+    ReconnectingStream::new(url, ReconnectConfig::default())
+        .take_while(|msg| {
+            if let Err(err) = msg {
+                log_warn!("giving up on the connection: {err}");
+            }
+            future::ready(msg.is_ok())
+        })
+        .filter_map(move |msg| {
+            future::ready(match msg {
+                Ok(msg) if msg.is_text() => {
+                    let text = msg.to_text().unwrap();
+                    Some(
+                        parse_book_ticker_dynamic(text, config).and_then(BookTickerOwned::try_from),
+                    )
+                }
+                _ => None,
+            })
+        })
+}
+
+/// Same as [`book_ticker_stream`] for `symbol` on `endpoint`, with the config detected from the
+/// first payload and again whenever the layout changes.
+pub fn endpoint_book_ticker_stream(
+    endpoint: &StreamEndpoint,
+    symbol: &str,
+) -> impl Stream<Item = Result<BookTickerOwned, ParseError>> + use<> {
+    let mut config = ParsingConfig {
+        schema_version: endpoint.schema_version(),
+        ..ParsingConfig::default()
+    };
+    ReconnectingStream::new(endpoint.book_ticker_url(symbol), ReconnectConfig::default())
+        .take_while(|msg| {
+            if let Err(err) = msg {
+                log_warn!("giving up on the connection: {err}");
+            }
+            future::ready(msg.is_ok())
+        })
+        .filter_map(move |msg| {
+            future::ready(match msg {
+                Ok(msg) if msg.is_text() => {
+                    let text = msg.to_text().unwrap();
+                    let current = check_len(text).and_then(|()| {
+                        if config.matches(text) {
+                            Ok(config)
+                        } else {
+                            detect_config(text)
+                        }
+                    });
+                    Some(current.and_then(|current| {
+                        config = current;
+                        parse_book_ticker_dynamic(text, current).and_then(BookTickerOwned::try_from)
+                    }))
+                }
+                _ => None,
+            })
+        })
+}
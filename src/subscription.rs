@@ -0,0 +1,223 @@
+//! Subscribing to streams and unsubscribing from them on a live connection, e.g. to follow a
+//! changing set of symbols on a combined stream without reconnecting.
+//!
+//! Requests are answered in the same connection as the events, with the id they were sent with:
+//!
+//! ```text
+//! -> {"method":"SUBSCRIBE","params":["btcusdt@bookTicker"],"id":1}
+//! <- {"result":null,"id":1}
+//! ```
+
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::{
+    net::TcpStream,
+    sync::{mpsc, oneshot},
+    task::JoinHandle,
+};
+use tokio_tungstenite::{
+    MaybeTlsStream, WebSocketStream, connect_async,
+    tungstenite::{Error, Message},
+};
+
+use crate::{
+    logging::{log_info, log_warn},
+    rate_limit::RateLimiter,
+};
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+type SubscribeResult = Result<(), SubscriptionError>;
+type Pending = Arc<Mutex<HashMap<u64, oneshot::Sender<SubscribeResult>>>>;
+
+/// Why a request wasn't acknowledged.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SubscriptionError {
+    /// Binance rejected the request, e.g. for an invalid stream name.
+    Rejected { code: i64, msg: String },
+    /// The connection was gone before the response came.
+    Closed,
+}
+
+impl core::fmt::Display for SubscriptionError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Rejected { code, msg } => write!(f, "request rejected with {code}: {msg}"),
+            Self::Closed => write!(f, "connection closed before the response"),
+        }
+    }
+}
+
+impl core::error::Error for SubscriptionError {}
+
+/// Drives a WebSocket connection on its own task, sending requests over it and matching the
+/// responses to them by id, while passing every other message on.
+pub struct SubscriptionManager {
+    requests: mpsc::UnboundedSender<Message>,
+    pending: Pending,
+    next_id: AtomicU64,
+    rate_limiter: RateLimiter,
+    task: JoinHandle<()>,
+}
+
+impl SubscriptionManager {
+    /// Connects to `url`, e.g. `wss://fstream.binance.com/stream` for a combined stream with
+    /// no streams to begin with.
+    pub async fn connect(
+        url: &str,
+    ) -> Result<(Self, mpsc::UnboundedReceiver<Result<Message, Error>>), Error> {
+        log_info!("connecting to {url}");
+        let (ws_stream, _) = connect_async(url).await?;
+        Ok(Self::spawn(ws_stream))
+    }
+
+    /// Takes over `ws_stream`, returning the receiver of the messages other than responses,
+    /// which ends along with the connection.
+    pub fn spawn(ws_stream: WsStream) -> (Self, mpsc::UnboundedReceiver<Result<Message, Error>>) {
+        let (requests, request_receiver) = mpsc::unbounded_channel();
+        let (messages, message_receiver) = mpsc::unbounded_channel();
+        let pending = Pending::default();
+        let task = tokio::spawn(run(
+            ws_stream,
+            request_receiver,
+            messages,
+            Arc::clone(&pending),
+        ));
+        let manager = Self {
+            requests,
+            pending,
+            next_id: AtomicU64::new(1),
+            rate_limiter: RateLimiter::default(),
+            task,
+        };
+        (manager, message_receiver)
+    }
+
+    /// Replaces the limit on requests, Binance's [`WS_MESSAGES_PER_SEC`] by default.
+    ///
+    /// [`WS_MESSAGES_PER_SEC`]: crate::rate_limit::WS_MESSAGES_PER_SEC
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    /// Subscribes to `streams`, e.g. `btcusdt@bookTicker`, returning the id of the request once
+    /// acknowledged.
+    pub async fn subscribe(&self, streams: &[&str]) -> Result<u64, SubscriptionError> {
+        self.request("SUBSCRIBE", streams).await
+    }
+
+    pub async fn unsubscribe(&self, streams: &[&str]) -> Result<(), SubscriptionError> {
+        self.request("UNSUBSCRIBE", streams).await.map(drop)
+    }
+
+    /// Waits for the rate limiter first, so that requests in a burst go out no faster than
+    /// Binance allows rather than getting the connection closed.
+    async fn request(&self, method: &str, streams: &[&str]) -> Result<u64, SubscriptionError> {
+        self.rate_limiter.acquire().await;
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (sender, response) = oneshot::channel();
+        // Registered first, so that the response can't come before.
+        self.pending.lock().unwrap().insert(id, sender);
+
+        let params = serde_json::to_string(streams).unwrap();
+        let request = format!(r#"{{"method":"{method}","params":{params},"id":{id}}}"#);
+        if self.requests.send(Message::text(request)).is_err() {
+            self.pending.lock().unwrap().remove(&id);
+            return Err(SubscriptionError::Closed);
+        }
+        response.await.map_err(|_| SubscriptionError::Closed)??;
+        Ok(id)
+    }
+}
+
+impl Drop for SubscriptionManager {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// A response to a request, as opposed to an event.
+#[derive(serde::Deserialize)]
+struct Response {
+    id: u64,
+    #[serde(default)]
+    error: Option<ErrorBody>,
+}
+
+#[derive(serde::Deserialize)]
+struct ErrorBody {
+    code: i64,
+    msg: String,
+}
+
+async fn run(
+    ws_stream: WsStream,
+    mut requests: mpsc::UnboundedReceiver<Message>,
+    messages: mpsc::UnboundedSender<Result<Message, Error>>,
+    pending: Pending,
+) {
+    let (mut sink, mut stream) = ws_stream.split();
+    loop {
+        tokio::select! {
+            msg = stream.next() => {
+                let Some(msg) = msg else {
+                    break;
+                };
+                if let Ok(Message::Text(text)) = &msg
+                    && let Some(response) = parse_response(text)
+                {
+                    let result = match response.error {
+                        Some(ErrorBody { code, msg }) => {
+                            Err(SubscriptionError::Rejected { code, msg })
+                        }
+                        None => Ok(()),
+                    };
+                    match pending.lock().unwrap().remove(&response.id) {
+                        // The requester may have stopped waiting.
+                        Some(sender) => _ = sender.send(result),
+                        None => log_warn!("response to unknown request {}", response.id),
+                    }
+                    continue;
+                }
+                let failed = msg.is_err();
+                // The receiver may be gone while requests are still made.
+                _ = messages.send(msg);
+                if failed {
+                    break;
+                }
+            }
+            request = requests.recv() => {
+                // Only once the manager is dropped, which aborts this task anyway.
+                let Some(request) = request else {
+                    break;
+                };
+                if let Err(err) = sink.send(request).await {
+                    _ = messages.send(Err(err));
+                    break;
+                }
+            }
+        }
+    }
+    // Fails whatever is still waiting with `Closed`, after making sure nothing more is sent.
+    drop(requests);
+    pending.lock().unwrap().clear();
+}
+
+/// Responses are told apart from events by their first key, which events never have.
+fn parse_response(text: &str) -> Option<Response> {
+    let is_response = ["{\"result\"", "{\"error\"", "{\"id\""]
+        .iter()
+        .any(|prefix| text.starts_with(prefix));
+    if !is_response {
+        return None;
+    }
+    serde_json::from_str(text).ok()
+}
@@ -0,0 +1,56 @@
+//! Integer parsing eight digits at a time within a `u64`, for the timestamps and ids whose
+//! number of digits is known from the config.
+
+/// `b'0'` in every byte.
+const ZEROS: u64 = 0x3030_3030_3030_3030;
+
+/// Parses the first `digits` bytes of `s` as a decimal, which must all be ASCII digits, with
+/// `digits` at most 19 so the value can't overflow. Eight digits at a time are combined in
+/// pairs, then fours, then eights, like in Drachennest, rather than going through them one by
+/// one with a check for each.
+///
+/// Invalid input gives an unspecified value rather than panicking, see [`all_digits`].
+pub fn parse_u64_fixed_digits(s: &[u8], digits: usize) -> u64 {
+    debug_assert!(digits <= 19 && digits <= s.len());
+    let s = &s[..digits];
+    // The leading digits that don't fill up a chunk, then the chunks.
+    let (head, chunks) = s.split_at(digits % 8);
+    let mut value = head.iter().fold(0u64, |value, &byte| {
+        value
+            .wrapping_mul(10)
+            .wrapping_add(u64::from(byte.wrapping_sub(b'0')))
+    });
+    for chunk in chunks.chunks_exact(8) {
+        value = value
+            .wrapping_mul(100_000_000)
+            .wrapping_add(parse_8(load(chunk)));
+    }
+    value
+}
+
+/// Whether the first `digits` bytes of `s` are all ASCII digits, checking eight at a time too.
+pub fn all_digits(s: &[u8], digits: usize) -> bool {
+    let s = &s[..digits];
+    let (head, chunks) = s.split_at(digits % 8);
+    head.iter().all(u8::is_ascii_digit)
+        & chunks.chunks_exact(8).fold(true, |valid, chunk| {
+            let chunk = load(chunk);
+            // Bytes 0x30 to 0x39 have 3 in the high nibble, and keep it when adding 6 to them.
+            let high = chunk & 0xF0F0_F0F0_F0F0_F0F0;
+            let carried = chunk.wrapping_add(0x0606_0606_0606_0606) & 0xF0F0_F0F0_F0F0_F0F0;
+            valid & ((high | carried >> 4) == 0x3333_3333_3333_3333)
+        })
+}
+
+/// The first digit goes to the lowest byte, being little-endian.
+fn load(chunk: &[u8]) -> u64 {
+    u64::from_le_bytes(chunk.try_into().unwrap())
+}
+
+fn parse_8(chunk: u64) -> u64 {
+    let chunk = chunk.wrapping_sub(ZEROS);
+    // Each byte is a digit more significant than the one above it, so multiply and add the next.
+    let pairs = (chunk.wrapping_mul(10).wrapping_add(chunk >> 8)) & 0x00FF_00FF_00FF_00FF;
+    let fours = (pairs.wrapping_mul(100).wrapping_add(pairs >> 16)) & 0x0000_FFFF_0000_FFFF;
+    (fours.wrapping_mul(10_000).wrapping_add(fours >> 32)) & 0xFFFF_FFFF
+}
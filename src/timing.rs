@@ -0,0 +1,205 @@
+//! Counters used to measure how long parsing takes.
+//!
+//! Readings are only meaningful relative to each other, so the units differ between backends:
+//! cycles for the hardware counters and nanoseconds for [`InstantTimer`]. On aarch64 the cycle
+//! counter may be inaccessible from userspace, in which case [`Pmccntr`] measures nanoseconds too.
+//! On wasm32 `Instant` panics, so `PerformanceNow` reads the clock of the browser instead.
+
+use std::{sync::OnceLock, time::Instant};
+
+/// Source of readings to measure with, see [`measure_with`].
+pub trait TimingBackend {
+    /// Takes the reading to pass to [`TimingBackend::elapsed`] later.
+    fn start() -> u64;
+    /// How much has passed since `start` was taken.
+    fn elapsed(start: u64) -> u64;
+}
+
+/// Reads the time stamp counter, measuring in cycles.
+#[cfg(target_arch = "x86_64")]
+pub struct Rdtsc;
+
+#[cfg(target_arch = "x86_64")]
+impl TimingBackend for Rdtsc {
+    fn start() -> u64 {
+        use std::arch::x86_64::{_mm_lfence, _rdtsc};
+
+        // Wait for the preceding instructions to finish so they don't leak into the measurement.
+        unsafe {
+            _mm_lfence();
+            _rdtsc()
+        }
+    }
+
+    fn elapsed(start: u64) -> u64 {
+        use std::arch::x86_64::_rdtsc;
+
+        unsafe { _rdtsc() - start }
+    }
+}
+
+/// Reads the PMU cycle counter `PMCCNTR_EL0`, measuring in cycles.
+///
+/// Userspace access to the PMU is up to the kernel, so when it isn't granted this falls back to
+/// [`InstantTimer`]. The choice is made once, so readings are always comparable with each other.
+#[cfg(target_arch = "aarch64")]
+pub struct Pmccntr;
+
+#[cfg(target_arch = "aarch64")]
+impl Pmccntr {
+    fn available() -> bool {
+        static AVAILABLE: OnceLock<bool> = OnceLock::new();
+        *AVAILABLE.get_or_init(|| unsafe { Self::enable() })
+    }
+
+    /// Enables the cycle counter if userspace is allowed to and checks that it actually counts.
+    ///
+    /// # Safety
+    ///
+    /// Relies on `PMUSERENR_EL0` being readable from EL0, which holds unless a hypervisor traps
+    /// all the PMU registers.
+    unsafe fn enable() -> bool {
+        use std::arch::asm;
+
+        let userenr: u64;
+        unsafe { asm!("mrs {}, pmuserenr_el0", out(reg) userenr, options(nomem, nostack)) };
+        // EN grants access to all the PMU registers, CR only to reading the cycle counter.
+        let en = userenr & 0b001 != 0;
+        let cr = userenr & 0b100 != 0;
+
+        if en {
+            unsafe {
+                let pmcr: u64;
+                asm!("mrs {}, pmcr_el0", out(reg) pmcr, options(nomem, nostack));
+                // Set E to enable the counters.
+                if pmcr & 1 == 0 {
+                    asm!("msr pmcr_el0, {}", in(reg) pmcr | 1, options(nomem, nostack));
+                }
+                // Set C to enable the cycle counter specifically.
+                asm!("msr pmcntenset_el0, {}", in(reg) 1u64 << 31, options(nomem, nostack));
+                asm!("isb", options(nomem, nostack));
+            }
+        } else if !cr {
+            return false;
+        }
+
+        // With only CR granted the counter may still be disabled, in which case it stays put.
+        let first = unsafe { Self::read() };
+        std::hint::spin_loop();
+        let second = unsafe { Self::read() };
+        second != first
+    }
+
+    unsafe fn read() -> u64 {
+        let cycles: u64;
+        // Wait for the preceding instructions to finish so they don't leak into the measurement.
+        unsafe {
+            std::arch::asm!(
+                "isb",
+                "mrs {}, pmccntr_el0",
+                out(reg) cycles,
+                options(nomem, nostack),
+            )
+        };
+        cycles
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+impl TimingBackend for Pmccntr {
+    fn start() -> u64 {
+        if Self::available() {
+            unsafe { Self::read() }
+        } else {
+            InstantTimer::start()
+        }
+    }
+
+    fn elapsed(start: u64) -> u64 {
+        if Self::available() {
+            unsafe { Self::read() - start }
+        } else {
+            InstantTimer::elapsed(start)
+        }
+    }
+}
+
+/// Portable fallback measuring in nanoseconds.
+pub struct InstantTimer;
+
+impl InstantTimer {
+    fn now() -> u64 {
+        // `Instant` is opaque, so readings are taken relative to the first one.
+        static EPOCH: OnceLock<Instant> = OnceLock::new();
+        EPOCH.get_or_init(Instant::now).elapsed().as_nanos() as u64
+    }
+}
+
+impl TimingBackend for InstantTimer {
+    fn start() -> u64 {
+        Self::now()
+    }
+
+    fn elapsed(start: u64) -> u64 {
+        Self::now() - start
+    }
+}
+
+/// `performance.now()`, measuring in nanoseconds, since `Instant` isn't available on wasm32.
+///
+/// Browsers coarsen it to somewhere between 5µs and 100µs, so single parses mostly read 0.
+#[cfg(target_arch = "wasm32")]
+pub struct PerformanceNow;
+
+#[cfg(target_arch = "wasm32")]
+mod performance {
+    use wasm_bindgen::prelude::*;
+
+    #[wasm_bindgen]
+    extern "C" {
+        #[wasm_bindgen(js_namespace = performance)]
+        pub fn now() -> f64;
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl TimingBackend for PerformanceNow {
+    fn start() -> u64 {
+        (performance::now() * 1e6) as u64
+    }
+
+    fn elapsed(start: u64) -> u64 {
+        Self::start() - start
+    }
+}
+
+/// The most precise backend available on the target.
+#[cfg(target_arch = "x86_64")]
+pub type DefaultTimer = Rdtsc;
+/// The most precise backend available on the target.
+#[cfg(target_arch = "aarch64")]
+pub type DefaultTimer = Pmccntr;
+/// The most precise backend available on the target.
+#[cfg(target_arch = "wasm32")]
+pub type DefaultTimer = PerformanceNow;
+/// The most precise backend available on the target.
+#[cfg(not(any(
+    target_arch = "x86_64",
+    target_arch = "aarch64",
+    target_arch = "wasm32"
+)))]
+pub type DefaultTimer = InstantTimer;
+
+/// Runs `f`, measuring it with [`DefaultTimer`].
+pub fn measure<T>(f: impl Fn() -> T) -> (u64, T) {
+    measure_with::<DefaultTimer, T>(f)
+}
+
+/// Runs `f`, measuring it with the given backend.
+pub fn measure_with<B: TimingBackend, T>(f: impl Fn() -> T) -> (u64, T) {
+    let start = B::start();
+    let res = f();
+    let elapsed = B::elapsed(start);
+
+    (elapsed, res)
+}
@@ -0,0 +1,79 @@
+//! A span around each parsed book ticker, for distributed tracing.
+//!
+//! [`Tracer`] and [`Span`] are the few calls of OpenTelemetry's tracer that the parser makes, so
+//! that one can be wired up without this crate depending on an exporter: see
+//! `examples/otlp_demo.rs`, which sends the spans to an OTLP endpoint. [`NoopTracer`] is the
+//! default, skipping the span and the timing altogether.
+
+use crate::{BookTicker, ParseError, ParsingConfig, parse_book_ticker_dynamic, timing::measure};
+
+/// What a span is started by, e.g. an adapter around an `opentelemetry::trace::Tracer`.
+pub trait Tracer {
+    type Span: Span;
+
+    /// Whether spans are recorded at all. Without them the payload isn't timed either.
+    const ENABLED: bool = true;
+
+    fn start(&self, name: &'static str) -> Self::Span;
+}
+
+pub trait Span {
+    fn set_attribute(&mut self, key: &'static str, value: Attribute<'_>);
+
+    fn end(self);
+}
+
+/// The value of a span attribute.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum Attribute<'a> {
+    Str(&'a str),
+    U64(u64),
+}
+
+/// Records nothing, compiling [`parse_book_ticker_traced`] down to [`parse_book_ticker_dynamic`].
+#[derive(Clone, Copy, Default, Debug)]
+pub struct NoopTracer;
+
+impl Tracer for NoopTracer {
+    type Span = NoopSpan;
+
+    const ENABLED: bool = false;
+
+    fn start(&self, _name: &'static str) -> NoopSpan {
+        NoopSpan
+    }
+}
+
+#[derive(Clone, Copy, Default, Debug)]
+pub struct NoopSpan;
+
+impl Span for NoopSpan {
+    fn set_attribute(&mut self, _key: &'static str, _value: Attribute<'_>) {}
+
+    fn end(self) {}
+}
+
+/// [`parse_book_ticker_dynamic`] in a `parse_book_ticker` span, with the `symbol` and `T` of the
+/// ticker and how long parsing it took as `elapsed_cycles`, in the units of
+/// [`DefaultTimer`](crate::timing::DefaultTimer). Payloads failing to parse only get the latter.
+pub fn parse_book_ticker_traced<'a, T: Tracer>(
+    json: &'a str,
+    config: impl Into<ParsingConfig>,
+    tracer: &T,
+) -> Result<BookTicker<'a>, ParseError> {
+    let config = config.into();
+    if !T::ENABLED {
+        return parse_book_ticker_dynamic(json, config);
+    }
+
+    let mut span = tracer.start("parse_book_ticker");
+    let (elapsed, ticker) = measure(|| parse_book_ticker_dynamic(json, config));
+    if let Ok(ticker) = &ticker {
+        span.set_attribute("symbol", Attribute::Str(ticker.s));
+        span.set_attribute("T", Attribute::U64(ticker.T));
+    }
+    span.set_attribute("elapsed_cycles", Attribute::U64(elapsed));
+    span.end();
+    ticker
+}
@@ -0,0 +1,134 @@
+//! Authenticated streams: connecting with extra headers like `X-MBX-APIKEY`, and keeping the
+//! listen key of a user data stream from expiring.
+//!
+//! ```no_run
+//! # async fn run() -> std::io::Result<()> {
+//! use binance_json_parsing::{
+//!     endpoint::StreamEndpoint,
+//!     user_data::{ListenKeyManager, spawn_renew_loop},
+//! };
+//! use std::sync::Arc;
+//!
+//! let api_key = std::env::var("BINANCE_API_KEY").unwrap();
+//! let endpoint = StreamEndpoint::UsdMFutures;
+//! let manager = Arc::new(ListenKeyManager::for_endpoint(&endpoint, api_key)?);
+//! let listen_key = manager.create().await?;
+//! let url = endpoint.stream_url(&listen_key);
+//! let renewing = spawn_renew_loop(manager.clone(), listen_key.clone());
+//! // Receive from `url`, then once done:
+//! renewing.abort();
+//! manager.delete(&listen_key).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{io, sync::Arc, time::Duration};
+
+use tokio::{
+    net::TcpStream,
+    task::JoinHandle,
+    time::{Instant, MissedTickBehavior},
+};
+use tokio_tungstenite::{
+    MaybeTlsStream, WebSocketStream, connect_async,
+    tungstenite::{
+        Error,
+        client::IntoClientRequest,
+        http::{HeaderName, HeaderValue},
+    },
+};
+
+use crate::{
+    endpoint::StreamEndpoint,
+    logging::{log_debug, log_info, log_warn},
+    rest::{RestClient, close_listen_key, create_listen_key, keep_alive_listen_key},
+};
+
+/// How often Binance recommends extending a listen key, which expires after 60 minutes.
+pub const LISTEN_KEY_KEEPALIVE: Duration = Duration::from_secs(30 * 60);
+
+/// Connects to the WebSocket at `url`, sending `headers` along with the upgrade request.
+pub async fn connect_with_headers(
+    url: &str,
+    headers: &[(HeaderName, HeaderValue)],
+) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>, Error> {
+    let mut request = url.into_client_request()?;
+    for (name, value) in headers {
+        request.headers_mut().insert(name, value.clone());
+    }
+    log_info!("connecting to {url}");
+    let (ws_stream, _) = connect_async(request).await?;
+    Ok(ws_stream)
+}
+
+/// Creates, renews and deletes listen keys of an API key.
+pub struct ListenKeyManager {
+    client: RestClient,
+    api_key: String,
+    renew_interval: Duration,
+}
+
+impl ListenKeyManager {
+    /// Manager of the listen keys of `api_key` at `base_url`, e.g.
+    /// [`BINANCE_FUTURES_URL`](crate::rest::BINANCE_FUTURES_URL).
+    pub fn new(base_url: &str, api_key: impl Into<String>) -> io::Result<Self> {
+        Ok(Self {
+            client: RestClient::new(base_url)?,
+            api_key: api_key.into(),
+            renew_interval: LISTEN_KEY_KEEPALIVE,
+        })
+    }
+
+    /// Manager of the listen keys of `api_key` on the REST API next to `endpoint`.
+    pub fn for_endpoint(endpoint: &StreamEndpoint, api_key: impl Into<String>) -> io::Result<Self> {
+        Self::new(&endpoint.rest_url(), api_key)
+    }
+
+    /// Renews every `period` in [`run_renew_loop`](Self::run_renew_loop) instead of every
+    /// [`LISTEN_KEY_KEEPALIVE`].
+    pub fn with_renew_interval(mut self, period: Duration) -> Self {
+        self.renew_interval = period;
+        self
+    }
+
+    /// Starts a user data stream, or returns the key of the one already started.
+    pub async fn create(&self) -> io::Result<String> {
+        create_listen_key(&self.client, &self.api_key).await
+    }
+
+    /// Extends the validity of `key` by 60 minutes.
+    ///
+    /// Futures have a single key per API key, so `key` isn't sent, only the API key is.
+    pub async fn renew(&self, key: &str) -> io::Result<()> {
+        log_debug!("renewing listen key {key}");
+        keep_alive_listen_key(&self.client, &self.api_key).await
+    }
+
+    /// Closes the stream of `key`, sending only the API key as with [`renew`](Self::renew).
+    pub async fn delete(&self, key: &str) -> io::Result<()> {
+        log_info!("deleting listen key {key}");
+        close_listen_key(&self.client, &self.api_key).await
+    }
+
+    /// Renews `key` every 30 minutes, or as set by
+    /// [`with_renew_interval`](Self::with_renew_interval), until dropped.
+    ///
+    /// Failures are only logged, the next attempt is still in time as long as the interval is
+    /// well under the 60 minutes a key lasts.
+    pub async fn run_renew_loop(&self, key: String) {
+        let period = self.renew_interval;
+        let mut interval = tokio::time::interval_at(Instant::now() + period, period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.renew(&key).await {
+                log_warn!("failed to renew listen key {key}: {err}");
+            }
+        }
+    }
+}
+
+/// Runs [`ListenKeyManager::run_renew_loop`] in a background task, until aborted.
+pub fn spawn_renew_loop(manager: Arc<ListenKeyManager>, key: String) -> JoinHandle<()> {
+    tokio::spawn(async move { manager.run_renew_loop(key).await })
+}
@@ -0,0 +1,43 @@
+#![cfg(feature = "serde")]
+
+use binance_json_parsing::{ParseError, account_update::parse_account_update};
+
+const ACCOUNT_UPDATE: &str = r#"{"e":"ACCOUNT_UPDATE","T":1744760290999,"E":1744760291000,"a":{"B":[{"a":"USDT","wb":"122624.12345678","cw":"100.12345678","bc":"50.12345678"},{"a":"BNB","wb":"1.00000000","cw":"0.00000000","bc":"0.00000000"}],"P":[{"s":"BTCUSDT","pa":"0.014","ep":"83000.00","bep":"83010.50","cr":"200.00","up":"8.51","mt":"cross","iw":"0","ps":"BOTH"}],"m":"ORDER"}}"#;
+
+#[test]
+fn parses_account_update() {
+    let update = parse_account_update(ACCOUNT_UPDATE).unwrap();
+    assert_eq!(
+        (update.E, update.T, update.m),
+        (1744760291000, 1744760290999, "ORDER")
+    );
+    let balances: Vec<_> = update.B.iter().map(|b| (b.a, b.wb, b.cw)).collect();
+    assert_eq!(
+        balances,
+        [
+            ("USDT", "122624.12345678", "100.12345678"),
+            ("BNB", "1.00000000", "0.00000000"),
+        ]
+    );
+    let positions: Vec<_> = update.P.iter().map(|p| (p.s, p.pa, p.up)).collect();
+    assert_eq!(positions, [("BTCUSDT", "0.014", "8.51")]);
+}
+
+#[test]
+fn parses_funding_fee_without_positions() {
+    let json = r#"{"e":"ACCOUNT_UPDATE","E":1744761600001,"T":1744761600000,"a":{"m":"FUNDING_FEE","B":[{"a":"USDT","wb":"122623.90000000","cw":"99.90000000","bc":"-0.22345678"}],"P":[]}}"#;
+    let update = parse_account_update(json).unwrap();
+    assert_eq!(update.m, "FUNDING_FEE");
+    assert_eq!(update.B.len(), 1);
+    assert!(update.P.is_empty());
+}
+
+#[test]
+fn fails_on_missing_field() {
+    let no_time = ACCOUNT_UPDATE.replace(r#""T":1744760290999,"#, "");
+    assert!(matches!(
+        parse_account_update(&no_time),
+        Err(ParseError::InvalidJson { line: 1, .. })
+    ));
+    assert!(parse_account_update(&ACCOUNT_UPDATE[..100]).is_err());
+}
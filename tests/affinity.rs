@@ -0,0 +1,23 @@
+#![cfg(feature = "std")]
+
+use binance_json_parsing::affinity::{AffinityError, pin_to_core};
+
+#[test]
+fn pins_to_core_0() {
+    pin_to_core(0).unwrap();
+}
+
+#[test]
+fn rejects_cores_out_of_range() {
+    let err = pin_to_core(usize::MAX).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            AffinityError::OutOfRange {
+                core_id: usize::MAX,
+                ..
+            }
+        ),
+        "{err:?}"
+    );
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "arena")]
+
+use binance_json_parsing::{
+    ParsingConfig, arena::TickArena, detect_config, parse_book_ticker_dynamic,
+};
+
+const PAYLOADS: &str = include_str!("fixtures/book_ticker.jsonl");
+
+#[test]
+fn tickers_outlive_payloads() {
+    let arena = TickArena::new();
+    let tickers: Vec<_> = PAYLOADS
+        .lines()
+        .map(|json| {
+            // Dropped right away, leaving only the copy in the arena.
+            let payload = json.to_owned();
+            arena
+                .alloc_ticker(&payload, detect_config(json).unwrap())
+                .unwrap()
+        })
+        .collect();
+
+    for (ticker, json) in tickers.iter().zip(PAYLOADS.lines()) {
+        assert_eq!(
+            *ticker,
+            parse_book_ticker_dynamic(json, detect_config(json).unwrap()).unwrap()
+        );
+    }
+}
+
+#[test]
+fn reuses_memory_after_reset() {
+    let mut arena = TickArena::new();
+    let fill = |arena: &TickArena| {
+        for json in PAYLOADS.lines() {
+            arena
+                .alloc_ticker(json, detect_config(json).unwrap())
+                .unwrap();
+        }
+    };
+    fill(&arena);
+    let allocated = arena.allocated_bytes();
+
+    arena.reset();
+    let used: usize = arena
+        .0
+        .iter_allocated_chunks()
+        .map(|chunk| chunk.len())
+        .sum();
+    assert_eq!(used, 0);
+    // The chunk kept is the largest one, so the next batch needs fewer new ones.
+    fill(&arena);
+    assert!(arena.allocated_bytes() < 2 * allocated);
+}
+
+#[test]
+fn fails_on_invalid_payload() {
+    let arena = TickArena::new();
+    assert!(arena.alloc_ticker("{}", ParsingConfig::default()).is_err());
+}
@@ -0,0 +1,115 @@
+#![cfg(feature = "std")]
+
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+};
+
+use binance_json_parsing::{
+    ParsingConfig, SchemaVersion, atomic_config::AtomicConfig, detect_config,
+    parse_book_ticker_dynamic,
+};
+
+const BTCUSDT: &str = r#"{"e":"bookTicker","u":7283946601071,"s":"BTCUSDT","b":"83604.80","B":"10.746","a":"83604.90","A":"9.514","T":1744760290967,"E":1744760290968}"#;
+
+#[test]
+fn round_trips_every_field() {
+    let configs = [
+        ParsingConfig::default(),
+        ParsingConfig::new(0, 0, 0, 0, 0, SchemaVersion::CoinMFutures),
+        ParsingConfig::new((1 << 24) - 1, 255, 255, 255, 255, SchemaVersion::Spot),
+        ParsingConfig::new(70, 8, 3, 13, 12, SchemaVersion::Futures),
+    ];
+    let shared = AtomicConfig::new(configs[0]).unwrap();
+    for config in configs {
+        shared.store(config).unwrap();
+        assert_eq!(shared.load(), config);
+    }
+
+    let shared = AtomicConfig::new(detect_config(BTCUSDT).unwrap()).unwrap();
+    assert_eq!(
+        parse_book_ticker_dynamic(BTCUSDT, &shared).unwrap().b,
+        "83604.80"
+    );
+}
+
+#[test]
+fn rejects_fields_over_their_bits() {
+    let fits = ParsingConfig::default();
+    let shared = AtomicConfig::new(fits).unwrap();
+    let cases = [
+        (
+            ParsingConfig::new(1 << 24, 2, 3, 13, 7, SchemaVersion::Futures),
+            "start",
+            1 << 24,
+            (1 << 24) - 1,
+        ),
+        (
+            ParsingConfig::new(70, 256, 3, 13, 7, SchemaVersion::Futures),
+            "price_precision",
+            256,
+            255,
+        ),
+        (
+            ParsingConfig::new(70, 2, 300, 13, 7, SchemaVersion::Futures),
+            "volume_precision",
+            300,
+            255,
+        ),
+        (
+            ParsingConfig::new(70, 2, 3, 256, 7, SchemaVersion::Futures),
+            "transaction_time_digits",
+            256,
+            255,
+        ),
+        (
+            ParsingConfig::new(70, 2, 3, 13, usize::MAX, SchemaVersion::Futures),
+            "symbol_len",
+            usize::MAX,
+            255,
+        ),
+    ];
+    for (config, field, value, max) in cases {
+        let err = shared.store(config).unwrap_err();
+        assert_eq!(
+            (err.field, err.value, err.max),
+            (field, value, max),
+            "{config:?}"
+        );
+        assert_eq!(err.to_string(), format!("{field} of {value} is over {max}"));
+        assert!(AtomicConfig::new(config).is_err());
+        // Left as it was.
+        assert_eq!(shared.load(), fits);
+    }
+}
+
+#[test]
+fn swaps_whole_configs_across_threads() {
+    // Every field differs, so that a config mixing both would show.
+    let a = ParsingConfig::new(70, 2, 3, 13, 7, SchemaVersion::Futures);
+    let b = ParsingConfig::new(4000, 8, 0, 14, 12, SchemaVersion::Spot);
+    let shared = AtomicConfig::new(a).unwrap();
+    let done = AtomicBool::new(false);
+
+    thread::scope(|scope| {
+        for _ in 0..2 {
+            let shared = shared.clone();
+            let done = &done;
+            scope.spawn(move || {
+                while !done.load(Ordering::Relaxed) {
+                    let config = shared.load();
+                    assert!(config == a || config == b, "{config:?}");
+                }
+            });
+        }
+
+        for i in 0..100_000 {
+            shared.store(if i % 2 == 0 { b } else { a }).unwrap();
+            if i % 100 == 0 {
+                thread::yield_now();
+            }
+        }
+        done.store(true, Ordering::Relaxed);
+    });
+    assert_eq!(shared.load(), a);
+}
@@ -0,0 +1,62 @@
+#![cfg(feature = "std")]
+
+use std::num::NonZeroUsize;
+
+use binance_json_parsing::{
+    BookTickerOwned, ParseError, batch::parse_batch, batch::parse_batch_with_threads,
+    detect_config, parse_book_ticker_dynamic,
+};
+
+const FIXTURE: &str = include_str!("fixtures/book_ticker.jsonl");
+
+/// 10,000 BTCUSDT payloads, every thousandth of them cut short so that it fails to parse.
+fn messages() -> Vec<String> {
+    FIXTURE
+        .lines()
+        .filter(|line| line.contains(r#""s":"BTCUSDT""#))
+        .cycle()
+        .take(10_000)
+        .enumerate()
+        .map(|(i, line)| match i % 1000 {
+            999 => line[..line.len() / 2].to_owned(),
+            _ => line.to_owned(),
+        })
+        .collect()
+}
+
+#[test]
+fn matches_sequential_parsing() {
+    let messages = messages();
+    let config = detect_config(&messages[0]).unwrap();
+    let sequential: Vec<Result<BookTickerOwned, ParseError>> = messages
+        .iter()
+        .map(|json| parse_book_ticker_dynamic(json, config).and_then(BookTickerOwned::try_from))
+        .collect();
+    assert_eq!(sequential.iter().filter(|res| res.is_err()).count(), 10);
+
+    assert_eq!(parse_batch(&messages, config), sequential);
+    for threads in [1, 3, 8] {
+        let threads = NonZeroUsize::new(threads).unwrap();
+        assert_eq!(
+            parse_batch_with_threads(&messages, config, threads),
+            sequential,
+            "{threads} threads"
+        );
+    }
+}
+
+#[test]
+fn parses_empty_batch() {
+    let config = detect_config(&messages()[0]).unwrap();
+    assert!(parse_batch(&[], config).is_empty());
+}
+
+#[test]
+fn fails_on_decimals_over_capacity() {
+    let mut messages = messages();
+    let config = detect_config(&messages[0]).unwrap();
+    messages[1] = messages[1].replacen(r#""B":""#, r#""B":"1234567890123"#, 1);
+    let parsed = parse_batch(&messages, config);
+    assert!(matches!(parsed[1], Err(ParseError::DecimalTooLong { .. })));
+    assert!(parsed[0].is_ok() && parsed[2].is_ok());
+}
@@ -0,0 +1,158 @@
+#![cfg(feature = "std")]
+
+use std::{fs, io::ErrorKind, path::PathBuf};
+
+use binance_json_parsing::{
+    BookTickerOwned,
+    binlog::{BinaryLog, BinaryLogReader, MAGIC, MAX_FRAME_LEN, VERSION, mmap_replay},
+    detect_config, parse_book_ticker_dynamic,
+};
+
+const FIXTURE: &str = include_str!("fixtures/book_ticker.jsonl");
+
+/// A path of its own for each test, removed when dropped.
+struct TempPath(PathBuf);
+
+impl TempPath {
+    fn new(name: &str) -> Self {
+        Self(std::env::temp_dir().join(format!("binlog-{}-{name}.bjpl", std::process::id())))
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        _ = fs::remove_file(&self.0);
+    }
+}
+
+/// 10,000 BTCUSDT payloads, all of the same layout.
+fn frames() -> Vec<&'static str> {
+    FIXTURE
+        .lines()
+        .filter(|line| line.contains(r#""s":"BTCUSDT""#))
+        .cycle()
+        .take(10_000)
+        .collect()
+}
+
+fn write_log(path: &TempPath, frames: &[&str]) {
+    let mut log = BinaryLog::create(&path.0).unwrap();
+    for frame in frames {
+        log.write_frame(frame).unwrap();
+    }
+    log.flush().unwrap();
+}
+
+#[test]
+fn mmap_replay_round_trips() {
+    let path = TempPath::new("round-trip");
+    let frames = frames();
+    write_log(&path, &frames);
+
+    let config = detect_config(frames[0]).unwrap();
+    let replayed: Vec<_> = mmap_replay(&path.0, config)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    let expected: Vec<BookTickerOwned> = frames
+        .iter()
+        .map(|json| {
+            let ticker = parse_book_ticker_dynamic(json, config).unwrap();
+            BookTickerOwned::try_from(ticker).unwrap()
+        })
+        .collect();
+    assert_eq!(replayed, expected);
+}
+
+#[test]
+fn mmap_replay_stops_at_truncated_frame() {
+    let path = TempPath::new("truncated");
+    let frames = frames();
+    write_log(&path, &frames[..3]);
+    let len = fs::metadata(&path.0).unwrap().len();
+    fs::File::options()
+        .write(true)
+        .open(&path.0)
+        .unwrap()
+        .set_len(len - 10)
+        .unwrap();
+
+    let config = detect_config(frames[0]).unwrap();
+    assert_eq!(mmap_replay(&path.0, config).unwrap().count(), 2);
+}
+
+#[test]
+fn mmap_replay_rejects_other_files() {
+    let path = TempPath::new("other");
+    let config = detect_config(frames()[0]).unwrap();
+
+    fs::write(&path.0, "").unwrap();
+    assert!(mmap_replay(&path.0, config).is_err());
+    fs::write(&path.0, FIXTURE).unwrap();
+    assert!(mmap_replay(&path.0, config).is_err());
+}
+
+#[test]
+fn reader_round_trips() {
+    let path = TempPath::new("reader-round-trip");
+    let frames = frames();
+    write_log(&path, &frames[..100]);
+    let read: Vec<_> = BinaryLogReader::open(&path.0)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(read, frames[..100]);
+}
+
+#[test]
+fn rejects_cut_short_frame_lengths() {
+    let path = TempPath::new("cut-short-len");
+    let frames = frames();
+    write_log(&path, &frames[..1]);
+    let log = fs::read(&path.0).unwrap();
+    for extra in 1..4 {
+        // A whole frame then the first bytes of the next length.
+        let mut truncated = log.clone();
+        truncated.extend_from_slice(&[1, 0, 0, 0][..extra]);
+        fs::write(&path.0, &truncated).unwrap();
+
+        let mut reader = BinaryLogReader::open(&path.0).unwrap();
+        assert_eq!(reader.next().unwrap().unwrap(), frames[0]);
+        let err = reader.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData, "{extra} bytes");
+        // Not taken for a clean end like with every frame whole.
+        let config = detect_config(frames[0]).unwrap();
+        assert_eq!(mmap_replay(&path.0, config).unwrap().count(), 1);
+    }
+}
+
+#[test]
+fn rejects_frames_over_max_len() {
+    let path = TempPath::new("over-max-len");
+    let mut log = MAGIC.to_vec();
+    log.push(VERSION);
+    // A corrupt length, which mustn't be allocated for.
+    log.extend_from_slice(&u32::MAX.to_le_bytes());
+    log.extend_from_slice(b"{}");
+    fs::write(&path.0, &log).unwrap();
+    let err = BinaryLogReader::open(&path.0)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    let config = detect_config(frames()[0]).unwrap();
+    assert_eq!(mmap_replay(&path.0, config).unwrap().count(), 0);
+
+    let mut writer = BinaryLog::create(&path.0).unwrap();
+    let frame = "x".repeat(MAX_FRAME_LEN + 1);
+    let err = writer.write_frame(&frame).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    writer.write_frame(&frame[1..]).unwrap();
+    writer.flush().unwrap();
+    let read: Vec<_> = BinaryLogReader::open(&path.0)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(read, [&frame[1..]]);
+}
@@ -0,0 +1,82 @@
+//! The broadcaster end to end, against a local server instead of Binance.
+#![cfg(feature = "ws")]
+
+mod common;
+
+use std::time::Duration;
+
+use binance_json_parsing::{
+    BookTickerOwned,
+    broadcaster::{TickerBroadcaster, TickerReceiver},
+};
+use common::mock_server::MockBinanceServer;
+
+const TICKS: u64 = 40;
+const CAPACITY: usize = 16;
+
+/// Ticks numbered by their update id, from 1.
+fn ticks() -> Vec<String> {
+    (1..=TICKS)
+        .map(|u| {
+            format!(
+                r#"{{"e":"bookTicker","u":{u},"s":"BTCUSDT","b":"83604.80","B":"10.746","a":"83604.90","A":"9.514","T":1744760290967,"E":1744760290968}}"#
+            )
+        })
+        .collect()
+}
+
+/// The first [`TICKS`] ticks `receiver` gets.
+async fn receive(mut receiver: TickerReceiver) -> Vec<BookTickerOwned> {
+    let mut ticks = Vec::new();
+    while ticks.len() < TICKS as usize {
+        ticks.push(receiver.recv().await.unwrap());
+    }
+    ticks
+}
+
+#[tokio::test]
+async fn sends_every_tick_to_every_subscriber() {
+    let server = MockBinanceServer::builder()
+        .messages(ticks())
+        .interval(Duration::from_millis(2))
+        .send_loop()
+        .build();
+    let broadcaster = TickerBroadcaster::spawn(server.url(), CAPACITY);
+    let first = broadcaster.subscribe();
+    let second = broadcaster.subscribe();
+
+    let (first, second) = tokio::join!(receive(first), receive(second));
+    assert_eq!(
+        first.iter().map(|tick| tick.u).collect::<Vec<_>>(),
+        (1..=TICKS).collect::<Vec<_>>()
+    );
+    assert_eq!(first, second);
+    assert_eq!(broadcaster.lagged(), 0);
+}
+
+#[tokio::test]
+async fn counts_lagging_subscribers() {
+    let server = MockBinanceServer::builder()
+        .messages(ticks())
+        .interval(Duration::from_millis(2))
+        .send_loop()
+        .build();
+    let broadcaster = TickerBroadcaster::spawn(server.url(), CAPACITY);
+    let mut keeping_up = broadcaster.subscribe();
+    let mut lagging = broadcaster.subscribe();
+
+    // Well over the capacity, while the other one isn't reading at all.
+    for u in 1..=TICKS {
+        assert_eq!(keeping_up.recv().await.unwrap().u, u);
+    }
+    assert_eq!(broadcaster.lagged(), 0);
+
+    // The oldest ticks are gone, so it skips ahead to the ones still kept.
+    let tick = lagging.recv().await.unwrap();
+    assert!(tick.u > 1, "{}", tick.u);
+    assert_eq!(broadcaster.lagged(), 1);
+
+    // Caught up from there, not lagging again.
+    lagging.recv().await.unwrap();
+    assert_eq!(broadcaster.lagged(), 1);
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "std")]
+
+use binance_json_parsing::{ParseError, cache::ConfigCache, detect_config};
+
+const BTCUSDT: &str = r#"{"e":"bookTicker","u":7283946601071,"s":"BTCUSDT","b":"83604.80","B":"10.746","a":"83604.90","A":"9.514","T":1744760290967,"E":1744760290968}"#;
+// The same layout up to `b`, but with one more digit of price precision.
+#[cfg(not(feature = "nom-parser"))]
+const BTCUSDT_REPRICED: &str = r#"{"e":"bookTicker","u":7283946601072,"s":"BTCUSDT","b":"83604.805","B":"10.746","a":"83604.905","A":"9.514","T":1744760290969,"E":1744760290970}"#;
+
+#[test]
+fn detects_configs_once_per_symbol() {
+    let mut cache = ConfigCache::default();
+    assert!(cache.get("BTCUSDT").is_none());
+
+    let config = *cache.get_or_detect("BTCUSDT", BTCUSDT).unwrap();
+    assert_eq!(config, detect_config(BTCUSDT).unwrap());
+    assert_eq!(cache.get("BTCUSDT"), Some(&config));
+    assert_eq!(cache.parse("BTCUSDT", BTCUSDT).unwrap().b, "83604.80");
+
+    cache.evict("BTCUSDT");
+    assert!(cache.get("BTCUSDT").is_none());
+    assert_eq!(
+        cache.get_or_detect("BTCUSDT", "{}"),
+        Err(ParseError::FieldNotFound("s"))
+    );
+}
+
+// The reference parser doesn't take the precisions from the config.
+#[cfg(not(feature = "nom-parser"))]
+#[test]
+fn detects_again_on_changed_precision() {
+    let mut cache = ConfigCache::default();
+    cache.parse("BTCUSDT", BTCUSDT).unwrap();
+    let stale = *cache.get("BTCUSDT").unwrap();
+    assert_eq!(stale.price_precision, 2);
+    // Still points at `b`, so it would be kept by `get_or_detect` alone.
+    assert!(stale.matches(BTCUSDT_REPRICED));
+
+    let ticker = cache.parse("BTCUSDT", BTCUSDT_REPRICED).unwrap();
+    assert_eq!((ticker.b, ticker.a), ("83604.805", "83604.905"));
+    let fresh = *cache.get("BTCUSDT").unwrap();
+    assert_eq!(fresh, detect_config(BTCUSDT_REPRICED).unwrap());
+    assert_eq!(fresh.price_precision, 3);
+    assert_ne!(fresh, stale);
+
+    // And back again.
+    assert_eq!(cache.parse("BTCUSDT", BTCUSDT).unwrap().b, "83604.80");
+    assert_eq!(cache.get("BTCUSDT"), Some(&stale));
+}
+
+#[cfg(not(feature = "nom-parser"))]
+#[test]
+fn keeps_symbols_apart() {
+    let solusdt = r#"{"e":"bookTicker","u":7283946605501,"s":"SOLUSDT","b":"131.1500","B":"43","a":"131.1600","A":"388","T":1744760291201,"E":1744760291202}"#;
+    let mut cache = ConfigCache::default();
+    cache.parse("BTCUSDT", BTCUSDT).unwrap();
+    cache.parse("SOLUSDT", solusdt).unwrap();
+    assert_eq!(cache.get("BTCUSDT").unwrap().price_precision, 2);
+    assert_eq!(cache.get("SOLUSDT").unwrap().price_precision, 4);
+
+    cache.parse("BTCUSDT", BTCUSDT_REPRICED).unwrap();
+    assert_eq!(cache.get("BTCUSDT").unwrap().price_precision, 3);
+    assert_eq!(cache.get("SOLUSDT").unwrap().price_precision, 4);
+}
@@ -0,0 +1,36 @@
+mod common;
+
+use binance_json_parsing::{
+    columnar::{TickColumns, to_columns},
+    small_string::SmallString,
+};
+use common::owned_ticks;
+
+#[test]
+fn lays_ticks_out_in_columns() {
+    let ticks = owned_ticks();
+    let columns = to_columns(&ticks).unwrap();
+
+    assert_eq!(columns.len(), 1000);
+    for (i, tick) in ticks.iter().enumerate() {
+        assert_eq!(columns.T[i], tick.T);
+        assert_eq!(columns.b[i], tick.b.parse::<f64>().unwrap());
+        assert_eq!(columns.B[i], tick.B.parse::<f64>().unwrap());
+        assert_eq!(columns.a[i], tick.a.parse::<f64>().unwrap());
+        assert_eq!(columns.A[i], tick.A.parse::<f64>().unwrap());
+    }
+    assert_eq!(columns.b[0], 83604.90);
+    assert_eq!(columns.A[0], 5.230);
+}
+
+#[test]
+fn skips_rows_of_invalid_decimals() {
+    let mut ticks = owned_ticks();
+    let mut columns = TickColumns::new();
+    columns.push(&ticks[0]).unwrap();
+
+    ticks[1].A = SmallString::new("5.2.3").unwrap();
+    assert!(columns.push(&ticks[1]).is_err());
+    assert!(to_columns(&ticks[..2]).is_err());
+    assert_eq!(columns, to_columns(&ticks[..1]).unwrap());
+}
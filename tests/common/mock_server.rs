@@ -0,0 +1,220 @@
+//! Local stand-in for the Binance WebSocket streams, serving stored payloads and answering
+//! subscription requests.
+
+use std::{
+    net::SocketAddr,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    task::{JoinHandle, JoinSet},
+    time::Instant,
+};
+use tokio_tungstenite::{WebSocketStream, accept_async, tungstenite::Message};
+
+/// What the server does once every message has been sent on a connection.
+#[derive(Clone, Copy, Debug)]
+enum Mode {
+    /// Closes the connection, so that clients have to reconnect.
+    SendAllThenClose,
+    /// Starts over from the first message, until the client leaves. Without messages, only
+    /// answers requests.
+    SendLoop,
+}
+
+pub struct Builder {
+    messages: Vec<String>,
+    interval: Duration,
+    mode: Mode,
+}
+
+impl Builder {
+    pub fn messages(mut self, messages: Vec<String>) -> Self {
+        self.messages = messages;
+        self
+    }
+
+    /// Delay between two messages, none by default.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn send_all_then_close(mut self) -> Self {
+        self.mode = Mode::SendAllThenClose;
+        self
+    }
+
+    pub fn send_loop(mut self) -> Self {
+        self.mode = Mode::SendLoop;
+        self
+    }
+
+    /// Binds a random local port and starts serving, so must be called within a Tokio runtime.
+    pub fn build(self) -> MockBinanceServer {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let listener = TcpListener::from_std(listener).unwrap();
+
+        let connections = Arc::new(AtomicUsize::new(0));
+        let closes = Arc::new(AtomicUsize::new(0));
+        let messages: Arc<[String]> = self.messages.into();
+        let (interval, mode) = (self.interval, self.mode);
+        let accepted = connections.clone();
+        let closed = closes.clone();
+        let task = tokio::spawn(async move {
+            // Dropped along with the task, which aborts every connection still open.
+            let mut conns = JoinSet::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                accepted.fetch_add(1, Ordering::Relaxed);
+                let messages = messages.clone();
+                let closed = closed.clone();
+                conns.spawn(async move {
+                    let Ok(ws_stream) = accept_async(stream).await else {
+                        return;
+                    };
+                    serve(ws_stream, &messages, interval, mode, &closed).await;
+                });
+            }
+        });
+
+        MockBinanceServer {
+            addr,
+            connections,
+            closes,
+            task,
+        }
+    }
+}
+
+/// Sends `messages` while answering the requests the client sends meanwhile, counting the
+/// close frames it sends in `closes`.
+async fn serve(
+    ws_stream: WebSocketStream<TcpStream>,
+    messages: &[String],
+    interval: Duration,
+    mode: Mode,
+    closes: &AtomicUsize,
+) {
+    let (mut sink, mut stream) = ws_stream.split();
+    let mut subscriptions = Vec::new();
+    let mut next = 0;
+    let mut due = Instant::now();
+    loop {
+        if next == messages.len() {
+            match mode {
+                Mode::SendAllThenClose => {
+                    _ = sink.close().await;
+                    return;
+                }
+                // Left answering requests only, if there's nothing to send.
+                Mode::SendLoop => next = 0,
+            }
+        }
+        tokio::select! {
+            request = stream.next() => {
+                let Some(Ok(request)) = request else {
+                    return;
+                };
+                if request.is_close() {
+                    closes.fetch_add(1, Ordering::Relaxed);
+                    // Read on until the end, which sends the close frame answering it.
+                    continue;
+                }
+                let Ok(request) = request.to_text() else {
+                    continue;
+                };
+                let response = answer(request, &mut subscriptions);
+                if sink.send(Message::text(response)).await.is_err() {
+                    return;
+                }
+            }
+            _ = tokio::time::sleep_until(due), if !messages.is_empty() => {
+                if sink.send(Message::text(messages[next].as_str())).await.is_err() {
+                    return;
+                }
+                next += 1;
+                due = Instant::now() + interval;
+            }
+        }
+    }
+}
+
+/// Responds to a request like `{"method":"SUBSCRIBE","params":["btcusdt@bookTicker"],"id":1}`
+/// as Binance does, keeping track of the streams subscribed to.
+fn answer(request: &str, subscriptions: &mut Vec<String>) -> String {
+    let request: serde_json::Value = serde_json::from_str(request).unwrap();
+    let id = &request["id"];
+    let streams = request["params"].as_array().into_iter().flatten();
+    let streams: Vec<String> = streams
+        .filter_map(|s| s.as_str())
+        .map(str::to_owned)
+        .collect();
+    if let Some(invalid) = streams.iter().find(|stream| !stream.contains('@')) {
+        let msg = format!("Invalid request: invalid stream name {invalid}");
+        return serde_json::json!({"error": {"code": 2, "msg": msg}, "id": id}).to_string();
+    }
+
+    let result = match request["method"].as_str() {
+        Some("SUBSCRIBE") => {
+            subscriptions.extend(streams);
+            serde_json::Value::Null
+        }
+        Some("UNSUBSCRIBE") => {
+            subscriptions.retain(|stream| !streams.contains(stream));
+            serde_json::Value::Null
+        }
+        Some("LIST_SUBSCRIPTIONS") => serde_json::json!(subscriptions),
+        _ => {
+            let msg = "Invalid request: unknown method";
+            return serde_json::json!({"error": {"code": 2, "msg": msg}, "id": id}).to_string();
+        }
+    };
+    serde_json::json!({"result": result, "id": id}).to_string()
+}
+
+/// Serves the same messages on every connection, until dropped.
+pub struct MockBinanceServer {
+    addr: SocketAddr,
+    connections: Arc<AtomicUsize>,
+    closes: Arc<AtomicUsize>,
+    task: JoinHandle<()>,
+}
+
+impl MockBinanceServer {
+    pub fn builder() -> Builder {
+        Builder {
+            messages: Vec::new(),
+            interval: Duration::ZERO,
+            mode: Mode::SendAllThenClose,
+        }
+    }
+
+    /// Base URL to connect to, any path is served the same.
+    pub fn url(&self) -> String {
+        format!("ws://{}", self.addr)
+    }
+
+    /// How many connections were accepted so far.
+    pub fn connections(&self) -> usize {
+        self.connections.load(Ordering::Relaxed)
+    }
+
+    /// How many close frames clients sent so far.
+    pub fn closes(&self) -> usize {
+        self.closes.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for MockBinanceServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
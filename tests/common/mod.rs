@@ -0,0 +1,27 @@
+//! Helpers shared by the integration tests.
+// Each test crate only uses some of them.
+#![allow(dead_code)]
+
+#[cfg(feature = "ws")]
+pub mod mock_server;
+
+use binance_json_parsing::{BookTicker, BookTickerOwned, detect_config, parse_book_ticker_dynamic};
+
+/// Book tickers of a few symbols, one payload per line.
+pub const FIXTURE: &str = include_str!("../fixtures/book_ticker.jsonl");
+
+/// The 1000 tickers of [`FIXTURE`], each parsed with the config detected for it.
+pub fn ticks() -> Vec<BookTicker<'static>> {
+    FIXTURE
+        .lines()
+        .map(|json| parse_book_ticker_dynamic(json, detect_config(json).unwrap()).unwrap())
+        .collect()
+}
+
+/// Same as [`ticks`], owned.
+pub fn owned_ticks() -> Vec<BookTickerOwned> {
+    ticks()
+        .into_iter()
+        .map(|tick| tick.try_into().unwrap())
+        .collect()
+}
@@ -0,0 +1,51 @@
+use binance_json_parsing::{ParseError, composite_index::parse_composite_index};
+
+const DEFIUSDT: &str = r#"{"e":"compositeIndex","E":1602310596000,"s":"DEFIUSDT","p":"USDT","c":[{"b":"AAVE","w":"0.05707234","W":"5.70723400","i":"77.82000000"},{"b":"UNI","w":"1.51329000","W":"8.23742500","i":"5.99230000"},{"b":"YFI","w":"0.00003900","W":"4.91337100","i":"12125.30000000"}],"i":"4431.76000000"}"#;
+
+#[test]
+fn parses_components() {
+    let index = parse_composite_index(DEFIUSDT).unwrap();
+    assert_eq!(
+        (index.E, index.s, index.p, index.i),
+        (1602310596000, "DEFIUSDT", "USDT", "4431.76000000")
+    );
+    let components: Vec<_> = index
+        .components
+        .iter()
+        .map(|component| (component.b, component.w, component.W, component.i))
+        .collect();
+    assert_eq!(
+        components,
+        [
+            ("AAVE", "0.05707234", "5.70723400", "77.82000000"),
+            ("UNI", "1.51329000", "8.23742500", "5.99230000"),
+            ("YFI", "0.00003900", "4.91337100", "12125.30000000"),
+        ]
+    );
+
+    let empty =
+        r#"{"e":"compositeIndex","E":1602310596000,"s":"DEFIUSDT","p":"USDT","c":[],"i":"0"}"#;
+    let index = parse_composite_index(empty).unwrap();
+    assert!(index.components.is_empty());
+    assert_eq!(index.i, "0");
+}
+
+#[test]
+fn rejects_malformed_payloads() {
+    let book_ticker = r#"{"e":"bookTicker","u":7283946601071,"s":"BTCUSDT","b":"83604.80","B":"10.746","a":"83604.90","A":"9.514","T":1744760290967,"E":1744760290968}"#;
+    assert_eq!(
+        parse_composite_index(book_ticker),
+        Err(ParseError::WrongEventType)
+    );
+
+    // Cut anywhere, the array is either unterminated or followed by nothing.
+    for end in 0..DEFIUSDT.len() {
+        assert!(parse_composite_index(&DEFIUSDT[..end]).is_err(), "{end}");
+    }
+
+    let reordered = DEFIUSDT.replace(r#"{"b":"UNI","w""#, r#"{"w""#);
+    assert!(matches!(
+        parse_composite_index(&reordered),
+        Err(ParseError::InvalidBoundary { field: "b", .. })
+    ));
+}
@@ -0,0 +1,205 @@
+#![cfg(all(feature = "serde", feature = "ws"))]
+
+use std::{fs, io, path::PathBuf, time::Duration};
+
+use binance_json_parsing::{
+    ParsingConfig, SchemaVersion,
+    config::{Config, ConnectionConfig},
+};
+
+/// A path of its own for each test, removed when dropped.
+struct TempPath(PathBuf);
+
+impl TempPath {
+    fn new(name: &str) -> Self {
+        Self(std::env::temp_dir().join(format!("config-{}-{name}.toml", std::process::id())))
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        _ = fs::remove_file(&self.0);
+    }
+}
+
+fn customized() -> Config {
+    Config {
+        connection: ConnectionConfig {
+            url: "wss://fstream.binance.com/ws/ethusdt@bookTicker".to_owned(),
+            initial_backoff_ms: 250,
+            max_backoff_ms: 60_000,
+            jitter: 0.5,
+            max_retries: Some(5),
+            ping_interval_ms: 0,
+            pong_timeout_ms: 3000,
+            deflate: true,
+        },
+        parsing: ParsingConfig::new(51, 2, 3, 13, 7, SchemaVersion::Spot),
+        validate_every: 0,
+    }
+}
+
+#[test]
+fn round_trips_configs() {
+    for config in [Config::default(), customized()] {
+        let text = config.to_toml();
+        assert_eq!(Config::from_toml(&text).unwrap(), config, "{text}");
+    }
+
+    let path = TempPath::new("round_trip");
+    fs::write(&path.0, customized().to_toml()).unwrap();
+    assert_eq!(Config::load(&path.0).unwrap(), customized());
+    assert_eq!(
+        customized().connection.reconnect().ping_interval,
+        None,
+        "0 is never"
+    );
+}
+
+#[test]
+fn keeps_defaults_of_what_is_left_out() {
+    let config = Config::from_toml(
+        r#"
+# Only the symbol differs.
+[connection]
+url = "wss://fstream.binance.com/ws/ethusdt@bookTicker" # trailing comments too
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        config.connection.url,
+        "wss://fstream.binance.com/ws/ethusdt@bookTicker"
+    );
+    assert_eq!(
+        config.connection.reconnect().ping_interval,
+        Some(Duration::from_secs(20))
+    );
+    let defaults = Config::default();
+    assert_eq!(config.parsing, defaults.parsing);
+    assert_eq!(config.validate_every, defaults.validate_every);
+    assert_eq!(Config::from_toml("").unwrap(), defaults);
+}
+
+#[test]
+fn reads_toml_values() {
+    let config = Config::from_toml(
+        r#"
+validate_every = 1_000_000
+
+[connection]
+url = 'C:\not\escaped'
+jitter = 1e-1
+max_backoff_ms = +30_000
+
+# Taken as a whole, its fields have no defaults of their own.
+[parsing]
+start = 51
+price_precision = 2
+volume_precision = 3
+transaction_time_digits = 13
+symbol_len = 7
+schema_version = "coin_m_futures"
+"#,
+    )
+    .unwrap();
+    assert_eq!(config.validate_every, 1_000_000);
+    assert_eq!(config.connection.url, r"C:\not\escaped");
+    assert_eq!(config.connection.jitter, 0.1);
+    assert_eq!(config.connection.max_backoff_ms, 30_000);
+    assert_eq!(config.parsing.schema_version, SchemaVersion::CoinMFutures);
+
+    // Quotes in a comment are fine.
+    let config = Config::from_toml("[connection] # it's quoted\nurl = '\"'").unwrap();
+    assert_eq!(config.connection.url, "\"");
+    let config = Config::from_toml("[connection]\nurl = \"\\\"\\u00e9\\t\\\\\"").unwrap();
+    assert_eq!(config.connection.url, "\"é\t\\");
+}
+
+#[test]
+fn reports_the_line_of_errors() {
+    let cases = [
+        ("[connection\n", "line 1: unclosed table header"),
+        ("\n\nvalidate_every = ", "line 3: invalid value"),
+        (
+            "[parsing]\nstart = 1\nstart = 2",
+            "line 3: key defined twice",
+        ),
+        ("[parsing]\n[parsing]", "line 2: table defined twice"),
+        ("[connection]\nurl = \"wss://", "line 2: unclosed string"),
+        ("[connection]\nurl = \"\\q\"", "line 2: invalid escape"),
+        ("validate_every = 01", "line 1: invalid value"),
+        ("validate_every = 1__0", "line 1: invalid value"),
+        (
+            "validate_every = 1 2",
+            "line 1: unexpected characters after the value",
+        ),
+        (
+            "[[connection]]",
+            "line 1: arrays of tables aren't supported",
+        ),
+        ("validate_every", "line 1: expected `key = value`"),
+        // The TOML that isn't supported, which fails rather than being misread.
+        (
+            "[connection]\n\"url\" = \"wss://\"",
+            "line 2: quoted keys aren't supported",
+        ),
+        (
+            "'validate_every' = 1",
+            "line 1: quoted keys aren't supported",
+        ),
+        (
+            "[\"connection\"]\nurl = \"wss://\"",
+            "line 1: quoted keys aren't supported",
+        ),
+        (
+            "[connection]\n\n[[connection.urls]]",
+            "line 3: arrays of tables aren't supported",
+        ),
+        (
+            "connection.url = \"wss://\"",
+            "line 1: dotted keys aren't supported",
+        ),
+        (
+            "[connection]\nurl = \"\"\"\nwss://\n\"\"\"",
+            "line 2: multiline strings aren't supported",
+        ),
+        (
+            "[connection]\nurl = '''wss://'''",
+            "line 2: multiline strings aren't supported",
+        ),
+        (
+            "[connection]\nurl = [\"wss://\"]",
+            "line 2: arrays and inline tables aren't supported",
+        ),
+        (
+            "connection = { url = \"wss://\" }",
+            "line 1: arrays and inline tables aren't supported",
+        ),
+        ("validate_every = 0x10", "line 1: invalid value"),
+        ("validate_every = inf", "line 1: invalid value"),
+    ];
+    for (text, msg) in cases {
+        let err = Config::from_toml(text).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{text:?}");
+        assert_eq!(err.to_string(), msg, "{text:?}");
+    }
+
+    // Valid TOML, but not a valid config.
+    let err = Config::from_toml("validate_every = -1").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    for jitter in ["1.5", "-0.1"] {
+        let err = Config::from_toml(&format!("[connection]\njitter = {jitter}")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            format!("jitter of {jitter} is not between 0 and 1")
+        );
+    }
+    assert_eq!(
+        Config::from_toml("[connection]\njitter = 1.0")
+            .unwrap()
+            .connection
+            .jitter,
+        1.0
+    );
+}
@@ -0,0 +1,108 @@
+#![cfg(feature = "ws")]
+
+mod common;
+
+use std::{fs, path::PathBuf, time::Duration};
+
+use binance_json_parsing::{
+    BookTicker,
+    csv::{CsvSink, FlushPolicy},
+    handler::TickHandler,
+};
+use common::ticks;
+
+/// A path of its own for each test, removed when dropped.
+struct TempPath(PathBuf);
+
+impl TempPath {
+    fn new(name: &str) -> Self {
+        Self(std::env::temp_dir().join(format!("csv-{}-{name}.csv", std::process::id())))
+    }
+
+    /// The rows flushed so far, past the header which is only flushed along with the first.
+    fn rows(&self) -> Vec<String> {
+        let csv = fs::read_to_string(&self.0).unwrap();
+        if csv.is_empty() {
+            return Vec::new();
+        }
+        let mut lines = csv.lines().map(str::to_owned);
+        assert_eq!(lines.next().as_deref(), Some("T,b,B,a,A"));
+        lines.collect()
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        _ = fs::remove_file(&self.0);
+    }
+}
+
+fn row(tick: &BookTicker<'_>) -> String {
+    format!("{},{},{},{},{}", tick.T, tick.b, tick.B, tick.a, tick.A)
+}
+
+#[tokio::test]
+async fn round_trips_ticks() {
+    let path = TempPath::new("round_trip");
+    let ticks = ticks();
+    let mut sink = CsvSink::create(&path.0, FlushPolicy::default()).unwrap();
+    for tick in &ticks {
+        sink.on_tick(*tick);
+    }
+    sink.flush().unwrap();
+
+    let rows = path.rows();
+    assert_eq!(rows.len(), ticks.len());
+    for (row, tick) in rows.iter().zip(&ticks) {
+        let fields: Vec<&str> = row.split(',').collect();
+        assert_eq!(
+            fields,
+            [tick.T.to_string().as_str(), tick.b, tick.B, tick.a, tick.A]
+        );
+    }
+}
+
+#[tokio::test]
+async fn flushes_every_rows() {
+    let path = TempPath::new("every_rows");
+    let ticks = ticks();
+    let policy = FlushPolicy {
+        every_rows: 100,
+        every: Duration::from_secs(3600),
+    };
+    let mut sink = CsvSink::create(&path.0, policy).unwrap();
+    for (i, tick) in ticks.iter().enumerate() {
+        sink.on_tick(*tick);
+        // Rows only reach the file by the hundred.
+        assert_eq!(path.rows().len(), (i + 1) / 100 * 100, "row {i}");
+    }
+    assert_eq!(path.rows(), ticks.iter().map(row).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn flushes_every_interval() {
+    let path = TempPath::new("every");
+    let ticks = ticks();
+    let policy = FlushPolicy {
+        every_rows: usize::MAX,
+        every: Duration::from_millis(50),
+    };
+    let mut sink = CsvSink::create(&path.0, policy).unwrap();
+    for tick in &ticks[..10] {
+        sink.on_tick(*tick);
+    }
+    assert!(path.rows().is_empty());
+
+    // Not on its own, only on the first row after the interval.
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert!(path.rows().is_empty());
+    sink.on_tick(ticks[10]);
+    assert_eq!(path.rows(), ticks[..11].iter().map(row).collect::<Vec<_>>());
+
+    for tick in &ticks[11..] {
+        sink.on_tick(*tick);
+    }
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    sink.on_tick(ticks[0]);
+    assert_eq!(path.rows().len(), ticks.len() + 1);
+}
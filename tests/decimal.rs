@@ -0,0 +1,247 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::{BuildHasher, RandomState},
+};
+
+use binance_json_parsing::{
+    ParseError,
+    decimal::{FixedPoint, MAX_SIGNIFICANT_DIGITS, parse_fixed},
+};
+
+/// Decimals written out here are always valid.
+fn fixed(s: &str) -> FixedPoint {
+    parse_fixed(s).unwrap()
+}
+
+#[test]
+fn normalizes_trailing_zeros() {
+    let normalized = FixedPoint {
+        mantissa: 1,
+        exponent: 0,
+    };
+    assert_eq!(
+        FixedPoint {
+            mantissa: 100,
+            exponent: -2
+        }
+        .normalize(),
+        normalized
+    );
+    assert_eq!(fixed("1.000").normalize(), normalized);
+    assert_eq!(
+        fixed("83604.80").normalize(),
+        FixedPoint {
+            mantissa: 836048,
+            exponent: -1
+        }
+    );
+    assert_eq!(
+        fixed("0.000").normalize(),
+        FixedPoint {
+            mantissa: 0,
+            exponent: 0
+        }
+    );
+}
+
+#[test]
+fn hashes_equal_values_equally() {
+    let hasher = RandomState::new();
+    for (lhs, rhs) in [("1.00", "1.000"), ("83604.80", "83604.8"), ("0", "-0.00")] {
+        let (lhs, rhs) = (fixed(lhs), fixed(rhs));
+        assert_eq!(lhs, rhs);
+        assert_eq!(hasher.hash_one(lhs), hasher.hash_one(rhs));
+    }
+
+    let mut levels = HashMap::new();
+    levels.insert(fixed("83604.80"), fixed("10.746"));
+    levels.insert(fixed("83604.8"), fixed("9.514"));
+    assert_eq!(levels.len(), 1);
+    assert_eq!(levels[&fixed("83604.800")], fixed("9.514"));
+}
+
+#[test]
+fn iterates_prices_in_ascending_order() {
+    let prices = [
+        "83605.5",
+        "83604.80",
+        "0.0074136",
+        "83604.9",
+        "1589.37",
+        "-1.5",
+    ];
+    let book: BTreeMap<_, _> = prices.iter().map(|price| (fixed(price), *price)).collect();
+
+    assert_eq!(
+        book.into_values().collect::<Vec<_>>(),
+        [
+            "-1.5",
+            "0.0074136",
+            "1589.37",
+            "83604.80",
+            "83604.9",
+            "83605.5"
+        ]
+    );
+}
+
+#[test]
+fn multiplies_and_negates() {
+    let notional = fixed("83604.80").checked_mul(fixed("0.014")).unwrap();
+    assert_eq!(notional, fixed("1170.4672"));
+    assert_eq!(notional.to_string(), "1170.46720");
+    assert_eq!(-fixed("0.014"), fixed("-0.014"));
+    assert_eq!(-fixed("-0.014"), fixed("0.014"));
+}
+
+#[test]
+fn multiplies_past_the_range_of_the_mantissa() {
+    // 36 digits, the lowest of which are dropped.
+    let max = fixed("999999999999999999");
+    let square = max.checked_mul(max).unwrap();
+    assert_eq!(square.to_string(), "999999999999999998000000000000000000");
+    assert_eq!(max.checked_mul(-max).unwrap(), -square);
+
+    // Below 10^-128, nothing is left.
+    let tiny = fixed(&format!("0.{}1", "0".repeat(99)));
+    assert_eq!(tiny.exponent, -100);
+    assert_eq!(tiny.checked_mul(tiny), Some(fixed("0")));
+
+    let huge = FixedPoint {
+        mantissa: 1,
+        exponent: 100,
+    };
+    assert_eq!(huge.checked_mul(huge), None);
+    assert_eq!(huge.checked_mul(tiny), Some(fixed("1")));
+}
+
+#[test]
+fn rejects_malformed_decimals() {
+    for malformed in [
+        "",
+        "-",
+        "10.74a",
+        "0.00010e00",
+        "1.2.3",
+        ".5",
+        "1.",
+        "-.5",
+        "+1",
+        " 1",
+        "1,5",
+    ] {
+        assert_eq!(
+            parse_fixed(malformed),
+            Err(ParseError::InvalidDecimal),
+            "{malformed}"
+        );
+    }
+}
+
+#[test]
+fn limits_significant_digits() {
+    let max = "9".repeat(MAX_SIGNIFICANT_DIGITS);
+    assert_eq!(fixed(&max).mantissa, 999_999_999_999_999_999);
+    assert_eq!(fixed(&format!("-{max}")).mantissa, -999_999_999_999_999_999);
+    // Would overflow the mantissa.
+    for too_long in ["99999999999999999999", "1234567890.123456789"] {
+        assert_eq!(
+            parse_fixed(too_long),
+            Err(ParseError::InvalidDecimal),
+            "{too_long}"
+        );
+    }
+
+    // Leading zeros don't count.
+    assert_eq!(
+        fixed("0.00000000000000000001"),
+        FixedPoint {
+            mantissa: 1,
+            exponent: -20
+        }
+    );
+    assert_eq!(
+        fixed(&format!("000000000{max}")).mantissa,
+        999_999_999_999_999_999
+    );
+}
+
+#[test]
+fn displays_tiny_exponents() {
+    let display = |mantissa, exponent| FixedPoint { mantissa, exponent }.to_string();
+    assert_eq!(display(1, -19), "0.0000000000000000001");
+    assert_eq!(display(1, -20), "0.00000000000000000001");
+    assert_eq!(display(-123, -25), "-0.0000000000000000000000123");
+    assert_eq!(display(i64::MAX, -20), "0.09223372036854775807");
+    assert_eq!(display(i64::MIN, -128).len(), "-0.".len() + 128);
+    assert_eq!(
+        fixed("0.00000000000000000001").to_string(),
+        "0.00000000000000000001"
+    );
+    assert_eq!(display(1, -20).parse::<f64>().unwrap(), 1e-20);
+    assert!(
+        (FixedPoint {
+            mantissa: 1,
+            exponent: -20
+        }
+        .to_f64()
+            / 1e-20
+            - 1.0)
+            .abs()
+            < 1e-12
+    );
+    assert!(
+        (FixedPoint {
+            mantissa: 3,
+            exponent: 25
+        }
+        .to_f64()
+            / 3e25
+            - 1.0)
+            .abs()
+            < 1e-12
+    );
+}
+
+#[test]
+fn compares_far_apart_exponents() {
+    let value = |mantissa, exponent| FixedPoint { mantissa, exponent };
+    let (huge, tiny) = (value(1, 100), value(i64::MAX, -100));
+    assert!(huge > tiny);
+    assert!(-huge < -tiny);
+    assert!(tiny > value(0, 0));
+    assert!(-tiny < value(0, 127));
+    assert!(value(1, -128) < value(1, 127));
+    assert!(value(9, 20) < value(1, 21));
+    // Equal however far apart their exponents are written.
+    assert_eq!(value(1, 18), value(1_000_000_000_000_000_000, 0));
+    assert_eq!(value(1, 0), value(1_000_000_000_000_000_000, -18));
+    assert_eq!(value(0, -128), value(0, 127));
+    assert!(value(999_999_999_999_999_999, -18) < value(1, 0));
+}
+
+#[test]
+fn adds_and_subtracts_far_apart_exponents() {
+    let value = |mantissa, exponent| FixedPoint { mantissa, exponent };
+    let one = fixed("1");
+
+    // Too far below to change the sum, rather than overflowing the scaled mantissa.
+    for tiny in [value(1, -30), value(i64::MAX, -100), value(-5, -128)] {
+        assert_eq!(one + tiny, one, "{tiny:?}");
+        assert_eq!(one - tiny, one, "{tiny:?}");
+        assert_eq!(value(1, 100) + tiny, value(1, 100), "{tiny:?}");
+    }
+    // Still exact within 18 digits.
+    assert_eq!(one + value(1, -18), value(1_000_000_000_000_000_001, -18));
+    assert_eq!(one - value(1, -18), value(999_999_999_999_999_999, -18));
+    assert_eq!(
+        value(1, 10) + value(5, -8),
+        value(1_000_000_000_000_000_005, -8)
+    );
+
+    // The lowest digits dropped once the mantissa overflows, instead of wrapping around.
+    let max = value(i64::MAX, 0);
+    assert_eq!(max + max, value(i64::MAX / 5, 1));
+    assert_eq!(-max - max, value(-(i64::MAX / 5), 1));
+    assert!(max + max > max);
+}
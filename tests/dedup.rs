@@ -0,0 +1,23 @@
+use binance_json_parsing::{ParsingConfig, dedup::Deduplicator, parse_book_ticker_dynamic};
+
+const BTCUSDT: &str = r#"{"e":"bookTicker","u":7283946601071,"s":"BTCUSDT","b":"83604.80","B":"10.746","a":"83604.90","A":"9.514","T":1744760290967,"E":1744760290968}"#;
+
+#[test]
+fn passes_only_first_of_identical_ticks() {
+    // Re-sent with a different event time and quantities, which don't make it a new tick.
+    let resent = BTCUSDT
+        .replace("10.746", "10.700")
+        .replace("1744760290968", "1744760290970");
+    let changed = BTCUSDT.replace("83604.90", "83605.00");
+
+    let mut dedup = Deduplicator::new();
+    let passed: Vec<_> = [BTCUSDT, &resent, &changed, BTCUSDT]
+        .into_iter()
+        .filter(|json| {
+            let tick = parse_book_ticker_dynamic(json, ParsingConfig::default()).unwrap();
+            !dedup.is_duplicate(&tick)
+        })
+        .collect();
+
+    assert_eq!(passed, [BTCUSDT, &changed, BTCUSDT]);
+}
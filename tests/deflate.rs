@@ -0,0 +1,235 @@
+//! Inflating what zlib compressed, the fixture being the book ticker one sent as with
+//! `permessage-deflate`: one raw deflate stream across the messages, each sync flushed with its
+//! `00 00 ff ff` left out, prefixed by its length in little-endian `u32`.
+
+use binance_json_parsing::deflate::{InflateError, Inflater};
+
+const FIXTURE: &str = include_str!("fixtures/book_ticker.jsonl");
+const COMPRESSED: &[u8] = include_bytes!("fixtures/book_ticker.deflate");
+
+fn messages() -> Vec<&'static [u8]> {
+    let mut messages = Vec::new();
+    let mut rest = COMPRESSED;
+    while let Some((len, tail)) = rest.split_first_chunk::<4>() {
+        let (message, tail) = tail.split_at(u32::from_le_bytes(*len) as usize);
+        messages.push(message);
+        rest = tail;
+    }
+    messages
+}
+
+fn hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+fn inflate(inflater: &mut Inflater, payload: &[u8]) -> Result<Vec<u8>, InflateError> {
+    let mut out = Vec::new();
+    inflater.inflate_message(payload, &mut out, 1 << 20)?;
+    Ok(out)
+}
+
+#[test]
+fn inflates_messages_referring_back_to_earlier_ones() {
+    let messages = messages();
+    assert_eq!(messages.len(), FIXTURE.lines().count());
+    // Most of each ticker is a match into the previous ones.
+    assert!(COMPRESSED.len() * 3 < FIXTURE.len());
+
+    let mut inflater = Inflater::new();
+    for (message, json) in messages.iter().zip(FIXTURE.lines()) {
+        assert_eq!(inflate(&mut inflater, message).unwrap(), json.as_bytes());
+    }
+}
+
+#[test]
+fn forgets_earlier_messages_on_reset() {
+    let messages = messages();
+    let mut inflater = Inflater::new();
+    inflate(&mut inflater, messages[0]).unwrap();
+    inflater.reset();
+    assert!(matches!(
+        inflate(&mut inflater, messages[1]),
+        Err(InflateError::DistanceTooFar { .. })
+    ));
+
+    // Starting over from the first is fine.
+    let first = FIXTURE.lines().next().unwrap();
+    assert_eq!(
+        inflate(&mut inflater, messages[0]).unwrap(),
+        first.as_bytes()
+    );
+}
+
+#[test]
+fn inflates_every_block_type() {
+    let mut inflater = Inflater::new();
+    // Fixed Huffman codes, as zlib's `Z_FIXED` strategy makes them.
+    let fixed = hex(
+        "aa564a55b2524acacfcf0ec94cce4e2d52d2512a55b23237b230b6343133333034b6b0d4512a062a710a710e\
+         0d760901ca27017916c66606267a960640ae13906ba867626102642742a54cf50c40528e40aea99e913188\
+         1da26465686e62626e66606469606966a1a3e48a26520b00",
+    );
+    let first = FIXTURE.lines().next().unwrap();
+    assert_eq!(inflate(&mut inflater, &fixed).unwrap(), first.as_bytes());
+
+    inflater.reset();
+    // Stored, at level 0.
+    assert_eq!(
+        inflate(&mut inflater, &hex("000600f9ff73746f72656400")).unwrap(),
+        b"stored"
+    );
+    // A final block, ending the stream before the tail.
+    assert_eq!(
+        inflate(&mut inflater, &hex("4b4c4a1c161000")).unwrap(),
+        b"ab".repeat(100)
+    );
+    // Empty messages, with or without their block.
+    assert_eq!(inflate(&mut inflater, &[0x00]).unwrap(), b"");
+    assert_eq!(inflate(&mut inflater, &[]).unwrap(), b"");
+}
+
+#[test]
+fn rejects_malformed_streams() {
+    let mut inflater = Inflater::new();
+    let cases: [(&[u8], InflateError); 4] = [
+        (&[0x07], InflateError::InvalidBlockType),
+        (
+            &[0x01, 0x05, 0x00, 0x00, 0x00],
+            InflateError::InvalidStoredLength,
+        ),
+        // Longer than everything left, the tail included.
+        (
+            &[0x01, 0x10, 0x00, 0xef, 0xff, b'a'],
+            InflateError::Truncated,
+        ),
+        // "hello world" compressed against itself as a preset dictionary.
+        (
+            &hex("ca40300100"),
+            InflateError::DistanceTooFar { distance: 11 },
+        ),
+    ];
+    for (payload, err) in cases {
+        assert_eq!(inflate(&mut inflater, payload), Err(err), "{payload:02x?}");
+    }
+
+    let mut out = Vec::new();
+    assert_eq!(
+        inflater.inflate_message(&hex("4b4c4a1c161000"), &mut out, 199),
+        Err(InflateError::TooLong { max: 199 })
+    );
+    assert_eq!(
+        InflateError::TooLong { max: 199 }.to_string(),
+        "message inflates to over 199 bytes"
+    );
+
+    // Cut short anywhere, a message fails one way or another, rather than panicking.
+    let message = messages()[0];
+    for len in 1..message.len() {
+        let mut inflater = Inflater::new();
+        let mut out = Vec::new();
+        if inflater
+            .inflate_message(&message[..len], &mut out, 1 << 20)
+            .is_ok()
+        {
+            assert_ne!(out, FIXTURE.lines().next().unwrap().as_bytes());
+        }
+    }
+}
+
+/// A server agreeing to `permessage-deflate` and sending the fixture compressed, written frame
+/// by frame since tungstenite can't, in chunks of a few bytes so that frames come in cut up.
+/// Returns the extensions the client offered.
+#[cfg(feature = "ws")]
+async fn deflate_server(listener: tokio::net::TcpListener) -> String {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+
+    let (mut stream, _) = listener.accept().await.unwrap();
+    let mut request = Vec::new();
+    while !request.ends_with(b"\r\n\r\n") {
+        request.push(stream.read_u8().await.unwrap());
+    }
+    let request = String::from_utf8(request).unwrap();
+    let header = |name: &str| {
+        request
+            .lines()
+            .filter_map(|line| line.split_once(": "))
+            .find_map(|(key, value)| key.eq_ignore_ascii_case(name).then_some(value))
+    };
+    let key = header("Sec-WebSocket-Key").unwrap();
+    let mut out = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+         Sec-WebSocket-Accept: {}\r\nSec-WebSocket-Extensions: permessage-deflate\r\n\r\n",
+        derive_accept_key(key.as_bytes())
+    )
+    .into_bytes();
+
+    let frame = |out: &mut Vec<u8>, first: u8, payload: &[u8]| {
+        out.push(first);
+        match payload.len() {
+            len @ 0..126 => out.push(len as u8),
+            len => {
+                out.push(126);
+                out.extend_from_slice(&(len as u16).to_be_bytes());
+            }
+        }
+        out.extend_from_slice(payload);
+    };
+    for (i, message) in messages().into_iter().enumerate() {
+        match i {
+            // Over two frames with a ping in between, which may come amid a message.
+            3 => {
+                let (head, tail) = message.split_at(message.len() / 2);
+                frame(&mut out, 0x41, head);
+                frame(&mut out, 0x89, b"");
+                frame(&mut out, 0x80, tail);
+            }
+            _ => frame(&mut out, 0xc1, message),
+        }
+        // Uncompressed, with no effect on the context of the others.
+        if i == 10 {
+            frame(&mut out, 0x81, b"plain");
+        }
+    }
+    for chunk in out.chunks(7) {
+        stream.write_all(chunk).await.unwrap();
+    }
+    // Open until the client is done, as closing with its pong unread would reset the
+    // connection.
+    _ = stream.read_to_end(&mut Vec::new()).await;
+    let offer = header("Sec-WebSocket-Extensions").map(str::to_owned);
+    offer.unwrap_or_default()
+}
+
+#[cfg(feature = "ws")]
+#[tokio::test]
+async fn receives_compressed_messages() {
+    use binance_json_parsing::reconnect::{ReconnectConfig, ReconnectingStream};
+    use futures_util::{StreamExt, TryStreamExt, future};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let server = tokio::spawn(deflate_server(listener));
+
+    let config = ReconnectConfig {
+        deflate: true,
+        // Failing rather than reconnecting to a server that's gone.
+        max_retries: Some(0),
+        ..ReconnectConfig::default()
+    };
+    let mut expected: Vec<&str> = FIXTURE.lines().collect();
+    expected.insert(11, "plain");
+    let received: Vec<String> = ReconnectingStream::new(url, config)
+        .try_filter(|msg| future::ready(msg.is_text()))
+        .map_ok(|msg| msg.into_text().unwrap().to_string())
+        .take(expected.len())
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(received, expected);
+
+    assert_eq!(server.await.unwrap(), "permessage-deflate");
+}
@@ -0,0 +1,124 @@
+use binance_json_parsing::{
+    ParseError,
+    decimal::{FixedPoint, parse_fixed},
+    depth::{DepthConfig, DepthUpdate, iter_levels, parse_depth_update},
+};
+
+const CONFIG: DepthConfig = DepthConfig::new(56, 7, 13);
+
+fn payload(bids: &str, asks: &str) -> String {
+    format!(
+        r#"{{"e":"depthUpdate","E":1744760290967,"T":1744760290965,"s":"BTCUSDT","U":7283946601071,"u":7283946601420,"pu":7283946600990,"b":{bids},"a":{asks}}}"#
+    )
+}
+
+fn level(price: &str, qty: &str) -> (FixedPoint, FixedPoint) {
+    (parse_fixed(price).unwrap(), parse_fixed(qty).unwrap())
+}
+
+#[test]
+fn parses_depth_updates() {
+    let json = payload(
+        r#"[["83604.80","10.746"],["83604.70","0.004"]]"#,
+        r#"[["83604.90","9.514"]]"#,
+    );
+    let DepthUpdate {
+        symbol,
+        first_update_id,
+        last_update_id,
+        bids,
+        asks,
+        ..
+    } = parse_depth_update(&json, CONFIG).unwrap();
+    assert_eq!(symbol, "BTCUSDT");
+    assert_eq!(
+        (first_update_id, last_update_id),
+        (7283946601071, 7283946601420)
+    );
+    assert_eq!(
+        iter_levels(bids).collect::<Result<Vec<_>, _>>().unwrap(),
+        [level("83604.80", "10.746"), level("83604.70", "0.004")]
+    );
+    assert_eq!(
+        iter_levels(asks).collect::<Result<Vec<_>, _>>().unwrap(),
+        [level("83604.90", "9.514")]
+    );
+}
+
+#[test]
+fn parses_empty_level_arrays() {
+    let json = payload("[]", "[]");
+    let update = parse_depth_update(&json, CONFIG).unwrap();
+    assert_eq!((update.bids, update.asks), ("[]", "[]"));
+    assert_eq!(iter_levels(update.bids).count(), 0);
+
+    let json = payload("[]", r#"[["83604.90","9.514"]]"#);
+    let update = parse_depth_update(&json, CONFIG).unwrap();
+    assert_eq!(update.bids, "[]");
+    assert_eq!(iter_levels(update.asks).count(), 1);
+}
+
+#[test]
+fn rejects_truncated_updates() {
+    let json = payload(r#"[["83604.80","10.746"]]"#, r#"[["83604.90","9.514"]]"#);
+    for len in 0..json.len() - 1 {
+        assert!(
+            parse_depth_update(&json[..len], CONFIG).is_err(),
+            "{}",
+            &json[..len]
+        );
+    }
+    assert_eq!(
+        parse_depth_update(&json, DepthConfig::new(56, 7, 12)),
+        Err(ParseError::InvalidBoundary {
+            field: "U",
+            pos: 85
+        })
+    );
+}
+
+#[test]
+fn rejects_bad_levels() {
+    let errors = |raw| iter_levels(raw).filter_map(Result::err).collect::<Vec<_>>();
+    assert_eq!(
+        errors(r#"[["83604.80","10.7a6"]]"#),
+        [ParseError::InvalidDecimal]
+    );
+    // The walk ends on the first bad pair.
+    assert_eq!(
+        errors(r#"[["83604.8.0","10.746"],["83604.70","0.004"]]"#),
+        [ParseError::InvalidDecimal]
+    );
+    assert_eq!(
+        errors(r#"[["83604.80"],["83604.70","0.004"]]"#),
+        [ParseError::InvalidBoundary {
+            field: "level",
+            pos: 11
+        }]
+    );
+    assert_eq!(
+        errors(r#"[["83604.80", "10.746"]]"#),
+        [ParseError::InvalidBoundary {
+            field: "level",
+            pos: 11
+        }]
+    );
+    assert_eq!(
+        errors(r#"[["83604.80"€"10.746"]]"#),
+        [ParseError::InvalidBoundary {
+            field: "level",
+            pos: 11
+        }]
+    );
+    assert_eq!(
+        errors(r#"[["83604.80","10.7"#),
+        [ParseError::FieldNotFound("level")]
+    );
+    assert_eq!(errors(r#"[["83604"#), [ParseError::FieldNotFound("level")]);
+    for raw in [r#"[["83604.80","10.746"]]"#, r#"[["83604.80","10.746"],["#] {
+        for len in 0..raw.len() {
+            // Whatever is left, never a panic.
+            let _ = iter_levels(&raw[..len]).count();
+        }
+    }
+}
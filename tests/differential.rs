@@ -0,0 +1,102 @@
+//! The positional parser against general purpose JSON ones and the reference one, on recorded
+//! payloads of several symbols with different precisions, and on decimals with leading zeros.
+#![cfg(feature = "serde")]
+
+use binance_json_parsing::{
+    BookTickerOwned,
+    decimal::{FixedPoint, parse_fixed},
+    detect_config, parse_book_ticker_dynamic, parse_book_ticker_nom,
+};
+
+/// Each with the number of payloads it should hold.
+const FIXTURES: [(&str, &str, usize); 2] = [
+    (
+        "book_ticker.jsonl",
+        include_str!("fixtures/book_ticker.jsonl"),
+        1000,
+    ),
+    // Quantities like "0.001" and prices like "0.00100000" of inverse contracts, with a single
+    // integer digit.
+    (
+        "leading_zeros.jsonl",
+        include_str!("fixtures/leading_zeros.jsonl"),
+        8,
+    ),
+];
+
+fn payloads(fixture: &'static str) -> impl Iterator<Item = &'static str> {
+    fixture.lines().filter(|line| !line.is_empty())
+}
+
+fn parse(json: &str) -> BookTickerOwned {
+    let config = detect_config(json).unwrap_or_else(|err| panic!("{err:?} detecting {json}"));
+    parse_book_ticker_dynamic(json, config)
+        .and_then(BookTickerOwned::try_from)
+        .unwrap_or_else(|err| panic!("{err:?} parsing {json} with {config:?}"))
+}
+
+/// Panics with every payload that the parsers disagree on, rather than just the first one.
+fn check_against(name: &str, reference: impl Fn(&str) -> BookTickerOwned) {
+    for (fixture_name, fixture, expected_count) in FIXTURES {
+        let mut count = 0;
+        let mismatches: Vec<_> = payloads(fixture)
+            .inspect(|_| count += 1)
+            .filter_map(|json| {
+                let ours = parse(json);
+                let theirs = reference(json);
+                (ours != theirs).then(|| {
+                    format!("{json}\n  parse_book_ticker_dynamic: {ours:?}\n  {name}: {theirs:?}")
+                })
+            })
+            .collect();
+
+        assert_eq!(
+            count, expected_count,
+            "{fixture_name} should hold {expected_count} payloads"
+        );
+        assert!(
+            mismatches.is_empty(),
+            "{} of {count} payloads of {fixture_name} parsed differently:\n{}",
+            mismatches.len(),
+            mismatches.join("\n")
+        );
+    }
+}
+
+#[test]
+fn agrees_with_serde_json() {
+    check_against("serde_json", |json| serde_json::from_str(json).unwrap());
+}
+
+#[cfg(feature = "sonic")]
+#[test]
+fn agrees_with_sonic_rs() {
+    check_against("sonic_rs", |json| sonic_rs::from_str(json).unwrap());
+}
+
+#[test]
+fn agrees_with_nom_parser() {
+    check_against("parse_book_ticker_nom", |json| {
+        parse_book_ticker_nom(json)
+            .and_then(BookTickerOwned::try_from)
+            .unwrap()
+    });
+}
+
+#[test]
+fn keeps_leading_zeros() {
+    let tickers: Vec<_> = payloads(FIXTURES[1].1).map(parse).collect();
+    let (first, inverse) = (&tickers[0], &tickers[3]);
+    assert_eq!((first.B.as_str(), first.A.as_str()), ("0.001", "0.100"));
+    assert_eq!(
+        (inverse.b.as_str(), inverse.a.as_str()),
+        ("0.00100000", "0.00100010")
+    );
+    assert_eq!(
+        parse_fixed(inverse.b.as_str()).unwrap(),
+        FixedPoint {
+            mantissa: 100000,
+            exponent: -8
+        }
+    );
+}
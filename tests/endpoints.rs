@@ -0,0 +1,189 @@
+//! Payloads of every market, each with its own schema.
+
+use binance_json_parsing::{
+    BookTicker, ParsingConfig, SchemaVersion, assert_config, detect_config,
+    endpoint::StreamEndpoint, parse_book_ticker_dynamic,
+};
+
+fn fixture(endpoint: &StreamEndpoint) -> &'static str {
+    match endpoint {
+        StreamEndpoint::UsdMFutures => include_str!("fixtures/endpoints/usd_m_futures.jsonl"),
+        StreamEndpoint::CoinMFutures => include_str!("fixtures/endpoints/coin_m_futures.jsonl"),
+        StreamEndpoint::Spot => include_str!("fixtures/endpoints/spot.jsonl"),
+        StreamEndpoint::SpotUs => include_str!("fixtures/endpoints/spot_us.jsonl"),
+        // The testnet, and custom ones like it.
+        _ => include_str!("fixtures/endpoints/testnet.jsonl"),
+    }
+}
+
+fn endpoints() -> [StreamEndpoint; 6] {
+    [
+        StreamEndpoint::UsdMFutures,
+        StreamEndpoint::CoinMFutures,
+        StreamEndpoint::Spot,
+        StreamEndpoint::SpotUs,
+        StreamEndpoint::Testnet,
+        StreamEndpoint::Custom("ws://127.0.0.1:9443".to_owned()),
+    ]
+}
+
+#[test]
+fn detects_schema_of_each_endpoint() {
+    for endpoint in endpoints() {
+        for json in fixture(&endpoint).lines() {
+            let config = detect_config(json).unwrap();
+            assert_eq!(config.schema_version, endpoint.schema_version(), "{json}");
+            parse_book_ticker_dynamic(json, config)
+                .unwrap_or_else(|err| panic!("{err:?} parsing {json}"));
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn agrees_with_serde_json_on_each_endpoint() {
+    for endpoint in endpoints() {
+        for json in fixture(&endpoint).lines() {
+            let parsed = parse_book_ticker_dynamic(json, detect_config(json).unwrap()).unwrap();
+            let deserialized: BookTicker = serde_json::from_str(json).unwrap();
+            assert_eq!(parsed, deserialized, "{json}");
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn round_trips_through_serde_json() {
+    for endpoint in endpoints() {
+        for json in fixture(&endpoint).lines() {
+            let config = detect_config(json).unwrap();
+            let ticker = parse_book_ticker_dynamic(json, config).unwrap();
+
+            let serialized = serde_json::to_string(&ticker).unwrap();
+            assert_eq!(
+                serde_json::from_str::<BookTicker>(&serialized).unwrap(),
+                ticker
+            );
+            let serialized = serde_json::to_string(&config).unwrap();
+            assert_eq!(
+                serde_json::from_str::<ParsingConfig>(&serialized).unwrap(),
+                config
+            );
+        }
+    }
+}
+
+type Fields<'a> = (
+    (&'a str, u64, &'a str, Option<&'a str>),
+    [&'a str; 4],
+    (u64, u64),
+);
+
+/// Every field of `ticker`: the identifying ones, then the prices and volumes, then the times.
+fn fields(ticker: BookTicker<'_>) -> Fields<'_> {
+    (
+        (ticker.e, ticker.u, ticker.s, ticker.pair),
+        [ticker.b, ticker.B, ticker.a, ticker.A],
+        (ticker.T, ticker.E),
+    )
+}
+
+#[test]
+fn parses_coin_m_futures() {
+    let json = fixture(&StreamEndpoint::CoinMFutures)
+        .lines()
+        .next()
+        .unwrap();
+    let ticker = parse_book_ticker_dynamic(json, detect_config(json).unwrap()).unwrap();
+
+    assert_eq!(
+        fields(ticker),
+        (
+            ("bookTicker", 728394660107, "BTCUSD_PERP", Some("BTCUSD")),
+            ["83610.3", "1523", "83610.4", "3397"],
+            (1744760290971, 1744760290973)
+        )
+    );
+}
+
+#[test]
+fn keeps_coin_m_pair() {
+    for json in fixture(&StreamEndpoint::CoinMFutures).lines() {
+        let ticker = parse_book_ticker_dynamic(json, detect_config(json).unwrap()).unwrap();
+        // Both perpetuals and quarterlies are named after their pair.
+        let pair = ticker.pair.unwrap();
+        assert_eq!(ticker.s.split_once('_').unwrap().0, pair, "{json}");
+    }
+}
+
+const SPOT_BTCUSDT: ParsingConfig = ParsingConfig::new(32, 8, 8, 0, 7, SchemaVersion::Spot);
+
+assert_config!(
+    SPOT_BTCUSDT,
+    r#"{"u":51278904874,"s":"BTCUSDT","b":"83627.99000000","B":"3.16329000","a":"83628.00000000","A":"4.37016000"}"#
+);
+
+#[test]
+fn parses_spot_without_times() {
+    let json = fixture(&StreamEndpoint::Spot).lines().next().unwrap();
+    assert_eq!(detect_config(json), Ok(SPOT_BTCUSDT));
+
+    assert_eq!(
+        fields(parse_book_ticker_dynamic(json, SPOT_BTCUSDT).unwrap()),
+        (
+            ("", 51278904874, "BTCUSDT", None),
+            [
+                "83627.99000000",
+                "3.16329000",
+                "83628.00000000",
+                "4.37016000"
+            ],
+            (0, 0)
+        )
+    );
+}
+
+#[test]
+fn builds_stream_urls() {
+    assert_eq!(
+        StreamEndpoint::UsdMFutures.book_ticker_url("BTCUSDT"),
+        "wss://fstream.binance.com/ws/btcusdt@bookTicker"
+    );
+    assert_eq!(
+        StreamEndpoint::Spot.book_ticker_url("ethusdt"),
+        "wss://stream.binance.com:9443/ws/ethusdt@bookTicker"
+    );
+    assert_eq!(
+        StreamEndpoint::Testnet.book_ticker_url("BTCUSDT"),
+        "wss://stream.binancefuture.com/ws/btcusdt@bookTicker"
+    );
+    assert_eq!(
+        StreamEndpoint::Custom("wss://fstream.binancefuture.com/".to_owned())
+            .stream_url("btcusdt@depth"),
+        "wss://fstream.binancefuture.com/ws/btcusdt@depth"
+    );
+    assert_eq!(
+        StreamEndpoint::UsdMFutures.combined_url(&["btcusdt@bookTicker", "btcusdt@markPrice"]),
+        "wss://fstream.binance.com/stream?streams=btcusdt@bookTicker/btcusdt@markPrice"
+    );
+}
+
+#[test]
+fn builds_rest_urls() {
+    assert_eq!(
+        StreamEndpoint::UsdMFutures.rest_url(),
+        "https://fapi.binance.com"
+    );
+    assert_eq!(
+        StreamEndpoint::Testnet.rest_url(),
+        "https://testnet.binancefuture.com"
+    );
+    assert_eq!(
+        StreamEndpoint::Custom("ws://127.0.0.1:9443/".to_owned()).rest_url(),
+        "http://127.0.0.1:9443"
+    );
+    assert_eq!(
+        StreamEndpoint::Custom("wss://example.com".to_owned()).rest_url(),
+        "https://example.com"
+    );
+}
@@ -0,0 +1,71 @@
+//! The C interface called the way C would, through raw pointers.
+#![cfg(feature = "ffi")]
+
+use std::{ffi::CStr, mem::MaybeUninit};
+
+use binance_json_parsing::ffi::{
+    BJP_ERR_INVALID_BOUNDARY, BJP_ERR_NULL, BJP_ERR_SCHEMA, BJP_ERR_WRONG_EVENT, BJP_OK,
+    BJP_SCHEMA_FUTURES, CBookTicker, CParsingConfig, parse_book_ticker_c,
+};
+
+const BTCUSDT: &str = r#"{"e":"bookTicker","u":7283946601071,"s":"BTCUSDT","b":"83604.80","B":"10.746","a":"83604.90","A":"9.514","T":1744760290967,"E":1744760290968}"#;
+
+const CONFIG: CParsingConfig = CParsingConfig {
+    start: 51,
+    price_precision: 2,
+    volume_precision: 3,
+    transaction_time_digits: 13,
+    symbol_len: 7,
+    schema_version: BJP_SCHEMA_FUTURES,
+};
+
+fn parse(json: &str, config: CParsingConfig) -> (i32, CBookTicker) {
+    let mut out = MaybeUninit::<CBookTicker>::zeroed();
+    let code =
+        unsafe { parse_book_ticker_c(json.as_ptr().cast(), json.len(), config, out.as_mut_ptr()) };
+    (code, unsafe { out.assume_init() })
+}
+
+fn string(chars: &[std::ffi::c_char]) -> &str {
+    unsafe { CStr::from_ptr(chars.as_ptr()) }.to_str().unwrap()
+}
+
+#[test]
+fn parses_into_c_struct() {
+    let (code, ticker) = parse(BTCUSDT, CONFIG);
+
+    assert_eq!(code, BJP_OK);
+    assert_eq!(string(&ticker.e), "bookTicker");
+    assert_eq!(ticker.u, 7283946601071);
+    assert_eq!(string(&ticker.s), "BTCUSDT");
+    assert_eq!(string(&ticker.b), "83604.80");
+    assert_eq!(string(&ticker.B), "10.746");
+    assert_eq!(string(&ticker.a), "83604.90");
+    assert_eq!(string(&ticker.A), "9.514");
+    assert_eq!(ticker.T, 1744760290967);
+    assert_eq!(ticker.E, 1744760290968);
+}
+
+#[test]
+fn returns_error_codes() {
+    // The reference parser doesn't take the precisions from the config.
+    if cfg!(not(feature = "nom-parser")) {
+        let wrong = CParsingConfig {
+            price_precision: 3,
+            ..CONFIG
+        };
+        assert_eq!(parse(BTCUSDT, wrong).0, BJP_ERR_INVALID_BOUNDARY);
+    }
+
+    let unknown = CParsingConfig {
+        schema_version: 42,
+        ..CONFIG
+    };
+    assert_eq!(parse(BTCUSDT, unknown).0, BJP_ERR_SCHEMA);
+
+    let shutdown = r#"{"e":"serverShutdown","E":1744760290968,"reason":"maintenance"}"#;
+    assert_eq!(parse(shutdown, CONFIG).0, BJP_ERR_WRONG_EVENT);
+
+    let code = unsafe { parse_book_ticker_c(std::ptr::null(), 0, CONFIG, std::ptr::null_mut()) };
+    assert_eq!(code, BJP_ERR_NULL);
+}
@@ -0,0 +1,84 @@
+use binance_json_parsing::{
+    ParseError,
+    decimal::{FixedPoint, parse_fixed},
+    funding_rate::{FundingRate, FundingRateConfig, parse_funding_rate},
+};
+
+const CONFIG: FundingRateConfig = FundingRateConfig::new(38, 7, 13);
+
+fn payload(rate: &str, next_funding_time: u64) -> String {
+    format!(
+        r#"{{"e":"fundingRate","E":1744760291000,"s":"BTCUSDT","r":"{rate}","T":{next_funding_time}}}"#
+    )
+}
+
+#[test]
+fn parses_positive_and_negative_rates() {
+    let json = payload("0.00010000", 1744761600000);
+    let FundingRate {
+        symbol,
+        rate,
+        next_funding_time,
+        ..
+    } = parse_funding_rate(&json, CONFIG).unwrap();
+    assert_eq!(symbol, "BTCUSDT");
+    assert_eq!(
+        rate,
+        FixedPoint {
+            mantissa: 10000,
+            exponent: -8
+        }
+    );
+    assert_eq!(next_funding_time, 1744761600000);
+    assert_eq!(
+        parse_funding_rate(&payload("-0.00050000", 1744761600000), CONFIG)
+            .unwrap()
+            .rate,
+        FixedPoint {
+            mantissa: -50000,
+            exponent: -8
+        }
+    );
+}
+
+#[test]
+fn follows_rate_turning_negative() {
+    let rates = [
+        "0.00010000",
+        "0.00002500",
+        "0.00000000",
+        "-0.00001250",
+        "-0.00050000",
+    ];
+    let zero = parse_fixed("0").unwrap();
+    let payloads: Vec<_> = rates
+        .iter()
+        .enumerate()
+        .map(|(i, rate)| payload(rate, 1744761600000 + i as u64 * 28_800_000))
+        .collect();
+    let parsed: Vec<_> = payloads
+        .iter()
+        .map(|json| parse_funding_rate(json, CONFIG).unwrap())
+        .collect();
+
+    // The layout stays the same whether or not the rate has a sign.
+    assert!(parsed.windows(2).all(|w| w[0].rate > w[1].rate));
+    assert!(parsed[..2].iter().all(|rate| rate.rate > zero));
+    assert_eq!(parsed[2].rate, zero);
+    assert!(parsed[3..].iter().all(|rate| rate.rate < zero));
+    assert_eq!(parsed[3].rate, -parse_fixed("0.0000125").unwrap());
+    assert_eq!(parsed[4].next_funding_time, 1744761600000 + 4 * 28_800_000);
+}
+
+#[test]
+fn rejects_other_layouts() {
+    let mark_price = r#"{"e":"markPriceUpdate","E":1744760291000,"s":"BTCUSDT","p":"83604.80000000","i":"83610.12345678","P":"83590.00000000","r":"0.00010000","T":1744761600000}"#;
+    assert!(matches!(
+        parse_funding_rate(mark_price, CONFIG),
+        Err(ParseError::InvalidBoundary { .. })
+    ));
+    assert!(matches!(
+        parse_funding_rate(&payload("0.00010000", 174476160000), CONFIG),
+        Err(ParseError::InvalidBoundary { field: "T", .. })
+    ));
+}
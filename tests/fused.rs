@@ -0,0 +1,58 @@
+//! Fused prices from the mock server, sending both streams as a combined one does.
+#![cfg(feature = "ws")]
+
+mod common;
+
+use std::{thread, time::Duration};
+
+use binance_json_parsing::{endpoint::StreamEndpoint, fused::FusedPriceStream};
+use common::mock_server::MockBinanceServer;
+
+const BOOK_TICKER: &str = r#"{"stream":"btcusdt@bookTicker","data":{"e":"bookTicker","u":7283946601071,"s":"BTCUSDT","b":"83604.80","B":"10.746","a":"83605.20","A":"9.514","T":1744760290967,"E":1744760290968}}"#;
+const MARK_PRICE: &str = r#"{"stream":"btcusdt@markPrice","data":{"e":"markPriceUpdate","E":1744760291000,"s":"BTCUSDT","p":"84000.00000000","i":"80000.00000000","P":"83590.00000000","r":"0.00010000","T":1744761600000}}"#;
+
+/// Waits for `value` to turn up, as the stream catches up on its own task.
+async fn eventually(value: impl Fn() -> Option<f64>) -> f64 {
+    for _ in 0..200 {
+        if let Some(value) = value() {
+            return value;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("no value in time");
+}
+
+#[tokio::test]
+async fn fuses_book_ticker_and_mark_price() {
+    let server = MockBinanceServer::builder()
+        .messages(vec![BOOK_TICKER.to_owned(), MARK_PRICE.to_owned()])
+        .interval(Duration::from_millis(10))
+        .send_loop()
+        .build();
+    let stream = FusedPriceStream::new(&StreamEndpoint::Custom(server.url()), "BTCUSDT");
+
+    assert_eq!(eventually(|| stream.mid_price()).await, 83605.0);
+    // A mark price 5% over the index.
+    let adjusted = eventually(|| stream.adjusted_mid_price()).await;
+    assert!((adjusted - 83605.0 * 1.05).abs() < 1e-6, "{adjusted}");
+
+    // Readable from a thread outside the runtime.
+    let prices = stream.prices();
+    let mid = thread::spawn(move || prices.mid_price()).join().unwrap();
+    assert_eq!(mid, Some(83605.0));
+}
+
+#[tokio::test]
+async fn waits_for_both_streams() {
+    let server = MockBinanceServer::builder()
+        .messages(vec![BOOK_TICKER.to_owned()])
+        .interval(Duration::from_millis(10))
+        .send_loop()
+        .build();
+    let stream = FusedPriceStream::new(&StreamEndpoint::Custom(server.url()), "btcusdt");
+
+    assert!(stream.adjusted_mid_price().is_none());
+    eventually(|| stream.mid_price()).await;
+    assert!(stream.prices().premium_index().is_none());
+    assert!(stream.adjusted_mid_price().is_none());
+}
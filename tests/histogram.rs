@@ -0,0 +1,54 @@
+#![cfg(feature = "std")]
+
+use binance_json_parsing::histogram::LatencyHistogram;
+
+/// The percentile of a histogram of just `cycles`, which is the upper bound of its bucket.
+fn upper_bound(cycles: u64) -> u64 {
+    let mut histogram = LatencyHistogram::new();
+    histogram.record(cycles);
+    histogram.percentile(50.0)
+}
+
+#[test]
+fn buckets_by_powers_of_two() {
+    // Zero goes along with one into the first bucket.
+    assert_eq!(upper_bound(0), 1);
+    assert_eq!(upper_bound(1), 1);
+    for i in 1..u64::BITS {
+        let low = 1u64 << i;
+        let high = u64::MAX >> (u64::BITS - 1 - i);
+        // Both ends of `[2^i, 2^(i + 1))`, and its neighbours on either side.
+        assert_eq!(upper_bound(low), high, "2^{i}");
+        assert_eq!(upper_bound(high), high, "2^{} - 1", i + 1);
+        assert_eq!(upper_bound(low - 1), high >> 1, "2^{i} - 1");
+    }
+    assert_eq!(upper_bound(u64::MAX), u64::MAX);
+}
+
+#[test]
+fn finds_percentiles_by_rank() {
+    let mut histogram = LatencyHistogram::default();
+    assert_eq!(histogram.percentile(99.0), 0);
+    assert_eq!(histogram.count(), 0);
+
+    // 90 fast ones in [64, 128), 9 slower in [1024, 2048) and one outlier in [2^20, 2^21).
+    for _ in 0..90 {
+        histogram.record(100);
+    }
+    for _ in 0..9 {
+        histogram.record(1500);
+    }
+    histogram.record(1_500_000);
+    assert_eq!(histogram.count(), 100);
+
+    assert_eq!(histogram.percentile(0.0), 127);
+    assert_eq!(histogram.percentile(50.0), 127);
+    assert_eq!(histogram.percentile(90.0), 127);
+    assert_eq!(histogram.percentile(90.1), 2047);
+    assert_eq!(histogram.percentile(99.0), 2047);
+    assert_eq!(histogram.percentile(99.5), (1 << 21) - 1);
+    assert_eq!(histogram.percentile(100.0), (1 << 21) - 1);
+    // Out of range, clamped to the lowest and highest.
+    assert_eq!(histogram.percentile(-1.0), 127);
+    assert_eq!(histogram.percentile(200.0), (1 << 21) - 1);
+}
@@ -0,0 +1,126 @@
+//! The sink against a local server standing in for InfluxDB, recording the writes it gets.
+#![cfg(feature = "influxdb")]
+
+mod common;
+
+use binance_json_parsing::{
+    ParsingConfig, detect_config,
+    influxdb::{InfluxConfig, InfluxSink, to_line_protocol},
+    parse_book_ticker_dynamic,
+};
+use common::ticks;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+    sync::mpsc,
+};
+
+const BTCUSDT: &str = r#"{"e":"bookTicker","u":7283946601071,"s":"BTCUSDT","b":"83604.80","B":"10.746","a":"83604.90","A":"9.514","T":1744760290967,"E":1744760290968}"#;
+const SPOT: &str = r#"{"u":400900217,"s":"BNBUSDT","b":"25.35190000","B":"31.21000000","a":"25.36520000","A":"40.66000000"}"#;
+
+/// Answers every request with `status`, sending the head and body of each.
+async fn mock_influx(status: &'static str) -> (String, mpsc::UnboundedReceiver<(String, String)>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (requests, received) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        loop {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 4096];
+            let head_end = loop {
+                let n = stream.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                if let Some(end) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                    break end + 4;
+                }
+            };
+            let head = String::from_utf8(request[..head_end].to_vec()).unwrap();
+            let len: usize = head
+                .lines()
+                .find_map(|line| line.strip_prefix("Content-Length: "))
+                .unwrap()
+                .parse()
+                .unwrap();
+            while request.len() < head_end + len {
+                let n = stream.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+            let body = String::from_utf8(request[head_end..].to_vec()).unwrap();
+
+            let response = format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\n\r\n");
+            stream.write_all(response.as_bytes()).await.unwrap();
+            requests.send((head, body)).unwrap();
+        }
+    });
+    (url, received)
+}
+
+#[test]
+fn formats_line_protocol() {
+    let tick = parse_book_ticker_dynamic(BTCUSDT, ParsingConfig::default()).unwrap();
+    assert_eq!(
+        to_line_protocol(&tick),
+        "bookTicker,symbol=BTCUSDT bid=83604.80,ask=83604.90,bidQty=10.746,askQty=9.514 1744760290967000000"
+    );
+
+    // No time to send, so the server's is used.
+    let spot = parse_book_ticker_dynamic(SPOT, detect_config(SPOT).unwrap()).unwrap();
+    assert_eq!(
+        to_line_protocol(&spot),
+        "bookTicker,symbol=BNBUSDT bid=25.35190000,ask=25.36520000,bidQty=31.21000000,askQty=40.66000000"
+    );
+}
+
+#[tokio::test]
+async fn writes_batches_of_100_lines() {
+    let (url, mut requests) = mock_influx("204 No Content").await;
+    let mut sink = InfluxSink::new(InfluxConfig::new(url, "my org", "ticks", "secret")).unwrap();
+    let ticks = ticks();
+    for tick in &ticks[..250] {
+        sink.write(tick).await.unwrap();
+    }
+    // The last 50 are only written once flushed.
+    for batch in [&ticks[..100], &ticks[100..200]] {
+        let (head, body) = requests.recv().await.unwrap();
+        assert!(
+            head.starts_with(
+                "POST /api/v2/write?org=my%20org&bucket=ticks&precision=ns HTTP/1.1\r\n"
+            ),
+            "{head}"
+        );
+        assert!(
+            head.contains("\r\nAuthorization: Token secret\r\n"),
+            "{head}"
+        );
+        assert!(
+            head.contains("\r\nContent-Type: text/plain; charset=utf-8\r\n"),
+            "{head}"
+        );
+        let lines: Vec<_> = batch
+            .iter()
+            .map(|tick| to_line_protocol(tick) + "\n")
+            .collect();
+        assert_eq!(body, lines.concat());
+    }
+    assert!(requests.is_empty());
+
+    sink.flush().await.unwrap();
+    let (_, body) = requests.recv().await.unwrap();
+    assert_eq!(body.lines().count(), 50);
+    sink.flush().await.unwrap();
+    assert!(requests.is_empty());
+}
+
+#[tokio::test]
+async fn reports_failed_writes() {
+    let (url, _requests) = mock_influx("401 Unauthorized").await;
+    let config = InfluxConfig {
+        batch_size: 1,
+        ..InfluxConfig::new(url, "org", "ticks", "wrong")
+    };
+    let mut sink = InfluxSink::new(config).unwrap();
+    let tick = parse_book_ticker_dynamic(BTCUSDT, ParsingConfig::default()).unwrap();
+    let err = sink.write(&tick).await.unwrap_err();
+    assert!(err.to_string().contains("401"), "{err}");
+}
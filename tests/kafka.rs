@@ -0,0 +1,242 @@
+//! The sink against a mock broker answering the metadata and produce requests it sends, since
+//! there's no Kafka to start here.
+#![cfg(feature = "kafka")]
+
+mod common;
+
+use binance_json_parsing::{
+    BookTickerOwned, detect_config,
+    kafka::{KafkaConfig, KafkaSink, to_msgpack},
+    parse_book_ticker_dynamic,
+};
+use common::owned_ticks;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+};
+
+const TOPIC: &str = "book-ticker";
+
+/// Fields of a request or response, read in order.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> &'a [u8] {
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        bytes
+    }
+
+    fn i16(&mut self) -> i16 {
+        i16::from_be_bytes(self.bytes(2).try_into().unwrap())
+    }
+
+    fn i32(&mut self) -> i32 {
+        i32::from_be_bytes(self.bytes(4).try_into().unwrap())
+    }
+
+    fn str(&mut self) -> &'a str {
+        let len = self.i16() as usize;
+        std::str::from_utf8(self.bytes(len)).unwrap()
+    }
+
+    fn varint(&mut self) -> i64 {
+        let (mut value, mut shift) = (0u64, 0);
+        loop {
+            let byte = self.bytes(1)[0];
+            value |= u64::from(byte & 0x7f) << shift;
+            shift += 7;
+            if byte & 0x80 == 0 {
+                return (value >> 1) as i64 ^ -((value & 1) as i64);
+            }
+        }
+    }
+}
+
+fn put_str(buf: &mut Vec<u8>, s: &str) {
+    buf.extend_from_slice(&(s.len() as i16).to_be_bytes());
+    buf.extend_from_slice(s.as_bytes());
+}
+
+/// Leads partition 0 of every topic, sending the key and value of every record produced to it.
+async fn mock_broker() -> (String, mpsc::UnboundedReceiver<(String, Vec<u8>)>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (records, received) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            tokio::spawn(serve(stream, port, records.clone()));
+        }
+    });
+    (format!("127.0.0.1:{port}"), received)
+}
+
+async fn serve(
+    mut stream: TcpStream,
+    port: u16,
+    records: mpsc::UnboundedSender<(String, Vec<u8>)>,
+) {
+    while let Ok(len) = stream.read_i32().await {
+        let mut request = vec![0; len as usize];
+        stream.read_exact(&mut request).await.unwrap();
+        let mut r = Reader(&request);
+        let (api_key, version, correlation_id) = (r.i16(), r.i16(), r.i32());
+        let _client_id = r.str();
+
+        let mut response = correlation_id.to_be_bytes().to_vec();
+        match (api_key, version) {
+            // Metadata: this broker alone, leading the only partition of the topic.
+            (3, 1) => {
+                r.i32();
+                let topic = r.str();
+                response.extend_from_slice(&1i32.to_be_bytes());
+                response.extend_from_slice(&0i32.to_be_bytes());
+                put_str(&mut response, "127.0.0.1");
+                response.extend_from_slice(&i32::from(port).to_be_bytes());
+                response.extend_from_slice(&(-1i16).to_be_bytes());
+                response.extend_from_slice(&0i32.to_be_bytes());
+                response.extend_from_slice(&1i32.to_be_bytes());
+                response.extend_from_slice(&0i16.to_be_bytes());
+                put_str(&mut response, topic);
+                response.push(0);
+                response.extend_from_slice(&1i32.to_be_bytes());
+                response.extend_from_slice(&0i16.to_be_bytes());
+                response.extend_from_slice(&0i32.to_be_bytes());
+                response.extend_from_slice(&0i32.to_be_bytes());
+                for _ in 0..2 {
+                    response.extend_from_slice(&1i32.to_be_bytes());
+                    response.extend_from_slice(&0i32.to_be_bytes());
+                }
+            }
+            // Produce: one batch of one partition of one topic.
+            (0, 3) => {
+                assert_eq!(r.i16(), -1, "no transactional id");
+                let (_acks, _timeout, _topics) = (r.i16(), r.i32(), r.i32());
+                let topic = r.str();
+                let (_partitions, partition, _len) = (r.i32(), r.i32(), r.i32());
+                // Up to the number of records, see `record_batch`.
+                assert_eq!(r.bytes(57)[16], 2, "magic");
+                for _ in 0..r.i32() {
+                    let len = r.varint() as usize;
+                    let mut record = Reader(r.bytes(len));
+                    record.bytes(1);
+                    let (_timestamp_delta, _offset_delta) = (record.varint(), record.varint());
+                    let key_len = record.varint() as usize;
+                    let key = std::str::from_utf8(record.bytes(key_len))
+                        .unwrap()
+                        .to_owned();
+                    let value_len = record.varint() as usize;
+                    let value = record.bytes(value_len).to_vec();
+                    assert_eq!(record.varint(), 0, "no headers");
+                    records.send((key, value)).unwrap();
+                }
+
+                response.extend_from_slice(&1i32.to_be_bytes());
+                put_str(&mut response, topic);
+                response.extend_from_slice(&1i32.to_be_bytes());
+                response.extend_from_slice(&partition.to_be_bytes());
+                response.extend_from_slice(&0i16.to_be_bytes());
+                response.extend_from_slice(&0i64.to_be_bytes());
+                response.extend_from_slice(&(-1i64).to_be_bytes());
+                response.extend_from_slice(&0i32.to_be_bytes());
+            }
+            request => panic!("unexpected request {request:?}"),
+        }
+
+        stream
+            .write_all(&(response.len() as i32).to_be_bytes())
+            .await
+            .unwrap();
+        stream.write_all(&response).await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn delivers_every_tick() {
+    let (broker, mut received) = mock_broker().await;
+    let sink = KafkaSink::connect(KafkaConfig::new([broker], TOPIC))
+        .await
+        .unwrap();
+    let ticks = owned_ticks();
+    for tick in &ticks {
+        assert!(sink.send(tick));
+    }
+    sink.close().await;
+
+    for tick in &ticks {
+        let (key, value) = received.recv().await.unwrap();
+        assert_eq!(key, tick.s);
+        assert_eq!(value, to_msgpack(tick));
+    }
+    assert!(received.is_empty());
+}
+
+#[tokio::test]
+async fn drops_ticks_beyond_queue_limit() {
+    let (broker, mut received) = mock_broker().await;
+    let config = KafkaConfig {
+        max_queued: 10,
+        ..KafkaConfig::new([broker], TOPIC)
+    };
+    let sink = KafkaSink::connect(config).await.unwrap();
+    // The publishing task doesn't get to run before the test yields, so the queue only fills up.
+    let ticks = owned_ticks();
+    let queued = ticks[..100].iter().filter(|tick| sink.send(tick)).count();
+    assert_eq!(queued, 10);
+    assert_eq!(sink.dropped_messages(), 90);
+    sink.close().await;
+
+    for tick in &ticks[..10] {
+        assert_eq!(received.recv().await.unwrap().1, to_msgpack(tick));
+    }
+    assert!(received.is_empty());
+}
+
+#[tokio::test]
+async fn fails_to_connect_without_brokers() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    drop(listener);
+    assert!(
+        KafkaSink::connect(KafkaConfig::new([addr], TOPIC))
+            .await
+            .is_err()
+    );
+    assert!(
+        KafkaSink::connect(KafkaConfig::new(Vec::<String>::new(), TOPIC))
+            .await
+            .is_err()
+    );
+}
+
+#[test]
+fn encodes_msgpack_map() {
+    let json = r#"{"e":"bookTicker","u":1,"s":"BTCUSD_PERP","b":"1.0","B":"2","a":"3.5","A":"4","T":1744760290967,"E":1744760290969}"#;
+    let config = detect_config(json).unwrap();
+    let ticker = parse_book_ticker_dynamic(json, config).unwrap();
+    let mut tick = BookTickerOwned::try_from(ticker).unwrap();
+    let mut expected = vec![0x89, 0xa1, b'e', 0xaa];
+    expected.extend_from_slice(b"bookTicker");
+    expected.extend_from_slice(&[0xa1, b'u', 0x01, 0xa1, b's', 0xab]);
+    expected.extend_from_slice(b"BTCUSD_PERP");
+    let rest = {
+        let mut rest = vec![0xa1, b'T', 0xcf];
+        rest.extend_from_slice(&1_744_760_290_967u64.to_be_bytes());
+        rest.extend_from_slice(&[0xa1, b'b', 0xa3, b'1', b'.', b'0']);
+        rest.extend_from_slice(&[0xa1, b'B', 0xa1, b'2']);
+        rest.extend_from_slice(&[0xa1, b'a', 0xa3, b'3', b'.', b'5']);
+        rest.extend_from_slice(&[0xa1, b'A', 0xa1, b'4']);
+        rest.extend_from_slice(&[0xa1, b'E', 0xcf]);
+        rest.extend_from_slice(&1_744_760_290_969u64.to_be_bytes());
+        rest
+    };
+    assert_eq!(to_msgpack(&tick), [expected.clone(), rest.clone()].concat());
+
+    tick.pair = Some("BTCUSD".to_owned());
+    expected[0] = 0x8a;
+    expected.extend_from_slice(&[0xa2, b'p', b's', 0xa6]);
+    expected.extend_from_slice(b"BTCUSD");
+    assert_eq!(to_msgpack(&tick), [expected, rest].concat());
+}
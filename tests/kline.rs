@@ -0,0 +1,90 @@
+use binance_json_parsing::{
+    ParseError,
+    kline::{Kline, KlineConfig, parse_kline},
+};
+
+const CONFIG: KlineConfig = KlineConfig::new(49, 7, 13);
+
+fn payload(interval: &str, closed: bool) -> String {
+    format!(
+        r#"{{"e":"kline","E":1744760290967,"s":"BTCUSDT","k":{{"t":1744760280000,"T":1744760339999,"s":"BTCUSDT","i":"{interval}","f":6186376000,"L":6186376620,"o":"83600.00","c":"83604.80","h":"83610.00","l":"83590.10","v":"123.456","n":620,"x":{closed},"q":"10321000.12","V":"60.000","Q":"5016000.00","B":"0"}}}}"#
+    )
+}
+
+#[test]
+fn parses_klines() {
+    let json = payload("1m", false);
+    let Kline {
+        t,
+        T,
+        s,
+        i,
+        o,
+        c,
+        h,
+        l,
+        v,
+        n,
+        x,
+        ..
+    } = parse_kline(&json, CONFIG).unwrap();
+    assert_eq!((t, T), (1744760280000, 1744760339999));
+    assert_eq!((s, i), ("BTCUSDT", "1m"));
+    assert_eq!(
+        [o, c, h, l, v],
+        ["83600.00", "83604.80", "83610.00", "83590.10", "123.456"]
+    );
+    assert_eq!((n, x), (620, false));
+
+    // The interval has a variable length, and the same config follows it.
+    let json = payload("15m", true);
+    let kline = parse_kline(&json, CONFIG).unwrap();
+    assert_eq!((kline.i, kline.c, kline.x), ("15m", "83604.80", true));
+}
+
+#[test]
+fn rejects_truncated_klines() {
+    let json = payload("1m", true);
+    // Whatever comes after `x` isn't looked at.
+    let x_end = json.find("true").unwrap() + 4;
+    for len in 0..x_end {
+        assert!(
+            parse_kline(&json[..len], CONFIG).is_err(),
+            "{}",
+            &json[..len]
+        );
+    }
+    assert!(parse_kline(&json[..x_end], CONFIG).is_ok());
+}
+
+#[test]
+fn rejects_mismatched_layouts() {
+    let json = payload("1m", false);
+    assert_eq!(
+        parse_kline(&json, KlineConfig::new(48, 7, 13)),
+        Err(ParseError::InvalidBoundary {
+            field: "k",
+            pos: 48
+        })
+    );
+    assert!(matches!(
+        parse_kline(&json, KlineConfig::new(49, 7, 12)),
+        Err(ParseError::InvalidBoundary { field: "t", .. })
+    ));
+    assert!(matches!(
+        parse_kline(&json, KlineConfig::new(49, 8, 13)),
+        Err(ParseError::InvalidBoundary { field: "s", .. })
+    ));
+    assert_eq!(
+        parse_kline(&json.replace("false", "fals"), CONFIG),
+        Err(ParseError::InvalidBool { pos: 225 })
+    );
+    assert!(matches!(
+        parse_kline(&json.replace("620", "6a0"), CONFIG),
+        Err(ParseError::IdParse(_))
+    ));
+    assert!(matches!(
+        parse_kline(&json.replace("1744760280000", "174476028000a"), CONFIG),
+        Err(ParseError::TimestampParse(_))
+    ));
+}
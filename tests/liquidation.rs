@@ -0,0 +1,96 @@
+use binance_json_parsing::{
+    ParseError,
+    liquidation::{LiquidationConfig, LiquidationOrder, Side, parse_liquidation},
+};
+
+const CONFIG: LiquidationConfig = LiquidationConfig::new(40, 7, 13);
+
+fn payload(side: &str) -> String {
+    format!(
+        r#"{{"e":"forceOrder","E":1744760291000,"o":{{"s":"BTCUSDT","S":"{side}","o":"LIMIT","f":"IOC","q":"0.014","p":"83000.00","ap":"83010.50","X":"FILLED","l":"0.014","z":"0.014","T":1744760290999}}}}"#
+    )
+}
+
+#[test]
+fn tells_sides_by_first_byte() {
+    assert_eq!(Side::from_byte(b'B'), Some(Side::Buy));
+    assert_eq!(Side::from_byte(b'S'), Some(Side::Sell));
+    for byte in (0..=u8::MAX).filter(|byte| !matches!(byte, b'B' | b'S')) {
+        assert_eq!(Side::from_byte(byte), None, "{byte}");
+    }
+}
+
+#[test]
+fn parses_liquidations() {
+    let json = payload("SELL");
+    let LiquidationOrder {
+        s,
+        S,
+        o,
+        f,
+        q,
+        p,
+        ap,
+        X,
+        l,
+        z,
+        T,
+        ..
+    } = parse_liquidation(&json, CONFIG).unwrap();
+    assert_eq!((s, S), ("BTCUSDT", Side::Sell));
+    assert_eq!(
+        [o, f, q, p, ap, X, l, z],
+        [
+            "LIMIT", "IOC", "0.014", "83000.00", "83010.50", "FILLED", "0.014", "0.014"
+        ]
+    );
+    assert_eq!(T, 1744760290999);
+
+    // One byte shorter, which the rest of the layout follows.
+    let json = payload("BUY");
+    let order = parse_liquidation(&json, CONFIG).unwrap();
+    assert_eq!(
+        (order.S, order.o, order.T),
+        (Side::Buy, "LIMIT", 1744760290999)
+    );
+}
+
+#[test]
+fn rejects_unknown_sides() {
+    let json = payload("HOLD");
+    assert_eq!(
+        parse_liquidation(&json, CONFIG),
+        Err(ParseError::InvalidSide { pos: 60 })
+    );
+    let json = payload("");
+    assert_eq!(
+        parse_liquidation(&json, CONFIG),
+        Err(ParseError::InvalidSide { pos: 60 })
+    );
+}
+
+#[test]
+fn rejects_truncated_liquidations() {
+    let json = payload("SELL");
+    for len in 0..json.len() - 1 {
+        assert!(
+            parse_liquidation(&json[..len], CONFIG).is_err(),
+            "{}",
+            &json[..len]
+        );
+    }
+    assert_eq!(
+        parse_liquidation(&json, LiquidationConfig::new(41, 7, 13)),
+        Err(ParseError::InvalidBoundary {
+            field: "o",
+            pos: 41
+        })
+    );
+    assert_eq!(
+        parse_liquidation(&json, LiquidationConfig::new(40, 7, 12)),
+        Err(ParseError::InvalidBoundary {
+            field: "T",
+            pos: json.len() - 3
+        })
+    );
+}
@@ -0,0 +1,97 @@
+use binance_json_parsing::{
+    ParseError,
+    decimal::FixedPoint,
+    mark_price::{MarkPrice, MarkPriceConfig, detect_mark_price_config, parse_mark_price},
+};
+
+const CONFIG: MarkPriceConfig = MarkPriceConfig::new(42, 7, 13);
+
+fn payload(rate: &str) -> String {
+    format!(
+        r#"{{"e":"markPriceUpdate","E":1744760291000,"s":"BTCUSDT","p":"83604.80000000","i":"83610.12345678","P":"83590.00000000","r":"{rate}","T":1744761600000}}"#
+    )
+}
+
+#[test]
+fn parses_mark_prices() {
+    let json = payload("0.00010000");
+    let MarkPrice {
+        s, p, i, P, r, T, ..
+    } = parse_mark_price(&json, CONFIG).unwrap();
+    assert_eq!(s, "BTCUSDT");
+    assert_eq!(
+        [p, i, P],
+        ["83604.80000000", "83610.12345678", "83590.00000000"]
+    );
+    assert_eq!(
+        r,
+        FixedPoint {
+            mantissa: 10000,
+            exponent: -8
+        }
+    );
+    assert_eq!(T, 1744761600000);
+}
+
+#[test]
+fn detects_mark_price_configs() {
+    let config = detect_mark_price_config(&payload("0.00010000")).unwrap();
+    assert_eq!(
+        (
+            config.start,
+            config.symbol_len,
+            config.transaction_time_digits
+        ),
+        (42, 7, 13)
+    );
+
+    let json = r#"{"e":"markPriceUpdate","E":1744760291000,"s":"1000PEPEUSDT","p":"0.0082480","i":"0.0082510","P":"0.0082400","r":"-0.00005000","T":1744761600000}"#;
+    let config = detect_mark_price_config(json).unwrap();
+    assert_eq!((config.start, config.symbol_len), (42, 12));
+    assert_eq!(parse_mark_price(json, config).unwrap().s, "1000PEPEUSDT");
+
+    assert_eq!(
+        detect_mark_price_config(r#"{"e":"markPriceUpdate","E":1744760291000}"#).unwrap_err(),
+        ParseError::FieldNotFound("s")
+    );
+    assert_eq!(
+        detect_mark_price_config(r#"{"s":"BTCUSDT"}"#).unwrap_err(),
+        ParseError::FieldNotFound("T")
+    );
+    assert_eq!(
+        detect_mark_price_config(r#"{"s":"BTCUS"#).unwrap_err(),
+        ParseError::FieldNotFound("s")
+    );
+}
+
+#[test]
+fn rejects_malformed_rates() {
+    for rate in ["0.00010e00", "1e-4", "0.0001.0", "", "-", "0x10"] {
+        assert_eq!(
+            parse_mark_price(&payload(rate), CONFIG),
+            Err(ParseError::InvalidDecimal),
+            "{rate}"
+        );
+    }
+}
+
+#[test]
+fn rejects_truncated_mark_prices() {
+    let json = payload("0.00010000");
+    for len in 0..json.len() {
+        assert!(
+            parse_mark_price(&json[..len], CONFIG).is_err(),
+            "{}",
+            &json[..len]
+        );
+        // Detection itself doesn't need the whole payload, but never panics on it.
+        let _ = detect_mark_price_config(&json[..len]);
+    }
+    assert_eq!(
+        parse_mark_price(&json, MarkPriceConfig::new(42, 7, 12)),
+        Err(ParseError::InvalidBoundary {
+            field: "T",
+            pos: json.len() - 2
+        })
+    );
+}
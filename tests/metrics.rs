@@ -0,0 +1,137 @@
+//! The metrics server over plain HTTP, fed by a stream against a local server.
+#![cfg(feature = "ws")]
+
+mod common;
+
+use std::sync::Arc;
+
+use binance_json_parsing::{
+    metrics::{Metrics, MetricsServer},
+    reconnect::{ReconnectConfig, ReconnectingStream},
+};
+use common::mock_server::MockBinanceServer;
+use futures_util::StreamExt;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+const BTCUSDT: &str = r#"{"e":"bookTicker","u":7283946601071,"s":"BTCUSDT","b":"83604.80","B":"10.746","a":"83604.90","A":"9.514","T":1744760290967,"E":1744760290968}"#;
+
+/// Status line and body of the response to `GET path`.
+async fn get(server: &MetricsServer, path: &str) -> (String, String) {
+    let mut conn = TcpStream::connect(server.local_addr()).await.unwrap();
+    let request = format!("GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n");
+    conn.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    conn.read_to_string(&mut response).await.unwrap();
+
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.lines().next().unwrap().to_owned();
+    let content_length = head
+        .lines()
+        .find_map(|line| line.strip_prefix("Content-Length: "))
+        .unwrap();
+    assert_eq!(content_length.parse::<usize>().unwrap(), body.len());
+    (status, body.to_owned())
+}
+
+async fn spawn(metrics: &Arc<Metrics>) -> MetricsServer {
+    MetricsServer::spawn("127.0.0.1:0".parse().unwrap(), Arc::clone(metrics))
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn serves_metrics() {
+    let metrics = Arc::new(Metrics::new());
+    let server = spawn(&metrics).await;
+    assert_ne!(server.local_addr().port(), 0);
+
+    metrics.message_received();
+    metrics.message_received();
+    metrics.parsed(100);
+    metrics.parsed(1500);
+    metrics.parse_error();
+
+    let (status, body) = get(&server, "/metrics").await;
+    assert_eq!(status, "HTTP/1.1 200 OK");
+    let lines: Vec<&str> = body.lines().collect();
+    for expected in [
+        "# TYPE binance_parse_duration_cycles histogram",
+        r#"binance_parse_duration_cycles_bucket{le="63"} 0"#,
+        r#"binance_parse_duration_cycles_bucket{le="127"} 1"#,
+        r#"binance_parse_duration_cycles_bucket{le="2047"} 2"#,
+        r#"binance_parse_duration_cycles_bucket{le="+Inf"} 2"#,
+        "binance_parse_duration_cycles_sum 1600",
+        "binance_parse_duration_cycles_count 2",
+        "# TYPE binance_messages_received_total counter",
+        "binance_messages_received_total 2",
+        "binance_parse_errors_total 1",
+    ] {
+        assert!(lines.contains(&expected), "{expected} not in\n{body}");
+    }
+
+    let (status, body) = get(&server, "/nope").await;
+    assert_eq!(
+        (status.as_str(), body.as_str()),
+        ("HTTP/1.1 404 Not Found", "")
+    );
+}
+
+#[tokio::test]
+async fn serves_health() {
+    let metrics = Arc::new(Metrics::new());
+    let server = spawn(&metrics).await;
+
+    let (status, body) = get(&server, "/health").await;
+    assert_eq!(status, "HTTP/1.1 503 Service Unavailable");
+    assert_eq!(
+        body,
+        r#"{"status":"degraded","connected":false,"last_message_age_ms":null}"#
+    );
+
+    // Receiving isn't enough without being connected, and the other way around.
+    metrics.message_received();
+    let (status, body) = get(&server, "/health").await;
+    assert_eq!(status, "HTTP/1.1 503 Service Unavailable");
+    assert!(
+        body.starts_with(r#"{"status":"degraded","connected":false,"#),
+        "{body}"
+    );
+
+    metrics.set_connected(true);
+    let (status, body) = get(&server, "/health").await;
+    assert_eq!(status, "HTTP/1.1 200 OK");
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body["status"], "ok");
+    assert_eq!(body["connected"], true);
+    assert!(body["last_message_age_ms"].as_u64().unwrap() < 1000);
+}
+
+#[tokio::test]
+async fn reports_connection_of_the_stream() {
+    let ws_server = MockBinanceServer::builder()
+        .messages(vec![BTCUSDT.to_owned()])
+        .send_loop()
+        .build();
+    let mut ws_stream = ReconnectingStream::new(ws_server.url(), ReconnectConfig::default());
+    let metrics = Arc::new(Metrics::tracking(ws_stream.connected()));
+    let server = spawn(&metrics).await;
+
+    // Not even tried to connect before being polled.
+    let (_, body) = get(&server, "/health").await;
+    assert!(body.contains(r#""connected":false"#), "{body}");
+
+    let msg = ws_stream.next().await.unwrap().unwrap();
+    assert!(msg.is_text());
+    metrics.message_received();
+    let (status, body) = get(&server, "/health").await;
+    assert_eq!(status, "HTTP/1.1 200 OK");
+    assert!(body.contains(r#""connected":true"#), "{body}");
+
+    ws_stream.close().await.unwrap();
+    let (status, body) = get(&server, "/health").await;
+    assert_eq!(status, "HTTP/1.1 503 Service Unavailable");
+    assert!(body.contains(r#""connected":false"#), "{body}");
+}
@@ -0,0 +1,76 @@
+use binance_json_parsing::{
+    ParseError,
+    mini_ticker::{MiniTicker, MiniTickerConfig, parse_mini_ticker},
+};
+
+const CONFIG: MiniTickerConfig = MiniTickerConfig::new(41, 7);
+
+fn payload(c: &str, o: &str, h: &str, l: &str) -> String {
+    format!(
+        r#"{{"e":"24hrMiniTicker","E":1744760291000,"s":"BTCUSDT","c":"{c}","o":"{o}","h":"{h}","l":"{l}","v":"123456.789","q":"10321000000.12"}}"#
+    )
+}
+
+fn prices<'a>(ticker: &MiniTicker<'a>) -> [&'a str; 4] {
+    [ticker.c, ticker.o, ticker.h, ticker.l]
+}
+
+#[test]
+fn parses_four_digit_integer_parts() {
+    let json = payload("8360.48", "8200.00", "8400.00", "8100.00");
+    let ticker = parse_mini_ticker(&json, CONFIG).unwrap();
+    assert_eq!(ticker.s, "BTCUSDT");
+    assert_eq!(
+        prices(&ticker),
+        ["8360.48", "8200.00", "8400.00", "8100.00"]
+    );
+    assert_eq!((ticker.v, ticker.q), ("123456.789", "10321000000.12"));
+}
+
+#[test]
+fn parses_five_digit_integer_parts() {
+    let json = payload("83604.80", "82000.00", "84000.00", "81000.00");
+    let ticker = parse_mini_ticker(&json, CONFIG).unwrap();
+    assert_eq!(
+        prices(&ticker),
+        ["83604.80", "82000.00", "84000.00", "81000.00"]
+    );
+}
+
+#[test]
+fn parses_six_digit_integer_parts() {
+    let json = payload("100204.80", "100000.00", "100500.00", "99800.00");
+    let ticker = parse_mini_ticker(&json, CONFIG).unwrap();
+    assert_eq!(
+        prices(&ticker),
+        ["100204.80", "100000.00", "100500.00", "99800.00"]
+    );
+}
+
+#[test]
+fn follows_integer_parts_across_frames() {
+    // The same config throughout, while the close gains and loses digits.
+    for c in ["9999.90", "10000.10", "99999.90", "100000.10", "9999.90"] {
+        let json = payload(c, "10000.00", "100000.00", "9000.00");
+        assert_eq!(parse_mini_ticker(&json, CONFIG).unwrap().c, c);
+    }
+}
+
+#[test]
+fn rejects_truncated_payloads() {
+    let json = payload("83604.80", "82000.00", "84000.00", "81000.00");
+    for len in 0..json.len() {
+        assert!(
+            parse_mini_ticker(&json[..len], CONFIG).is_err(),
+            "{}",
+            &json[..len]
+        );
+    }
+    assert_eq!(
+        parse_mini_ticker(&json, MiniTickerConfig::new(40, 7)),
+        Err(ParseError::InvalidBoundary {
+            field: "s",
+            pos: 40
+        })
+    );
+}
@@ -0,0 +1,55 @@
+use binance_json_parsing::{
+    ParseError,
+    decimal::parse_fixed,
+    open_interest::{OpenInterest, OpenInterestConfig, parse_open_interest},
+};
+
+const CONFIG: OpenInterestConfig = OpenInterestConfig::new(39, 7, 13);
+
+fn payload(open_interest: &str) -> String {
+    format!(
+        r#"{{"e":"openInterest","E":1744760291000,"s":"BTCUSDT","o":"{open_interest}","T":1744760290967}}"#
+    )
+}
+
+#[test]
+fn parses_open_interest() {
+    let json = payload("123456.789");
+    let OpenInterest {
+        symbol,
+        open_interest,
+        transaction_time,
+        ..
+    } = parse_open_interest(&json, CONFIG).unwrap();
+    assert_eq!(symbol, "BTCUSDT");
+    assert_eq!(open_interest, parse_fixed("123456.789").unwrap());
+    assert_eq!(transaction_time, 1744760290967);
+}
+
+#[test]
+fn parses_open_interest_of_any_length() {
+    // Far longer than any price, and without a fractional part at all.
+    for open_interest in ["0", "7.5", "98765432109.123", "4200000000000"] {
+        assert_eq!(
+            parse_open_interest(&payload(open_interest), CONFIG)
+                .unwrap()
+                .open_interest,
+            parse_fixed(open_interest).unwrap(),
+            "{open_interest}"
+        );
+    }
+}
+
+#[test]
+fn rejects_other_layouts() {
+    let funding_rate =
+        r#"{"e":"fundingRate","E":1744760291000,"s":"BTCUSDT","r":"0.00010000","T":1744761600000}"#;
+    assert!(matches!(
+        parse_open_interest(funding_rate, CONFIG),
+        Err(ParseError::InvalidBoundary { field: "s", .. })
+    ));
+    assert!(matches!(
+        parse_open_interest(&payload("123456.789")[..70], CONFIG),
+        Err(ParseError::InvalidBoundary { field: "T", .. })
+    ));
+}
@@ -0,0 +1,128 @@
+//! Pinned connections to a local `wss://` server with the self-signed certificate in
+//! `fixtures/pinning`, for `127.0.0.1`.
+#![cfg(feature = "ws")]
+
+use binance_json_parsing::{
+    pinning::{cert_sha256, connect_pinned},
+    reconnect::{ReconnectConfig, ReconnectingStream},
+};
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::TcpListener;
+use tokio_native_tls::{TlsAcceptor, native_tls};
+use tokio_tungstenite::tungstenite::Message;
+
+const CERT: &[u8] = include_bytes!("fixtures/pinning/cert.pem");
+const KEY: &[u8] = include_bytes!("fixtures/pinning/key.pem");
+/// `openssl x509 -in fixtures/pinning/cert.pem -outform der | sha256sum`
+const CERT_SHA256: [u8; 32] =
+    hex("3c405510ed45577f4d177202760fdbe146097c8e917929472190f031a346537a");
+const BTCUSDT: &str = r#"{"e":"bookTicker","u":7283946601071,"s":"BTCUSDT","b":"83604.80","B":"10.746","a":"83604.90","A":"9.514","T":1744760290967,"E":1744760290968}"#;
+
+const fn hex(s: &str) -> [u8; 32] {
+    const fn digit(c: u8) -> u8 {
+        match c {
+            b'0'..=b'9' => c - b'0',
+            _ => c - b'a' + 10,
+        }
+    }
+    let s = s.as_bytes();
+    let mut bytes = [0; 32];
+    let mut i = 0;
+    while i < 32 {
+        bytes[i] = digit(s[2 * i]) << 4 | digit(s[2 * i + 1]);
+        i += 1;
+    }
+    bytes
+}
+
+/// Sends [`BTCUSDT`] to every client completing the handshake, returning the URL to connect to.
+async fn tls_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let identity = native_tls::Identity::from_pkcs8(CERT, KEY).unwrap();
+    let acceptor = TlsAcceptor::from(native_tls::TlsAcceptor::new(identity).unwrap());
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            let acceptor = acceptor.clone();
+            tokio::spawn(async move {
+                // Clients rejecting the certificate hang up mid handshake.
+                let Ok(stream) = acceptor.accept(stream).await else {
+                    return;
+                };
+                let Ok(mut ws_stream) = tokio_tungstenite::accept_async(stream).await else {
+                    return;
+                };
+                _ = ws_stream.send(Message::text(BTCUSDT)).await;
+                while ws_stream.next().await.is_some_and(|msg| msg.is_ok()) {}
+            });
+        }
+    });
+    format!("wss://127.0.0.1:{port}/ws/btcusdt@bookTicker")
+}
+
+/// The vectors of NIST's SHA-256 examples, then the fixture against `openssl`.
+#[test]
+fn hashes_sha256() {
+    assert_eq!(
+        cert_sha256(b""),
+        hex("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+    );
+    assert_eq!(
+        cert_sha256(b"abc"),
+        hex("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+    );
+    // Over a block, so padded into a second one.
+    assert_eq!(
+        cert_sha256(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+        hex("248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1")
+    );
+    // Many blocks, carrying the state across them.
+    assert_eq!(
+        cert_sha256(&b"a".repeat(1_000_000)),
+        hex("cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0")
+    );
+
+    let der = native_tls::Certificate::from_pem(CERT)
+        .unwrap()
+        .to_der()
+        .unwrap();
+    assert_eq!(cert_sha256(&der), CERT_SHA256);
+}
+
+#[tokio::test]
+async fn connects_with_pinned_certificate() {
+    let url = tls_server().await;
+    let mut ws_stream = connect_pinned(&url, &CERT_SHA256).await.unwrap();
+    let msg = ws_stream.next().await.unwrap().unwrap();
+    assert_eq!(msg.into_text().unwrap().as_str(), BTCUSDT);
+}
+
+#[tokio::test]
+async fn rejects_other_certificates() {
+    let url = tls_server().await;
+    let mut wrong = CERT_SHA256;
+    wrong[0] ^= 1;
+    let err = connect_pinned(&url, &wrong).await.unwrap_err();
+    assert!(err.to_string().contains("doesn't match"), "{err}");
+
+    let err = connect_pinned(&url.replacen("wss", "ws", 1), &CERT_SHA256)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("only wss://"), "{err}");
+}
+
+#[tokio::test]
+async fn reconnects_with_pinned_certificate() {
+    let url = tls_server().await;
+    let config = ReconnectConfig {
+        cert_sha256: Some(CERT_SHA256),
+        ..ReconnectConfig::default()
+    };
+    let msg = ReconnectingStream::new(url, config)
+        .next()
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(msg.into_text().unwrap().as_str(), BTCUSDT);
+}
@@ -0,0 +1,111 @@
+#![cfg(feature = "serde")]
+
+use binance_json_parsing::{
+    ParseError,
+    decimal::{FixedPoint, parse_fixed},
+    liquidation::Side,
+    order_update::parse_order_trade_update,
+    position::{PositionError, PositionTracker},
+};
+
+/// Decimals written out here are always valid.
+fn fixed(s: &str) -> FixedPoint {
+    parse_fixed(s).unwrap()
+}
+
+const PARTIAL_FILL: &str = r#"{"e":"ORDER_TRADE_UPDATE","E":1744760291000,"T":1744760290999,"o":{"s":"BTCUSDT","c":"web_AbCdEf","S":"SELL","o":"LIMIT","f":"GTC","q":"0.010","p":"84000.00","ap":"84000.00","sp":"0","x":"TRADE","X":"PARTIALLY_FILLED","i":8886774,"l":"0.004","z":"0.004","L":"84000.00","N":"USDT","n":"0.06720000","T":1744760290999,"t":5146970,"b":"0","a":"0","m":false,"R":false,"wt":"CONTRACT_PRICE","ot":"LIMIT","ps":"BOTH","cp":false,"rp":"4.00000000","pP":false,"si":0,"ss":0,"V":"NONE","pm":"NONE","gtd":0}}"#;
+
+#[test]
+fn tracks_buy_then_partial_sell() {
+    let mut tracker = PositionTracker::new();
+    tracker
+        .apply_fill("BTCUSDT", Side::Buy, fixed("0.010"), fixed("83000.00"))
+        .unwrap();
+    assert_eq!(tracker.position("BTCUSDT"), fixed("0.01"));
+    assert_eq!(
+        tracker.unrealized_pnl("BTCUSDT", fixed("83500.00")),
+        Some(fixed("5"))
+    );
+
+    let update = parse_order_trade_update(PARTIAL_FILL).unwrap();
+    assert!(tracker.apply_order_update(&update).unwrap());
+    assert_eq!(tracker.position("BTCUSDT"), fixed("0.006"));
+    // Still entered at 83000.
+    assert_eq!(
+        tracker.unrealized_pnl("BTCUSDT", fixed("83500.00")),
+        Some(fixed("3"))
+    );
+    assert_eq!(tracker.position("ETHUSDT"), fixed("0"));
+}
+
+#[test]
+fn flips_and_closes_positions() {
+    let mut tracker = PositionTracker::new();
+    tracker
+        .apply_fill("BTCUSDT", Side::Buy, fixed("1"), fixed("100"))
+        .unwrap();
+    tracker
+        .apply_fill("BTCUSDT", Side::Sell, fixed("3"), fixed("110"))
+        .unwrap();
+    assert_eq!(tracker.position("BTCUSDT"), fixed("-2"));
+    // Short 2 from 110.
+    assert_eq!(
+        tracker.unrealized_pnl("BTCUSDT", fixed("105")),
+        Some(fixed("10"))
+    );
+
+    tracker
+        .apply_fill("BTCUSDT", Side::Buy, fixed("2"), fixed("90"))
+        .unwrap();
+    assert_eq!(tracker.position("BTCUSDT"), fixed("0"));
+    assert_eq!(
+        tracker.unrealized_pnl("BTCUSDT", fixed("105")),
+        Some(fixed("0"))
+    );
+}
+
+#[test]
+fn rejects_fills_out_of_range() {
+    let mut tracker = PositionTracker::new();
+    let huge = FixedPoint {
+        mantissa: 1,
+        exponent: 100,
+    };
+    assert_eq!(
+        tracker.apply_fill("BTCUSDT", Side::Buy, huge, huge),
+        Err(PositionError::Overflow)
+    );
+    // Left as it was.
+    assert_eq!(tracker.position("BTCUSDT"), fixed("0"));
+
+    tracker
+        .apply_fill("BTCUSDT", Side::Buy, huge, fixed("1"))
+        .unwrap();
+    assert_eq!(tracker.unrealized_pnl("BTCUSDT", huge), None);
+    assert_eq!(tracker.position("BTCUSDT"), huge);
+}
+
+#[test]
+fn parses_order_trade_update() {
+    let update = parse_order_trade_update(PARTIAL_FILL).unwrap();
+    assert_eq!(update.s, "BTCUSDT");
+    assert_eq!(update.S, Side::Sell);
+    assert_eq!(update.i, 8886774);
+    assert_eq!(
+        (update.l, update.z, update.L),
+        ("0.004", "0.004", "84000.00")
+    );
+    assert!(update.is_fill());
+
+    let new = PARTIAL_FILL.replace(r#""x":"TRADE""#, r#""x":"NEW""#);
+    let mut tracker = PositionTracker::new();
+    let update = parse_order_trade_update(&new).unwrap();
+    assert!(!tracker.apply_order_update(&update).unwrap());
+    assert_eq!(tracker.position("BTCUSDT"), fixed("0"));
+
+    let bad_side = PARTIAL_FILL.replace(r#""S":"SELL""#, r#""S":"HOLD""#);
+    assert!(matches!(
+        parse_order_trade_update(&bad_side),
+        Err(ParseError::InvalidJson { .. })
+    ));
+}
@@ -0,0 +1,330 @@
+use std::cmp::Reverse;
+
+use binance_json_parsing::{
+    BookTicker, BookTickerOwned, DiscoveryError, LayoutConfig, MAX_MESSAGE_BYTES,
+    MIN_MESSAGE_BYTES, ParseError, ParsingConfig, detect_config, discover_config,
+    exchange_latency_ms, is_book_ticker_frame, parse_book_ticker, parse_book_ticker_dynamic,
+    parse_book_ticker_fixed,
+};
+
+const BTCUSDT: &str = r#"{"e":"bookTicker","u":7283946601071,"s":"BTCUSDT","b":"83604.80","B":"10.746","a":"83604.90","A":"9.514","T":1744760290967,"E":1744760290968}"#;
+
+#[test]
+fn parses_with_detected_config() {
+    let config = detect_config(BTCUSDT).unwrap();
+    let book_ticker = parse_book_ticker_dynamic(BTCUSDT, config).unwrap();
+
+    let BookTicker {
+        e,
+        u,
+        s,
+        pair,
+        T: transaction_time,
+        b,
+        B: bid_volume,
+        a,
+        A: ask_volume,
+        E: event_time,
+        ..
+    } = book_ticker;
+    assert_eq!(
+        (e, u, s, pair),
+        ("bookTicker", 7283946601071, "BTCUSDT", None)
+    );
+    assert_eq!(
+        [b, bid_volume, a, ask_volume],
+        ["83604.80", "10.746", "83604.90", "9.514"]
+    );
+    assert_eq!(
+        (transaction_time, event_time),
+        (1744760290967, 1744760290968)
+    );
+}
+
+#[test]
+fn discovers_config_of_sample() {
+    assert_eq!(discover_config(BTCUSDT), Ok(ParsingConfig::default()));
+}
+
+#[test]
+fn fails_discovery_on_missing_field() {
+    let no_ask = BTCUSDT.replace(r#""a":"83604.90","#, "");
+    assert_eq!(
+        discover_config(&no_ask),
+        Err(DiscoveryError::MissingField("a"))
+    );
+}
+
+#[test]
+fn fails_discovery_on_config_not_parsing_sample() {
+    // The config has a single price precision, taken from the bid.
+    let longer_ask = BTCUSDT.replace("83604.90", "83604.901");
+    assert!(matches!(
+        discover_config(&longer_ask),
+        Err(DiscoveryError::Validation(ParseError::InvalidBoundary {
+            field: "a",
+            ..
+        }))
+    ));
+}
+
+#[test]
+fn parses_with_const_precisions() {
+    let ticker = parse_book_ticker::<2, 3>(BTCUSDT, LayoutConfig::default()).unwrap();
+    assert_eq!(
+        ticker.into_dynamic(),
+        parse_book_ticker_dynamic(BTCUSDT, ParsingConfig::default()).unwrap()
+    );
+
+    // Detects the layout of a symbol of another length, the precisions being the same.
+    let ethusdt = r#"{"e":"bookTicker","u":7283946603312,"s":"ETHUSDT_250627","b":"1589.37","B":"112.294","a":"1589.38","A":"27.310","T":1744760291012,"E":1744760291014}"#;
+    let ticker = parse_book_ticker::<2, 3>(ethusdt, LayoutConfig::default()).unwrap();
+    assert_eq!(ticker.s, "ETHUSDT_250627");
+}
+
+#[test]
+fn rejects_other_precisions() {
+    assert!(matches!(
+        parse_book_ticker::<3, 3>(BTCUSDT, LayoutConfig::default()),
+        Err(ParseError::InvalidBoundary { field: "b", .. })
+    ));
+    assert!(matches!(
+        parse_book_ticker::<2, 0>(BTCUSDT, LayoutConfig::default()),
+        Err(ParseError::InvalidBoundary { field: "B", .. })
+    ));
+}
+
+#[test]
+fn rejects_messages_out_of_bounds() {
+    let large = format!("{BTCUSDT:<MAX_MESSAGE_BYTES$} ");
+    assert_eq!(
+        parse_book_ticker_dynamic(&large, ParsingConfig::default()),
+        Err(ParseError::MessageTooLarge {
+            len: MAX_MESSAGE_BYTES + 1
+        })
+    );
+    assert_eq!(
+        parse_book_ticker::<2, 3>(&large, LayoutConfig::default()),
+        Err(ParseError::MessageTooLarge {
+            len: MAX_MESSAGE_BYTES + 1
+        })
+    );
+
+    let spot = r#"{"u":0,"s":"X","b":"0","B":"0","a":"0","A":"0"}"#;
+    assert_eq!(spot.len(), MIN_MESSAGE_BYTES);
+    assert!(parse_book_ticker_dynamic(spot, detect_config(spot).unwrap()).is_ok());
+    assert_eq!(
+        parse_book_ticker_dynamic(&spot[1..], ParsingConfig::default()),
+        Err(ParseError::MessageTooShort {
+            len: MIN_MESSAGE_BYTES - 1
+        })
+    );
+}
+
+#[test]
+fn rejects_tiny_messages_without_panicking() {
+    let tiny = [String::new()]
+        .into_iter()
+        .chain((0..=127u8).map(|b| char::from(b).to_string()))
+        .chain((0..=127u8).map(|b| format!("{{{}", char::from(b))));
+    for json in tiny {
+        assert!(parse_book_ticker_dynamic(&json, ParsingConfig::default()).is_err());
+        assert!(parse_book_ticker::<2, 3>(&json, LayoutConfig::default()).is_err());
+        assert!(detect_config(&json).is_err());
+    }
+}
+
+#[test]
+fn tells_book_tickers_from_other_frames() {
+    let spot = r#"{"u":400900217,"s":"BNBUSDT","b":"25.35190000","B":"31.21000000","a":"25.36520000","A":"40.66000000"}"#;
+    assert!(is_book_ticker_frame(BTCUSDT));
+    assert!(is_book_ticker_frame(spot));
+
+    let others = [
+        // Responses to requests.
+        r#"{"result":null,"id":1}"#,
+        r#"{"result":["btcusdt@bookTicker","ethusdt@bookTicker"],"id":3}"#,
+        r#"{"error":{"code":2,"msg":"Invalid request: unknown variant `SUBSCRIBE_ALL`"},"id":4}"#,
+        r#"{"code":-1121,"msg":"Invalid symbol.","id":"5db6cb3e-3e3c-4d2a-a5bc-cde0de4ba421"}"#,
+        // Other events, e.g. on a stream meant for another one.
+        r#"{"e":"markPriceUpdate","E":1744760290968,"s":"BTCUSDT","p":"83604.85000000","i":"83610.12340000","P":"83580.10000000","r":"0.00010000","T":1744761600000}"#,
+        r#"{"e":"listenKeyExpired","E":1744760290968,"listenKey":"pqia91ma19a5s61cv6a81va65sdf19v8a65a1a5s61cv6a81va65sdf19v8a65a1"}"#,
+        r#"{"e":"serverShutdown","E":1744760290968,"reason":"Server is shutting down for maintenance"}"#,
+        // A spot-like prefix without the symbol after the id.
+        r#"{"u":400900217,"e":"bookTicker","s":"BNBUSDT","b":"25.35190000","B":"31.21000000"}"#,
+        // Heartbeats, as text rather than ping frames.
+        "ping",
+        "",
+    ];
+    for json in others {
+        assert!(!is_book_ticker_frame(json), "{json}");
+        assert!(parse_book_ticker_dynamic(json, ParsingConfig::default()).is_err());
+        assert!(parse_book_ticker::<2, 3>(json, LayoutConfig::default()).is_err());
+        if json.len() >= MIN_MESSAGE_BYTES {
+            assert_eq!(
+                parse_book_ticker_dynamic(json, ParsingConfig::default()),
+                Err(ParseError::WrongEventType),
+                "{json}"
+            );
+        }
+    }
+}
+
+#[test]
+fn computes_exchange_latency() {
+    let tick = |transaction_time, event_time| {
+        let mut ticker = parse_book_ticker_dynamic(BTCUSDT, ParsingConfig::default()).unwrap();
+        ticker.T = transaction_time;
+        ticker.E = event_time;
+        ticker
+    };
+    assert_eq!(
+        exchange_latency_ms(&parse_book_ticker_dynamic(BTCUSDT, ParsingConfig::default()).unwrap()),
+        1
+    );
+    assert_eq!(exchange_latency_ms(&tick(1744760290967, 1744760290972)), 5);
+    assert_eq!(exchange_latency_ms(&tick(1744760290967, 1744760290967)), 0);
+    // The matching engine's clock ahead of the one sending the event.
+    assert_eq!(exchange_latency_ms(&tick(1744760290967, 1744760290964)), -3);
+}
+
+#[test]
+fn converts_between_borrowed_and_owned() {
+    let ticker = parse_book_ticker_dynamic(BTCUSDT, ParsingConfig::default()).unwrap();
+    let owned = BookTickerOwned::try_from(ticker).unwrap();
+    assert_eq!(owned.s, "BTCUSDT");
+    assert_eq!(owned.b.as_str(), "83604.80");
+    assert_eq!(BookTicker::from(&owned), ticker);
+    assert_eq!(
+        BookTickerOwned::try_from(BookTicker::from(&owned)).unwrap(),
+        owned
+    );
+
+    let coin_m = r#"{"e":"bookTicker","u":7283946601071,"s":"BTCUSD_PERP","ps":"BTCUSD","b":"83604.8","B":"107","a":"83604.9","A":"95","T":1744760290967,"E":1744760290968}"#;
+    let ticker = parse_book_ticker_dynamic(coin_m, detect_config(coin_m).unwrap()).unwrap();
+    let owned = BookTickerOwned::try_from(ticker).unwrap();
+    assert_eq!(BookTicker::from(&owned), ticker);
+    assert_eq!(BookTicker::from(&owned).pair, Some("BTCUSD"));
+}
+
+#[test]
+fn results_can_be_cloned_and_hashed() {
+    fn assert_clone<T: Clone>() {}
+    fn assert_copy<T: Copy>() {}
+    assert_clone::<ParseError>();
+    assert_clone::<DiscoveryError>();
+    assert_copy::<BookTicker<'_>>();
+
+    let ticker = parse_book_ticker_dynamic(BTCUSDT, ParsingConfig::default()).unwrap();
+    let owned = BookTickerOwned::try_from(ticker).unwrap();
+    let retained: std::collections::HashSet<_> = [owned.clone(), owned].into_iter().collect();
+    assert_eq!(retained.len(), 1);
+}
+
+#[test]
+fn displays_best_bid_and_ask() {
+    let ticker = parse_book_ticker_dynamic(BTCUSDT, ParsingConfig::default()).unwrap();
+    let line = "BTCUSDT  83604.80 × 10.746  |  83604.90 × 9.514  @ 2025-04-15 23:38:10.967 UTC";
+    assert_eq!(ticker.to_string(), line);
+    assert_eq!(
+        BookTickerOwned::try_from(ticker)
+            .unwrap()
+            .to_display_string(),
+        line
+    );
+
+    let at = |transaction_time| {
+        let mut ticker = ticker;
+        ticker.T = transaction_time;
+        ticker.to_display_string()
+    };
+    assert!(at(1713177090967).ends_with("@ 2024-04-15 10:31:30.967 UTC"));
+    // A leap day, and the first instant of a year.
+    assert!(at(951782400000).ends_with("@ 2000-02-29 00:00:00.000 UTC"));
+    assert!(at(1704067200001).ends_with("@ 2024-01-01 00:00:00.001 UTC"));
+
+    // Spot tickers have no time to show.
+    let spot = r#"{"u":400900217,"s":"BNBUSDT","b":"25.35190000","B":"31.21000000","a":"25.36520000","A":"40.66000000"}"#;
+    let ticker = parse_book_ticker_dynamic(spot, detect_config(spot).unwrap()).unwrap();
+    assert_eq!(
+        ticker.to_display_string(),
+        "BNBUSDT  25.35190000 × 31.21000000  |  25.36520000 × 40.66000000"
+    );
+}
+
+#[test]
+fn orders_by_transaction_time() {
+    let ticker = parse_book_ticker_dynamic(BTCUSDT, ParsingConfig::default()).unwrap();
+    let at = |transaction_time, update_id, bid| {
+        let mut ticker = ticker;
+        ticker.T = transaction_time;
+        ticker.u = update_id;
+        ticker.b = bid;
+        BookTickerOwned::try_from(ticker).unwrap()
+    };
+
+    // Out of order, with ties on the time broken by the update id, then by the bid.
+    let mut heap = std::collections::BinaryHeap::from([
+        Reverse(at(1744760290970, 3, "83604.80")),
+        Reverse(at(1744760290967, 1, "83604.80")),
+        Reverse(at(1744760290968, 2, "83604.90")),
+        Reverse(at(1744760290968, 2, "83604.70")),
+        Reverse(at(1744760290968, 1, "83605.00")),
+        Reverse(at(1744760290969, 2, "83604.80")),
+    ]);
+    let popped: Vec<_> = std::iter::from_fn(|| heap.pop())
+        .map(|Reverse(ticker)| (ticker.T, ticker.u, ticker.b.to_string()))
+        .collect();
+    assert_eq!(
+        popped,
+        [
+            (1744760290967, 1, "83604.80".to_owned()),
+            (1744760290968, 1, "83605.00".to_owned()),
+            (1744760290968, 2, "83604.70".to_owned()),
+            (1744760290968, 2, "83604.90".to_owned()),
+            (1744760290969, 2, "83604.80".to_owned()),
+            (1744760290970, 3, "83604.80".to_owned()),
+        ]
+    );
+
+    // Only equal tickers compare equal, even with the same time, id and bid.
+    let a = at(1744760290967, 1, "83604.80");
+    assert_eq!(a.cmp(&a.clone()), std::cmp::Ordering::Equal);
+    let mut b = a.clone();
+    b.s = "ETHUSDT".to_owned();
+    assert_ne!(a.cmp(&b), std::cmp::Ordering::Equal);
+}
+
+#[test]
+fn rejects_malformed_decimals_in_fixed_point() {
+    let ticker = parse_book_ticker_fixed(BTCUSDT, ParsingConfig::default()).unwrap();
+    assert_eq!(ticker.b.to_string(), "83604.80");
+    assert_eq!(ticker.A.to_string(), "9.514");
+
+    // Same layout, so only caught once the volume is parsed.
+    let malformed = BTCUSDT.replace(r#""B":"10.746""#, r#""B":"10.74a""#);
+    assert!(parse_book_ticker_dynamic(&malformed, ParsingConfig::default()).is_ok());
+    assert_eq!(
+        parse_book_ticker_fixed(&malformed, ParsingConfig::default()),
+        Err(ParseError::InvalidDecimal)
+    );
+}
+
+#[test]
+fn fails_to_own_decimals_over_capacity() {
+    let long = BTCUSDT.replace(r#""B":"10.746""#, r#""B":"1234567890123.746""#);
+    let config = detect_config(&long).unwrap();
+    let ticker = parse_book_ticker_dynamic(&long, config).unwrap();
+    assert_eq!(ticker.B, "1234567890123.746");
+    assert_eq!(
+        BookTickerOwned::try_from(ticker),
+        Err(ParseError::DecimalTooLong { len: 17 })
+    );
+
+    let fits = BTCUSDT.replace(r#""B":"10.746""#, r#""B":"1234567890.746""#);
+    let ticker = parse_book_ticker_dynamic(&fits, detect_config(&fits).unwrap()).unwrap();
+    assert_eq!(
+        BookTickerOwned::try_from(ticker).unwrap().B.as_str(),
+        "1234567890.746"
+    );
+}
@@ -0,0 +1,115 @@
+#![cfg(feature = "std")]
+
+use std::{sync::Arc, thread};
+
+use binance_json_parsing::queue::SpscQueue;
+
+#[test]
+fn pops_in_push_order() {
+    let (mut producer, mut consumer) = SpscQueue::<u32, 8>::new().split();
+    assert_eq!(consumer.try_pop(), None);
+
+    for value in 0..5 {
+        producer.try_push(value).unwrap();
+    }
+    assert_eq!(consumer.try_pop(), Some(0));
+    assert_eq!(consumer.try_pop(), Some(1));
+    producer.try_push(5).unwrap();
+    let rest: Vec<_> = std::iter::from_fn(|| consumer.try_pop()).collect();
+    assert_eq!(rest, [2, 3, 4, 5]);
+    assert_eq!(consumer.try_pop(), None);
+}
+
+#[test]
+fn gives_back_values_once_full() {
+    let (mut producer, mut consumer) = SpscQueue::<u32, 4>::new().split();
+    for value in 0..4 {
+        producer.try_push(value).unwrap();
+    }
+    assert_eq!(producer.try_push(4), Err(4));
+    assert_eq!(producer.try_push(5), Err(5));
+
+    // One slot freed, one more value taken.
+    assert_eq!(consumer.try_pop(), Some(0));
+    producer.try_push(4).unwrap();
+    assert_eq!(producer.try_push(5), Err(5));
+}
+
+#[test]
+fn wraps_around_capacity() {
+    let (mut producer, mut consumer) = SpscQueue::<usize, 4>::new().split();
+    // Many more than the 4 slots, pushed in bursts of each length up to full.
+    let mut next = 0;
+    for round in 0..100 {
+        let burst = round % 4 + 1;
+        for i in 0..burst {
+            producer.try_push(next + i).unwrap();
+        }
+        for i in 0..burst {
+            assert_eq!(consumer.try_pop(), Some(next + i), "round {round}");
+        }
+        assert_eq!(consumer.try_pop(), None);
+        next += burst;
+    }
+}
+
+#[test]
+fn drops_values_left_in_the_queue() {
+    let value = Arc::new(());
+    {
+        let (mut producer, mut consumer) = SpscQueue::<Arc<()>, 8>::new().split();
+        for _ in 0..5 {
+            producer.try_push(Arc::clone(&value)).unwrap();
+        }
+        // Popped ones are the caller's, only the other 3 are left for the queue to drop.
+        drop(consumer.try_pop());
+        drop(consumer.try_pop());
+        assert_eq!(Arc::strong_count(&value), 4);
+
+        // Still there with only one side gone.
+        drop(producer);
+        assert_eq!(Arc::strong_count(&value), 4);
+    }
+    assert_eq!(Arc::strong_count(&value), 1);
+
+    // Wrapped around, so that the values left are on both ends of the slots.
+    let (mut producer, mut consumer) = SpscQueue::<Arc<()>, 4>::new().split();
+    for _ in 0..3 {
+        producer.try_push(Arc::clone(&value)).unwrap();
+        drop(consumer.try_pop());
+    }
+    for _ in 0..4 {
+        producer.try_push(Arc::clone(&value)).unwrap();
+    }
+    assert_eq!(Arc::strong_count(&value), 5);
+    drop((producer, consumer));
+    assert_eq!(Arc::strong_count(&value), 1);
+}
+
+#[test]
+fn hands_over_values_between_threads() {
+    const COUNT: u64 = 100_000;
+
+    let (mut producer, mut consumer) = SpscQueue::<u64, 64>::new().split();
+    let pushing = thread::spawn(move || {
+        for mut value in 0..COUNT {
+            while let Err(rejected) = producer.try_push(value) {
+                value = rejected;
+                thread::yield_now();
+            }
+        }
+    });
+
+    let mut expected = 0;
+    while expected < COUNT {
+        match consumer.try_pop() {
+            Some(value) => {
+                assert_eq!(value, expected);
+                expected += 1;
+            }
+            None => thread::yield_now(),
+        }
+    }
+    pushing.join().unwrap();
+    assert_eq!(consumer.try_pop(), None);
+}
@@ -0,0 +1,70 @@
+#![cfg(feature = "std")]
+
+use std::time::{Duration, Instant};
+
+use binance_json_parsing::rate::RateCounter;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn counts_within_the_last_second() {
+    let start = Instant::now();
+    let mut rate = RateCounter::starting_at(start);
+    assert_eq!(rate.rate(), 0);
+    for _ in 0..5 {
+        rate.tick_at(start);
+    }
+    assert_eq!(rate.rate(), 5);
+
+    // Second and last of the 10 slots of 100ms.
+    rate.tick_at(start + ms(150));
+    assert_eq!(rate.rate(), 6);
+    rate.tick_at(start + ms(999));
+    assert_eq!(rate.rate(), 7);
+}
+
+#[test]
+fn rolls_slots_over() {
+    let start = Instant::now();
+    let mut rate = RateCounter::starting_at(start);
+    for _ in 0..5 {
+        rate.tick_at(start);
+    }
+    rate.tick_at(start + ms(150));
+    rate.tick_at(start + ms(950));
+
+    // Back to the first slot, which lets go of the 5 counted in it a second ago.
+    rate.tick_at(start + ms(1000));
+    assert_eq!(rate.rate(), 3);
+    rate.tick_at(start + ms(1099));
+    assert_eq!(rate.rate(), 4);
+    rate.tick_at(start + ms(1150));
+    assert_eq!(rate.rate(), 4);
+    // Every slot in between is cleared on the way, not just the one landed in, so the tick at
+    // 950ms is gone too.
+    rate.tick_at(start + ms(2050));
+    assert_eq!(rate.rate(), 2);
+}
+
+#[test]
+fn clears_every_slot_after_a_gap() {
+    let start = Instant::now();
+    let mut rate = RateCounter::starting_at(start);
+    for i in 0..10 {
+        rate.tick_at(start + ms(i * 100));
+    }
+    assert_eq!(rate.rate(), 10);
+
+    rate.tick_at(start + ms(5_050));
+    assert_eq!(rate.rate(), 1);
+    // Slots still line up with the start afterwards.
+    rate.tick_at(start + ms(5_099));
+    rate.tick_at(start + ms(5_100));
+    assert_eq!(rate.rate(), 3);
+    rate.tick_at(start + ms(6_000));
+    assert_eq!(rate.rate(), 2);
+    rate.tick_at(start + ms(6_050));
+    assert_eq!(rate.rate(), 3);
+}
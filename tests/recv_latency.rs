@@ -0,0 +1,48 @@
+#![cfg(feature = "std")]
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use binance_json_parsing::{
+    ParsingConfig, parse_book_ticker_dynamic,
+    recv_latency::{LatencyStats, RecvLatencyTracker, WINDOW, receive_latency_ms},
+};
+
+const BTCUSDT: &str = r#"{"e":"bookTicker","u":7283946601071,"s":"BTCUSDT","b":"83604.80","B":"10.746","a":"83604.90","A":"9.514","T":1744760290967,"E":1744760290968}"#;
+
+#[test]
+fn computes_receive_latency() {
+    let tick = parse_book_ticker_dynamic(BTCUSDT, ParsingConfig::default()).unwrap();
+    let at = |ms| UNIX_EPOCH + Duration::from_millis(ms);
+    assert_eq!(receive_latency_ms(&tick, at(1744760291009)), 42);
+    assert_eq!(receive_latency_ms(&tick, at(1744760290967)), 0);
+    // The local clock behind the exchange's.
+    assert_eq!(receive_latency_ms(&tick, at(1744760290960)), -7);
+    assert!(receive_latency_ms(&tick, SystemTime::now()) > 0);
+}
+
+fn percentiles(stats: LatencyStats) -> [i64; 4] {
+    [stats.p50, stats.p90, stats.p99, stats.max]
+}
+
+#[test]
+fn tracks_latest_window() {
+    let mut tracker = RecvLatencyTracker::new();
+    assert_eq!(tracker.stats(), LatencyStats::default());
+
+    tracker.record(5);
+    assert_eq!(percentiles(tracker.stats()), [5, 5, 5, 5]);
+
+    // Way off at first, then pushed out of the window by 1..=1000.
+    for _ in 0..WINDOW {
+        tracker.record(1_000_000);
+    }
+    for latency in 1..=WINDOW as i64 {
+        tracker.record(latency);
+    }
+    assert_eq!(percentiles(tracker.stats()), [500, 900, 990, 1000]);
+
+    tracker.record(-3);
+    assert_eq!(tracker.stats().max, 1000);
+    tracker.record(2000);
+    assert_eq!(tracker.stats().max, 2000);
+}
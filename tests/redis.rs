@@ -0,0 +1,205 @@
+//! The sink against a mock server recording the commands it gets, and against a real Redis in
+//! the ignored tests, run with e.g.
+//!
+//! ```sh
+//! REDIS_URL=redis://127.0.0.1/ cargo test --features redis --test redis -- --ignored
+//! ```
+//!
+//! which default to `redis://127.0.0.1/` without `REDIS_URL`.
+#![cfg(feature = "redis")]
+
+use binance_json_parsing::{
+    BookTicker, ParsingConfig, parse_book_ticker_dynamic,
+    redis::{RedisConfig, RedisSink, Reply},
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::TcpListener,
+    sync::mpsc,
+};
+
+const BTCUSDT: &str = r#"{"e":"bookTicker","u":7283946601071,"s":"BTCUSDT","b":"83604.80","B":"10.746","a":"83604.90","A":"9.514","T":1744760290967,"E":1744760290968}"#;
+
+fn tick() -> BookTicker<'static> {
+    parse_book_ticker_dynamic(BTCUSDT, ParsingConfig::default()).unwrap()
+}
+
+/// Sends every command it gets, acknowledging them like Redis would.
+async fn mock_server() -> (u16, mpsc::UnboundedReceiver<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (commands, received) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut stream = BufReader::new(stream);
+        let mut line = String::new();
+        while stream.read_line(&mut line).await.unwrap() > 0 {
+            let len: usize = line.trim_end().strip_prefix('*').unwrap().parse().unwrap();
+            let mut args = Vec::with_capacity(len);
+            for _ in 0..len {
+                line.clear();
+                stream.read_line(&mut line).await.unwrap();
+                let len: usize = line.trim_end().strip_prefix('$').unwrap().parse().unwrap();
+                let mut arg = vec![0; len + 2];
+                stream.read_exact(&mut arg).await.unwrap();
+                arg.truncate(len);
+                args.push(String::from_utf8(arg).unwrap());
+            }
+            line.clear();
+
+            let reply: &[u8] = match args[0].as_str() {
+                "XADD" => b"$15\r\n1744760290968-0\r\n",
+                "FAIL" => b"-ERR unknown command 'FAIL'\r\n",
+                _ => b"+OK\r\n",
+            };
+            stream.get_mut().write_all(reply).await.unwrap();
+            commands.send(args).unwrap();
+        }
+    });
+    (port, received)
+}
+
+#[tokio::test]
+async fn sends_xadd_with_every_field() {
+    let (port, mut commands) = mock_server().await;
+    let mut sink = RedisSink::connect(RedisConfig::new(format!("redis://127.0.0.1:{port}")))
+        .await
+        .unwrap();
+
+    assert_eq!(sink.add(&tick()).await.unwrap(), "1744760290968-0");
+    assert_eq!(
+        commands.recv().await.unwrap(),
+        [
+            "XADD",
+            "BTCUSDT:bookTicker",
+            "*",
+            "u",
+            "7283946601071",
+            "T",
+            "1744760290967",
+            "b",
+            "83604.80",
+            "B",
+            "10.746",
+            "a",
+            "83604.90",
+            "A",
+            "9.514",
+            "E",
+            "1744760290968"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn trims_configured_key() {
+    let (port, mut commands) = mock_server().await;
+    let config = RedisConfig {
+        key: "ticks:{symbol}".to_owned(),
+        max_len: Some(100_000),
+        ..RedisConfig::new(format!("redis://:secret@127.0.0.1:{port}/2"))
+    };
+    let mut sink = RedisSink::connect(config).await.unwrap();
+    sink.add(&tick()).await.unwrap();
+
+    assert_eq!(commands.recv().await.unwrap(), ["AUTH", "secret"]);
+    assert_eq!(commands.recv().await.unwrap(), ["SELECT", "2"]);
+    let xadd = commands.recv().await.unwrap();
+    assert_eq!(
+        xadd[..6],
+        ["XADD", "ticks:BTCUSDT", "MAXLEN", "~", "100000", "*"]
+    );
+}
+
+#[tokio::test]
+async fn returns_error_replies() {
+    let (port, _commands) = mock_server().await;
+    let mut sink = RedisSink::connect(RedisConfig::new(format!("redis://127.0.0.1:{port}/")))
+        .await
+        .unwrap();
+
+    let err = sink.command(&[b"FAIL"]).await.unwrap_err();
+    assert_eq!(err.to_string(), "ERR unknown command 'FAIL'");
+    assert_eq!(
+        sink.command(&[b"PING"]).await.unwrap(),
+        Reply::Simple("OK".to_owned())
+    );
+}
+
+async fn connect_to_redis(key: &str) -> RedisSink {
+    let url = std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1/".to_owned());
+    let config = RedisConfig {
+        key: key.to_owned(),
+        max_len: Some(1000),
+        ..RedisConfig::new(url)
+    };
+    RedisSink::connect(config).await.unwrap()
+}
+
+#[tokio::test]
+#[ignore = "needs a Redis server, see REDIS_URL"]
+async fn appends_to_redis_stream() {
+    let key = format!(
+        "binance-json-parsing-test:{}:{{symbol}}",
+        std::process::id()
+    );
+    let mut sink = connect_to_redis(&key).await;
+    let key = sink.key("BTCUSDT");
+    sink.command(&[b"DEL", key.as_bytes()]).await.unwrap();
+
+    let id = sink.add(&tick()).await.unwrap();
+    let entries = sink
+        .command(&[b"XRANGE", key.as_bytes(), b"-", b"+"])
+        .await
+        .unwrap();
+    sink.command(&[b"DEL", key.as_bytes()]).await.unwrap();
+
+    let bulk = |s: &str| Reply::Bulk(Some(s.as_bytes().to_vec()));
+    let fields = [
+        "u",
+        "7283946601071",
+        "T",
+        "1744760290967",
+        "b",
+        "83604.80",
+        "B",
+        "10.746",
+        "a",
+        "83604.90",
+        "A",
+        "9.514",
+        "E",
+        "1744760290968",
+    ];
+    assert_eq!(
+        entries,
+        Reply::Array(Some(vec![Reply::Array(Some(vec![
+            bulk(&id),
+            Reply::Array(Some(fields.into_iter().map(bulk).collect())),
+        ]))]))
+    );
+}
+
+#[tokio::test]
+#[ignore = "needs a Redis server, see REDIS_URL"]
+async fn trims_redis_stream() {
+    let key = format!(
+        "binance-json-parsing-test:{}:trim:{{symbol}}",
+        std::process::id()
+    );
+    let mut sink = connect_to_redis(&key).await;
+    let key = sink.key("BTCUSDT");
+    sink.command(&[b"DEL", key.as_bytes()]).await.unwrap();
+
+    for _ in 0..5000 {
+        sink.add(&tick()).await.unwrap();
+    }
+    let len = sink.command(&[b"XLEN", key.as_bytes()]).await.unwrap();
+    sink.command(&[b"DEL", key.as_bytes()]).await.unwrap();
+
+    // Trimmed by whole nodes of the stream, so only roughly down to the limit.
+    let Reply::Integer(len) = len else {
+        panic!("{len:?}")
+    };
+    assert!((1000..2000).contains(&len), "{len} entries");
+}
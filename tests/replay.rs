@@ -0,0 +1,95 @@
+#![cfg(feature = "ws")]
+
+use std::{fs, path::PathBuf, time::Duration};
+
+use binance_json_parsing::{
+    binlog::{BinaryLog, replay_realtime},
+    detect_config,
+};
+use futures_util::StreamExt;
+use tokio::time::Instant;
+
+const BTCUSDT: &str = r#"{"e":"bookTicker","u":7283946601071,"s":"BTCUSDT","b":"83604.80","B":"10.746","a":"83604.90","A":"9.514","T":1744760290967,"E":1744760290968}"#;
+const T0: u64 = 1744760290967;
+
+/// A path of its own for each test, removed when dropped.
+struct TempPath(PathBuf);
+
+impl TempPath {
+    fn new(name: &str) -> Self {
+        Self(std::env::temp_dir().join(format!("replay-{}-{name}.bjpl", std::process::id())))
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        _ = fs::remove_file(&self.0);
+    }
+}
+
+/// Records a tick for each offset from [`T0`] in milliseconds.
+fn write_log(path: &TempPath, offsets: &[u64]) {
+    let mut log = BinaryLog::create(&path.0).unwrap();
+    for offset in offsets {
+        let time = (T0 + offset).to_string();
+        log.write_frame(&BTCUSDT.replace(&T0.to_string(), &time))
+            .unwrap();
+    }
+    log.flush().unwrap();
+}
+
+/// How long after the first tick each tick was yielded at `speed`.
+async fn replay(path: &TempPath, speed: f64) -> Vec<Duration> {
+    let config = detect_config(BTCUSDT).unwrap();
+    let ticks = replay_realtime(&path.0, config, speed).unwrap();
+    let start = Instant::now();
+    ticks
+        .map(|tick| {
+            tick.unwrap();
+            start.elapsed()
+        })
+        .collect()
+        .await
+}
+
+fn assert_within_5_percent(delays: &[Duration], expected_ms: &[f64]) {
+    assert_eq!(delays.len(), expected_ms.len());
+    for (delay, expected) in delays.iter().zip(expected_ms) {
+        let delay = delay.as_secs_f64() * 1000.0;
+        assert!(
+            (delay - expected).abs() <= expected * 0.05,
+            "{delay} ms instead of {expected} ms"
+        );
+    }
+}
+
+// Inter-tick delays of 10 ms, 90 ms, 400 ms and 1.5 s.
+const OFFSETS: [u64; 5] = [0, 10, 100, 500, 2000];
+
+#[tokio::test(start_paused = true)]
+async fn paces_ticks_like_recorded() {
+    let path = TempPath::new("original");
+    write_log(&path, &OFFSETS);
+    let delays = replay(&path, 1.0).await;
+    assert_within_5_percent(&delays, &[0.0, 10.0, 100.0, 500.0, 2000.0]);
+    let gaps: Vec<_> = delays.windows(2).map(|pair| pair[1] - pair[0]).collect();
+    assert_within_5_percent(&gaps, &[10.0, 90.0, 400.0, 1500.0]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn speeds_up_replays() {
+    let path = TempPath::new("sped-up");
+    write_log(&path, &OFFSETS);
+    assert_within_5_percent(&replay(&path, 2.0).await, &[0.0, 5.0, 50.0, 250.0, 1000.0]);
+    assert_within_5_percent(
+        &replay(&path, 0.5).await,
+        &[0.0, 20.0, 200.0, 1000.0, 4000.0],
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn yields_ticks_back_in_time_right_away() {
+    let path = TempPath::new("back-in-time");
+    write_log(&path, &[100, 200, 0, 300]);
+    assert_within_5_percent(&replay(&path, 1.0).await, &[0.0, 100.0, 100.0, 200.0]);
+}
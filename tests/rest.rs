@@ -0,0 +1,144 @@
+//! The REST client against a local server answering with stored responses.
+#![cfg(all(feature = "ws", feature = "serde"))]
+
+use std::{io, time::Duration};
+
+use binance_json_parsing::{
+    decimal::parse_fixed,
+    depth::parse_depth_snapshot,
+    order_book::OrderBook,
+    rest::{MAX_RESPONSE_LEN, RestClient, fetch_depth_snapshot},
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+    task::JoinHandle,
+};
+
+const SNAPSHOT: &str = include_str!("fixtures/depth_snapshot.json");
+
+/// Serves one connection with `response`, returning the request it got.
+async fn serve_once(response: String) -> (RestClient, JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let client = RestClient::new(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+    let server = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        let mut buf = [0; 1024];
+        while !request.ends_with(b"\r\n\r\n") {
+            let n = stream.read(&mut buf).await.unwrap();
+            request.extend_from_slice(&buf[..n]);
+        }
+        stream.write_all(response.as_bytes()).await.unwrap();
+        String::from_utf8(request).unwrap()
+    });
+    (client, server)
+}
+
+#[tokio::test]
+async fn fetches_snapshot() {
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{SNAPSHOT}",
+        SNAPSHOT.len()
+    );
+    let (client, server) = serve_once(response).await;
+
+    let snapshot = fetch_depth_snapshot("BTCUSDT", 5, &client).await.unwrap();
+    let request = server.await.unwrap();
+
+    assert!(request.starts_with("GET /fapi/v1/depth?symbol=BTCUSDT&limit=5 HTTP/1.1\r\n"));
+    assert_eq!(snapshot.last_update_id, 7283946601070);
+    assert_eq!(snapshot.bids.len(), 5);
+    assert_eq!(snapshot.asks.len(), 5);
+    assert_eq!(
+        snapshot.bids[0],
+        (
+            parse_fixed("83604.80").unwrap(),
+            parse_fixed("10.746").unwrap()
+        )
+    );
+    assert_eq!(
+        snapshot.asks[4],
+        (
+            parse_fixed("83606.00").unwrap(),
+            parse_fixed("0.035").unwrap()
+        )
+    );
+    // Same as the positional parser.
+    assert_eq!(snapshot, parse_depth_snapshot(SNAPSHOT).unwrap());
+
+    let mut book = OrderBook::new();
+    book.apply_snapshot(&snapshot);
+    assert_eq!(book.best_bid(), Some(snapshot.bids[0]));
+    assert_eq!(book.best_ask(), Some(snapshot.asks[0]));
+}
+
+#[tokio::test]
+async fn fetches_chunked_snapshot() {
+    let (head, tail) = SNAPSHOT.split_at(100);
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n{:x}\r\n{head}\r\n{:x}\r\n{tail}\r\n0\r\n\r\n",
+        head.len(),
+        tail.len()
+    );
+    let (client, _server) = serve_once(response).await;
+
+    let snapshot = fetch_depth_snapshot("BTCUSDT", 5, &client).await.unwrap();
+    assert_eq!(snapshot, parse_depth_snapshot(SNAPSHOT).unwrap());
+}
+
+#[tokio::test]
+async fn rejects_chunk_sizes_past_the_body() {
+    let response =
+        "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\nffffffffffffffff\r\n{}\r\n0\r\n\r\n";
+    let (client, _server) = serve_once(response.to_owned()).await;
+
+    let err = fetch_depth_snapshot("BTCUSDT", 5, &client)
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[tokio::test]
+async fn rejects_responses_too_long() {
+    let body = " ".repeat(MAX_RESPONSE_LEN);
+    let response = format!("HTTP/1.1 200 OK\r\nContent-Length: {MAX_RESPONSE_LEN}\r\n\r\n{body}");
+    let (client, _server) = serve_once(response).await;
+
+    let err = client.get("/fapi/v1/depth").await.unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert!(err.to_string().contains("too long"), "{err}");
+}
+
+#[tokio::test]
+async fn times_out_on_silent_servers() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let client = RestClient::new(&format!("http://{}", listener.local_addr().unwrap()))
+        .unwrap()
+        .with_timeout(Duration::from_millis(100));
+    // Accepting and then never answering.
+    let _server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        std::future::pending::<()>().await;
+        drop(stream);
+    });
+
+    let err = fetch_depth_snapshot("BTCUSDT", 5, &client)
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+}
+
+#[tokio::test]
+async fn fails_on_error_status() {
+    let body = r#"{"code":-1121,"msg":"Invalid symbol."}"#;
+    let response = format!(
+        "HTTP/1.1 400 Bad Request\r\nContent-Length: {}\r\n\r\n{body}",
+        body.len()
+    );
+    let (client, _server) = serve_once(response).await;
+
+    let err = fetch_depth_snapshot("NOPE", 5, &client).await.unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::Other);
+    assert!(err.to_string().contains("Invalid symbol."), "{err}");
+}
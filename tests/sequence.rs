@@ -0,0 +1,41 @@
+use binance_json_parsing::sequence::{GapEvent, SequenceChecker};
+
+/// What a gap is down to, since it can't be built here.
+fn range(gap: GapEvent) -> (u64, u64) {
+    (gap.expected, gap.got)
+}
+
+#[test]
+fn reports_gap() {
+    let mut checker = SequenceChecker::new();
+
+    assert_eq!(checker.check(1), None);
+    assert_eq!(checker.check(2), None);
+    assert_eq!(checker.check(4).map(range), Some((3, 4)));
+    // Continues from after the gap.
+    assert_eq!(checker.check(5), None);
+}
+
+#[test]
+fn ignores_repeated_ids() {
+    let mut checker = SequenceChecker::new();
+
+    assert_eq!(checker.check(10), None);
+    assert_eq!(checker.check(10), None);
+    assert_eq!(checker.check(9), None);
+    assert_eq!(checker.check(11), None);
+}
+
+#[test]
+fn calls_back_on_gap() {
+    let mut checker = SequenceChecker::new();
+    let mut gaps = Vec::new();
+    for id in [1, 2, 4, 5, 8] {
+        checker.check_with(id, |gap| gaps.push(gap));
+    }
+
+    assert_eq!(
+        gaps.into_iter().map(range).collect::<Vec<_>>(),
+        [(3, 4), (6, 8)]
+    );
+}
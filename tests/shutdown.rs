@@ -0,0 +1,71 @@
+//! Shutting down the stream, against a local server instead of Binance.
+#![cfg(feature = "ws")]
+
+mod common;
+
+use std::time::Duration;
+
+use binance_json_parsing::{
+    reconnect::{ReconnectConfig, ReconnectingStream},
+    shutdown::ShutdownToken,
+};
+use common::mock_server::MockBinanceServer;
+use futures_util::StreamExt;
+
+const BTCUSDT: &str = r#"{"e":"bookTicker","u":7283946601071,"s":"BTCUSDT","b":"83604.80","B":"10.746","a":"83604.90","A":"9.514","T":1744760290967,"E":1744760290968}"#;
+
+#[tokio::test]
+async fn cancelling_closes_the_connection() {
+    let server = MockBinanceServer::builder()
+        .messages(vec![BTCUSDT.to_owned()])
+        .interval(Duration::from_millis(5))
+        .send_loop()
+        .build();
+    let mut ws_stream = ReconnectingStream::new(server.url(), ReconnectConfig::default());
+    let shutdown = ShutdownToken::new();
+
+    // As in the receiving loop of the binary, cancelled from elsewhere after a few messages.
+    let mut received = 0;
+    loop {
+        let msg = tokio::select! {
+            msg = ws_stream.next() => msg,
+            () = shutdown.cancelled() => break,
+        };
+        assert!(msg.unwrap().unwrap().is_text());
+        received += 1;
+        if received == 3 {
+            let shutdown = shutdown.clone();
+            tokio::spawn(async move { shutdown.cancel() });
+        }
+    }
+    assert!(shutdown.is_cancelled());
+    assert_eq!(server.closes(), 0);
+
+    // Answered by the server right away, well within the time `close` would wait for it.
+    tokio::time::timeout(Duration::from_millis(500), ws_stream.close())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(server.closes(), 1);
+    assert!(ws_stream.next().await.is_none());
+    assert_eq!(server.connections(), 1);
+}
+
+#[tokio::test]
+async fn cancelling_wakes_every_clone() {
+    let shutdown = ShutdownToken::default();
+    let waiting: Vec<_> = (0..3)
+        .map(|_| {
+            let shutdown = shutdown.clone();
+            tokio::spawn(async move { shutdown.cancelled().await })
+        })
+        .collect();
+    assert!(!shutdown.is_cancelled());
+
+    shutdown.clone().cancel();
+    for waiting in waiting {
+        waiting.await.unwrap();
+    }
+    // And right away once cancelled already.
+    shutdown.cancelled().await;
+}
@@ -0,0 +1,86 @@
+//! `simd_find_byte` against a plain scalar search. Only vectorized when built with the `sse2` or
+//! `avx2` feature for a target that has it, as CI does with `-C target-cpu=native`.
+
+use binance_json_parsing::simd::simd_find_byte;
+
+/// Bytes with the sign bit set too, since the vectorized versions compare them as `i8`.
+const NEEDLES: [u8; 4] = [b'"', 0x00, 0x80, 0xff];
+
+fn scalar(haystack: &[u8], needle: u8) -> Option<usize> {
+    haystack.iter().position(|&byte| byte == needle)
+}
+
+/// `len` bytes of anything but `needle`, differing so that no lane sees a uniform chunk.
+fn filler(len: usize, needle: u8) -> Vec<u8> {
+    (0..len)
+        .map(|i| match (i * 7 + 1) as u8 {
+            byte if byte == needle => byte.wrapping_add(1),
+            byte => byte,
+        })
+        .collect()
+}
+
+#[test]
+fn matches_scalar_at_every_position() {
+    for needle in NEEDLES {
+        for len in 0..=64 {
+            let haystack = filler(len, needle);
+            assert_eq!(simd_find_byte(&haystack, needle), None, "len {len}");
+
+            for pos in 0..len {
+                let mut haystack = haystack.clone();
+                haystack[pos] = needle;
+                assert_eq!(simd_find_byte(&haystack, needle), Some(pos), "len {len}");
+
+                // Only the first counts, whichever chunk the others are in.
+                for later in pos + 1..len {
+                    let mut haystack = haystack.clone();
+                    haystack[later] = needle;
+                    assert_eq!(
+                        simd_find_byte(&haystack, needle),
+                        Some(pos),
+                        "len {len}, also at {later}"
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn matches_scalar_at_every_offset() {
+    // Slices starting anywhere in the buffer, so that loads aren't always aligned.
+    let mut buffer = filler(64 + 32, b'"');
+    for (i, byte) in buffer.iter_mut().enumerate() {
+        if i % 13 == 5 {
+            *byte = b'"';
+        }
+    }
+    for start in 0..32 {
+        for len in 0..=64 {
+            let haystack = &buffer[start..start + len];
+            for needle in NEEDLES {
+                assert_eq!(
+                    simd_find_byte(haystack, needle),
+                    scalar(haystack, needle),
+                    "start {start}, len {len}, needle {needle:#04x}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn finds_needles_at_the_ends() {
+    for len in 1..=64 {
+        let mut haystack = filler(len, b'"');
+        haystack[0] = b'"';
+        assert_eq!(simd_find_byte(&haystack, b'"'), Some(0));
+
+        let mut haystack = filler(len, b'"');
+        haystack[len - 1] = b'"';
+        assert_eq!(simd_find_byte(&haystack, b'"'), Some(len - 1));
+        assert_eq!(simd_find_byte(&haystack[..len - 1], b'"'), None);
+    }
+    assert_eq!(simd_find_byte(&[], b'"'), None);
+}
@@ -0,0 +1,36 @@
+use binance_json_parsing::small_string::SmallString;
+
+#[test]
+fn holds_up_to_capacity() {
+    let price = SmallString::<16>::new("83604.80").unwrap();
+    assert_eq!(price, "83604.80");
+    assert_eq!(price.len(), 8);
+    assert_eq!(price.to_string(), "83604.80");
+    assert_eq!(format!("{price:?}"), r#""83604.80""#);
+
+    assert_eq!(
+        SmallString::<16>::new("0.00000001000000").unwrap(),
+        "0.00000001000000"
+    );
+    assert_eq!(SmallString::<16>::new("0.000000010000000"), None);
+    assert_eq!(SmallString::<16>::new(""), Some(SmallString::default()));
+}
+
+#[test]
+fn is_copied_by_value() {
+    let price = SmallString::<16>::new("83604.80").unwrap();
+    let copy = price;
+    assert_eq!(price, copy);
+    assert!(size_of::<SmallString<16>>() <= 17);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn deserializes_from_strings_that_fit() {
+    let price: SmallString<16> = serde_json::from_str(r#""83604.80""#).unwrap();
+    assert_eq!(price, "83604.80");
+    assert_eq!(serde_json::to_string(&price).unwrap(), r#""83604.80""#);
+
+    let err = serde_json::from_str::<SmallString<4>>(r#""83604.80""#).unwrap_err();
+    assert!(err.to_string().contains("at most 4 bytes"), "{err}");
+}
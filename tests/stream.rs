@@ -0,0 +1,166 @@
+//! The streams end to end, against a local server instead of Binance.
+#![cfg(feature = "ws")]
+
+mod common;
+
+use std::time::Duration;
+
+use binance_json_parsing::{
+    ParsingConfig,
+    dedup::Deduplicator,
+    endpoint::StreamEndpoint,
+    parse_book_ticker_dynamic,
+    reconnect::{ReconnectConfig, ReconnectingStream},
+    sequence::SequenceChecker,
+    stream::{book_ticker_stream, endpoint_book_ticker_stream},
+};
+use common::mock_server::MockBinanceServer;
+use futures_util::{StreamExt, TryStreamExt, future};
+
+const BTCUSDT: &str = r#"{"e":"bookTicker","u":7283946601071,"s":"BTCUSDT","b":"83604.80","B":"10.746","a":"83604.90","A":"9.514","T":1744760290967,"E":1744760290968}"#;
+
+fn lines(fixture: &str) -> Vec<String> {
+    fixture.lines().map(str::to_owned).collect()
+}
+
+/// Quick to retry, so that tests don't wait on the backoff.
+fn quick_retries() -> ReconnectConfig {
+    ReconnectConfig {
+        initial_backoff: Duration::from_millis(10),
+        ..ReconnectConfig::default()
+    }
+}
+
+/// The text of the first `n` messages received from `server`.
+async fn receive(server: &MockBinanceServer, n: usize) -> Vec<String> {
+    ReconnectingStream::new(server.url(), quick_retries())
+        // Close frames come through too.
+        .try_filter(|msg| future::ready(msg.is_text()))
+        .map_ok(|msg| msg.into_text().unwrap().to_string())
+        .take(n)
+        .try_collect()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn reconnects_after_close() {
+    let messages = lines(include_str!("fixtures/endpoints/usd_m_futures.jsonl"));
+    let server = MockBinanceServer::builder()
+        .messages(messages.clone())
+        .send_all_then_close()
+        .build();
+
+    let received = receive(&server, 2 * messages.len()).await;
+
+    assert_eq!(received, [&messages[..], &messages[..]].concat());
+    assert_eq!(server.connections(), 2);
+}
+
+#[tokio::test]
+async fn keeps_sending_in_a_loop() {
+    let server = MockBinanceServer::builder()
+        .messages(vec![BTCUSDT.to_owned()])
+        .interval(Duration::from_millis(5))
+        .send_loop()
+        .build();
+
+    let tickers: Vec<_> = book_ticker_stream(&server.url(), ParsingConfig::default())
+        .take(3)
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert!(tickers.iter().all(|ticker| ticker.u == 7283946601071));
+    assert_eq!(server.connections(), 1);
+}
+
+#[tokio::test]
+async fn detects_config_of_each_symbol() {
+    // Prices and symbols of different lengths, so each one needs a config of its own.
+    let messages = lines(include_str!("fixtures/endpoints/usd_m_futures.jsonl"));
+    let server = MockBinanceServer::builder()
+        .messages(messages.clone())
+        .send_loop()
+        .build();
+
+    let endpoint = StreamEndpoint::Custom(server.url());
+    let symbols: Vec<_> = endpoint_book_ticker_stream(&endpoint, "btcusdt")
+        .map_ok(|ticker| ticker.s)
+        .take(6)
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        symbols,
+        [
+            "BTCUSDT",
+            "ETHUSDT",
+            "1000PEPEUSDT",
+            "BTCUSDT",
+            "ETHUSDT",
+            "1000PEPEUSDT"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn drops_resent_ticks() {
+    let changed = BTCUSDT.replace("83604.90", "83605.00");
+    let resent = changed.replace("1744760290968", "1744760290970");
+    let server = MockBinanceServer::builder()
+        .messages(vec![BTCUSDT.to_owned(), changed.clone(), resent])
+        .build();
+
+    let mut dedup = Deduplicator::new();
+    let passed: Vec<_> = receive(&server, 3)
+        .await
+        .into_iter()
+        .filter(|json| {
+            let tick = parse_book_ticker_dynamic(json, ParsingConfig::default()).unwrap();
+            !dedup.is_duplicate(&tick)
+        })
+        .collect();
+
+    assert_eq!(passed, [BTCUSDT.to_owned(), changed]);
+}
+
+#[tokio::test]
+async fn reports_gaps_in_update_ids() {
+    let messages =
+        [1, 2, 4, 5].map(|n| BTCUSDT.replace("7283946601071", &(7283946601070u64 + n).to_string()));
+    let server = MockBinanceServer::builder()
+        .messages(messages.to_vec())
+        .build();
+
+    let mut checker = SequenceChecker::new();
+    let gaps: Vec<_> = receive(&server, messages.len())
+        .await
+        .iter()
+        .filter_map(|json| {
+            let tick = parse_book_ticker_dynamic(json, ParsingConfig::default()).unwrap();
+            checker.check(tick.u).map(|gap| (gap.expected, gap.got))
+        })
+        .collect();
+
+    assert_eq!(gaps, [(7283946601073, 7283946601074)]);
+}
+
+#[tokio::test]
+async fn retries_with_jitter_out_of_range() {
+    // A port nothing listens on, so that every attempt fails.
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    drop(listener);
+
+    for jitter in [5.0, -1.0, f64::NAN] {
+        let config = ReconnectConfig {
+            jitter,
+            max_retries: Some(2),
+            ..quick_retries()
+        };
+        let result = ReconnectingStream::new(url.clone(), config).next().await;
+        assert!(matches!(result, Some(Err(_))), "jitter {jitter}");
+    }
+}
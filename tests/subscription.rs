@@ -0,0 +1,127 @@
+//! Subscriptions against the mock server, which keeps track of them.
+#![cfg(all(feature = "ws", feature = "serde"))]
+
+mod common;
+
+use std::time::{Duration, Instant};
+
+use binance_json_parsing::{
+    rate_limit::RateLimiter,
+    subscription::{SubscriptionError, SubscriptionManager},
+};
+use common::mock_server::MockBinanceServer;
+
+const BTCUSDT: &str = r#"{"e":"bookTicker","u":7283946601071,"s":"BTCUSDT","b":"83604.80","B":"10.746","a":"83604.90","A":"9.514","T":1744760290967,"E":1744760290968}"#;
+
+fn idle_server() -> MockBinanceServer {
+    MockBinanceServer::builder().send_loop().build()
+}
+
+#[tokio::test]
+async fn subscribes_and_unsubscribes() {
+    let server = idle_server();
+    let (manager, _messages) = SubscriptionManager::connect(&format!("{}/stream", server.url()))
+        .await
+        .unwrap();
+
+    let first = manager
+        .subscribe(&["btcusdt@bookTicker", "ethusdt@bookTicker"])
+        .await
+        .unwrap();
+    let second = manager.subscribe(&["solusdt@bookTicker"]).await.unwrap();
+    assert_ne!(first, second);
+    manager.unsubscribe(&["ethusdt@bookTicker"]).await.unwrap();
+
+    assert_eq!(server.connections(), 1);
+}
+
+#[tokio::test]
+async fn runs_requests_concurrently() {
+    let server = idle_server();
+    let (manager, _messages) = SubscriptionManager::connect(&server.url()).await.unwrap();
+
+    let manager = &manager;
+    let streams = [
+        "btcusdt@bookTicker",
+        "ethusdt@bookTicker",
+        "solusdt@bookTicker",
+    ];
+    let ids = futures_util::future::try_join_all(
+        streams.map(|stream| async move { manager.subscribe(&[stream]).await }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(ids.len(), 3);
+}
+
+#[tokio::test]
+async fn returns_rejections() {
+    let server = idle_server();
+    let (manager, _messages) = SubscriptionManager::connect(&server.url()).await.unwrap();
+
+    let err = manager.subscribe(&["btcusdt"]).await.unwrap_err();
+    assert!(
+        matches!(&err, SubscriptionError::Rejected { code: 2, msg } if msg.contains("btcusdt")),
+        "{err}"
+    );
+    // Still usable afterwards.
+    manager.subscribe(&["btcusdt@bookTicker"]).await.unwrap();
+}
+
+#[tokio::test]
+async fn passes_events_on() {
+    let server = MockBinanceServer::builder()
+        .messages(vec![BTCUSDT.to_owned()])
+        .send_loop()
+        .build();
+    let (manager, mut messages) = SubscriptionManager::connect(&server.url()).await.unwrap();
+
+    manager.subscribe(&["btcusdt@bookTicker"]).await.unwrap();
+    for _ in 0..3 {
+        let msg = messages.recv().await.unwrap().unwrap();
+        assert_eq!(msg.to_text().unwrap(), BTCUSDT);
+    }
+}
+
+#[tokio::test]
+async fn fails_once_closed() {
+    let server = MockBinanceServer::builder().send_all_then_close().build();
+    let (manager, mut messages) = SubscriptionManager::connect(&server.url()).await.unwrap();
+
+    // The close frame, then the end of the connection.
+    while messages.recv().await.is_some() {}
+    assert!(matches!(
+        manager.subscribe(&["btcusdt@bookTicker"]).await,
+        Err(SubscriptionError::Closed)
+    ));
+}
+
+#[tokio::test]
+async fn limits_request_rate() {
+    let server = idle_server();
+    let (manager, _messages) = SubscriptionManager::connect(&server.url()).await.unwrap();
+
+    let started = Instant::now();
+    for i in 0..11 {
+        manager
+            .subscribe(&[&format!("sym{i}usdt@bookTicker")])
+            .await
+            .unwrap();
+    }
+    assert!(started.elapsed() >= Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn refills_tokens_every_second() {
+    let limiter = RateLimiter::new(2, 1);
+    let started = Instant::now();
+    limiter.acquire().await;
+    limiter.acquire().await;
+    assert!(!limiter.try_acquire());
+    assert!(started.elapsed() < Duration::from_secs(1));
+
+    // One token a second from then on, however many are waiting.
+    let limiter = &limiter;
+    futures_util::future::join(limiter.acquire(), limiter.acquire()).await;
+    assert!(started.elapsed() >= Duration::from_secs(2));
+}
@@ -0,0 +1,50 @@
+use binance_json_parsing::swar::{all_digits, parse_u64_fixed_digits};
+
+/// Milliseconds since the epoch at 2020-01-01 and 2038-01-01.
+const FROM: u64 = 1_577_836_800_000;
+const TO: u64 = 2_145_916_800_000;
+
+fn check(value: u64) {
+    let s = value.to_string();
+    assert!(all_digits(s.as_bytes(), s.len()), "{s}");
+    assert_eq!(parse_u64_fixed_digits(s.as_bytes(), s.len()), value, "{s}");
+}
+
+#[test]
+fn parses_timestamps_from_2020_to_2038() {
+    // A prime stride just short of every quarter of an hour, so that every digit position
+    // takes all its values, along with the last millisecond of each day.
+    for value in (FROM..TO).step_by(899_981) {
+        check(value);
+    }
+    for value in (FROM + 86_399_999..TO).step_by(86_400_000) {
+        check(value);
+    }
+}
+
+#[test]
+fn parses_any_number_of_digits() {
+    for digits in 1..=19 {
+        check(10u64.pow(digits - 1));
+        check(10u64.pow(digits) - 1);
+    }
+    // Only the given digits are read.
+    assert_eq!(parse_u64_fixed_digits(b"1744760290967,", 13), 1744760290967);
+}
+
+#[test]
+fn rejects_non_digits() {
+    // Right below and above the digits, in the chunk and in the leading ones.
+    for invalid in [
+        "174476029096/",
+        "17447602:0967",
+        "1744760290 67",
+        "/744760290967",
+        "1744:60290967",
+    ] {
+        assert!(!all_digits(invalid.as_bytes(), 13), "{invalid}");
+    }
+    assert!(!all_digits(b"123456789abcdef", 15));
+    assert!(all_digits(b"1744760290967}", 13));
+    assert!(all_digits(b"", 0));
+}
@@ -0,0 +1,72 @@
+//! Against the futures testnet, only run with a key of it in `BINANCE_TESTNET_KEY`, e.g.
+//!
+//! ```sh
+//! BINANCE_TESTNET_KEY=... cargo test --test testnet
+//! ```
+//!
+//! and otherwise skipped, as they need the network.
+#![cfg(all(feature = "ws", feature = "serde"))]
+
+use std::time::Duration;
+
+use binance_json_parsing::{
+    endpoint::StreamEndpoint,
+    rest::{RestClient, fetch_depth_snapshot},
+    stream::endpoint_book_ticker_stream,
+    user_data::ListenKeyManager,
+};
+use futures_util::StreamExt;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+fn testnet_key() -> Option<String> {
+    let key = std::env::var("BINANCE_TESTNET_KEY").ok();
+    if key.is_none() {
+        eprintln!("skipped, BINANCE_TESTNET_KEY isn't set");
+    }
+    key
+}
+
+#[tokio::test]
+async fn fetches_testnet_snapshot() {
+    if testnet_key().is_none() {
+        return;
+    }
+    let client = RestClient::for_endpoint(&StreamEndpoint::Testnet).unwrap();
+    let snapshot = tokio::time::timeout(TIMEOUT, fetch_depth_snapshot("BTCUSDT", 5, &client))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(snapshot.last_update_id > 0);
+    assert_eq!(snapshot.bids.len(), 5);
+    assert!(snapshot.bids[0].0 < snapshot.asks[0].0);
+}
+
+#[tokio::test]
+async fn streams_testnet_book_tickers() {
+    if testnet_key().is_none() {
+        return;
+    }
+    let stream = endpoint_book_ticker_stream(&StreamEndpoint::Testnet, "btcusdt");
+    let tickers: Vec<_> = tokio::time::timeout(TIMEOUT, stream.take(3).collect())
+        .await
+        .unwrap();
+    for ticker in tickers {
+        assert_eq!(ticker.unwrap().s, "BTCUSDT");
+    }
+}
+
+#[tokio::test]
+async fn manages_testnet_listen_key() {
+    let Some(api_key) = testnet_key() else {
+        return;
+    };
+    let manager = ListenKeyManager::for_endpoint(&StreamEndpoint::Testnet, api_key).unwrap();
+    let lifecycle = async {
+        let key = manager.create().await.unwrap();
+        assert!(!key.is_empty());
+        manager.renew(&key).await.unwrap();
+        manager.delete(&key).await.unwrap();
+    };
+    tokio::time::timeout(TIMEOUT, lifecycle).await.unwrap();
+}
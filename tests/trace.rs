@@ -0,0 +1,89 @@
+#![cfg(feature = "opentelemetry")]
+
+use std::{cell::RefCell, rc::Rc};
+
+use binance_json_parsing::{
+    ParseError, detect_config, parse_book_ticker_dynamic,
+    trace::{Attribute, NoopTracer, Span, Tracer, parse_book_ticker_traced},
+};
+
+const BTCUSDT: &str = r#"{"e":"bookTicker","u":7283946601071,"s":"BTCUSDT","b":"83604.80","B":"10.746","a":"83604.90","A":"9.514","T":1744760290967,"E":1744760290968}"#;
+
+/// A span as ended, its attributes as `key=value`.
+type Recorded = (&'static str, Vec<String>);
+
+#[derive(Default)]
+struct RecordingTracer(Rc<RefCell<Vec<Recorded>>>);
+
+struct RecordingSpan {
+    span: Recorded,
+    ended: Rc<RefCell<Vec<Recorded>>>,
+}
+
+impl Tracer for RecordingTracer {
+    type Span = RecordingSpan;
+
+    fn start(&self, name: &'static str) -> RecordingSpan {
+        RecordingSpan {
+            span: (name, Vec::new()),
+            ended: Rc::clone(&self.0),
+        }
+    }
+}
+
+impl Span for RecordingSpan {
+    fn set_attribute(&mut self, key: &'static str, value: Attribute<'_>) {
+        let value = match value {
+            Attribute::Str(value) => value.to_owned(),
+            Attribute::U64(value) => value.to_string(),
+            value => panic!("{value:?}"),
+        };
+        self.span.1.push(format!("{key}={value}"));
+    }
+
+    fn end(self) {
+        self.ended.borrow_mut().push(self.span);
+    }
+}
+
+#[test]
+fn records_a_span_per_ticker() {
+    let config = detect_config(BTCUSDT).unwrap();
+    let tracer = RecordingTracer::default();
+    let ticker = parse_book_ticker_traced(BTCUSDT, config, &tracer).unwrap();
+    assert_eq!(ticker, parse_book_ticker_dynamic(BTCUSDT, config).unwrap());
+
+    let spans = tracer.0.borrow();
+    let [(name, attributes)] = spans.as_slice() else {
+        panic!("{spans:?}");
+    };
+    assert_eq!(*name, "parse_book_ticker");
+    assert_eq!(attributes[..2], ["symbol=BTCUSDT", "T=1744760290967"]);
+    assert!(
+        attributes[2].starts_with("elapsed_cycles="),
+        "{attributes:?}"
+    );
+    assert_eq!(attributes.len(), 3);
+}
+
+#[test]
+fn records_failures_without_the_ticker() {
+    let tracer = RecordingTracer::default();
+    let json = BTCUSDT.replace("bookTicker", "aggTrade");
+    let err = parse_book_ticker_traced(&json, detect_config(BTCUSDT).unwrap(), &tracer);
+    assert_eq!(err, Err(ParseError::WrongEventType));
+
+    let spans = tracer.0.borrow();
+    assert_eq!(spans.len(), 1);
+    assert_eq!(spans[0].1.len(), 1);
+    assert!(spans[0].1[0].starts_with("elapsed_cycles="));
+}
+
+#[test]
+fn traces_nothing_by_default() {
+    let config = detect_config(BTCUSDT).unwrap();
+    assert_eq!(
+        parse_book_ticker_traced(BTCUSDT, config, &NoopTracer),
+        parse_book_ticker_dynamic(BTCUSDT, config)
+    );
+}
@@ -0,0 +1,150 @@
+//! Authenticated connections and listen keys against local servers.
+#![cfg(all(feature = "ws", feature = "serde"))]
+
+use std::{sync::Arc, time::Duration};
+
+use binance_json_parsing::{
+    rest::{RestClient, create_listen_key},
+    user_data::{ListenKeyManager, connect_with_headers, spawn_renew_loop},
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+    sync::mpsc,
+};
+use tokio_tungstenite::tungstenite::{
+    handshake::server::{Request, Response},
+    http::{HeaderName, HeaderValue},
+};
+
+const API_KEY: &str = "vmPUZE6mv9SD5VNHk4HlWFsOr6aKE2zvsw0MuIgwCIPy6utIco14y7Ju91duEh8A";
+const LISTEN_KEY: &str = "pqia91ma19a5s61cv6a81va65sdf19v8a65a1a5s61cv6a81va65sdf19v8a65a1";
+const LISTEN_KEY_RESPONSE: &str =
+    r#"{"listenKey":"pqia91ma19a5s61cv6a81va65sdf19v8a65a1a5s61cv6a81va65sdf19v8a65a1"}"#;
+
+/// Answers every request with `body`, sending the head of each one.
+async fn rest_server(body: &'static str) -> (String, mpsc::UnboundedReceiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (requests, received) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        loop {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let n = stream.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{body}",
+                body.len()
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+            requests.send(String::from_utf8(request).unwrap()).unwrap();
+        }
+    });
+    (url, received)
+}
+
+#[tokio::test]
+async fn creates_listen_key() {
+    let (url, mut requests) = rest_server(LISTEN_KEY_RESPONSE).await;
+    let client = RestClient::new(&url).unwrap();
+    assert_eq!(
+        create_listen_key(&client, API_KEY).await.unwrap(),
+        LISTEN_KEY
+    );
+
+    let request = requests.recv().await.unwrap();
+    assert!(request.starts_with("POST /fapi/v1/listenKey HTTP/1.1\r\n"));
+    assert!(request.contains(&format!("X-MBX-APIKEY: {API_KEY}\r\n")));
+}
+
+#[tokio::test]
+async fn manages_listen_key_lifecycle() {
+    let (url, mut requests) = rest_server(LISTEN_KEY_RESPONSE).await;
+    let manager = Arc::new(
+        ListenKeyManager::new(&url, API_KEY)
+            .unwrap()
+            .with_renew_interval(Duration::from_millis(50)),
+    );
+    let mut next_request = async || {
+        let request = requests.recv().await.unwrap();
+        assert!(
+            request.contains(&format!("X-MBX-APIKEY: {API_KEY}\r\n")),
+            "{request}"
+        );
+        request
+    };
+
+    assert_eq!(manager.create().await.unwrap(), LISTEN_KEY);
+    assert!(
+        next_request()
+            .await
+            .starts_with("POST /fapi/v1/listenKey HTTP/1.1\r\n")
+    );
+
+    let renewing = spawn_renew_loop(manager.clone(), LISTEN_KEY.to_owned());
+    for _ in 0..3 {
+        assert!(
+            next_request()
+                .await
+                .starts_with("PUT /fapi/v1/listenKey HTTP/1.1\r\n")
+        );
+    }
+    renewing.abort();
+
+    manager.delete(LISTEN_KEY).await.unwrap();
+    // Renewals may have been on the way when aborted.
+    while !next_request().await.starts_with("DELETE ") {}
+}
+
+#[tokio::test]
+async fn fails_on_error_response() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        _ = stream.read(&mut [0; 1024]).await;
+        let body = r#"{"code":-2015,"msg":"Invalid API-key, IP, or permissions for action."}"#;
+        let response = format!(
+            "HTTP/1.1 401 Unauthorized\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        );
+        stream.write_all(response.as_bytes()).await.unwrap();
+    });
+
+    let manager = ListenKeyManager::new(&url, "wrong").unwrap();
+    let err = manager.renew(LISTEN_KEY).await.unwrap_err();
+    assert!(err.to_string().contains("-2015"), "{err}");
+}
+
+#[tokio::test]
+async fn sends_headers_with_upgrade() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut api_key = None;
+        // The error type is tungstenite's to pick.
+        #[allow(clippy::result_large_err)]
+        let record = |request: &Request, response: Response| {
+            api_key = request.headers().get("X-MBX-APIKEY").cloned();
+            Ok(response)
+        };
+        let _ws_stream = tokio_tungstenite::accept_hdr_async(stream, record)
+            .await
+            .unwrap();
+        api_key
+    });
+
+    let headers = [(
+        HeaderName::from_static("x-mbx-apikey"),
+        HeaderValue::from_static(API_KEY),
+    )];
+    connect_with_headers(&format!("ws://127.0.0.1:{port}/ws/{LISTEN_KEY}"), &headers)
+        .await
+        .unwrap();
+    assert_eq!(server.await.unwrap().unwrap(), API_KEY);
+}
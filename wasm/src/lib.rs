@@ -0,0 +1,48 @@
+//! Bindings of the book ticker parser for the browser, built with
+//! `wasm-pack build --target web --out-dir ../www/pkg`.
+//!
+//! Configs cross the boundary as JSON, with the fields of `[parsing]` in the `--config` file of
+//! the binary.
+
+use binance_json_parsing::{
+    ParsingConfig, detect_config, parse_book_ticker_dynamic, timing::measure,
+};
+use js_sys::{Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+/// Detects the config of `json`, returned as JSON to pass to [`parse_book_ticker_js`].
+#[wasm_bindgen]
+pub fn detect_config_js(json: &str) -> Result<String, JsError> {
+    let config = detect_config(json).map_err(|err| JsError::new(&err.to_string()))?;
+    Ok(serde_json::to_string(&config)?)
+}
+
+/// Parses `json` with the config given as JSON, into an object with the same fields as the
+/// payload plus `elapsed`, how long parsing took in nanoseconds.
+///
+/// The ids and timestamps become numbers, which are exact up to 2^53.
+#[wasm_bindgen]
+pub fn parse_book_ticker_js(json: &str, config_json: &str) -> Result<JsValue, JsError> {
+    let config: ParsingConfig = serde_json::from_str(config_json)?;
+    // Through `performance.now()` on wasm32, see `timing::PerformanceNow`.
+    let (elapsed, ticker) = measure(|| parse_book_ticker_dynamic(json, config));
+    let ticker = ticker.map_err(|err| JsError::new(&err.to_string()))?;
+
+    let object = Object::new();
+    let fields: [(&str, JsValue); 10] = [
+        ("e", ticker.e.into()),
+        ("u", (ticker.u as f64).into()),
+        ("s", ticker.s.into()),
+        ("b", ticker.b.into()),
+        ("B", ticker.B.into()),
+        ("a", ticker.a.into()),
+        ("A", ticker.A.into()),
+        ("T", (ticker.T as f64).into()),
+        ("E", (ticker.E as f64).into()),
+        ("elapsed", (elapsed as f64).into()),
+    ];
+    for (key, value) in fields {
+        Reflect::set(&object, &key.into(), &value).map_err(|_| JsError::new("set failed"))?;
+    }
+    Ok(object.into())
+}
@@ -0,0 +1,42 @@
+//! Run with `wasm-pack test --headless --chrome`.
+
+use binance_json_parsing_wasm::{detect_config_js, parse_book_ticker_js};
+use js_sys::Reflect;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const BTCUSDT: &str = r#"{"e":"bookTicker","u":7283946601071,"s":"BTCUSDT","b":"83604.80","B":"10.746","a":"83604.90","A":"9.514","T":1744760290967,"E":1744760290968}"#;
+
+fn field(object: &JsValue, key: &str) -> JsValue {
+    Reflect::get(object, &key.into()).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn parses_with_detected_config() {
+    let config = detect_config_js(BTCUSDT).unwrap();
+    let ticker = parse_book_ticker_js(BTCUSDT, &config).unwrap();
+
+    assert_eq!(field(&ticker, "s").as_string().as_deref(), Some("BTCUSDT"));
+    assert_eq!(field(&ticker, "b").as_string().as_deref(), Some("83604.80"));
+    assert_eq!(field(&ticker, "A").as_string().as_deref(), Some("9.514"));
+    assert_eq!(field(&ticker, "T").as_f64(), Some(1744760290967.0));
+}
+
+#[wasm_bindgen_test]
+fn rejects_changed_precision() {
+    let config = detect_config_js(BTCUSDT)
+        .unwrap()
+        .replace(r#""price_precision":2"#, r#""price_precision":3"#);
+    assert!(parse_book_ticker_js(BTCUSDT, &config).is_err());
+}
+
+/// `Instant` panics here, so `measure` has to go through `performance.now()`.
+#[wasm_bindgen_test]
+fn measures_with_the_browser_clock() {
+    let (elapsed, sum) = binance_json_parsing::timing::measure(|| (0..1000u64).sum::<u64>());
+    assert_eq!(sum, 499500);
+    // Coarsened to maybe 0, but well under a second.
+    assert!(elapsed < 1_000_000_000);
+}